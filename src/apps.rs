@@ -0,0 +1,79 @@
+//! Per-application contexts for SS9K
+//!
+//! This module handles:
+//! - Which app is focused when an utterance comes in (see
+//!   `system::focused_app`)
+//! - Switching to the profile `[apps]` maps that app to, and back to the
+//!   profile and case mode you had before once focus moves to an app that
+//!   isn't listed
+//!
+//! Checked once per utterance instead of polling, so nothing runs while
+//! you're not talking, and only when `[apps]` has entries.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use arc_swap::ArcSwap;
+use tracing::{debug, warn};
+
+use crate::commands::{get_case_mode, set_case_mode, sync_mode_dnd, CaseMode};
+use crate::{control, profiles, system, Config};
+
+/// What following the focused app has done so far
+struct Context {
+    app: Option<String>,                        // Focused app at the last utterance
+    applied: Option<String>,                    // Profile switched to for it
+    before: Option<(Option<String>, CaseMode)>, // Profile and mode to go back to
+}
+
+static CONTEXT: Mutex<Context> = Mutex::new(Context { app: None, applied: None, before: None });
+
+/// Focused app as of the last utterance (None = unknown, or `[apps]` is empty)
+pub fn focused() -> Option<String> {
+    CONTEXT.lock().ok().and_then(|c| c.app.clone())
+}
+
+/// Profile `[apps]` maps an app to (class matched ignoring case)
+pub fn profile_for<'a>(config: &'a Config, app: &str) -> Option<&'a String> {
+    config.apps.iter().find(|(class, _)| class.eq_ignore_ascii_case(app)).map(|(_, profile)| profile)
+}
+
+/// Switch profile to match the focused app, if it changed since last time
+pub fn follow(config: &ArcSwap<Config>, path: Option<&Path>) {
+    if config.load().apps.is_empty() {
+        return;
+    }
+    let app = system::focused_app();
+    let Ok(mut context) = CONTEXT.lock() else {
+        return;
+    };
+    if app != context.app {
+        debug!("🪟 Focused app: {}", app.as_deref().unwrap_or("unknown"));
+        context.app = app.clone();
+    }
+    let wanted = app.as_deref().and_then(|app| profile_for(&config.load(), app).cloned());
+    if wanted == context.applied {
+        return;
+    }
+
+    match &wanted {
+        Some(profile) => {
+            if context.applied.is_none() {
+                context.before = Some((profiles::active(), get_case_mode()));
+            }
+            if let Err(e) = control::set_profile(profile, config, path) {
+                warn!("⚠️ [apps] {}: {}", app.as_deref().unwrap_or_default(), e);
+            }
+        }
+        None => {
+            let (profile, mode) = context.before.take().unwrap_or_default();
+            if let Err(e) = control::set_profile(profile.as_deref().unwrap_or("off"), config, path) {
+                warn!("⚠️ [apps] Can't go back to the previous profile: {}", e);
+            }
+            set_case_mode(mode);
+            sync_mode_dnd(&config.load().dnd_modes);
+        }
+    }
+    // Set even when switching failed, so a bad entry warns once per focus change
+    context.applied = wanted;
+}
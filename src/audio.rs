@@ -2,17 +2,34 @@
 //!
 //! This module handles:
 //! - Microphone device detection (platform-specific)
-//! - Audio stream building
-//! - Sample rate conversion (resampling to 16kHz for Whisper)
+//! - Audio stream building (all cpal sample formats)
+//! - Sample rate conversion (persistent resamplers, 16kHz for Whisper)
+//! - Audio file decoding (wav/mp3/ogg/flac via symphonia)
+//! - Raw PCM input from a pipe (`--stdin-audio`) or the network (see netmic)
 //! - Whisper transcription
 
-use anyhow::Result;
-use cpal::Sample;
+use cpal::{Sample, SampleFormat};
 use rubato::{Resampler, SincFixedIn, SincInterpolationParameters, SincInterpolationType, WindowFunction};
+use serde::Serialize;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, LazyLock, Mutex};
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use tracing::{error, info};
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext};
 
+use crate::error::{AudioError, TranscribeError};
 use crate::Config;
 
 pub const WHISPER_SAMPLE_RATE: u32 = 16000;
@@ -55,7 +72,7 @@ pub fn build_stream<T>(
     channels: usize,
     recording: Arc<std::sync::atomic::AtomicBool>,
     err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
-) -> Result<cpal::Stream>
+) -> Result<cpal::Stream, AudioError>
 where
     T: cpal::SizedSample,
     f32: cpal::FromSample<T>,
@@ -82,33 +99,333 @@ where
     Ok(stream)
 }
 
+/// Build a hotkey-mode input stream for whatever sample format the device reports
+/// cpal always delivers interleaved frames, so planar devices are handled by the host backend
+pub fn build_stream_for_format(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    buffer: AudioBuffer,
+    channels: usize,
+    recording: Arc<std::sync::atomic::AtomicBool>,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, AudioError> {
+    let stream_config: cpal::StreamConfig = config.clone().into();
+    match config.sample_format() {
+        SampleFormat::I8 => build_stream::<i8>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::I16 => build_stream::<i16>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::I32 => build_stream::<i32>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::I64 => build_stream::<i64>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::U8 => build_stream::<u8>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::U16 => build_stream::<u16>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::U32 => build_stream::<u32>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::U64 => build_stream::<u64>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::F32 => build_stream::<f32>(device, &stream_config, buffer, channels, recording, err_fn),
+        SampleFormat::F64 => build_stream::<f64>(device, &stream_config, buffer, channels, recording, err_fn),
+        format => Err(AudioError::UnsupportedFormat(format)),
+    }
+}
+
+/// Sample encodings accepted for raw piped audio (ffmpeg/arecord names)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RawSampleFormat {
+    U8,
+    S16Le,
+    S32Le,
+    F32Le,
+}
+
+impl RawSampleFormat {
+    fn bytes(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::S16Le => 2,
+            Self::S32Le | Self::F32Le => 4,
+        }
+    }
+
+    fn decode(self, b: &[u8]) -> f32 {
+        match self {
+            Self::U8 => (b[0] as f32 - 128.0) / 128.0,
+            Self::S16Le => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            Self::S32Le => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2147483648.0,
+            Self::F32Le => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        }
+    }
+}
+
+/// Layout of a raw PCM stream, written as `encoding:rate:channels` (e.g. `s16le:48000:1`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RawFormat {
+    pub sample: RawSampleFormat,
+    pub rate: u32,
+    pub channels: usize,
+}
+
+impl FromStr for RawFormat {
+    type Err = AudioError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AudioError::RawFormat(s.to_string());
+        let mut parts = s.split(':');
+        let sample = match parts.next().map(|p| p.to_lowercase()).as_deref() {
+            Some("u8") => RawSampleFormat::U8,
+            Some("s16le") => RawSampleFormat::S16Le,
+            Some("s32le") => RawSampleFormat::S32Le,
+            Some("f32le") => RawSampleFormat::F32Le,
+            _ => return Err(invalid()),
+        };
+        let rate: u32 = parts.next().and_then(|p| p.parse().ok()).filter(|&r| r > 0).ok_or_else(invalid)?;
+        let channels: usize = match parts.next() {
+            Some(p) => p.parse().ok().filter(|&c| c > 0).ok_or_else(invalid)?,
+            None => 1,
+        };
+        if parts.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(Self { sample, rate, channels })
+    }
+}
+
+impl fmt::Display for RawFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sample = match self.sample {
+            RawSampleFormat::U8 => "u8",
+            RawSampleFormat::S16Le => "s16le",
+            RawSampleFormat::S32Le => "s32le",
+            RawSampleFormat::F32Le => "f32le",
+        };
+        write!(f, "{}:{}:{}", sample, self.rate, self.channels)
+    }
+}
+
+/// Read raw PCM from `reader` on a background thread
+/// Delivers ~20ms chunks of mono f32 to `sink`, the same shape a cpal callback produces,
+/// so the rest of the pipeline doesn't care where the audio came from
+pub fn spawn_raw_reader<R>(
+    reader: R,
+    format: RawFormat,
+    mut sink: impl FnMut(&[f32]) + Send + 'static,
+) -> std::thread::JoinHandle<()>
+where
+    R: Read + Send + 'static,
+{
+    std::thread::spawn(move || {
+        read_raw(reader, format, &mut sink);
+        info!("🔌 Raw audio input ended");
+    })
+}
+
+/// Read raw PCM from `reader` until it ends, handing `sink` mono chunks
+/// like `spawn_raw_reader` (which runs this on its own thread)
+pub fn read_raw(mut reader: impl Read, format: RawFormat, sink: &mut impl FnMut(&[f32])) {
+    let frame_bytes = format.sample.bytes() * format.channels;
+    let frames_per_chunk = (format.rate as usize / 50).max(1);
+    let mut raw = vec![0u8; frame_bytes * frames_per_chunk];
+    let mut filled = 0;
+    let mut mono = Vec::with_capacity(frames_per_chunk);
+
+    loop {
+        let n = match reader.read(&mut raw[filled..]) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                error!("❌ Raw audio read error: {}", e);
+                break;
+            }
+        };
+        filled += n;
+
+        // Only hand over whole frames; keep any partial frame for the next read
+        let whole = filled - filled % frame_bytes;
+        if whole == 0 {
+            continue;
+        }
+        CALLBACK_COUNT.fetch_add(1, Ordering::SeqCst);
+
+        mono.clear();
+        mono.extend(raw[..whole].chunks_exact(frame_bytes).map(|frame| {
+            let sum: f32 = frame.chunks_exact(format.sample.bytes()).map(|b| format.sample.decode(b)).sum();
+            sum / format.channels as f32
+        }));
+        sink(&mono);
+
+        raw.copy_within(whole..filled, 0);
+        filled -= whole;
+    }
+}
+
+/// Input block size for persistent resamplers (frames per process call)
+const RESAMPLE_BLOCK: usize = 1024;
+
+/// Persistent resamplers for whole-clip conversion, keyed by (from_rate, to_rate)
+static RESAMPLERS: LazyLock<Mutex<HashMap<(u32, u32), StreamingResampler>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// A sinc resampler that is built once and reused
+/// Construction (256-tap sinc table) is expensive, so callers keep one around
+/// and feed it fixed-size blocks instead of building one per utterance
+pub struct StreamingResampler {
+    resampler: SincFixedIn<f32>,
+    ratio: f64,
+    /// Input samples waiting for a full block
+    pending: Vec<f32>,
+    /// Preallocated output block
+    output: Vec<Vec<f32>>,
+}
+
+impl StreamingResampler {
+    /// Create a resampler converting `from_rate` to `to_rate` (mono)
+    pub fn new(from_rate: u32, to_rate: u32) -> Result<Self, AudioError> {
+        let params = SincInterpolationParameters {
+            sinc_len: 256,
+            f_cutoff: 0.95,
+            interpolation: SincInterpolationType::Linear,
+            oversampling_factor: 256,
+            window: WindowFunction::BlackmanHarris2,
+        };
+
+        let ratio = to_rate as f64 / from_rate as f64;
+        let resampler = SincFixedIn::<f32>::new(
+            ratio,
+            2.0,
+            params,
+            RESAMPLE_BLOCK,
+            1,
+        )?;
+        let output = resampler.output_buffer_allocate(true);
+
+        Ok(Self {
+            resampler,
+            ratio,
+            pending: Vec::with_capacity(RESAMPLE_BLOCK * 2),
+            output,
+        })
+    }
+
+    /// Feed the next chunk of a continuous stream
+    /// Returns output for every complete block; leftover input waits for the next call
+    pub fn process(&mut self, input: &[f32]) -> Result<Vec<f32>, AudioError> {
+        self.pending.extend_from_slice(input);
+
+        let blocks = self.pending.len() / RESAMPLE_BLOCK;
+        let mut result = Vec::with_capacity((blocks as f64 * RESAMPLE_BLOCK as f64 * self.ratio) as usize + 1);
+
+        for block in self.pending.chunks_exact(RESAMPLE_BLOCK) {
+            let (_, written) = self.resampler.process_into_buffer(&[block], &mut self.output, None)?;
+            result.extend_from_slice(&self.output[0][..written]);
+        }
+        self.pending.drain(..blocks * RESAMPLE_BLOCK);
+
+        Ok(result)
+    }
+
+    /// Resample a complete clip from a clean state
+    /// Flushes the partial last block, so the output covers the whole input
+    pub fn process_clip(&mut self, input: &[f32]) -> Result<Vec<f32>, AudioError> {
+        self.reset();
+
+        let expected = (input.len() as f64 * self.ratio).round() as usize;
+        let mut result = self.process(input)?;
+
+        // Push out the remaining input, padding with zeros until the tail is through
+        while result.len() < expected {
+            let (_, written) = if self.pending.is_empty() {
+                self.resampler.process_partial_into_buffer(None::<&[Vec<f32>]>, &mut self.output, None)?
+            } else {
+                let tail = std::mem::take(&mut self.pending);
+                self.resampler.process_partial_into_buffer(Some(&[tail]), &mut self.output, None)?
+            };
+            if written == 0 {
+                break;
+            }
+            result.extend_from_slice(&self.output[0][..written]);
+        }
+
+        result.truncate(expected);
+        self.reset();
+
+        Ok(result)
+    }
+
+    /// Clear filter state and pending input (call when the stream is interrupted)
+    pub fn reset(&mut self) {
+        self.resampler.reset();
+        self.pending.clear();
+    }
+}
+
+/// Decode an audio file to mono f32 samples
+/// Returns the samples and their sample rate; multi-channel audio is averaged down
+pub fn decode_file(path: &Path) -> Result<(Vec<f32>, u32), AudioError> {
+    let file = std::fs::File::open(path).map_err(|source| AudioError::Open {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe().format(&hint, mss, &FormatOptions::default(), &MetadataOptions::default())?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or(AudioError::NoAudioTrack)?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+
+    let mut samples = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // End of stream
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // Corrupt packet - skip it and keep going
+            Err(SymphoniaError::DecodeError(_)) => continue,
+            Err(e) => return Err(e.into()),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let channels = spec.channels.count().max(1);
+        let mut buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        buf.copy_interleaved_ref(decoded);
+
+        samples.extend(buf.samples().chunks(channels).map(|frame| frame.iter().sum::<f32>() / channels as f32));
+    }
+
+    Ok((samples, sample_rate))
+}
+
 /// Resample audio from one sample rate to another
-pub fn resample_audio(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>> {
+/// Reuses a cached resampler for each (from, to) pair
+pub fn resample_audio(input: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, AudioError> {
     if from_rate == to_rate {
         return Ok(input.to_vec());
     }
 
-    let params = SincInterpolationParameters {
-        sinc_len: 256,
-        f_cutoff: 0.95,
-        interpolation: SincInterpolationType::Linear,
-        oversampling_factor: 256,
-        window: WindowFunction::BlackmanHarris2,
+    let mut cache = RESAMPLERS.lock().map_err(|_| AudioError::Poisoned)?;
+    let resampler = match cache.entry((from_rate, to_rate)) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(StreamingResampler::new(from_rate, to_rate)?),
     };
 
-    let ratio = to_rate as f64 / from_rate as f64;
-    let mut resampler = SincFixedIn::<f32>::new(
-        ratio,
-        2.0,
-        params,
-        input.len(),
-        1,
-    )?;
-
-    let waves_in = vec![input.to_vec()];
-    let waves_out = resampler.process(&waves_in, None)?;
-
-    Ok(waves_out.into_iter().next().unwrap_or_default())
+    resampler.process_clip(input)
 }
 
 /// Build an audio input stream for VAD mode - sends all audio to a channel
@@ -118,7 +435,7 @@ pub fn build_stream_with_vad<T>(
     vad_tx: std::sync::mpsc::Sender<Vec<f32>>,
     channels: usize,
     err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
-) -> Result<cpal::Stream>
+) -> Result<cpal::Stream, AudioError>
 where
     T: cpal::SizedSample,
     f32: cpal::FromSample<T>,
@@ -149,8 +466,47 @@ where
     Ok(stream)
 }
 
+/// Build a VAD-mode input stream for whatever sample format the device reports
+pub fn build_stream_with_vad_for_format(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    vad_tx: std::sync::mpsc::Sender<Vec<f32>>,
+    channels: usize,
+    err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+) -> Result<cpal::Stream, AudioError> {
+    let stream_config: cpal::StreamConfig = config.clone().into();
+    match config.sample_format() {
+        SampleFormat::I8 => build_stream_with_vad::<i8>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::I16 => build_stream_with_vad::<i16>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::I32 => build_stream_with_vad::<i32>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::I64 => build_stream_with_vad::<i64>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::U8 => build_stream_with_vad::<u8>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::U16 => build_stream_with_vad::<u16>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::U32 => build_stream_with_vad::<u32>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::U64 => build_stream_with_vad::<u64>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::F32 => build_stream_with_vad::<f32>(device, &stream_config, vad_tx, channels, err_fn),
+        SampleFormat::F64 => build_stream_with_vad::<f64>(device, &stream_config, vad_tx, channels, err_fn),
+        format => Err(AudioError::UnsupportedFormat(format)),
+    }
+}
+
+/// A timed piece of transcript (whisper segment)
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Segment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
 /// Transcribe audio using Whisper
-pub fn transcribe(ctx: &WhisperContext, audio: &[f32], config: &Config) -> Result<String> {
+pub fn transcribe(ctx: &WhisperContext, audio: &[f32], config: &Config) -> Result<String, TranscribeError> {
+    let segments = transcribe_segments(ctx, audio, config)?;
+    let result: String = segments.iter().map(|s| s.text.as_str()).collect();
+    Ok(result.trim().to_string())
+}
+
+/// Transcribe audio using Whisper, keeping per-segment timestamps
+pub fn transcribe_segments(ctx: &WhisperContext, audio: &[f32], config: &Config) -> Result<Vec<Segment>, TranscribeError> {
     // Whisper requires minimum 1 second of audio (16000 samples at 16kHz)
     // Pad with silence if shorter - use 1.1s to avoid edge cases
     let min_samples = (WHISPER_SAMPLE_RATE as f32 * 1.1) as usize; // ~17600 samples
@@ -174,13 +530,16 @@ pub fn transcribe(ctx: &WhisperContext, audio: &[f32], config: &Config) -> Resul
     state.full(params, &audio)?;
 
     let num_segments = state.full_n_segments()?;
-    let mut result = String::new();
+    let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
 
     for i in 0..num_segments {
-        if let Ok(segment) = state.full_get_segment_text(i) {
-            result.push_str(&segment);
+        if let Ok(text) = state.full_get_segment_text(i) {
+            // Whisper timestamps are in centiseconds
+            let start = state.full_get_segment_t0(i).unwrap_or(0).max(0) as u64 * 10;
+            let end = state.full_get_segment_t1(i).unwrap_or(0).max(0) as u64 * 10;
+            segments.push(Segment { start_ms: start, end_ms: end, text });
         }
     }
 
-    Ok(result.trim().to_string())
+    Ok(segments)
 }
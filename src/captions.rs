@@ -0,0 +1,80 @@
+//! Live captions file for SS9K
+//!
+//! This module handles:
+//! - Keeping the last few dictated lines in `captions_file` (commands are left out)
+//! - Clearing it after `captions_clear_secs` without new speech
+//!
+//! Point an OBS Text source at the file ("Read from file") and it shows
+//! what you say as captions, no cloud service involved. The file is
+//! replaced in one go (write + rename) so OBS never reads half a line.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use tracing::{info, warn};
+
+use crate::commands::{parse_action, Action, Phrases};
+use crate::control::{self, Event};
+use crate::error::CaptionsError;
+use crate::Config;
+
+/// Start captioning into `path`; it's emptied now and updated on each transcription
+pub fn start(path: PathBuf, config: Arc<ArcSwap<Config>>) -> Result<JoinHandle<()>, CaptionsError> {
+    write(&path, "")?;
+    info!("💬 Live captions: {:?}", path);
+
+    let events = control::subscribe();
+    Ok(std::thread::spawn(move || {
+        let mut lines: VecDeque<String> = VecDeque::new();
+        let mut failed = false; // Log the first failure, not every caption
+        loop {
+            let clear_secs = config.load().captions_clear_secs;
+            let event = if lines.is_empty() || clear_secs == 0 {
+                events.recv().map_err(|_| RecvTimeoutError::Disconnected)
+            } else {
+                events.recv_timeout(Duration::from_secs(clear_secs))
+            };
+            match event {
+                Ok(Event::Transcription(text)) => {
+                    let cfg = config.load();
+                    let Action::Type(text) = parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases)
+                    else {
+                        continue;
+                    };
+                    lines.push_back(text.trim().to_string());
+                    let keep = cfg.captions_lines.max(1);
+                    while lines.len() > keep {
+                        lines.pop_front();
+                    }
+                }
+                Ok(_) => continue,
+                Err(RecvTimeoutError::Timeout) => lines.clear(),
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+
+            let text = lines.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+            match write(&path, &text) {
+                Ok(()) => failed = false,
+                Err(e) if !failed => {
+                    warn!("⚠️ {}", e);
+                    failed = true;
+                }
+                Err(_) => {}
+            }
+        }
+    }))
+}
+
+/// Replace the file's contents atomically
+fn write(path: &Path, text: &str) -> Result<(), CaptionsError> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, text)
+        .and_then(|()| std::fs::rename(&tmp, path))
+        .map_err(|source| CaptionsError::Write { path: path.to_path_buf(), source })
+}
@@ -0,0 +1,539 @@
+//! Config validation for SS9K
+//!
+//! This module handles:
+//! - Mistakes serde can't catch: unknown keys, hotkeys and mode names that
+//!   don't exist, custom commands that can never match, phrases that collide
+//!   once normalized, apps mapped to missing profiles, bad `[midi]` entries,
+//!   `[[patterns]]` and `[[replacements]]` regexes that don't compile,
+//!   `[priorities]` for phrases that aren't set
+//! - Finding the config.toml line each problem is on, for `ss9k check`
+//!
+//! SS9K still runs with a config that has problems (bad values act like
+//! defaults); startup logs them as warnings, `ss9k check` prints them with
+//! context and fails.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use tracing::warn;
+
+use crate::audio::RawFormat;
+use crate::commands::{
+    compile_pattern, compile_replacement, expand_env_vars, fill_keys, fill_step, match_phrase, normalize_for_matching, parse_action, parse_keybind,
+    parse_mode_name, parse_step, Action, Captures, Phrases, ShellContext,
+};
+use crate::config::merge_includes;
+use crate::error::ConfigError;
+use crate::fillers::Fillers;
+use crate::hotkeys::{
+    parse_device_id, parse_gamepad_button, parse_hotkey, parse_midi_action, parse_midi_message, parse_switch, MidiAction,
+};
+use crate::migrate::{migrate, unknown_keys, CONFIG_VERSION};
+use crate::netmic::{self, NetSource};
+use crate::text::edit_distance;
+use crate::{profiles, security, Config};
+
+/// Settings that only take a fixed set of values
+const CHOICES: &[(&str, &[&str])] = &[
+    ("hotkey_mode", &["hold", "toggle"]),
+    ("activation_mode", &["hotkey", "vad"]),
+    ("notifications", &["off", "errors", "results", "all"]),
+    ("output_mode", &["type", "clipboard", "stdout", "file", "webhook", "exec:<command>", "homeassistant"]),
+    ("log_rotation", &["hourly", "daily", "never"]),
+    ("keyboard_backend", &["enigo", "wtype", "ydotool", "uinput"]),
+    ("hotkey_backend", &["rdev", "evdev"]),
+    ("fuzzy_match", &["off", "levenshtein", "jaro-winkler"]),
+    ("spelling_alphabet", &["nato", "german", "none"]),
+    ("swear_style", &["grawlix", "asterisks", "first-letter", "remove"]),
+    ("numbers", &["spoken", "digits", "formatted"]),
+    ("mode_revert", &["never", "utterance"]),
+];
+
+/// One thing wrong with a config
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub table: Option<String>, // "commands" for [commands] entries, None at the top level
+    pub key: String,           // Empty when it's about the whole file
+    pub line: Option<usize>,   // 1-based, filled in by `check_file`
+    pub message: String,
+}
+
+impl Problem {
+    fn new(table: Option<&str>, key: &str, message: String) -> Self {
+        Self { table: table.map(str::to_string), key: key.to_string(), line: None, message }
+    }
+}
+
+/// Problems with an already-loaded config
+pub fn validate(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let hotkeys = [
+        ("hotkey", &config.hotkey),
+        ("command_hotkey", &config.command_hotkey),
+        ("abort_hotkey", &config.abort_hotkey),
+    ];
+    for (key, name) in hotkeys {
+        if !name.is_empty() && parse_hotkey(name).is_none() {
+            problems.push(Problem::new(
+                None,
+                key,
+                format!(
+                    "unknown hotkey '{}' (F1-F12, ScrollLock, Pause, PrintScreen, Insert, Home, End, PageUp, PageDown, Num0-Num9, Mouse4, Mouse5, or a combo like Ctrl+Alt+Space)",
+                    name
+                ),
+            ));
+        }
+    }
+    if parse_hotkey(&config.command_hotkey).is_some_and(|command| parse_hotkey(&config.hotkey) == Some(command)) {
+        problems.push(Problem::new(None, "command_hotkey", "is the same key as hotkey, so it never fires".to_string()));
+    }
+    let abort = parse_hotkey(&config.abort_hotkey);
+    if abort.is_some() && [&config.hotkey, &config.command_hotkey].iter().any(|key| parse_hotkey(key) == abort) {
+        problems.push(Problem::new(None, "abort_hotkey", "is the same key as a recording hotkey".to_string()));
+    }
+    if !config.audio_source.is_empty() && let Err(e) = config.audio_source.parse::<NetSource>() {
+        problems.push(Problem::new(None, "audio_source", e.to_string()));
+    }
+    if let Err(e) = netmic::parse_allow(&config.audio_source_allow) {
+        problems.push(Problem::new(None, "audio_source_allow", e.to_string()));
+    }
+    if let Err(e) = config.audio_source_format.parse::<RawFormat>() {
+        problems.push(Problem::new(None, "audio_source_format", e.to_string()));
+    }
+    #[cfg(feature = "http")]
+    if !config.remote_listen.is_empty() && config.remote_token.trim().len() < crate::remote::MIN_TOKEN_LEN {
+        problems.push(Problem::new(
+            None,
+            "remote_token",
+            format!("needs at least {} characters for remote_listen to start (openssl rand -hex 16)", crate::remote::MIN_TOKEN_LEN),
+        ));
+    }
+    if !config.trigger_device.is_empty() && parse_device_id(&config.trigger_device).is_none() {
+        problems.push(Problem::new(
+            None,
+            "trigger_device",
+            format!("invalid ID '{}' (vendor:product in hex, as lsusb shows it, e.g. 05f3:00ff)", config.trigger_device),
+        ));
+    }
+    if !config.gamepad_button.is_empty() && parse_gamepad_button(&config.gamepad_button).is_none() {
+        problems.push(Problem::new(
+            None,
+            "gamepad_button",
+            format!("unknown button '{}' (A, B, X, Y, LB, RB, LT, RT, L3, R3, Back, Start, Guide, DPadUp... or a raw code)", config.gamepad_button),
+        ));
+    }
+    if !config.switch_key.is_empty() && parse_switch(&config.switch_key).is_none() {
+        problems.push(Problem::new(
+            None,
+            "switch_key",
+            format!("unknown switch '{}' (a hotkey name, SPACE, ENTER, MOUSE_MIDDLE or MOUSE4-MOUSE9)", config.switch_key),
+        ));
+    }
+
+    let values = [
+        &config.hotkey_mode,
+        &config.activation_mode,
+        &config.notifications,
+        &config.output_mode,
+        &config.log_rotation,
+        &config.keyboard_backend,
+        &config.hotkey_backend,
+        &config.fuzzy_match,
+        &config.spelling_alphabet,
+        &config.swear_style,
+        &config.numbers,
+        &config.mode_revert,
+    ];
+    for ((key, choices), value) in CHOICES.iter().zip(values) {
+        let exec = *key == "output_mode" && value.strip_prefix("exec:").is_some_and(|c| !c.trim().is_empty());
+        if !choices.contains(&value.as_str()) && !exec {
+            problems.push(Problem::new(None, key, format!("'{}' isn't one of: {}", value, choices.join(", "))));
+        }
+    }
+
+    if !(0.0..=1.0).contains(&config.fuzzy_threshold) {
+        problems.push(Problem::new(None, "fuzzy_threshold", format!("{} isn't between 0 and 1", config.fuzzy_threshold)));
+    }
+
+    let needs = [("file", "output_file", &config.output_file), ("webhook", "output_webhook", &config.output_webhook)];
+    for (mode, key, value) in needs {
+        if config.output_mode == mode && value.is_empty() {
+            problems.push(Problem::new(None, key, format!("is empty, but output_mode is \"{}\"", mode)));
+        }
+    }
+
+    if config.remove_fillers && Fillers::for_language(&config.language, &config.filler_words).is_empty() {
+        problems.push(Problem::new(
+            None,
+            "remove_fillers",
+            format!("is on, but there's no filler list for language \"{}\" and filler_words is empty", config.language),
+        ));
+    }
+
+    if !config.script.is_empty() && !Path::new(shellexpand::tilde(&config.script).as_ref()).is_file() {
+        problems.push(Problem::new(None, "script", format!("no such file '{}'", config.script)));
+    }
+
+    for mode in &config.dnd_modes {
+        if parse_mode_name(mode).is_none() {
+            problems.push(Problem::new(None, "dnd_modes", format!("unknown mode '{}'", mode)));
+        }
+    }
+
+    let mut profiles: Vec<_> = config.profiles.iter().collect();
+    profiles.sort_by_key(|(name, _)| *name);
+    for (name, profile) in profiles {
+        if !profile.mode.is_empty() && parse_mode_name(&profile.mode).is_none() {
+            problems.push(Problem::new(Some(&format!("profiles.{}", name)), "mode", format!("unknown mode '{}'", profile.mode)));
+        }
+    }
+    let mut apps: Vec<_> = config.apps.iter().collect();
+    apps.sort();
+    for (app, profile) in apps {
+        if profiles::find(config, profile).is_none() {
+            problems.push(Problem::new(
+                Some("apps"),
+                app,
+                format!("unknown profile '{}' (have: {})", profile, profiles::names(config).join(", ")),
+            ));
+        }
+    }
+
+    let mut midi: Vec<_> = config.midi.iter().collect();
+    midi.sort();
+    for (message, action) in midi {
+        if parse_midi_message(message).is_none() {
+            problems.push(Problem::new(Some("midi"), message, "isn't \"note <0-127>\" or \"cc <0-127>\"".to_string()));
+        }
+        match parse_midi_action(action) {
+            None => problems.push(Problem::new(
+                Some("midi"),
+                message,
+                format!("unknown action '{}' (record, command, abort or \"mode <name>\")", action),
+            )),
+            Some(MidiAction::Mode(name)) if parse_mode_name(&name).is_none() => {
+                problems.push(Problem::new(Some("midi"), message, format!("unknown mode '{}'", name)));
+            }
+            Some(_) => {}
+        }
+    }
+
+    problems.extend(duplicate_phrases("commands", config.commands.keys()));
+    problems.extend(duplicate_phrases("keybinds", config.keybinds.keys()));
+    problems.extend(duplicate_phrases("macros", config.macros.keys()));
+    problems.extend(duplicate_phrases("aliases", config.aliases.keys()));
+    problems.extend(duplicate_phrases("command_aliases", config.command_aliases.keys()));
+    problems.extend(duplicate_phrases("punctuation", config.punctuation.keys()));
+    problems.extend(duplicate_phrases("emoji", config.emoji.keys()));
+    problems.extend(duplicate_phrases("spelling", config.spelling.keys()));
+
+    for (table, entries) in [("punctuation", &config.punctuation), ("emoji", &config.emoji)] {
+        let mut entries: Vec<(&String, &String)> = entries.iter().collect();
+        entries.sort();
+        for (name, symbol) in entries {
+            if symbol.is_empty() {
+                problems.push(Problem::new(Some(table), name, "types nothing".to_string()));
+            }
+        }
+    }
+
+    let mut spelling: Vec<(&String, &String)> = config.spelling.iter().collect();
+    spelling.sort();
+    for (word, ch) in spelling {
+        if word.split_whitespace().count() != 1 {
+            problems.push(Problem::new(Some("spelling"), word, "isn't one word, and spell mode hears one word at a time".to_string()));
+        }
+        if ch.chars().count() != 1 {
+            problems.push(Problem::new(Some("spelling"), word, format!("\"{}\" isn't exactly one character", ch)));
+        }
+    }
+
+    let mut command_aliases: Vec<(&String, &String)> = config.command_aliases.iter().collect();
+    command_aliases.sort();
+    for (phrase, builtins) in command_aliases {
+        if builtins.split(',').all(|b| b.trim().is_empty()) {
+            problems.push(Problem::new(Some("command_aliases"), phrase, "names no built-in".to_string()));
+        }
+    }
+
+    let mut configured: Vec<&String> = config.command_settings.keys().collect();
+    configured.sort();
+    for phrase in configured {
+        if !config.commands.contains_key(phrase) {
+            problems.push(Problem::new(Some("command_settings"), phrase, "isn't a [commands] phrase".to_string()));
+        }
+        if let Some(dir) = ShellContext::of(&config.command_settings[phrase]).dir()
+            && !dir.is_dir()
+        {
+            problems.push(Problem::new(Some("command_settings"), phrase, format!("cwd {:?} isn't a directory", dir)));
+        }
+    }
+
+    let mut commands: Vec<(&String, &String)> = config.commands.iter().collect();
+    commands.sort();
+    for (phrase, cmd) in commands {
+        if let Err(e) = security::check(&expand_env_vars(cmd), &config.security) {
+            problems.push(Problem::new(Some("commands"), phrase, e.to_string()));
+        }
+    }
+    if config.security.allowed_programs.iter().any(|p| p.trim().is_empty()) {
+        problems.push(Problem::new(Some("security"), "allowed_programs", "has an empty entry".to_string()));
+    }
+
+    let mut prioritized: Vec<&String> = config.priorities.keys().collect();
+    prioritized.sort();
+    for phrase in prioritized {
+        let known = config.commands.contains_key(phrase) || config.keybinds.contains_key(phrase) || config.macros.contains_key(phrase);
+        if !known {
+            problems.push(Problem::new(Some("priorities"), phrase, "isn't a [commands], [keybinds] or [macros] phrase".to_string()));
+        }
+    }
+
+    let mut phrases: Vec<&String> = config.commands.keys().collect();
+    phrases.sort();
+    for phrase in phrases {
+        let action = parse_action(phrase, &config.leader, &config.leaderless_commands, &Phrases::of(config), &config.aliases);
+        let instead = match action {
+            Action::Custom(_) | Action::Within(..) | Action::Confirm(_) => continue,
+            Action::Type(text) if text.trim().is_empty() => "nothing (no words left to match)".to_string(),
+            Action::Type(text) => format!("types \"{}\" (an alias changes it)", text.trim()),
+            Action::Builtin(cmd) if phrase.to_lowercase().starts_with(&config.leader.to_lowercase()) => {
+                format!("runs built-in '{}' (it starts with the leader word; drop it)", cmd)
+            }
+            Action::Builtin(cmd) => format!("runs built-in '{}' (listed in leaderless_commands)", cmd),
+            other => format!("runs {:?}", other),
+        };
+        problems.push(Problem::new(Some("commands"), phrase, format!("can never run: saying it {}", instead)));
+    }
+
+    let mut keybinds: Vec<_> = config.keybinds.iter().collect();
+    keybinds.sort();
+    for (phrase, spec) in keybinds {
+        if parse_keybind(&fill_keys(spec, &sample_captures(phrase))).is_none() {
+            problems.push(Problem::new(Some("keybinds"), phrase, format!("unknown key in '{}'", spec)));
+        }
+        let action = parse_action(phrase, &config.leader, &config.leaderless_commands, &Phrases::of(config), &config.aliases);
+        let instead = match action {
+            Action::Keybind(_) => continue,
+            Action::Custom(_) | Action::Within(..) | Action::Confirm(_) => "runs the [commands] entry for it".to_string(),
+            Action::Type(text) if text.trim().is_empty() => "nothing (no words left to match)".to_string(),
+            Action::Type(text) => format!("types \"{}\" (an alias changes it)", text.trim()),
+            Action::Builtin(cmd) => format!("runs built-in '{}'", cmd),
+            other => format!("runs {:?}", other),
+        };
+        problems.push(Problem::new(Some("keybinds"), phrase, format!("can never run: saying it {}", instead)));
+    }
+
+    let mut macros: Vec<_> = config.macros.iter().collect();
+    macros.sort();
+    for (phrase, steps) in macros {
+        let sample = sample_captures(phrase);
+        if let Some(bad) = steps.iter().find(|step| parse_step(&fill_step(step, &sample)).is_none()) {
+            problems.push(Problem::new(
+                Some("macros"),
+                phrase,
+                format!("bad step '{}' (type \"...\", key \"...\", wait <ms>, shell \"...\", type_shell \"...\" or builtin \"...\")", bad),
+            ));
+        }
+        let action = parse_action(phrase, &config.leader, &config.leaderless_commands, &Phrases::of(config), &config.aliases);
+        let instead = match action {
+            Action::Macro(_) => continue,
+            Action::Custom(_) | Action::Within(..) | Action::Confirm(_) => "runs the [commands] entry for it".to_string(),
+            Action::Keybind(_) => "presses the [keybinds] entry for it".to_string(),
+            Action::Type(text) if text.trim().is_empty() => "nothing (no words left to match)".to_string(),
+            Action::Type(text) => format!("types \"{}\" (an alias changes it)", text.trim()),
+            Action::Builtin(cmd) => format!("runs built-in '{}'", cmd),
+            other => format!("runs {:?}", other),
+        };
+        problems.push(Problem::new(Some("macros"), phrase, format!("can never run: saying it {}", instead)));
+    }
+
+    for pattern in &config.patterns {
+        let re = match compile_pattern(&pattern.regex) {
+            Ok(re) => re,
+            Err(e) => {
+                problems.push(Problem::new(Some("patterns"), &pattern.regex, format!("bad regex: {}", regex_error(&e))));
+                continue;
+            }
+        };
+        let set = [!pattern.shell.trim().is_empty(), !pattern.keys.trim().is_empty(), !pattern.steps.is_empty()];
+        if set.iter().filter(|&&s| s).count() != 1 {
+            problems.push(Problem::new(Some("patterns"), &pattern.regex, "needs exactly one of shell, keys or steps".to_string()));
+        }
+        // Every group filled with "1", to see what the keys and steps become
+        let mut sample = Captures::new();
+        for (i, name) in re.capture_names().enumerate() {
+            sample.extend([Some(i.to_string()), name.map(str::to_string)].into_iter().flatten().map(|key| (key, "1".to_string())));
+        }
+        if !pattern.keys.trim().is_empty() && parse_keybind(&fill_keys(&pattern.keys, &sample)).is_none() {
+            problems.push(Problem::new(Some("patterns"), &pattern.regex, format!("unknown key in '{}'", pattern.keys)));
+        }
+        if let Some(bad) = pattern.steps.iter().find(|step| parse_step(&fill_step(step, &sample)).is_none()) {
+            problems.push(Problem::new(Some("patterns"), &pattern.regex, format!("bad step '{}'", bad)));
+        }
+    }
+
+    for replacement in &config.replacements {
+        match compile_replacement(&replacement.regex) {
+            Err(e) => problems.push(Problem::new(Some("replacements"), &replacement.regex, format!("bad regex: {}", regex_error(&e)))),
+            Ok(re) if re.is_match("") => {
+                problems.push(Problem::new(Some("replacements"), &replacement.regex, "can match nothing, which puts `with` between letters".to_string()));
+            }
+            Ok(_) => {}
+        }
+    }
+    problems
+}
+
+/// The last line of a regex error, the one that says what's wrong
+fn regex_error(e: &regex::Error) -> String {
+    e.to_string().lines().last().unwrap_or_default().trim().trim_start_matches("error: ").to_string()
+}
+
+/// Stand-in words for a phrase's `{placeholders}`, to check what they fill in
+fn sample_captures(phrase: &str) -> Captures {
+    let sample = phrase.split_whitespace().map(|word| if word.starts_with('{') { "1" } else { word }).collect::<Vec<_>>();
+    match_phrase(phrase, &sample.join(" ")).unwrap_or_default()
+}
+
+/// Phrases in one table that match the same words once normalized
+fn duplicate_phrases<'a>(table: &str, phrases: impl Iterator<Item = &'a String>) -> Vec<Problem> {
+    let mut groups: BTreeMap<String, Vec<&String>> = BTreeMap::new();
+    for phrase in phrases {
+        groups.entry(normalize_for_matching(phrase)).or_default().push(phrase);
+    }
+    groups
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort();
+            let others: Vec<String> = group[1..].iter().map(|p| format!("\"{}\"", p)).collect();
+            Problem::new(
+                Some(table),
+                group[0],
+                format!("is the same phrase as {} once normalized; which one wins is arbitrary", others.join(", ")),
+            )
+        })
+        .collect()
+}
+
+/// Every problem with a config file, each with its line where there is one
+pub fn check_file(path: &Path) -> Result<Vec<Problem>, ConfigError> {
+    let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read { path: path.to_path_buf(), source })?;
+    let mut table: toml::Table = match toml::from_str(&contents) {
+        Ok(table) => table,
+        Err(e) => return Ok(vec![from_toml_error(&e, &contents)]),
+    };
+    // Wrong types (a string where a number goes...) stop the whole file from loading
+    if let Err(e) = toml::from_str::<Config>(&contents) {
+        return Ok(vec![from_toml_error(&e, &contents)]);
+    }
+
+    merge_includes(&mut table, path)?;
+    let report = migrate(&mut table);
+    let mut problems: Vec<Problem> = unknown_keys(&table)
+        .into_iter()
+        .map(|key| {
+            let message = match suggest(&key) {
+                Some(known) => format!("unknown key (did you mean '{}'?)", known),
+                None => "unknown key (ignored - typo, or renamed in a newer release?)".to_string(),
+            };
+            Problem::new(None, &key, message)
+        })
+        .collect();
+    if report.from_version > CONFIG_VERSION {
+        problems.push(Problem::new(None, "config_version", "is newer than this build understands".to_string()));
+    }
+    match toml::Value::Table(table).try_into::<Config>() {
+        Ok(config) => problems.extend(validate(&config)),
+        Err(e) => problems.push(Problem::new(None, "", e.message().to_string())),
+    }
+
+    for problem in &mut problems {
+        if !problem.key.is_empty() {
+            problem.line = find_line(&contents, problem.table.as_deref(), &problem.key);
+        }
+    }
+    Ok(problems)
+}
+
+fn from_toml_error(e: &toml::de::Error, contents: &str) -> Problem {
+    let line = e.span().and_then(|span| contents.get(..span.start)).map(|before| before.matches('\n').count() + 1);
+    Problem { table: None, key: String::new(), line, message: e.message().to_string() }
+}
+
+/// Closest known key to a misspelled one (at most 2 edits away)
+fn suggest(key: &str) -> Option<String> {
+    let toml::Value::Table(known) = toml::Value::try_from(Config::default()).ok()? else {
+        return None;
+    };
+    known
+        .keys()
+        .map(|k| (edit_distance(key, k), k))
+        .filter(|(d, _)| *d <= 2)
+        .min()
+        .map(|(_, k)| k.clone())
+}
+
+/// Line (1-based) where `key` is set, inside `[table]` if given
+/// For `[[patterns]]`, `key` is the regex and the line is its `match = ...`.
+fn find_line(contents: &str, table: Option<&str>, key: &str) -> Option<usize> {
+    let mut section: Option<String> = None;
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.trim_start_matches('[').split(']').next()) {
+            section = Some(header.trim().to_string());
+            continue;
+        }
+        let Some((name, _)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim().trim_matches(|c| c == '"' || c == '\'');
+        if section.as_deref() != table {
+            continue;
+        }
+        if name == key {
+            return Some(i + 1);
+        }
+        if name == "match"
+            && let Ok(entry) = toml::from_str::<toml::Table>(line)
+            && entry.get("match").and_then(toml::Value::as_str) == Some(key)
+        {
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+/// Problems as `file:line: [table] "key": message`, each followed by its line
+pub fn render(path: &Path, contents: &str, problems: &[Problem]) -> String {
+    let lines: Vec<&str> = contents.lines().collect();
+    let mut out = String::new();
+    for problem in problems {
+        let location = match problem.line {
+            Some(line) => format!("{}:{}", path.display(), line),
+            None => path.display().to_string(),
+        };
+        let subject = match (&problem.table, problem.key.as_str()) {
+            (_, "") => String::new(),
+            (Some(table), key) => format!("[{}] \"{}\": ", table, key),
+            (None, key) => format!("{}: ", key),
+        };
+        let _ = writeln!(out, "{}: {}{}", location, subject, problem.message);
+        if let Some(text) = problem.line.and_then(|line| lines.get(line - 1)) {
+            let _ = writeln!(out, "  {:>4} | {}", problem.line.unwrap_or_default(), text);
+        }
+    }
+    out
+}
+
+/// Log what's wrong with a loaded config (startup and reloads)
+pub fn warn_problems(config: &Config) {
+    for problem in validate(config) {
+        match problem.table {
+            Some(table) => warn!("⚠️ Config: [{}] \"{}\": {}", table, problem.key, problem.message),
+            None => warn!("⚠️ Config: {}: {}", problem.key, problem.message),
+        }
+    }
+}
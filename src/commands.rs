@@ -1,27 +1,52 @@
 //! Command execution for SS9K
 //!
 //! This module handles:
-//! - Voice command parsing and execution
+//! - Voice command parsing (`parse_action`) and execution
 //! - Built-in commands (navigation, editing, media)
 //! - Shift mode (text selection)
 //! - Spell mode (NATO phonetic input)
+//! - Case modes, and sending vim and emacs modes' keystrokes (see `vim`, `emacs`)
 //! - Hold/Release (key holding for gaming/accessibility)
 //! - Custom shell command execution
 
-use anyhow::Result;
-use enigo::{Enigo, Key as EnigoKey, Keyboard, Settings};
+use enigo::{Key as EnigoKey, Keyboard};
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
-use std::sync::Mutex;
-use std::time::Duration;
-
-use crate::lookups::{execute_emoji, execute_punctuation, parse_key_name, word_to_char};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::process::Stdio;
+use std::sync::{mpsc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::control;
+use crate::dates;
+use crate::emacs;
+use crate::fillers;
+use crate::error::OutputError;
+use crate::keyboard::Keys;
+use crate::mouse;
+use crate::lookups::{execute_emoji, execute_punctuation, execute_unicode, parse_key_name, word_to_char};
+use crate::notifications::{self, Kind};
+use crate::numbers;
+use crate::output::{self, OutputSink, Typing};
+use crate::privacy;
+use crate::profanity;
+use crate::security;
+use crate::system;
+use crate::text;
+use crate::timers;
+use crate::tts;
+use crate::vim;
+use crate::config::{CommandSettings, Pattern, Replacement};
+use crate::{Config, ABORTS};
+
+type Result<T> = std::result::Result<T, OutputError>;
 
 // Wrapper for EnigoKey to implement Hash/Eq (using discriminant)
 #[derive(Clone, Debug)]
-pub(crate) struct HeldKey(EnigoKey);
+pub struct HeldKey(EnigoKey);
 
 impl PartialEq for HeldKey {
     fn eq(&self, other: &Self) -> bool {
@@ -38,7 +63,7 @@ impl Hash for HeldKey {
 }
 
 /// Case transformation modes for dictation
-#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum CaseMode {
     #[default]
     Off,         // passthrough
@@ -46,6 +71,8 @@ pub enum CaseMode {
     Camel,       // helloWorld
     Pascal,      // HelloWorld
     Kebab,       // hello-world
+    Dot,         // hello.world
+    Path,        // hello/world
     Screaming,   // HELLO_WORLD
     Caps,        // HELLO WORLD
     Lower,       // hello world
@@ -53,6 +80,8 @@ pub enum CaseMode {
     Code,        // open paren x close paren -> (x)
     Alternating, // aLtErNaTiNg CaPs
     Swearing,    // fuck -> @#$%!
+    Vim,         // delete two words -> d2w, sent as keys
+    Emacs,       // save buffer -> C-x C-s, sent as keys; other text typed
 }
 
 // Statics for command state
@@ -64,33 +93,94 @@ pub static HOLD_THREAD_RUNNING: AtomicBool = AtomicBool::new(false);
 pub static KEY_REPEAT_MS: AtomicU64 = AtomicU64::new(50);
 pub static CURRENT_MODE: std::sync::LazyLock<Mutex<CaseMode>> =
     std::sync::LazyLock::new(|| Mutex::new(CaseMode::Off));
-pub static LAST_TYPED_LEN: AtomicUsize = AtomicUsize::new(0);
+pub static LAST_TYPED: Mutex<Vec<String>> = Mutex::new(Vec::new()); // What the last utterances typed, newest last, for the scratch commands
+pub static LAST_TYPED_TEXT: std::sync::LazyLock<Mutex<String>> =
+    std::sync::LazyLock::new(|| Mutex::new(String::new())); // For "read that"
+pub static DND_FROM_MODE: AtomicBool = AtomicBool::new(false); // True if a dnd_modes mode turned DND on
+static PENDING_CONFIRM: Mutex<Option<(Action, Instant)>> = Mutex::new(None); // Waiting for "yes", until the deadline
+static MODE_REVERTS_EACH_UTTERANCE: AtomicBool = AtomicBool::new(false); // mode_revert = "utterance"
+static MODE_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(0); // 0 = modes stay on
+static MODE_USED: Mutex<Option<Instant>> = Mutex::new(None); // When the case mode was switched to or last typed in
+static PATTERN_REGEXES: std::sync::LazyLock<RwLock<Regexes>> = std::sync::LazyLock::new(Default::default); // [[patterns]] compiled, by source
+static REPLACEMENT_REGEXES: std::sync::LazyLock<RwLock<Regexes>> = std::sync::LazyLock::new(Default::default); // [[replacements]] compiled, by source
+
+/// Compiled regexes by their source; None for one that doesn't compile
+type Regexes = HashMap<String, Option<regex::Regex>>;
+type Compile = fn(&str) -> std::result::Result<regex::Regex, regex::Error>;
+
+/// Apply `mode_revert` and `mode_timeout_secs`, and compile `[[patterns]]`
+/// and `[[replacements]]` (startup and config reloads)
+pub fn configure(config: &Config) {
+    MODE_REVERTS_EACH_UTTERANCE.store(config.mode_revert == "utterance", Ordering::SeqCst);
+    MODE_TIMEOUT_SECS.store(config.mode_timeout_secs, Ordering::SeqCst);
+    let patterns = compile_all(config.patterns.iter().map(|p| p.regex.as_str()), compile_pattern);
+    if let Ok(mut regexes) = PATTERN_REGEXES.write() {
+        *regexes = patterns;
+    }
+    let replacements = compile_all(config.replacements.iter().map(|r| r.regex.as_str()), compile_replacement);
+    if let Ok(mut regexes) = REPLACEMENT_REGEXES.write() {
+        *regexes = replacements;
+    }
+}
+
+/// Compile each regex once (`check::warn_problems` has already warned
+/// about any that don't compile)
+fn compile_all<'a>(sources: impl Iterator<Item = &'a str>, compile: Compile) -> Regexes {
+    sources.map(|source| (source.to_string(), compile(source).ok())).collect()
+}
+
+/// `source` from `cache`, compiled and kept there if `configure` didn't see it
+fn cached(cache: &RwLock<Regexes>, source: &str, compile: Compile) -> Option<regex::Regex> {
+    if let Ok(regexes) = cache.read()
+        && let Some(re) = regexes.get(source)
+    {
+        return re.clone();
+    }
+    let re = compile(source).ok();
+    if let Ok(mut regexes) = cache.write() {
+        regexes.insert(source.to_string(), re.clone());
+    }
+    re
+}
+
+/// Modes that `mode_revert` and `mode_timeout_secs` turn off: all but off and
+/// the editor modes, which are meant to stay on for a whole session
+fn mode_reverts(mode: CaseMode) -> bool {
+    !matches!(mode, CaseMode::Off | CaseMode::Vim | CaseMode::Emacs)
+}
+
+/// Dictation went out in `mode`: with `mode_revert = "utterance"` that's all
+/// it gets, otherwise its `mode_timeout_secs` starts again
+fn mode_used(mode: CaseMode) {
+    if !mode_reverts(mode) {
+        return;
+    }
+    if MODE_REVERTS_EACH_UTTERANCE.load(Ordering::SeqCst) {
+        set_case_mode(CaseMode::Off);
+        info!("🔤 Mode: off (after one utterance in {:?})", mode);
+    } else if let Ok(mut used) = MODE_USED.lock() {
+        *used = Some(Instant::now());
+    }
+}
+
+/// Whether a `confirm` command is waiting for its "yes"
+pub fn confirmation_pending() -> bool {
+    PENDING_CONFIRM.lock().is_ok_and(|p| p.as_ref().is_some_and(|(_, deadline)| Instant::now() < *deadline))
+}
+
+/// The action waiting for confirmation, if it hasn't timed out; clears it either way
+fn take_pending_confirmation() -> Option<Action> {
+    let (action, deadline) = PENDING_CONFIRM.lock().ok()?.take()?;
+    (Instant::now() < deadline).then_some(action)
+}
 
 /// Normalize text by applying aliases (e.g., "e max" -> "emacs")
 /// Preserves original case for non-aliased text (important for languages with meaningful capitals)
 pub fn normalize_aliases(text: &str, aliases: &HashMap<String, String>) -> String {
     let mut result = text.to_string();
-
     for (from, to) in aliases {
-        // Case-insensitive search, but preserve case of non-matched parts
-        let from_lower = from.to_lowercase();
-        let mut new_result = String::with_capacity(result.len());
-        let mut search_start = 0;
-
-        while let Some(pos) = result[search_start..].to_lowercase().find(&from_lower) {
-            let abs_pos = search_start + pos;
-            // Append everything before the match (preserving case)
-            new_result.push_str(&result[search_start..abs_pos]);
-            // Append the replacement
-            new_result.push_str(to);
-            // Move past the matched portion
-            search_start = abs_pos + from.len();
-        }
-        // Append any remaining text after the last match
-        new_result.push_str(&result[search_start..]);
-        result = new_result;
+        result = text::replace_case_insensitive(&result, from, to);
     }
-
     result
 }
 
@@ -119,26 +209,246 @@ pub fn normalize_for_matching(s: &str) -> String {
         .join("")
 }
 
-/// Expand environment variables in a string (e.g., "$TERMINAL" -> "kitty")
-pub fn expand_env_vars(s: &str) -> String {
-    let mut result = s.to_string();
-    while let Some(start) = result.find('$') {
-        let rest = &result[start + 1..];
-        let end = rest
-            .find(|c: char| !c.is_alphanumeric() && c != '_')
-            .unwrap_or(rest.len());
-        let var_name = &rest[..end];
-
-        if var_name.is_empty() {
-            break;
+/// Words a `{name}` placeholder in a command phrase captured, by name
+pub type Captures = Vec<(String, String)>;
+
+/// Whether a command phrase has `{name}` placeholders
+pub fn has_placeholders(phrase: &str) -> bool {
+    phrase.split_whitespace().any(|word| placeholder_name(word).is_some())
+}
+
+/// "{query}" -> "query"
+fn placeholder_name(word: &str) -> Option<&str> {
+    word.strip_prefix('{')?.strip_suffix('}').filter(|name| !name.is_empty() && !name.contains(['{', '}']))
+}
+
+/// Match an utterance against a phrase like "search for {query}"
+/// Literal words match like `normalize_for_matching`; each placeholder takes
+/// one or more words (as many as it can), kept as spoken minus punctuation
+/// at the ends.
+pub fn match_phrase(phrase: &str, spoken: &str) -> Option<Captures> {
+    enum Part<'a> {
+        Literal(String),
+        Capture(&'a str),
+    }
+    let mut parts: Vec<Part> = Vec::new();
+    for word in phrase.split_whitespace() {
+        match (placeholder_name(word), parts.last_mut()) {
+            (Some(name), _) => parts.push(Part::Capture(name)),
+            (None, Some(Part::Literal(literal))) => literal.push_str(&match_key(word)),
+            (None, _) => parts.push(Part::Literal(match_key(word))),
+        }
+    }
+    let words: Vec<(&str, String)> = spoken.split_whitespace().map(|word| (word, match_key(word))).collect();
+
+    fn go(parts: &[Part], words: &[(&str, String)], captures: &mut Captures) -> bool {
+        let Some((part, rest)) = parts.split_first() else {
+            return words.iter().all(|(_, key)| key.is_empty());
+        };
+        match part {
+            Part::Literal(literal) if literal.is_empty() => go(rest, words, captures),
+            Part::Literal(literal) => {
+                let mut joined = String::new();
+                for (i, (_, key)) in words.iter().enumerate() {
+                    joined.push_str(key);
+                    if !literal.starts_with(joined.as_str()) {
+                        return false;
+                    }
+                    if joined == *literal && go(rest, &words[i + 1..], captures) {
+                        return true;
+                    }
+                }
+                false
+            }
+            Part::Capture(name) => (1..=words.len()).rev().any(|n| {
+                let value = words[..n].iter().map(|(word, _)| *word).collect::<Vec<_>>().join(" ");
+                let value = value.trim_matches(['.', ',', '!', '?', ';', ':']);
+                if value.is_empty() {
+                    return false;
+                }
+                captures.push((name.to_string(), value.to_string()));
+                let matched = go(rest, &words[n..], captures);
+                if !matched {
+                    captures.pop();
+                }
+                matched
+            }),
+        }
+    }
+
+    let mut captures = Vec::new();
+    go(&parts, &words, &mut captures).then_some(captures)
+}
+
+/// Compile a `[[patterns]]` regex: case-insensitive, and anchored so it has
+/// to match the whole utterance
+pub fn compile_pattern(regex: &str) -> std::result::Result<regex::Regex, regex::Error> {
+    regex::RegexBuilder::new(&format!("^(?:{})$", regex)).case_insensitive(true).build()
+}
+
+/// Match an utterance against a `[[patterns]]` regex (None if it doesn't
+/// match or doesn't compile)
+/// Regexes are compiled once, on config load, and kept.
+/// Groups are captured as `{1}`, `{2}`... and by name for `(?P<name>...)`,
+/// `{0}` being the whole utterance; a group that took no part is empty.
+/// Punctuation at the ends of the utterance is ignored.
+pub fn match_pattern(regex: &str, spoken: &str) -> Option<Captures> {
+    let spoken = spoken.trim().trim_matches(['.', ',', '!', '?', ';', ':']).trim();
+    let re = cached(&PATTERN_REGEXES, regex, compile_pattern)?;
+    let caps = re.captures(spoken)?;
+    Some(captures_of(&re, &caps))
+}
+
+/// A regex match's groups by number and by name
+fn captures_of(re: &regex::Regex, caps: &regex::Captures) -> Captures {
+    let mut captures: Captures = Vec::new();
+    for (i, name) in re.capture_names().enumerate() {
+        let value = caps.get(i).map(|m| m.as_str().to_string()).unwrap_or_default();
+        if let Some(name) = name {
+            captures.push((name.to_string(), value.clone()));
+        }
+        captures.push((i.to_string(), value));
+    }
+    captures
+}
+
+/// Compile a `[[replacements]]` regex: case-insensitive, matching anywhere
+pub fn compile_replacement(regex: &str) -> std::result::Result<regex::Regex, regex::Error> {
+    regex::RegexBuilder::new(regex).case_insensitive(true).build()
+}
+
+/// Rewrite an utterance with `[[replacements]]`, each in turn on the result
+/// of the last: every match becomes `with`, its `{1}` or `{name}` filled
+/// with the groups. Regexes are compiled once, on config load; entries whose
+/// regex doesn't compile were warned about then and are skipped.
+pub fn apply_replacements(text: &str, replacements: &[Replacement]) -> String {
+    replacements.iter().fold(text.to_string(), |text, replacement| match cached(&REPLACEMENT_REGEXES, &replacement.regex, compile_replacement) {
+        Some(re) => re.replace_all(&text, |caps: &regex::Captures| fill_text(&replacement.with, &captures_of(&re, caps))).into_owned(),
+        None => text,
+    })
+}
+
+/// What a matched `[[patterns]]` entry does, with its captures filled in
+/// None when it has no `shell`, `keys` or `steps`.
+pub fn pattern_action(pattern: &Pattern, captures: &Captures) -> Option<Action> {
+    if !pattern.shell.trim().is_empty() {
+        Some(Action::Custom(fill_shell(&pattern.shell, captures)))
+    } else if !pattern.keys.trim().is_empty() {
+        Some(Action::Keybind(fill_keys(&pattern.keys, captures)))
+    } else if !pattern.steps.is_empty() {
+        Some(Action::Macro(pattern.steps.iter().map(|step| fill_step(step, captures)).collect()))
+    } else {
+        None
+    }
+}
+
+/// One word as `normalize_for_matching` sees it, without punctuation
+fn match_key(word: &str) -> String {
+    normalize_for_matching(&word.chars().filter(|c| c.is_alphanumeric()).collect::<String>())
+}
+
+/// Put captured words into a shell command, quoted so the shell takes them
+/// as plain text wherever the placeholder sits: a bare `{query}` becomes one
+/// quoted word, and inside `'…'` or `"…"` the quotes are closed around it.
+/// One pass over the template, so a capture that says "{other}" stays text.
+pub fn fill_shell(template: &str, captures: &Captures) -> String {
+    let mut result = String::with_capacity(template.len());
+    // The quote we're inside, and where in `result` it was opened
+    let mut open: Option<(char, usize)> = None;
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        if c == '{'
+            && let Some(end) = rest.find('}')
+            && let Some((_, value)) = captures.iter().find(|(name, _)| *name == rest[1..end])
+        {
+            rest = &rest[end + 1..];
+            let quoted = shell_quote(value);
+            match open {
+                None => result.push_str(&quoted),
+                Some((quote, at)) => {
+                    // '{q}' -> 'value' rather than ''"'value'"''
+                    if at + 1 == result.len() {
+                        result.pop();
+                    } else {
+                        result.push(quote);
+                    }
+                    result.push_str(&quoted);
+                    if rest.starts_with(quote) {
+                        rest = &rest[1..];
+                        open = None;
+                    } else {
+                        open = Some((quote, result.len()));
+                        result.push(quote);
+                    }
+                }
+            }
+            continue;
+        }
+
+        rest = &rest[c.len_utf8()..];
+        result.push(c);
+        match (open, c) {
+            (None, '\\') | (Some(('"', _)), '\\') => {
+                // Escaped: the next character is taken as it is
+                if let Some(next) = rest.chars().next() {
+                    result.push(next);
+                    rest = &rest[next.len_utf8()..];
+                }
+            }
+            (None, '\'' | '"') => open = Some((c, result.len() - 1)),
+            (Some((quote, _)), _) if c == quote => open = None,
+            _ => {}
         }
+    }
+    result
+}
+
+/// Put captured words into a key spec: "alt+{n}" with "three" -> "alt+3"
+pub fn fill_keys(template: &str, captures: &Captures) -> String {
+    let mut result = template.to_string();
+    for (name, value) in captures {
+        result = result.replace(&format!("{{{}}}", name), &normalize_for_matching(value));
+    }
+    result
+}
 
-        let value = std::env::var(var_name).unwrap_or_default();
-        result = format!("{}{}{}", &result[..start], value, &rest[end..]);
+/// Put captured words into text to type, as spoken
+pub fn fill_text(template: &str, captures: &Captures) -> String {
+    let mut result = template.to_string();
+    for (name, value) in captures {
+        result = result.replace(&format!("{{{}}}", name), value);
     }
     result
 }
 
+/// Put captured words into a `[macros]` step, quoted to suit its verb
+pub fn fill_step(step: &str, captures: &Captures) -> String {
+    match step.split_whitespace().next().map(str::to_lowercase).as_deref() {
+        Some("shell" | "run" | "type_shell") => fill_shell(step, captures),
+        Some("key") => fill_keys(step, captures),
+        _ => fill_text(step, captures),
+    }
+}
+
+/// Quote spoken words for the shell. `$` is kept out of the quotes as `"$"`
+/// too, so `expand_env_vars` leaves it alone
+#[cfg(not(target_os = "windows"))]
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''").replace('$', r#"'"$"'"#))
+}
+
+/// Quote spoken words for cmd.exe, dropping characters it would act on
+#[cfg(target_os = "windows")]
+fn shell_quote(value: &str) -> String {
+    format!("\"{}\"", value.replace(['"', '%', '^', '&', '|', '<', '>', '$'], ""))
+}
+
+/// Expand environment variables in a string (e.g., "$TERMINAL" -> "kitty")
+/// Unset variables expand to nothing
+pub fn expand_env_vars(s: &str) -> String {
+    text::expand_vars(s, |name| std::env::var(name).ok())
+}
+
 /// Capitalize the first letter of a word
 fn capitalize_word(s: &str) -> String {
     let mut chars = s.chars();
@@ -150,8 +460,16 @@ fn capitalize_word(s: &str) -> String {
 
 /// Apply case transformation based on current mode
 pub fn apply_case_mode(text: &str) -> String {
-    let mode = CURRENT_MODE.lock().map(|m| *m).unwrap_or(CaseMode::Off);
+    let mode = get_case_mode();
+    let output = apply_mode(mode, text);
+    if !text.trim().is_empty() {
+        mode_used(mode);
+    }
+    output
+}
 
+/// Apply case transformation for `mode`
+fn apply_mode(mode: CaseMode, text: &str) -> String {
     if mode == CaseMode::Off {
         return text.to_string();
     }
@@ -173,6 +491,8 @@ pub fn apply_case_mode(text: &str) -> String {
         }
         CaseMode::Pascal => words.iter().map(|w| capitalize_word(&w.to_lowercase())).collect(),
         CaseMode::Kebab => words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("-"),
+        CaseMode::Dot => words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("."),
+        CaseMode::Path => words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join("/"),
         CaseMode::Screaming => words.iter().map(|w| w.to_uppercase()).collect::<Vec<_>>().join("_"),
         CaseMode::Caps => words.iter().map(|w| w.to_uppercase()).collect::<Vec<_>>().join(" "),
         CaseMode::Lower => words.iter().map(|w| w.to_lowercase()).collect::<Vec<_>>().join(" "),
@@ -180,11 +500,13 @@ pub fn apply_case_mode(text: &str) -> String {
         CaseMode::Code => apply_code_mode(text),
         CaseMode::Alternating => apply_alternating_mode(text),
         CaseMode::Swearing => apply_swearing_mode(text),
+        CaseMode::Vim => vim::translate(text).unwrap_or_default(),
+        CaseMode::Emacs => emacs::translate(text).unwrap_or_else(|| text.to_string()),
     }
 }
 
 /// Strip punctuation from a word for matching (keeps the word itself clean)
-fn strip_punct(word: &str) -> String {
+pub(crate) fn strip_punct(word: &str) -> String {
     word.chars()
         .filter(|c| c.is_alphanumeric())
         .collect::<String>()
@@ -269,31 +591,20 @@ pub fn apply_math_mode(text: &str) -> String {
             }
         }
 
+        // Numbers: "one hundred twenty three", "three point one four", "negative seven",
+        // "three quarters" (3/4), "third" (3)
+        if let Some(number) = numbers::parse_prefix(&clean[i..].iter().map(String::as_str).collect::<Vec<_>>()) {
+            result.push(number.math());
+            i += number.words;
+            continue;
+        }
+
         // Single words (use pre-cleaned version)
         let word = &clean[i];
         let converted = match word.as_str() {
-            // Numbers 0-20
-            "zero" => "0",
-            "one" => "1",
-            "two" | "to" | "too" => "2",
-            "three" => "3",
-            "four" | "for" => "4",
-            "five" => "5",
-            "six" => "6",
-            "seven" => "7",
-            "eight" => "8",
-            "nine" => "9",
-            "ten" => "10",
-            "eleven" => "11",
-            "twelve" => "12",
-            "thirteen" => "13",
-            "fourteen" => "14",
-            "fifteen" => "15",
-            "sixteen" => "16",
-            "seventeen" => "17",
-            "eighteen" => "18",
-            "nineteen" => "19",
-            "twenty" => "20",
+            // Whisper's homophones for numbers
+            "to" | "too" => "2",
+            "for" => "4",
 
             // Operators
             "plus" | "add" => "+",
@@ -467,39 +778,9 @@ pub fn apply_alternating_mode(text: &str) -> String {
         .collect()
 }
 
-/// Apply swearing mode: replaces swear words with grawlix (@#$%!)
+/// Apply swearing mode: censors swear words (@#$%! by default, see `profanity`)
 pub fn apply_swearing_mode(text: &str) -> String {
-    let grawlix = ["@", "#", "$", "%", "!", "&", "*"];
-    let swears = [
-        "fuck", "fucking", "fucked", "fucker", "fucks",
-        "shit", "shitting", "shitty", "shits",
-        "damn", "damned", "dammit",
-        "ass", "asses", "asshole", "assholes",
-        "bitch", "bitches", "bitchy",
-        "crap", "crappy",
-        "hell", "heck",
-        "bastard", "bastards",
-        "dick", "dicks",
-        "piss", "pissed", "pissing",
-        "cock", "cocks",
-        "cunt", "cunts",
-    ];
-
-    let words: Vec<&str> = text.split_whitespace().collect();
-    let result: Vec<String> = words.iter().map(|word| {
-        let lower = word.to_lowercase();
-        let clean = lower.trim_matches(|c: char| !c.is_alphanumeric());
-
-        if swears.contains(&clean) {
-            // Generate grawlix of similar length
-            let len = word.len().max(3).min(7);
-            (0..len).map(|i| grawlix[i % grawlix.len()]).collect()
-        } else {
-            word.to_string()
-        }
-    }).collect();
-
-    result.join(" ")
+    profanity::censor(text)
 }
 
 /// Set the current case mode
@@ -507,11 +788,23 @@ pub fn set_case_mode(mode: CaseMode) {
     if let Ok(mut current) = CURRENT_MODE.lock() {
         *current = mode;
     }
+    if let Ok(mut used) = MODE_USED.lock() {
+        *used = Some(Instant::now());
+    }
 }
 
 /// Get the current case mode
+/// One that's gone unused for `mode_timeout_secs` is switched off first.
 pub fn get_case_mode() -> CaseMode {
-    CURRENT_MODE.lock().map(|m| *m).unwrap_or(CaseMode::Off)
+    let mode = CURRENT_MODE.lock().map(|m| *m).unwrap_or(CaseMode::Off);
+    let timeout = Duration::from_secs(MODE_TIMEOUT_SECS.load(Ordering::SeqCst));
+    let unused = MODE_USED.lock().ok().and_then(|used| *used).is_some_and(|used| used.elapsed() >= timeout);
+    if !timeout.is_zero() && mode_reverts(mode) && unused {
+        set_case_mode(CaseMode::Off);
+        info!("🔤 Mode: off ({:?} unused for {}s)", mode, timeout.as_secs());
+        return CaseMode::Off;
+    }
+    mode
 }
 
 /// Parse a mode name into CaseMode
@@ -522,6 +815,8 @@ pub fn parse_mode_name(name: &str) -> Option<CaseMode> {
         "camel" => Some(CaseMode::Camel),
         "pascal" => Some(CaseMode::Pascal),
         "kebab" | "kebob" => Some(CaseMode::Kebab),
+        "dot" | "dotted" => Some(CaseMode::Dot),
+        "path" | "slash" | "slashes" => Some(CaseMode::Path),
         "screaming" | "scream" | "yelling" | "yell" => Some(CaseMode::Screaming),
         "caps" | "upper" | "uppercase" | "capital" | "capitals" => Some(CaseMode::Caps),
         "lower" | "lowercase" => Some(CaseMode::Lower),
@@ -529,6 +824,8 @@ pub fn parse_mode_name(name: &str) -> Option<CaseMode> {
         "code" | "coding" | "programming" | "symbols" => Some(CaseMode::Code),
         "alternating" | "alternate" | "spongebob" | "mocking" => Some(CaseMode::Alternating),
         "swearing" | "swear" | "grawlix" | "censored" | "censor" => Some(CaseMode::Swearing),
+        "vim" | "vi" | "neovim" => Some(CaseMode::Vim),
+        "emacs" => Some(CaseMode::Emacs),
         _ => None,
     }
 }
@@ -544,6 +841,8 @@ pub fn execute_mode(mode_name: &str) -> Result<bool> {
                 CaseMode::Camel => "camelCase",
                 CaseMode::Pascal => "PascalCase",
                 CaseMode::Kebab => "kebab-case",
+                CaseMode::Dot => "dot.case",
+                CaseMode::Path => "path/case",
                 CaseMode::Screaming => "SCREAMING_SNAKE_CASE",
                 CaseMode::Caps => "CAPS LOCK",
                 CaseMode::Lower => "lowercase",
@@ -551,49 +850,38 @@ pub fn execute_mode(mode_name: &str) -> Result<bool> {
                 CaseMode::Code => "code (open paren → ()",
                 CaseMode::Alternating => "aLtErNaTiNg CaPs",
                 CaseMode::Swearing => "swearing (fuck → @#$%!)",
+                CaseMode::Vim => "vim (delete two words → d2w)",
+                CaseMode::Emacs => "emacs (save buffer → C-x C-s)",
             };
-            println!("[SS9K] 🔤 Mode: {}", mode_str);
+            info!("🔤 Mode: {}", mode_str);
+            tts::say(&format!("mode {}", mode_name));
             Ok(true)
         }
         None => {
-            eprintln!("[SS9K] ⚠️ Unknown mode: {}", mode_name);
-            eprintln!("[SS9K] Available: off, snake, camel, pascal, kebab, screaming, caps, lower, math, code, alternating, swearing");
+            warn!("⚠️ Unknown mode: {}", mode_name);
+            warn!("Available: off, snake, camel, pascal, kebab, dot, path, screaming, caps, lower, math, code, alternating, swearing, vim, emacs");
             Ok(false)
         }
     }
 }
 
 /// Execute a custom shell command
-pub fn execute_custom_command(cmd: &str) -> Result<()> {
+pub fn execute_custom_command(cmd: &str, context: &ShellContext) -> Result<()> {
     let expanded = expand_env_vars(cmd);
 
     if expanded.trim().is_empty() {
-        eprintln!("[SS9K] ⚠️ Command expanded to empty string (check env vars): {}", cmd);
+        warn!("⚠️ Command expanded to empty string (check env vars): {}", privacy::redact(cmd));
         return Ok(());
     }
 
-    println!("[SS9K] 🚀 Executing: {}", expanded);
+    info!("🚀 Executing: {}", privacy::redact(&expanded));
 
-    let cmd_owned = expanded.to_string();
+    let mut command = security::command(&expanded)?;
+    context.apply(&mut command);
     std::thread::spawn(move || {
         #[cfg(target_os = "windows")]
-        let result = std::process::Command::new("cmd")
-            .args(["/C", &cmd_owned])
-            .stdout(std::process::Stdio::null())
-            .stderr(std::process::Stdio::null())
-            .spawn();
-
-        #[cfg(not(target_os = "windows"))]
-        let result = {
-            let parts: Vec<&str> = cmd_owned.split_whitespace().collect();
-            if parts.len() == 1 && !cmd_owned.contains(['|', '&', ';', '>', '<', '$', '`', '(', ')']) {
-                std::process::Command::new(&cmd_owned).spawn()
-            } else {
-                std::process::Command::new("sh")
-                    .args(["-c", &cmd_owned])
-                    .spawn()
-            }
-        };
+        command.stdout(std::process::Stdio::null()).stderr(std::process::Stdio::null());
+        let result = command.spawn();
 
         match result {
             Ok(mut child) => {
@@ -601,27 +889,270 @@ pub fn execute_custom_command(cmd: &str) -> Result<()> {
                 match child.try_wait() {
                     Ok(Some(status)) => {
                         if !status.success() {
-                            eprintln!("[SS9K] ⚠️ Command exited with: {}", status);
+                            warn!("⚠️ Command exited with: {}", status);
                         }
                     }
-                    Ok(None) => println!("[SS9K] ✅ Command running"),
-                    Err(e) => eprintln!("[SS9K] ❌ Error checking command: {}", e),
+                    Ok(None) => info!("✅ Command running"),
+                    Err(e) => error!("❌ Error checking command: {}", e),
                 }
             }
-            Err(e) => eprintln!("[SS9K] ❌ Failed to spawn: {}", e),
+            Err(e) => error!("❌ Failed to spawn: {}", e),
+        }
+    });
+
+    Ok(())
+}
+
+/// How long a command whose output is typed or filled in gets to finish
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Run a shell command to completion and return its stdout, trailing
+/// newlines removed (None if it couldn't start)
+/// It's killed if it runs past `CAPTURE_TIMEOUT`, or when the abort hotkey
+/// is pressed, and gives None then too.
+pub fn capture_shell(cmd: &str, context: &ShellContext) -> Option<String> {
+    let mut command = match security::command(cmd) {
+        Ok(command) => command,
+        Err(e) => {
+            warn!("⚠️ {}", e);
+            return None;
+        }
+    };
+    context.apply(&mut command);
+    command.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) => {
+            warn!("⚠️ Shell command failed: {}", e);
+            return None;
+        }
+    };
+
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let started = Instant::now();
+    let aborts = ABORTS.load(Ordering::SeqCst);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= CAPTURE_TIMEOUT || ABORTS.load(Ordering::SeqCst) != aborts => {
+                let _ = child.kill();
+                let _ = child.wait();
+                warn!("⚠️ Stopped '{}' after {} ms", privacy::redact(cmd), started.elapsed().as_millis());
+                return None;
+            }
+            Ok(None) => std::thread::sleep(WAIT_SLICE),
+            Err(e) => {
+                warn!("⚠️ Shell command failed: {}", e);
+                return None;
+            }
         }
+    };
+
+    // Something it started in the background can hold the pipes open after it exits
+    let left = CAPTURE_TIMEOUT.saturating_sub(started.elapsed());
+    let out = stdout.recv_timeout(left).unwrap_or_default();
+    if !status.success() {
+        let err = stderr.recv_timeout(WAIT_SLICE).unwrap_or_default();
+        warn!("⚠️ '{}' exited with {}: {}", privacy::redact(cmd), status, String::from_utf8_lossy(&err).trim());
+    }
+    Some(String::from_utf8_lossy(&out).trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Everything a child writes to `pipe`, read on its own thread so a chatty
+/// command can't fill one pipe and stall while we wait on it
+fn read_pipe(pipe: Option<impl std::io::Read + Send + 'static>) -> mpsc::Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        let _ = sender.send(bytes);
     });
+    receiver
+}
+
+/// Run a shell command and type what it prints at the cursor (no case mode)
+fn type_shell_output(enigo: &mut Keys, cmd: &str, context: &ShellContext) -> Result<bool> {
+    let expanded = expand_env_vars(cmd);
+    info!("🚀 Executing for output: {}", privacy::redact(&expanded));
+    let Some(output) = capture_shell(&expanded, context) else {
+        return Ok(false);
+    };
+    if output.is_empty() {
+        warn!("⚠️ '{}' printed nothing", privacy::redact(&expanded));
+        return Ok(false);
+    }
+    enigo.text(&output)?;
+    output::typed(&output);
+    remember_typed(&output);
+    info!("📋 Typed output of '{}': {}", privacy::redact(&expanded), privacy::redact(&output));
+    Ok(true)
+}
+
+/// Something a script hook, plugin or `[macros]` entry asked for, done in order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Key(String),     // "ctrl+shift+t", or chords in a row like [keybinds]
+    Type(String),    // Typed with the case mode applied
+    Run(String),     // Shell command, like a [commands] entry
+    TypeShell(String), // Shell command whose output is typed
+    Builtin(String), // Words after the leader: "enter times 3"
+    Wait(u64),       // Milliseconds ("wait 200" or "wait 2 seconds"), for a window to open before typing into it
+}
+
+/// One piece of what an editor mode (vim, emacs) sends for a phrase
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Keystroke {
+    Text(String),  // Typed as-is: "d2w", ":wq"
+    Chord(String), // Pressed like a [keybinds] value: "escape", "ctrl+r"
+}
+
+/// Parse a `[macros]` step: `type "..."`, `key "ctrl+c"`, `wait 200` (ms) or `wait 2 seconds`,
+/// `shell "..."`, `type_shell "date +%F"` or `builtin "enter"` (quotes optional)
+pub fn parse_step(s: &str) -> Option<Step> {
+    let (verb, arg) = s.trim().split_once(char::is_whitespace).unwrap_or((s.trim(), ""));
+    let arg = arg.trim();
+    let arg = arg.strip_prefix('"').and_then(|a| a.strip_suffix('"')).unwrap_or(arg).to_string();
+    match verb.to_lowercase().as_str() {
+        "type" if !arg.is_empty() => Some(Step::Type(arg)),
+        "key" => parse_keybind(&arg).map(|_| Step::Key(arg)),
+        "wait" => arg.parse().ok().or_else(|| parse_wait(&arg.to_lowercase())).map(Step::Wait),
+        "shell" | "run" if !arg.is_empty() => Some(Step::Run(arg)),
+        "type_shell" if !arg.is_empty() => Some(Step::TypeShell(arg)),
+        "builtin" if !arg.is_empty() => Some(Step::Builtin(arg.to_lowercase())),
+        _ => None,
+    }
+}
+
+/// Do what a script hook or plugin asked for, in order
+pub fn perform_steps(steps: &[Step]) -> Result<()> {
+    let mut enigo = Keys::new()?;
+    run_steps(&mut enigo, steps)
+}
+
+/// Run steps on an existing keyboard
+fn run_steps(enigo: &mut Keys, steps: &[Step]) -> Result<()> {
+    for step in steps {
+        match step {
+            Step::Key(spec) => press_keybind(enigo, spec)?,
+            Step::Type(text) => type_text(enigo, text)?,
+            Step::Run(cmd) => execute_custom_command(cmd, &ShellContext::default())?,
+            Step::TypeShell(cmd) => {
+                type_shell_output(enigo, cmd, &ShellContext::default())?;
+            }
+            Step::Builtin(cmd) => {
+                if !execute_builtin_command(enigo, cmd)? {
+                    warn!("⚠️ Unknown built-in '{}'", privacy::redact(cmd));
+                }
+            }
+            Step::Wait(ms) => {
+                if !wait(*ms) {
+                    info!("🛑 Aborted, skipping the rest of the steps");
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Longest a "wait" phrase or step pauses for; dictation and commands are
+/// on hold until it's over
+const MAX_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// How often a wait checks for the abort hotkey
+const WAIT_SLICE: Duration = Duration::from_millis(50);
+
+/// Pause for a "wait" phrase or step, at most `MAX_WAIT`; false if the
+/// abort hotkey cut it short
+fn wait(ms: u64) -> bool {
+    let mut length = Duration::from_millis(ms);
+    if length > MAX_WAIT {
+        warn!("⚠️ Waiting {} s instead of {} s, the longest wait", MAX_WAIT.as_secs(), length.as_secs());
+        length = MAX_WAIT;
+    }
+    info!("⏳ Waiting {} ms", length.as_millis());
+    let aborts = ABORTS.load(Ordering::SeqCst);
+    let deadline = Instant::now() + length;
+    loop {
+        if ABORTS.load(Ordering::SeqCst) != aborts {
+            return false;
+        }
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(WAIT_SLICE));
+    }
+}
+
+/// Run a `[macros]` entry; `type` steps get insert placeholders ({date}, {shell:...})
+fn execute_macro(enigo: &mut Keys, lines: &[String]) -> Result<()> {
+    let mut steps = Vec::with_capacity(lines.len());
+    for line in lines {
+        match parse_step(line) {
+            Some(Step::Type(text)) => steps.push(Step::Type(expand_placeholders(&text))),
+            Some(step) => steps.push(step),
+            None => {
+                warn!("⚠️ Bad macro step '{}', not running the macro", line);
+                return Ok(());
+            }
+        }
+    }
+    run_steps(enigo, &steps)
+}
+
+/// Parse "ctrl+shift+t" into its keys (None if any is unknown)
+pub fn parse_combo(combo: &str) -> Option<Vec<EnigoKey>> {
+    let keys = combo.split('+').map(|name| parse_key_name(name.trim())).collect::<Option<Vec<_>>>()?;
+    (!keys.is_empty()).then_some(keys)
+}
+
+/// Parse a `[keybinds]` value: one chord or several in a row, "ctrl+k ctrl+c"
+pub fn parse_keybind(spec: &str) -> Option<Vec<Vec<EnigoKey>>> {
+    let chords = spec.split_whitespace().map(parse_combo).collect::<Option<Vec<_>>>()?;
+    (!chords.is_empty()).then_some(chords)
+}
 
+/// Press each chord of a `[keybinds]` value in turn
+fn press_keybind(enigo: &mut Keys, spec: &str) -> Result<()> {
+    let Some(chords) = parse_keybind(spec) else {
+        warn!("⚠️ Unknown key in '{}'", spec);
+        return Ok(());
+    };
+    for keys in &chords {
+        press_keys(enigo, keys)?;
+    }
     Ok(())
 }
 
+/// All but the last key held, the last one clicked, then the rest released in reverse
+fn press_keys(enigo: &mut Keys, keys: &[EnigoKey]) -> Result<()> {
+    let Some((last, modifiers)) = keys.split_last() else {
+        return Ok(());
+    };
+    for key in modifiers {
+        enigo.key(*key, enigo::Direction::Press)?;
+    }
+    let clicked = enigo.key(*last, enigo::Direction::Click);
+    for key in modifiers.iter().rev() {
+        enigo.key(*key, enigo::Direction::Release)?;
+    }
+    Ok(clicked?)
+}
+
 /// Expand placeholders in insert text
 /// {date} → 2026-01-17
 /// {time} → 13:52
 /// {datetime} → 2026-01-17 13:52
 /// {timestamp} → Unix timestamp
 /// {iso} → ISO 8601 format
+/// {date:tomorrow}, {time:in 2 hours}, {datetime:next friday at 9 am} → a spoken date (see `dates`)
 /// {shell:command} → output of shell command
+/// {clipboard} → what the clipboard holds
 fn expand_placeholders(text: &str) -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -639,27 +1170,22 @@ fn expand_placeholders(text: &str) -> String {
         }
     }
 
-    // Expand {shell:command} placeholders
-    while let Some(start) = result.find("{shell:") {
-        if let Some(end) = result[start..].find('}') {
-            let end = start + end;
-            let cmd = &result[start + 7..end]; // skip "{shell:"
-            let output = match std::process::Command::new("sh")
-                .args(["-c", cmd])
-                .output()
-            {
-                Ok(out) => String::from_utf8_lossy(&out.stdout).trim().to_string(),
-                Err(e) => {
-                    eprintln!("[SS9K] ⚠️ Shell command failed: {}", e);
-                    String::new()
-                }
-            };
-            result = format!("{}{}{}", &result[..start], output, &result[end + 1..]);
-        } else {
-            break; // malformed placeholder, stop
-        }
+    // Spoken dates; one that doesn't parse is kept as it is
+    for (name, format) in dates::FORMATS {
+        result = text::expand_placeholder(&result, &format!("{}:", name), |phrase| {
+            match dates::parse(phrase, now.naive_local()) {
+                Some(when) => when.format(format).to_string(),
+                None => format!("{{{}:{}}}", name, phrase),
+            }
+        });
     }
 
+    // Expand {shell:command} placeholders
+    result = text::expand_placeholder(&result, "shell:", |cmd| {
+        capture_shell(cmd, &ShellContext::default()).map(|out| out.trim().to_string()).unwrap_or_default()
+    });
+    result = expand_clipboard(&result);
+
     // Handle escaped newlines
     result = result.replace("\\n", "\n");
     result = result.replace("\\t", "\t");
@@ -667,121 +1193,812 @@ fn expand_placeholders(text: &str) -> String {
     result
 }
 
-/// Execute a voice command or type the text
-/// Uses a configurable leader word (default "command") to trigger commands
-/// Everything goes through the leader: "command enter", "command emoji smile", "command punctuation comma"
-/// Returns true if a command was executed, false if text was typed
-pub fn execute_command(
-    enigo: &mut Enigo,
-    text: &str,
-    leader: &str,
-    custom_commands: &HashMap<String, String>,
-    aliases: &HashMap<String, String>,
-    inserts: &HashMap<String, String>,
-    wrappers: &HashMap<String, String>,
-) -> Result<bool> {
-    let aliased = normalize_aliases(text, aliases);
-
-    let trimmed: String = aliased
-        .trim()
-        .chars()
-        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
-        .collect::<String>()
-        .to_lowercase();
+/// `{clipboard}` → what the clipboard holds ("" when it's empty or not text)
+fn expand_clipboard(text: &str) -> String {
+    if !text.contains("{clipboard}") {
+        return text.to_string();
+    }
+    text.replace("{clipboard}", &output::clipboard_text().unwrap_or_default())
+}
 
-    // Build the leader prefix (e.g., "command ")
-    let leader_prefix = format!("{} ", leader.to_lowercase());
+/// What an utterance resolves to, before anything is executed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// "command emoji <name>"
+    Emoji(String),
+    /// "command punctuation <name>" / "command punk <name>"
+    Punctuation(String),
+    /// "command insert <name>"
+    Insert(String),
+    /// "command wrap <name> <text>"
+    Wrap { name: String, text: String },
+    /// "command to <sink> <text>": dictation for one output sink (case and
+    /// punctuation kept)
+    Output { sink: String, text: String },
+    /// "command <mode> <text>": dictation in a case mode, this once; the
+    /// current mode stays as it is
+    Cased { mode: CaseMode, text: String },
+    /// "command correct <wrong> to <right>": swap a misheard word in what the
+    /// last utterance typed
+    Correct { wrong: String, right: String },
+    /// Any other "command ..." (navigation, editing, media, modes, ...)
+    Builtin(String),
+    /// A custom command phrase matched; holds the shell command to run
+    Custom(String),
+    /// "command run and type <phrase>": holds the [commands] entry for the
+    /// phrase, whose output gets typed
+    TypeShell(String),
+    /// A `[keybinds]` phrase matched; holds the chords to press
+    Keybind(String),
+    /// A `[macros]` phrase matched; holds its steps, unparsed
+    Macro(Vec<String>),
+    /// A `[command_settings]` `confirm` entry matched: asks first, runs the
+    /// action on a "yes"
+    Confirm(Box<Action>),
+    /// A `[commands]` entry with a `cwd` or `env` in `[command_settings]`:
+    /// the shell command in the action runs there
+    Within(ShellContext, Box<Action>),
+    /// "yes" (true) or "no" (false) while a confirmation is waiting
+    Answer(bool),
+    /// A number, "click" or "cancel" while the mouse grid is up
+    Grid(mouse::GridStep),
+    /// Plain dictation (aliases applied, case mode not yet applied)
+    Type(String),
+}
 
-    // Check if input starts with the leader word
-    if let Some(after_leader) = trimmed.strip_prefix(&leader_prefix) {
-        let cmd = after_leader.trim();
+/// Where a `[commands]` entry runs and what extra environment it gets, from
+/// its `[command_settings]`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ShellContext {
+    pub cwd: String,
+    pub env: Vec<(String, String)>, // Sorted by name
+}
 
-        // Check for emoji subcommand
-        if let Some(emoji_name) = cmd.strip_prefix("emoji ") {
-            return execute_emoji(enigo, emoji_name.trim());
-        }
+impl ShellContext {
+    pub fn of(settings: &CommandSettings) -> Self {
+        let mut env: Vec<(String, String)> = settings.env.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        env.sort();
+        Self { cwd: settings.cwd.clone(), env }
+    }
 
-        // Check for punctuation subcommand
-        if let Some(punct) = cmd.strip_prefix("punctuation ").or_else(|| cmd.strip_prefix("punk ")) {
-            return execute_punctuation(enigo, punct.trim());
+    /// The directory to run in, `~` and $VARS expanded (None = ss9k's own)
+    pub fn dir(&self) -> Option<PathBuf> {
+        if self.cwd.trim().is_empty() {
+            return None;
         }
+        Some(PathBuf::from(shellexpand::tilde(&expand_env_vars(self.cwd.trim())).as_ref()))
+    }
 
-        // Check for insert subcommand
-        if let Some(insert_name) = cmd.strip_prefix("insert ") {
-            let name = insert_name.trim();
-            if let Some(template) = inserts.get(name) {
-                let expanded = expand_placeholders(template);
-                enigo.text(&expanded)?;
-                LAST_TYPED_LEN.store(expanded.chars().count(), Ordering::SeqCst);
-                println!("[SS9K] 📋 Inserted '{}': {}", name, expanded.chars().take(50).collect::<String>());
-                return Ok(true);
-            } else {
-                eprintln!("[SS9K] ⚠️ Unknown insert: '{}'", name);
-                eprintln!("[SS9K] Available: {:?}", inserts.keys().collect::<Vec<_>>());
-                return Ok(false);
-            }
+    /// Set the directory and environment on a command about to start
+    fn apply(&self, command: &mut std::process::Command) {
+        if let Some(dir) = self.dir() {
+            command.current_dir(dir);
         }
+        command.envs(self.env.iter().map(|(k, v)| (k, expand_env_vars(v))));
+    }
+}
 
-        // Check for wrap subcommand: "wrap <name> <text>"
-        if let Some(wrap_rest) = cmd.strip_prefix("wrap ") {
-            let parts: Vec<&str> = wrap_rest.splitn(2, ' ').collect();
-            if parts.is_empty() {
-                eprintln!("[SS9K] ⚠️ Wrap requires a name and text: 'command wrap quotes hello'");
-                return Ok(false);
-            }
-            let wrapper_name = parts[0].trim();
-            let wrap_text = if parts.len() > 1 { parts[1].trim() } else { "" };
+/// How near a miss of a phrase still runs it (`fuzzy_match`), with the
+/// similarity needed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fuzzy {
+    Off,
+    Levenshtein(f64),
+    JaroWinkler(f64),
+}
 
-            if let Some(wrapper) = wrappers.get(wrapper_name) {
-                let (left, right) = if let Some(idx) = wrapper.find('|') {
-                    (&wrapper[..idx], &wrapper[idx + 1..])
-                } else {
-                    (wrapper.as_str(), wrapper.as_str())
-                };
-                let wrapped = format!("{}{}{}", left, wrap_text, right);
-                enigo.text(&wrapped)?;
-                LAST_TYPED_LEN.store(wrapped.chars().count(), Ordering::SeqCst);
-                println!("[SS9K] 🎁 Wrapped '{}': {}", wrapper_name, wrapped);
-                return Ok(true);
-            } else {
-                eprintln!("[SS9K] ⚠️ Unknown wrapper: '{}'", wrapper_name);
-                eprintln!("[SS9K] Available: {:?}", wrappers.keys().collect::<Vec<_>>());
-                return Ok(false);
-            }
+impl Fuzzy {
+    /// From `fuzzy_match` and `fuzzy_threshold`; unknown names are Off
+    pub fn of(config: &Config) -> Self {
+        match config.fuzzy_match.to_lowercase().as_str() {
+            "levenshtein" => Self::Levenshtein(config.fuzzy_threshold),
+            "jaro-winkler" | "jaro_winkler" => Self::JaroWinkler(config.fuzzy_threshold),
+            _ => Self::Off,
         }
-
-        // Otherwise it's a builtin command
-        return execute_builtin_command(enigo, cmd);
     }
 
-    // Check custom commands (these work without the leader word)
-    let normalized_input = normalize_for_matching(&trimmed);
-    for (phrase, cmd) in custom_commands {
-        if normalized_input == normalize_for_matching(phrase) {
-            execute_custom_command(cmd)?;
-            return Ok(true);
-        }
+    /// How similar two normalized phrases are, if it's enough
+    fn score(self, a: &str, b: &str) -> Option<f64> {
+        let (score, threshold) = match self {
+            Self::Off => return None,
+            Self::Levenshtein(threshold) => (text::levenshtein_similarity(a, b), threshold),
+            Self::JaroWinkler(threshold) => (text::jaro_winkler(a, b), threshold),
+        };
+        (score >= threshold).then_some(score)
     }
+}
 
-    // Default: type the text with case mode applied
-    let output = apply_case_mode(&aliased);
-    enigo.text(&output)?;
+/// The phrase tables `parse_action` matches against
+pub struct Phrases<'a> {
+    pub commands: &'a HashMap<String, String>,
+    pub keybinds: &'a HashMap<String, String>,
+    pub macros: &'a HashMap<String, Vec<String>>,
+    pub patterns: &'a [Pattern],
+    pub replacements: &'a [Replacement], // [[replacements]]: rewrite the utterance before anything matches it
+    pub fuzzy: Fuzzy,
+    pub phonetic: bool, // Also match phrases and built-ins that sound the same
+    pub priorities: &'a HashMap<String, i64>, // [priorities]: phrase -> rank when several match
+    pub settings: &'a HashMap<String, CommandSettings>, // [command_settings]: confirm, ...
+    pub command_aliases: &'a HashMap<String, String>, // [command_aliases]: after the leader, phrase -> built-ins
+}
+
+impl<'a> Phrases<'a> {
+    /// The tables in `config`
+    pub fn of(config: &'a Config) -> Self {
+        Self {
+            commands: &config.commands,
+            keybinds: &config.keybinds,
+            macros: &config.macros,
+            patterns: &config.patterns,
+            replacements: &config.replacements,
+            fuzzy: Fuzzy::of(config),
+            phonetic: config.phonetic_match,
+            priorities: &config.priorities,
+            settings: &config.command_settings,
+            command_aliases: &config.command_aliases,
+        }
+    }
+}
+
+/// The leaderless table a phrase is in, in the order ties between tables go
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Table {
+    Commands,
+    Keybinds,
+    Macros,
+}
+
+impl Phrases<'_> {
+    /// Every leaderless phrase, with its table
+    fn all(&self) -> impl Iterator<Item = (&String, Table)> {
+        let commands = self.commands.keys().map(|p| (p, Table::Commands));
+        let keybinds = self.keybinds.keys().map(|p| (p, Table::Keybinds));
+        let macros = self.macros.keys().map(|p| (p, Table::Macros));
+        commands.chain(keybinds).chain(macros)
+    }
+
+    /// How a matching phrase ranks against others that match too: higher
+    /// `[priorities]` first, then more literal words (so "search code for
+    /// {q}" beats "search {q}"), then table order, then alphabetical
+    fn rank<'p>(&self, phrase: &'p str, table: Table) -> (i64, usize, Reverse<Table>, Reverse<&'p str>) {
+        let priority = self.priorities.get(phrase).copied().unwrap_or(0);
+        let literal = phrase.split_whitespace().filter(|w| placeholder_name(w).is_none()).map(|w| match_key(w).len()).sum();
+        (priority, literal, Reverse(table), Reverse(phrase))
+    }
+
+    /// What a phrase does, with its captures filled in
+    fn action(&self, phrase: &str, table: Table, captures: &Captures) -> Action {
+        match table {
+            Table::Commands => {
+                let mut action = Action::Custom(fill_shell(&self.commands[phrase], captures));
+                if let Some(settings) = self.settings.get(phrase) {
+                    let context = ShellContext::of(settings);
+                    if context != ShellContext::default() {
+                        action = Action::Within(context, Box::new(action));
+                    }
+                    if settings.confirm {
+                        action = Action::Confirm(Box::new(action));
+                    }
+                }
+                action
+            }
+            Table::Keybinds => Action::Keybind(fill_keys(&self.keybinds[phrase], captures)),
+            Table::Macros => Action::Macro(self.macros[phrase].iter().map(|step| fill_step(step, captures)).collect()),
+        }
+    }
+
+    /// The best-ranked phrase `matches` accepts, as an action
+    fn best(&self, matches: impl Fn(&str, Table) -> Option<Captures>) -> Option<Action> {
+        let (phrase, table, captures) = self
+            .all()
+            .filter_map(|(phrase, table)| matches(phrase, table).map(|captures| (phrase, table, captures)))
+            .max_by(|(a, at, _), (b, bt, _)| self.rank(a, *at).cmp(&self.rank(b, *bt)))?;
+        Some(self.action(phrase, table, &captures))
+    }
+}
+
+/// The built-ins a `[command_aliases]` phrase stands for, "times N" kept:
+/// "zap times three" -> ["backspace times 3"]; a list repeats as a whole
+fn command_alias(cmd: &str, aliases: &HashMap<String, String>) -> Option<Vec<String>> {
+    let (base, count) = parse_times_suffix(cmd);
+    let wanted = normalize_for_matching(base);
+    let (_, target) = aliases.iter().filter(|(phrase, _)| normalize_for_matching(phrase) == wanted).min()?;
+    let builtins: Vec<String> = target.split(',').map(|b| b.trim().to_lowercase()).filter(|b| !b.is_empty()).collect();
+    Some(match builtins.as_slice() {
+        [builtin] if count > 0 => vec![format!("{} times {}", builtin, count)],
+        _ => builtins.iter().cycle().take(builtins.len() * count.max(1)).cloned().collect(),
+    })
+}
+
+/// The shell command an action runs, if it runs one
+fn shell_of(action: &Action) -> Option<&str> {
+    match action {
+        Action::Custom(cmd) | Action::TypeShell(cmd) => Some(cmd),
+        Action::Within(_, action) | Action::Confirm(action) => shell_of(action),
+        _ => None,
+    }
+}
+
+/// The same action with its `[commands]` entry's output typed instead of run
+fn typing_output(action: Action) -> Option<Action> {
+    match action {
+        Action::Custom(shell) => Some(Action::TypeShell(shell)),
+        Action::Within(context, action) => Some(Action::Within(context, Box::new(typing_output(*action)?))),
+        Action::Confirm(action) => Some(Action::Confirm(Box::new(typing_output(*action)?))),
+        _ => None,
+    }
+}
+
+/// How a phrase sounds: each word's Metaphone code, number words as digits
+pub fn phonetic_key(phrase: &str) -> String {
+    phrase.split_whitespace().map(|word| text::metaphone(&match_key(word))).collect()
+}
+
+/// The phrase that sounds like `spoken` ("knew tab" for "new tab")
+fn phonetic_action(spoken: &str, phrases: &Phrases) -> Option<Action> {
+    let key = phonetic_key(spoken);
+    if key.is_empty() {
+        return None;
+    }
+    phrases.best(|phrase, _| (!has_placeholders(phrase) && phonetic_key(phrase) == key).then(Vec::new))
+}
+
+/// The built-in that sounds like `cmd`, keeping any "times N" ("knew tab
+/// times two" -> "new tab times two"); None if `cmd` is already one
+fn phonetic_builtin(cmd: &str) -> Option<String> {
+    let (base, times) = cmd.find(" times ").map_or((cmd, ""), |i| cmd.split_at(i));
+    if BUILTINS.contains(&base) {
+        return None;
+    }
+    let key = phonetic_key(base);
+    let builtin = BUILTINS.iter().find(|b| !key.is_empty() && phonetic_key(b) == key)?;
+    Some(format!("{}{}", builtin, times))
+}
+
+/// The phrase `input` (normalized) comes nearest to, if `fuzzy` allows it
+/// Equal scores go by `Phrases::rank`.
+fn fuzzy_action(input: &str, phrases: &Phrases) -> Option<Action> {
+    if input.is_empty() {
+        return None;
+    }
+    let (_, phrase, table) = phrases
+        .all()
+        .filter(|(phrase, _)| !has_placeholders(phrase))
+        .filter_map(|(phrase, table)| {
+            phrases.fuzzy.score(input, &normalize_for_matching(phrase)).map(|score| (score, phrase, table))
+        })
+        .max_by(|(a, pa, ta), (b, pb, tb)| a.total_cmp(b).then_with(|| phrases.rank(pa, *ta).cmp(&phrases.rank(pb, *tb))))?;
+    Some(phrases.action(phrase, table, &Vec::new()))
+}
+
+/// Work out what an utterance should do without doing it
+/// Uses a configurable leader word (default "command") to trigger commands
+/// Builtins listed in `leaderless` also work without the leader (for fast aborts)
+/// `[aliases]` and then `[[replacements]]` rewrite the utterance first.
+pub fn parse_action(
+    text: &str,
+    leader: &str,
+    leaderless: &[String],
+    phrases: &Phrases,
+    aliases: &HashMap<String, String>,
+) -> Action {
+    let aliased = apply_replacements(&normalize_aliases(text, aliases), phrases.replacements);
+
+    let trimmed: String = aliased
+        .trim()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+
+    // A "yes" or "no" to a confirmation prompt comes before anything else
+    if confirmation_pending() {
+        match trimmed.split_whitespace().collect::<Vec<_>>().join(" ").as_str() {
+            "yes" | "yeah" | "yep" | "confirm" | "do it" => return Action::Answer(true),
+            "no" | "nope" | "cancel" | "stop" => return Action::Answer(false),
+            _ => {}
+        }
+    }
+
+    // While the mouse grid is up, numbers zoom in and "click" clicks there
+    if mouse::grid_active()
+        && let Some(step) = mouse::grid_step(&trimmed.split_whitespace().collect::<Vec<_>>().join(" "))
+    {
+        return Action::Grid(step);
+    }
+
+    // Build the leader prefix (e.g., "command ")
+    let leader_prefix = format!("{} ", leader.to_lowercase());
+
+    // Check if input starts with the leader word
+    if let Some(after_leader) = trimmed.strip_prefix(&leader_prefix) {
+        let cmd = after_leader.trim();
+
+        // Your own names for built-ins; several built-ins run as a macro
+        let alias = command_alias(cmd, phrases.command_aliases);
+        let cmd = match alias.as_deref() {
+            Some([builtin]) => builtin.as_str(),
+            Some(builtins) => return Action::Macro(builtins.iter().map(|b| format!("builtin \"{}\"", b)).collect()),
+            None => cmd,
+        };
+
+        if let Some(emoji_name) = cmd.strip_prefix("emoji ") {
+            return Action::Emoji(emoji_name.trim().to_string());
+        }
+
+        if let Some(punct) = cmd.strip_prefix("punctuation ").or_else(|| cmd.strip_prefix("punk ")) {
+            return Action::Punctuation(punct.trim().to_string());
+        }
+
+        if let Some(insert_name) = cmd.strip_prefix("insert ") {
+            return Action::Insert(insert_name.trim().to_string());
+        }
+
+        // "wrap <name> <text>"
+        if let Some(wrap_rest) = cmd.strip_prefix("wrap ") {
+            let (name, text) = wrap_rest.split_once(' ').unwrap_or((wrap_rest, ""));
+            return Action::Wrap {
+                name: name.trim().to_string(),
+                text: text.trim().to_string(),
+            };
+        }
+
+        // "to <sink> <text>"
+        if let Some(sink) = cmd.strip_prefix("to ").and_then(|rest| rest.split_whitespace().next())
+            && output::SINKS.contains(&sink)
+        {
+            let skip = leader.split_whitespace().count() + 2;
+            let text = aliased.split_whitespace().skip(skip).collect::<Vec<_>>().join(" ");
+            return Action::Output { sink: sink.to_string(), text };
+        }
+
+        // "snake the quick brown fox": one utterance in a mode
+        if let Some((name, rest)) = cmd.split_once(' ')
+            && rest != "selection"
+            && let Some(mode) = parse_mode_name(name).filter(|m| *m != CaseMode::Off)
+        {
+            let skip = leader.split_whitespace().count() + 1;
+            let text = aliased.split_whitespace().skip(skip).collect::<Vec<_>>().join(" ");
+            return Action::Cased { mode, text: numbers::dictation(&fillers::strip(&text)) };
+        }
+
+        // "correct there to their": the last "to" splits them, so "correct going to to gonna" works
+        if cmd.starts_with("correct ") {
+            let skip = leader.split_whitespace().count() + 1;
+            let words: Vec<&str> = aliased.split_whitespace().skip(skip).collect();
+            let trim = |words: &[&str]| words.join(" ").trim_matches(['.', ',', '!', '?']).to_string();
+            if let Some(to) = words.iter().rposition(|w| strip_punct(w) == "to").filter(|&i| i > 0 && i + 1 < words.len()) {
+                return Action::Correct { wrong: trim(&words[..to]), right: trim(&words[to + 1..]) };
+            }
+        }
+
+        // "run and type <custom command phrase>"
+        if let Some(phrase) = cmd.strip_prefix("run and type ") {
+            let wanted = normalize_for_matching(phrase);
+            let exact = phrases.best(|p, table| (table == Table::Commands && normalize_for_matching(p) == wanted).then(Vec::new));
+            let captured = || {
+                phrases.best(|p, table| {
+                    if table == Table::Commands && has_placeholders(p) { match_phrase(p, phrase) } else { None }
+                })
+            };
+            if let Some(action) = exact.or_else(captured).and_then(typing_output) {
+                return action;
+            }
+        }
+
+        // A built-in misheard as one that sounds the same (phonetic_match)
+        if phrases.phonetic && let Some(builtin) = phonetic_builtin(cmd) {
+            return Action::Builtin(builtin);
+        }
+
+        return Action::Builtin(cmd.to_string());
+    }
+
+    let normalized_input = normalize_for_matching(&trimmed);
+
+    // Whitelisted builtins that skip the leader ("escape", "scratch that", ...)
+    if let Some(phrase) = leaderless.iter().find(|p| normalized_input == normalize_for_matching(p)) {
+        return Action::Builtin(phrase.to_lowercase().split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+
+    // [commands], [keybinds] and [macros] phrases work without the leader word.
+    // Exact phrases first; when several match, `Phrases::rank` picks
+    if let Some(action) = phrases.best(|phrase, _| (normalized_input == normalize_for_matching(phrase)).then(Vec::new)) {
+        return action;
+    }
+
+    // Phrases with {placeholders} come after every exact phrase
+    if let Some(action) = phrases.best(|phrase, _| if has_placeholders(phrase) { match_phrase(phrase, &aliased) } else { None }) {
+        return action;
+    }
 
-    // Track length for "scratch that" undo
-    LAST_TYPED_LEN.store(output.chars().count(), Ordering::SeqCst);
+    // Then [[patterns]], first match in file order
+    for pattern in phrases.patterns {
+        if let Some(captures) = match_pattern(&pattern.regex, &aliased)
+            && let Some(action) = pattern_action(pattern, &captures)
+        {
+            return action;
+        }
+    }
+
+    // Then phrases that sound the same (phonetic_match)
+    if phrases.phonetic && let Some(action) = phonetic_action(&aliased, phrases) {
+        return action;
+    }
 
+    // Last, the nearest phrase if it's close enough (fuzzy_match)
+    if let Some(action) = fuzzy_action(&normalized_input, phrases) {
+        return action;
+    }
+
+    Action::Type(numbers::dictation(&fillers::strip(&aliased)))
+}
+
+/// Type dictated text at the cursor with case mode applied
+/// Remembers it for "scratch that" and "read that"
+/// In vim mode the text is a Vim phrase and its keystrokes are sent instead;
+/// in emacs mode that happens for Emacs phrases, and other text is typed.
+pub fn type_text(enigo: &mut Keys, text: &str) -> Result<()> {
     let mode = get_case_mode();
+    type_in_mode(enigo, text, mode)?;
+    if !text.trim().is_empty() {
+        mode_used(mode);
+    }
+    Ok(())
+}
+
+/// How many utterances back "scratch that" can reach
+const SCRATCH_HISTORY: usize = 20;
+
+/// Remember what an utterance typed for the scratch commands; "" when it
+/// can't be backspaced away, which also drops the ones before it since the
+/// cursor may have moved
+fn remember_typed(output: &str) {
+    if let Ok(mut typed) = LAST_TYPED.lock() {
+        if output.is_empty() {
+            typed.clear();
+            return;
+        }
+        typed.push(output.to_string());
+        let extra = typed.len().saturating_sub(SCRATCH_HISTORY);
+        typed.drain(..extra);
+    }
+}
+
+/// Tell smart spacing what's in front of the cursor again after backspacing
+fn retrack_typed(typed: &[String]) {
+    output::forget_typed();
+    for text in typed {
+        output::typed(text);
+    }
+}
+
+/// Backspace over the end of what the last utterances typed, back to where
+/// `start` says in the newest one (applied `times` times, moving on to the
+/// one before when it's used up), keeping the rest for the next scratch
+fn execute_scratch(enigo: &mut Keys, start: fn(&str) -> usize, times: usize) -> Result<bool> {
+    let Ok(mut typed) = LAST_TYPED.lock() else {
+        return Ok(false);
+    };
+    let mut len = 0;
+    for _ in 0..times {
+        typed.retain(|text| !text.is_empty());
+        let Some(last) = typed.last_mut() else {
+            break;
+        };
+        let keep = start(last);
+        len += last[keep..].chars().count();
+        last.truncate(keep);
+    }
+    typed.retain(|text| !text.is_empty());
+    if len == 0 {
+        warn!("⚠️ Nothing to scratch");
+        return Ok(false);
+    }
+    for _ in 0..len {
+        enigo.key(EnigoKey::Backspace, enigo::Direction::Click)?;
+    }
+    retrack_typed(&typed);
+    info!("⏪ Scratched {} character(s)", len);
+    Ok(true)
+}
+
+/// Where the last `wrong` is in `text`, whole words first so "correct the to
+/// a" leaves "then" alone
+fn last_match(text: &str, wrong: &str) -> Option<std::ops::Range<usize>> {
+    if wrong.is_empty() {
+        return None;
+    }
+    let found = text::find_case_insensitive(text, wrong);
+    let whole = found.iter().rev().find(|r| {
+        !text[..r.start].chars().next_back().is_some_and(char::is_alphanumeric)
+            && !text[r.end..].chars().next().is_some_and(char::is_alphanumeric)
+    });
+    whole.or(found.last()).cloned()
+}
+
+/// Backspace to the last `wrong` in what the last utterances typed and retype
+/// from there with `right` in its place; a capital on `wrong` carries over
+fn execute_correct(enigo: &mut Keys, wrong: &str, right: &str) -> Result<bool> {
+    let Ok(mut typed) = LAST_TYPED.lock() else {
+        return Ok(false);
+    };
+    let Some((index, range)) = typed.iter().enumerate().rev().find_map(|(i, text)| Some((i, last_match(text, wrong)?))) else {
+        warn!("⚠️ No '{}' in what was just typed", privacy::redact(wrong));
+        return Ok(false);
+    };
+
+    let mut right = right.to_string();
+    if typed[index][range.clone()].starts_with(char::is_uppercase)
+        && let Some(first) = right.chars().next()
+    {
+        right.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+    }
+    // Everything from `wrong` on comes off, later utterances included
+    let after = typed[index + 1..].concat();
+    let len = typed[index][range.start..].chars().count() + after.chars().count();
+    let tail = format!("{}{}{}", right, &typed[index][range.end..], after);
+    for _ in 0..len {
+        enigo.key(EnigoKey::Backspace, enigo::Direction::Click)?;
+    }
+    enigo.text(&tail)?;
+
+    typed.truncate(index + 1);
+    typed[index].truncate(range.start);
+    typed[index].push_str(&tail);
+    retrack_typed(&typed);
+    if let Ok(mut read) = LAST_TYPED_TEXT.lock() {
+        read.clone_from(&typed[index]);
+    }
+    info!("✏️ Corrected '{}' to '{}'", privacy::redact(wrong), privacy::redact(&right));
+    Ok(true)
+}
+
+/// Copy the selection, rewrite it in `mode` and paste it back. Modes that
+/// join words split identifiers first, so "getUserName" becomes
+/// "get_user_name"; each line is done on its own so line breaks and
+/// indentation stay.
+fn execute_transform_selection(enigo: &mut Keys, mode: CaseMode) -> Result<bool> {
+    let joins = matches!(
+        mode,
+        CaseMode::Snake | CaseMode::Camel | CaseMode::Pascal | CaseMode::Kebab | CaseMode::Dot | CaseMode::Path | CaseMode::Screaming
+    );
+    let rewrite = |line: &str| {
+        let body = line.trim();
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let end = &line[indent.len() + body.len()..];
+        let body = if joins { apply_mode(mode, &text::identifier_words(body)) } else { apply_mode(mode, body) };
+        format!("{}{}{}", indent, body, end)
+    };
+    let replaced = output::transform_selection(enigo, |selected| selected.split('\n').map(rewrite).collect::<Vec<_>>().join("\n"))?;
+    let Some(replaced) = replaced else {
+        warn!("⚠️ Nothing selected");
+        return Ok(false);
+    };
+    remember_typed(""); // Pasted over text scratch doesn't know about
+    info!("🔁 Selection ({:?}): {}", mode, privacy::redact(&replaced));
+    Ok(true)
+}
+
+/// Type dictated text with `mode` applied, whatever the current mode is
+fn type_in_mode(enigo: &mut Keys, text: &str, mode: CaseMode) -> Result<()> {
+    match mode {
+        CaseMode::Vim => return send_vim(enigo, text),
+        CaseMode::Emacs => {
+            if let Some(notation) = emacs::translate(text) {
+                send_keystrokes(enigo, &emacs::keystrokes(&notation))?;
+                output::forget_typed();
+                remember_typed("");
+                info!("⌨️ Emacs: {}", notation);
+                return Ok(());
+            }
+        }
+        _ => {}
+    }
+
+    let output = match mode {
+        CaseMode::Off | CaseMode::Emacs => output::auto_capitalize(text),
+        _ => apply_mode(mode, text),
+    };
+    let output = output::smart_space(&output);
+    if output::should_paste(&output) {
+        output::paste(enigo, &output)?;
+    } else {
+        enigo.text(&output)?;
+    }
+    output::typed(&output);
+
+    // Track it for the scratch commands and "read that"
+    remember_typed(&output);
+    if let Ok(mut last) = LAST_TYPED_TEXT.lock() {
+        last.clone_from(&output);
+    }
+
     if mode != CaseMode::Off {
-        println!("[SS9K] ⌨️ Typed ({:?}): {}", mode, output);
+        info!("⌨️ Typed ({:?}): {}", mode, privacy::redact(&output));
     } else {
-        println!("[SS9K] ⌨️ Typed!");
+        info!("⌨️ Typed!");
+    }
+    Ok(())
+}
+
+/// Send the keystrokes for a Vim phrase; nothing for one that isn't understood
+fn send_vim(enigo: &mut Keys, text: &str) -> Result<()> {
+    let Some(notation) = vim::translate(text) else {
+        warn!("⚠️ Not a vim phrase: {}", privacy::redact(text));
+        return Ok(());
+    };
+    send_keystrokes(enigo, &vim::keystrokes(&notation))?;
+    output::forget_typed();
+    remember_typed(""); // Keystrokes can't be backspaced away
+    info!("⌨️ Vim: {}", notation);
+    Ok(())
+}
+
+/// Send editor mode keystrokes: text typed as-is (no case mode, never pasted), chords pressed
+pub fn send_keystrokes(enigo: &mut Keys, keys: &[Keystroke]) -> Result<()> {
+    for key in keys {
+        match key {
+            Keystroke::Text(text) => enigo.text(text)?,
+            Keystroke::Chord(chord) => press_keybind(enigo, chord)?,
+        }
+    }
+    Ok(())
+}
+
+/// Execute a voice command or type the text
+/// Everything goes through the leader: "command enter", "command emoji smile", "command punctuation comma"
+/// Returns true if a command was executed, false if text was typed
+/// Leader, whitelist, commands, aliases, inserts and wrappers all come from `config`
+pub fn execute_command(enigo: &mut Keys, text: &str, config: &Config) -> Result<bool> {
+    let action = parse_action(text, &config.leader, &config.leaderless_commands, &Phrases::of(config), &config.aliases);
+    // Saying anything but yes or no drops a waiting confirmation
+    if !matches!(action, Action::Answer(_)) && take_pending_confirmation().is_some() {
+        info!("❎ Not confirmed, skipped");
+    }
+    run_action(enigo, action, config)
+}
+
+/// Carry out a parsed action
+fn run_action(enigo: &mut Keys, action: Action, config: &Config) -> Result<bool> {
+    let inserts = &config.inserts;
+    let wrappers = &config.wrappers;
+    // Keys and programs can move the cursor, so what was typed before no longer sits in front of it
+    if matches!(
+        action,
+        Action::Builtin(_) | Action::Keybind(_) | Action::Macro(_) | Action::Custom(_) | Action::Within(..) | Action::Grid(_)
+    ) {
+        output::forget_typed();
+    }
+    match action {
+        Action::Emoji(name) => execute_emoji(enigo, &name, &config.emoji),
+        Action::Punctuation(name) => execute_punctuation(enigo, &name, &config.punctuation),
+        Action::Insert(name) => {
+            // An [inserts] entry, or a date: "insert date tomorrow", "insert next friday"
+            if let Some(template) = inserts.get(&name).cloned().or_else(|| dates::placeholder(&name)) {
+                let expanded = expand_placeholders(&template);
+                enigo.text(&expanded)?;
+                output::typed(&expanded);
+                remember_typed(&expanded);
+                info!("📋 Inserted '{}': {}", name, expanded.chars().take(50).collect::<String>());
+                Ok(true)
+            } else {
+                warn!("⚠️ Unknown insert: '{}'", name);
+                warn!("Available: {:?}", inserts.keys().collect::<Vec<_>>());
+                Ok(false)
+            }
+        }
+        Action::Wrap { name, text } => {
+            if let Some(wrapper) = wrappers.get(&name) {
+                let (left, right) = if let Some(idx) = wrapper.find('|') {
+                    (&wrapper[..idx], &wrapper[idx + 1..])
+                } else {
+                    (wrapper.as_str(), wrapper.as_str())
+                };
+                let wrapped = format!("{}{}{}", expand_clipboard(left), text, expand_clipboard(right));
+                enigo.text(&wrapped)?;
+                output::typed(&wrapped);
+                remember_typed(&wrapped);
+                info!("🎁 Wrapped '{}': {}", name, wrapped);
+                Ok(true)
+            } else {
+                warn!("⚠️ Unknown wrapper: '{}'", name);
+                warn!("Available: {:?}", wrappers.keys().collect::<Vec<_>>());
+                Ok(false)
+            }
+        }
+        Action::Output { sink, text } => {
+            if text.is_empty() {
+                warn!("⚠️ Nothing to send to {}", sink);
+                return Ok(false);
+            }
+            match sink.as_str() {
+                "type" => Typing(enigo).write(&text)?,
+                name => output::open(name, config)?.write(&text)?,
+            }
+            Ok(true)
+        }
+        Action::Cased { mode, text } if output::is_sink_mode(&output::mode(config)) => {
+            output::open(&output::mode(config), config)?.emit(&apply_mode(mode, &text))?;
+            Ok(false)
+        }
+        Action::Cased { mode, text } => {
+            type_in_mode(enigo, &text, mode)?;
+            Ok(false)
+        }
+        Action::Correct { wrong, right } => execute_correct(enigo, &wrong, &right),
+        Action::Grid(step) => {
+            match mouse::run_grid(enigo, &step)? {
+                Some(grid) => info!("🔲 Grid: {}×{} at ({}, {})", grid.width, grid.height, grid.x, grid.y),
+                None => info!("🔲 Grid closed"),
+            }
+            Ok(true)
+        }
+        Action::Builtin(cmd) if is_status_query(&cmd) => execute_status(config),
+        Action::Builtin(cmd) => execute_builtin_command(enigo, &cmd),
+        Action::Custom(cmd) => {
+            execute_custom_command(&cmd, &ShellContext::default())?;
+            Ok(true)
+        }
+        Action::TypeShell(cmd) => type_shell_output(enigo, &cmd, &ShellContext::default()),
+        Action::Within(context, action) => match *action {
+            Action::Custom(cmd) => {
+                execute_custom_command(&cmd, &context)?;
+                Ok(true)
+            }
+            Action::TypeShell(cmd) => type_shell_output(enigo, &cmd, &context),
+            other => run_action(enigo, other, config),
+        },
+        Action::Keybind(spec) => {
+            press_keybind(enigo, &spec)?;
+            info!("⌨️ Keybind: {}", spec);
+            Ok(true)
+        }
+        Action::Macro(steps) => {
+            info!("🎬 Macro: {} steps", steps.len());
+            execute_macro(enigo, &steps)?;
+            Ok(true)
+        }
+        Action::Confirm(action) => {
+            let what = shell_of(&action).map_or_else(|| format!("{:?}", action), str::to_string);
+            let deadline = Instant::now() + Duration::from_secs(config.confirm_timeout_secs);
+            if let Ok(mut pending) = PENDING_CONFIRM.lock() {
+                *pending = Some((*action, deadline));
+            }
+            info!("❓ Say yes to confirm: {}", what);
+            tts::say("say yes to confirm");
+            notifications::notify(config, Kind::Result, &format!("Say yes to confirm: {}", what));
+            Ok(true)
+        }
+        Action::Answer(yes) => match take_pending_confirmation() {
+            Some(action) if yes => {
+                info!("✅ Confirmed");
+                run_action(enigo, action, config)
+            }
+            Some(_) => {
+                info!("❎ Cancelled");
+                tts::say("cancelled");
+                Ok(true)
+            }
+            None => {
+                warn!("⚠️ Nothing to confirm (it timed out)");
+                Ok(true)
+            }
+        },
+        Action::Type(aliased) if output::is_sink_mode(&output::mode(config)) => {
+            output::open(&output::mode(config), config)?.write(&aliased)?;
+            Ok(false)
+        }
+        Action::Type(aliased) => {
+            Typing(enigo).write(&aliased)?;
+            Ok(false)
+        }
     }
-    Ok(false)
 }
 
 /// Execute a built-in command (navigation, editing, media)
 /// Handles "times N" suffix and "repeat" command
-pub fn execute_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
+pub fn execute_builtin_command(enigo: &mut Keys, cmd: &str) -> Result<bool> {
     let (base_cmd, count) = parse_times_suffix(cmd);
 
     if base_cmd == "repeat" || base_cmd.starts_with("repeat ") {
@@ -789,21 +2006,20 @@ pub fn execute_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
             count.max(1)
         } else {
             base_cmd.strip_prefix("repeat ")
-                .and_then(|s| s.split_whitespace().next())
-                .and_then(parse_number_word)
+                .and_then(|s| parse_number_word(s.trim()).or_else(|| s.split_whitespace().next().and_then(parse_number_word)))
                 .unwrap_or(1)
                 .max(1) * count.max(1)
         };
 
         let last_cmd = LAST_COMMAND.lock().ok().and_then(|g| g.clone());
         if let Some(ref cmd_to_repeat) = last_cmd {
-            println!("[SS9K] 🔁 Repeating '{}' {} time(s)", cmd_to_repeat, repeat_count);
+            info!("🔁 Repeating '{}' {} time(s)", privacy::redact(cmd_to_repeat), repeat_count);
             for _ in 0..repeat_count {
                 execute_single_builtin_command(enigo, cmd_to_repeat)?;
             }
             return Ok(true);
         } else {
-            eprintln!("[SS9K] ⚠️ Nothing to repeat");
+            warn!("⚠️ Nothing to repeat");
             return Ok(false);
         }
     }
@@ -816,6 +2032,10 @@ pub fn execute_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
         return execute_spell_mode(enigo, spell_input.trim());
     }
 
+    if let Some(character) = base_cmd.strip_prefix("unicode ") {
+        return execute_unicode(enigo, character.trim());
+    }
+
     if let Some(hold_key) = base_cmd.strip_prefix("hold ") {
         return execute_hold(enigo, hold_key.trim());
     }
@@ -827,25 +2047,120 @@ pub fn execute_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
         return execute_release(enigo, release_key.trim());
     }
 
-    // Scratch that - undo last typed text
+    // Scratch that - undo last typed text, or just its last word or sentence
     if base_cmd == "scratch that" || base_cmd == "undo" || base_cmd == "scratch" {
-        let len = LAST_TYPED_LEN.swap(0, Ordering::SeqCst);
-        if len > 0 {
-            for _ in 0..len {
-                enigo.key(EnigoKey::Backspace, enigo::Direction::Click)?;
-            }
-            println!("[SS9K] ⏪ Scratched {} character(s)", len);
-            return Ok(true);
-        } else {
-            eprintln!("[SS9K] ⚠️ Nothing to scratch");
-            return Ok(false);
-        }
+        return execute_scratch(enigo, |_| 0, count.max(1));
+    }
+    if base_cmd == "scratch word" || base_cmd == "scratch last word" {
+        return execute_scratch(enigo, text::last_word_start, count.max(1));
+    }
+    if base_cmd == "scratch sentence" || base_cmd == "scratch last sentence" {
+        return execute_scratch(enigo, text::last_sentence_start, count.max(1));
+    }
+
+    // "snake selection": the selected text, rewritten in a mode
+    if let Some(mode) = base_cmd
+        .strip_suffix(" selection")
+        .and_then(parse_mode_name)
+        .filter(|m| !matches!(m, CaseMode::Off | CaseMode::Vim | CaseMode::Emacs))
+    {
+        return execute_transform_selection(enigo, mode);
     }
 
     if let Some(mode_name) = base_cmd.strip_prefix("mode ") {
         return execute_mode(mode_name.trim());
     }
 
+    // "wait three seconds": a pause, mostly for macros waiting on an app to open
+    if let Some(ms) = base_cmd.strip_prefix("wait ").and_then(parse_wait) {
+        wait(ms);
+        return Ok(true);
+    }
+
+    if let Some(spec) = ["set timer ", "set a timer ", "start timer ", "start a timer "]
+        .iter()
+        .find_map(|prefix| base_cmd.strip_prefix(prefix))
+    {
+        return execute_set_timer(spec);
+    }
+    if matches!(base_cmd, "list timers" | "timers" | "show timers") {
+        return execute_list_timers();
+    }
+    if base_cmd == "cancel timer" || base_cmd == "stop timer" {
+        return execute_cancel_timer(None);
+    }
+    if let Some(name) = base_cmd.strip_prefix("cancel timer ").or_else(|| base_cmd.strip_prefix("stop timer ")) {
+        return execute_cancel_timer(Some(name));
+    }
+
+    if matches!(base_cmd, "read that" | "read back" | "read last") {
+        return execute_read_that();
+    }
+    if matches!(base_cmd, "again" | "say that again" | "type that again") {
+        return execute_again(enigo);
+    }
+    // "mouse up 200", "right click", "scroll down three"
+    if let Some(pointer) = mouse::parse(base_cmd) {
+        if matches!(pointer, mouse::Pointer::Click { .. }) {
+            mouse::close_grid();
+        }
+        for _ in 0..count.max(1) {
+            mouse::run(enigo, pointer)?;
+        }
+        info!("🖱️ {:?}", pointer);
+        return Ok(true);
+    }
+    if matches!(base_cmd, "grid" | "mouse grid" | "show grid") {
+        return execute_grid(enigo);
+    }
+    if matches!(base_cmd, "grid off" | "close grid" | "hide grid") {
+        mouse::close_grid();
+        info!("🔲 Grid closed");
+        return Ok(true);
+    }
+
+    if base_cmd == "read clipboard" {
+        return execute_read_clipboard();
+    }
+    if base_cmd == "type clipboard" || base_cmd == "type clipboard slowly" {
+        return execute_type_clipboard(enigo, base_cmd.ends_with("slowly"));
+    }
+
+    // Absolute levels: "volume fifty percent", "brightness thirty"
+    // Falls through for relative forms like "volume up"
+    if let Some(level) = base_cmd.strip_prefix("volume ").and_then(parse_level) {
+        return execute_set_volume(enigo, level);
+    }
+    if let Some(level) = base_cmd.strip_prefix("brightness ").and_then(parse_level) {
+        return execute_set_brightness(level);
+    }
+
+    if let Some(name) = base_cmd.strip_prefix("output ") {
+        return execute_output(name.trim());
+    }
+
+    if let Some(state) = base_cmd.strip_prefix("privacy ") {
+        return match state.trim() {
+            "on" | "enable" | "enabled" => execute_privacy(true),
+            "off" | "disable" | "disabled" => execute_privacy(false),
+            other => {
+                warn!("⚠️ Privacy takes 'on' or 'off', got: {}", other);
+                Ok(false)
+            }
+        };
+    }
+
+    if let Some(state) = base_cmd.strip_prefix("do not disturb ") {
+        return match state.trim() {
+            "on" | "enable" | "enabled" => execute_do_not_disturb(true),
+            "off" | "disable" | "disabled" => execute_do_not_disturb(false),
+            other => {
+                warn!("⚠️ Do not disturb takes 'on' or 'off', got: {}", other);
+                Ok(false)
+            }
+        };
+    }
+
     for i in 0..count.max(1) {
         if !execute_single_builtin_command(enigo, base_cmd)? {
             return Ok(false);
@@ -860,117 +2175,161 @@ pub fn execute_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
     }
 
     if count > 1 {
-        println!("[SS9K] 🔁 Executed {} times", count);
+        info!("🔁 Executed {} times", count);
     }
 
     Ok(true)
 }
 
-/// Parse a number from digit or word form
+/// Parse a number from digit or word form: "7", "seven", "one hundred twenty"
+/// "to", "too" and "for" count as 2 and 4, which Whisper often writes instead.
 pub fn parse_number_word(s: &str) -> Option<usize> {
     if let Ok(n) = s.parse::<usize>() {
         return Some(n);
     }
     match s {
-        "zero" => Some(0),
-        "one" => Some(1),
-        "two" | "to" | "too" => Some(2),
-        "three" => Some(3),
-        "four" | "for" => Some(4),
-        "five" => Some(5),
-        "six" => Some(6),
-        "seven" => Some(7),
-        "eight" => Some(8),
-        "nine" => Some(9),
-        "ten" => Some(10),
-        "eleven" => Some(11),
-        "twelve" => Some(12),
-        "thirteen" => Some(13),
-        "fourteen" => Some(14),
-        "fifteen" => Some(15),
-        "sixteen" => Some(16),
-        "seventeen" => Some(17),
-        "eighteen" => Some(18),
-        "nineteen" => Some(19),
-        "twenty" => Some(20),
-        _ => None,
+        "to" | "too" => Some(2),
+        "for" => Some(4),
+        _ => numbers::parse_integer(s).and_then(|n| usize::try_from(n).ok()),
+    }
+}
+
+/// Parse a spoken level from 0-100 for absolute controls
+/// "fifty percent" → 50, "seventy five" → 75, "a hundred" → 100, "40" → 40
+pub fn parse_level(s: &str) -> Option<u32> {
+    let mut words = Vec::new();
+    for word in s.split_whitespace() {
+        match word {
+            // Filler words: "volume to fifty percent", "brightness at a hundred"
+            "to" | "at" | "percent" | "per" | "cent" => continue,
+            "max" | "maximum" | "full" => return Some(100),
+            "min" | "minimum" => return Some(0),
+            "hundred" if words.is_empty() => words.push("one"),
+            _ => {}
+        }
+        words.push(word);
     }
+    let level = parse_number_word(&words.join(" "))?;
+    Some(level.min(100) as u32)
+}
+
+/// Parse a count or line number for vim and emacs modes: "forty two" -> 42,
+/// "two hundred fifty" -> 250; None for zero
+pub(crate) fn parse_count(s: &str) -> Option<usize> {
+    parse_number_word(s).filter(|&n| n > 0)
+}
+
+/// Parse a spoken wait: "three seconds", "1.5 s", "half a second", "500 milliseconds"
+/// The unit is required; None if there isn't one or the amount isn't a number.
+pub fn parse_wait(s: &str) -> Option<u64> {
+    let (amount, scale) = match text::split_last_word(s.trim())? {
+        (amount, "ms" | "millisecond" | "milliseconds" | "millis") => (amount.trim(), 1.0),
+        (amount, "s" | "sec" | "secs" | "second" | "seconds") => (amount.trim(), 1000.0),
+        (amount, "minute" | "minutes") => (amount.trim(), 60_000.0),
+        (amount, "hour" | "hours") => (amount.trim(), 3_600_000.0),
+        _ => return None,
+    };
+    let value = match amount {
+        "a" | "an" => 1.0,
+        "half a" | "half" => 0.5,
+        _ => numbers::parse_decimal(amount)?,
+    };
+    (value.is_finite() && value >= 0.0).then(|| (value * scale).round() as u64)
 }
 
-/// Parse "times N" suffix from a command
+/// Parse "times N" suffix from a command: "backspace times twenty five",
+/// "backspace twenty five times"
 /// Returns (base_command, count) where count is 0 if no suffix found
 pub fn parse_times_suffix(cmd: &str) -> (&str, usize) {
-    if let Some(idx) = cmd.rfind(" times ") {
-        let after = &cmd[idx + 7..].trim();
-        if let Some(n) = parse_number_word(after) {
-            return (&cmd[..idx], n);
+    // "<cmd> times <n>", the longest number that ends the command
+    for (at, _) in cmd.match_indices(" times ") {
+        if let Some(n) = parse_number_word(cmd[at + " times ".len()..].trim()) {
+            return (cmd[..at].trim_end(), n);
         }
     }
-    let words: Vec<&str> = cmd.split_whitespace().collect();
-    if words.len() >= 2 && words[words.len() - 1] == "times" {
-        if let Some(n) = parse_number_word(words[words.len() - 2]) {
-            let end_idx = cmd.rfind(words[words.len() - 2]).unwrap_or(cmd.len());
-            return (cmd[..end_idx].trim(), n);
+    // "<cmd> <n> times"
+    if let Some(head) = cmd.trim_end().strip_suffix(" times") {
+        let starts = std::iter::once(0).chain(head.match_indices(' ').map(|(at, _)| at + 1));
+        for start in starts {
+            if let Some(n) = parse_number_word(head[start..].trim()) {
+                return (head[..start].trim_end(), n);
+            }
         }
     }
     (cmd, 0)
 }
 
+/// Built-ins without arguments, for phonetic matching (`phonetic_match`)
+/// Keep in step with `execute_single_builtin_command`.
+pub const BUILTINS: &[&str] = &[
+    "enter", "new line", "newline", "return", "tab", "escape", "cancel", "backspace", "delete",
+    "delete that", "oops", "space", "up", "arrow up", "down", "arrow down", "left", "arrow left", "right",
+    "arrow right", "home", "end", "page up", "page down", "select all", "copy", "copy that", "paste", "cut",
+    "undo", "redo", "save", "find", "close", "close tab", "new tab", "play", "pause", "play pause",
+    "playpause", "next", "next track", "skip", "previous", "previous track", "prev", "back", "volume up",
+    "louder", "volume down", "quieter", "softer", "mute", "unmute", "mute toggle", "help", "languages",
+    "language list", "list languages", "config", "settings", "edit config", "repeat", "release all",
+    "scratch that", "scratch word", "scratch sentence", "read that", "again", "say that again", "read clipboard",
+    "type clipboard", "type clipboard slowly", "list timers", "show timers", "cancel timer", "stop timer", "status",
+    "what mode", "click", "left click", "right click", "middle click", "double click", "triple click", "grid",
+    "mouse grid", "grid off",
+];
+
 /// Execute a single built-in command once (internal helper)
-pub fn execute_single_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
+pub fn execute_single_builtin_command(enigo: &mut Keys, cmd: &str) -> Result<bool> {
     match cmd {
         // Navigation
         "enter" | "new line" | "newline" | "return" => {
             enigo.key(EnigoKey::Return, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Enter");
+            info!("⌨️ Command: Enter");
         }
         "tab" => {
             enigo.key(EnigoKey::Tab, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Tab");
+            info!("⌨️ Command: Tab");
         }
         "escape" | "cancel" => {
             enigo.key(EnigoKey::Escape, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Escape");
+            info!("⌨️ Command: Escape");
         }
         "backspace" | "delete" | "delete that" | "oops" => {
             enigo.key(EnigoKey::Backspace, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Backspace");
+            info!("⌨️ Command: Backspace");
         }
         "space" => {
             enigo.key(EnigoKey::Space, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Space");
+            info!("⌨️ Command: Space");
         }
         "up" | "arrow up" => {
             enigo.key(EnigoKey::UpArrow, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Up");
+            info!("⌨️ Command: Up");
         }
         "down" | "arrow down" => {
             enigo.key(EnigoKey::DownArrow, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Down");
+            info!("⌨️ Command: Down");
         }
         "left" | "arrow left" => {
             enigo.key(EnigoKey::LeftArrow, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Left");
+            info!("⌨️ Command: Left");
         }
         "right" | "arrow right" => {
             enigo.key(EnigoKey::RightArrow, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Right");
+            info!("⌨️ Command: Right");
         }
         "home" => {
             enigo.key(EnigoKey::Home, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Home");
+            info!("⌨️ Command: Home");
         }
         "end" => {
             enigo.key(EnigoKey::End, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: End");
+            info!("⌨️ Command: End");
         }
         "page up" => {
             enigo.key(EnigoKey::PageUp, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Page Up");
+            info!("⌨️ Command: Page Up");
         }
         "page down" => {
             enigo.key(EnigoKey::PageDown, enigo::Direction::Click)?;
-            println!("[SS9K] ⌨️ Command: Page Down");
+            info!("⌨️ Command: Page Down");
         }
 
         // Editing shortcuts
@@ -978,31 +2337,31 @@ pub fn execute_single_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bo
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('a'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Select All");
+            info!("⌨️ Command: Select All");
         }
         "copy" | "copy that" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('c'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Copy");
+            info!("⌨️ Command: Copy");
         }
         "paste" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('v'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Paste");
+            info!("⌨️ Command: Paste");
         }
         "cut" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('x'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Cut");
+            info!("⌨️ Command: Cut");
         }
         "undo" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('z'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Undo");
+            info!("⌨️ Command: Undo");
         }
         "redo" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
@@ -1010,57 +2369,57 @@ pub fn execute_single_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bo
             enigo.key(EnigoKey::Unicode('z'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Shift, enigo::Direction::Release)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Redo");
+            info!("⌨️ Command: Redo");
         }
         "save" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('s'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Save");
+            info!("⌨️ Command: Save");
         }
         "find" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('f'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Find");
+            info!("⌨️ Command: Find");
         }
         "close" | "close tab" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('w'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: Close");
+            info!("⌨️ Command: Close");
         }
         "new tab" => {
             enigo.key(EnigoKey::Control, enigo::Direction::Press)?;
             enigo.key(EnigoKey::Unicode('t'), enigo::Direction::Click)?;
             enigo.key(EnigoKey::Control, enigo::Direction::Release)?;
-            println!("[SS9K] ⌨️ Command: New Tab");
+            info!("⌨️ Command: New Tab");
         }
 
         // Media controls
         "play" | "pause" | "play pause" | "playpause" => {
             enigo.key(EnigoKey::MediaPlayPause, enigo::Direction::Click)?;
-            println!("[SS9K] 🎵 Command: Play/Pause");
+            info!("🎵 Command: Play/Pause");
         }
         "next" | "next track" | "skip" => {
             enigo.key(EnigoKey::MediaNextTrack, enigo::Direction::Click)?;
-            println!("[SS9K] 🎵 Command: Next Track");
+            info!("🎵 Command: Next Track");
         }
         "previous" | "previous track" | "prev" | "back" => {
             enigo.key(EnigoKey::MediaPrevTrack, enigo::Direction::Click)?;
-            println!("[SS9K] 🎵 Command: Previous Track");
+            info!("🎵 Command: Previous Track");
         }
         "volume up" | "louder" => {
             enigo.key(EnigoKey::VolumeUp, enigo::Direction::Click)?;
-            println!("[SS9K] 🔊 Command: Volume Up");
+            info!("🔊 Command: Volume Up");
         }
         "volume down" | "quieter" | "softer" => {
             enigo.key(EnigoKey::VolumeDown, enigo::Direction::Click)?;
-            println!("[SS9K] 🔉 Command: Volume Down");
+            info!("🔉 Command: Volume Down");
         }
         "mute" | "unmute" | "mute toggle" => {
             enigo.key(EnigoKey::VolumeMute, enigo::Direction::Click)?;
-            println!("[SS9K] 🔇 Command: Mute Toggle");
+            info!("🔇 Command: Mute Toggle");
         }
 
         // Help, Config & Info
@@ -1076,28 +2435,282 @@ pub fn execute_single_builtin_command(enigo: &mut Enigo, cmd: &str) -> Result<bo
                 .unwrap_or_else(|| PathBuf::from("~/.config/ss9k/config.toml"));
 
             let editor = std::env::var("EDITOR").unwrap_or_else(|_| "xdg-open".to_string());
-            println!("[SS9K] 📝 Opening config: {:?}", config_path);
+            info!("📝 Opening config: {:?}", config_path);
 
             if let Err(e) = std::process::Command::new(&editor)
                 .arg(&config_path)
                 .spawn()
             {
-                eprintln!("[SS9K] ⚠️ Failed to open config: {}", e);
-                println!("[SS9K] Config path: {:?}", config_path);
+                warn!("⚠️ Failed to open config: {}", e);
+                info!("Config path: {:?}", config_path);
             }
         }
 
         _ => {
-            eprintln!("[SS9K] ⚠️ Unknown command: {}", cmd);
+            warn!("⚠️ Unknown command: {}", privacy::redact(cmd));
             return Ok(false);
         }
     }
     Ok(true)
 }
 
+/// Set the output volume to an absolute level
+pub fn execute_set_volume(enigo: &mut Keys, level: u32) -> Result<bool> {
+    match system::set_volume(enigo, level) {
+        Some(backend) => {
+            info!("🔊 Volume: {}% (via {})", level, backend);
+            tts::say(&format!("volume {} percent", level));
+            Ok(true)
+        }
+        None => {
+            warn!("⚠️ No volume backend available (install wpctl, pactl, or amixer)");
+            Ok(false)
+        }
+    }
+}
+
+/// Set the display brightness to an absolute level
+pub fn execute_set_brightness(level: u32) -> Result<bool> {
+    match system::set_brightness(level) {
+        Some(backend) => {
+            info!("🔆 Brightness: {}% (via {})", level, backend);
+            tts::say(&format!("brightness {} percent", level));
+            Ok(true)
+        }
+        None => {
+            warn!("⚠️ No brightness backend available (install brightnessctl or light)");
+            Ok(false)
+        }
+    }
+}
+
+/// Speak the last dictated text back (needs a TTS backend)
+pub fn execute_read_that() -> Result<bool> {
+    if !tts::enabled() {
+        warn!("⚠️ Read that needs a TTS backend (set tts_backend in config)");
+        return Ok(false);
+    }
+    let last = LAST_TYPED_TEXT.lock().map(|t| t.clone()).unwrap_or_default();
+    if last.trim().is_empty() {
+        warn!("⚠️ Nothing to read");
+        tts::say("nothing to read");
+        return Ok(false);
+    }
+    info!("🗣️ Reading back: {}", last);
+    tts::say(&last);
+    Ok(true)
+}
+
+/// Type the last dictation again, for when the window it was meant for didn't have focus
+pub fn execute_again(enigo: &mut Keys) -> Result<bool> {
+    let last = LAST_TYPED_TEXT.lock().map(|t| t.trim_start().to_string()).unwrap_or_default();
+    if last.is_empty() {
+        warn!("⚠️ Nothing to type again");
+        return Ok(false);
+    }
+    if output::should_paste(&last) {
+        output::paste(enigo, &last)?;
+    } else {
+        enigo.text(&last)?;
+    }
+    output::typed(&last);
+    remember_typed(&last);
+    info!("🔁 Typed again: {}", privacy::redact(&last));
+    Ok(true)
+}
+
+/// Put the mouse grid over the screen; numbers zoom in from there
+pub fn execute_grid(enigo: &mut Keys) -> Result<bool> {
+    match mouse::open_grid(enigo) {
+        Ok(grid) => {
+            info!("🔲 Grid: {}×{}, say a number to zoom in, then \"click\"", grid.width, grid.height);
+            notifications::show(Kind::Result, "🔲 Say a number to zoom in, then \"click\"\n1 2 3\n4 5 6\n7 8 9");
+            Ok(true)
+        }
+        Err(e) => {
+            warn!("⚠️ Can't open the mouse grid: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+/// Say what the clipboard holds (and log it, `privacy` permitting)
+pub fn execute_read_clipboard() -> Result<bool> {
+    let Some(text) = output::clipboard_text() else {
+        warn!("⚠️ Nothing on the clipboard");
+        tts::say("clipboard is empty");
+        return Ok(false);
+    };
+    info!("📋 Clipboard: {}", privacy::redact(&text));
+    tts::say(&text);
+    Ok(true)
+}
+
+/// Pause between characters for "type clipboard slowly"
+const SLOW_TYPING_DELAY: Duration = Duration::from_millis(30);
+
+/// Type what the clipboard holds, for fields that block pasting; `slowly`
+/// types a character at a time for apps that drop fast input
+pub fn execute_type_clipboard(enigo: &mut Keys, slowly: bool) -> Result<bool> {
+    let Some(text) = output::clipboard_text() else {
+        warn!("⚠️ Nothing on the clipboard");
+        return Ok(false);
+    };
+    if slowly {
+        let mut buf = [0; 4];
+        for c in text.chars() {
+            enigo.text(c.encode_utf8(&mut buf))?;
+            std::thread::sleep(SLOW_TYPING_DELAY);
+        }
+    } else {
+        enigo.text(&text)?;
+    }
+    output::typed(&text);
+    remember_typed(&text);
+    info!("📋 Typed the clipboard: {}", privacy::redact(&text));
+    Ok(true)
+}
+
+/// Start a voice timer: "five minutes named tea"
+pub fn execute_set_timer(spec: &str) -> Result<bool> {
+    let Some((duration, name)) = timers::parse_timer(spec) else {
+        warn!("⚠️ Couldn't read a timer from '{}' (try \"set timer five minutes named tea\")", spec);
+        return Ok(false);
+    };
+    let timer = timers::start(&name, duration);
+    info!("⏲️ Timer '{}' set: {}", timer.name, timers::format_remaining(&timer));
+    tts::say(&format!("{} timer set", timer.name));
+    Ok(true)
+}
+
+/// Say which timers are running and how long each has left
+pub fn execute_list_timers() -> Result<bool> {
+    let running = timers::list();
+    if running.is_empty() {
+        info!("⏲️ No timers running");
+        tts::say("no timers");
+        return Ok(true);
+    }
+    let lines: Vec<String> = running.iter().map(|t| format!("{}: {} left", t.name, timers::format_remaining(t))).collect();
+    for line in &lines {
+        info!("⏲️ {}", line);
+    }
+    notifications::show(Kind::Result, &lines.join("\n"));
+    tts::say(&lines.join(", "));
+    Ok(true)
+}
+
+/// "status" or "what mode", which need the config so aren't in `execute_builtin_command`
+fn is_status_query(cmd: &str) -> bool {
+    let words: Vec<String> = cmd.split_whitespace().map(strip_punct).collect();
+    matches!(words.join(" ").as_str(), "status" | "what mode" | "what mode am i in")
+}
+
+/// Say which sticky state is active: case mode, held keys, profile, model and language
+pub fn execute_status(config: &Config) -> Result<bool> {
+    let status = control::status(config);
+    let held = if status.held_keys.is_empty() { "none".to_string() } else { status.held_keys.join(", ") };
+    let profile = if status.profile.is_empty() { "none" } else { &status.profile };
+    let lines = [
+        format!("Mode: {}", status.mode),
+        format!("Held keys: {}", held),
+        format!("Profile: {}", profile),
+        format!("Model: {}", status.model),
+        format!("Language: {}", status.language),
+    ];
+    for line in &lines {
+        info!("ℹ️ {}", line);
+    }
+    notifications::show(Kind::Result, &lines.join("\n"));
+    tts::say(&lines.join(", "));
+    Ok(true)
+}
+
+/// Stop a timer by name, or the newest one
+pub fn execute_cancel_timer(name: Option<&str>) -> Result<bool> {
+    match timers::cancel(name) {
+        Some(timer) => {
+            info!("⏲️ Timer '{}' cancelled", timer.name);
+            tts::say(&format!("{} timer cancelled", timer.name));
+            Ok(true)
+        }
+        None => {
+            warn!("⚠️ No timer {}", name.map_or("running".to_string(), |n| format!("named '{}'", n.trim())));
+            Ok(false)
+        }
+    }
+}
+
+/// Turn desktop do-not-disturb on or off
+pub fn execute_do_not_disturb(enabled: bool) -> Result<bool> {
+    match system::set_do_not_disturb(enabled) {
+        Some(backend) => {
+            // A manual toggle takes over from any mode-driven DND
+            DND_FROM_MODE.store(false, Ordering::SeqCst);
+            let state = if enabled { "on" } else { "off" };
+            info!("🔕 Do not disturb: {} (via {})", state, backend);
+            tts::say(&format!("do not disturb {}", state));
+            Ok(true)
+        }
+        None => {
+            warn!("⚠️ No do-not-disturb backend available (swaync, dunst, mako, or GNOME)");
+            Ok(false)
+        }
+    }
+}
+
+/// Turn privacy mode on or off (until the next config reload)
+pub fn execute_privacy(enabled: bool) -> Result<bool> {
+    privacy::set(enabled);
+    let state = if enabled { "on" } else { "off" };
+    info!("🙈 Privacy mode: {}", state);
+    tts::say(&format!("privacy {}", state));
+    Ok(true)
+}
+
+/// Send dictation to another output sink (until the next config reload)
+/// "default" goes back to `output_mode`
+pub fn execute_output(name: &str) -> Result<bool> {
+    if name == "default" {
+        output::set_mode(None);
+    } else if output::SINKS.contains(&name) {
+        output::set_mode(Some(name.to_string()));
+    } else {
+        warn!("⚠️ Unknown output '{}' ({}, default)", name, output::SINKS.join(", "));
+        return Ok(false);
+    }
+    info!("📤 Output: {}", name);
+    tts::say(&format!("output {}", name));
+    Ok(true)
+}
+
+/// Enable do-not-disturb while the current mode is listed in `dnd_modes`
+/// Turns it back off when leaving the mode, but only if we turned it on
+pub fn sync_mode_dnd(dnd_modes: &[String]) {
+    if dnd_modes.is_empty() && !DND_FROM_MODE.load(Ordering::SeqCst) {
+        return;
+    }
+
+    let mode = get_case_mode();
+    let wants_dnd = dnd_modes.iter().filter_map(|name| parse_mode_name(name)).any(|m| m == mode);
+    let dnd_on = DND_FROM_MODE.load(Ordering::SeqCst);
+
+    if wants_dnd && !dnd_on {
+        if let Some(backend) = system::set_do_not_disturb(true) {
+            DND_FROM_MODE.store(true, Ordering::SeqCst);
+            info!("🔕 Do not disturb: on for {:?} mode (via {})", mode, backend);
+        }
+    } else if !wants_dnd && dnd_on {
+        DND_FROM_MODE.store(false, Ordering::SeqCst);
+        if let Some(backend) = system::set_do_not_disturb(false) {
+            info!("🔔 Do not disturb: off (via {})", backend);
+        }
+    }
+}
+
 /// Execute shift-modified commands (for selections and shift+key combos)
 /// Supports "times N" suffix for repetition
-pub fn execute_shift(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
+pub fn execute_shift(enigo: &mut Keys, cmd: &str) -> Result<bool> {
     let (base_cmd, count) = parse_times_suffix(cmd);
     let times = count.max(1);
 
@@ -1132,7 +2745,7 @@ pub fn execute_shift(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
 
             _ => {
                 enigo.key(EnigoKey::Shift, enigo::Direction::Release)?;
-                eprintln!("[SS9K] ⚠️ Unknown shift command: {}", base_cmd);
+                warn!("⚠️ Unknown shift command: {}", base_cmd);
                 return Ok(false);
             }
         };
@@ -1150,16 +2763,16 @@ pub fn execute_shift(enigo: &mut Enigo, cmd: &str) -> Result<bool> {
     enigo.key(EnigoKey::Shift, enigo::Direction::Release)?;
 
     if times > 1 {
-        println!("[SS9K] ⇧ Shift+{} × {}", base_cmd, times);
+        info!("⇧ Shift+{} × {}", base_cmd, times);
     } else {
-        println!("[SS9K] ⇧ Shift+{}", base_cmd);
+        info!("⇧ Shift+{}", base_cmd);
     }
 
     Ok(true)
 }
 
-/// Execute spell mode - spell out letters using NATO phonetic, raw letters, or numbers
-pub fn execute_spell_mode(enigo: &mut Enigo, input: &str) -> Result<bool> {
+/// Execute spell mode - spell out letters using the spelling alphabet, raw letters, or numbers
+pub fn execute_spell_mode(enigo: &mut Keys, input: &str) -> Result<bool> {
     let words: Vec<&str> = input.split_whitespace().collect();
     let mut result = String::new();
     let mut next_capital = false;
@@ -1172,23 +2785,24 @@ pub fn execute_spell_mode(enigo: &mut Enigo, input: &str) -> Result<bool> {
 
         if let Some(ch) = word_to_char(word) {
             if next_capital {
-                result.push(ch.to_ascii_uppercase());
+                result.extend(ch.to_uppercase());
                 next_capital = false;
             } else {
                 result.push(ch);
             }
         } else {
-            eprintln!("[SS9K] ⚠️ Unknown spell word: {}", word);
+            warn!("⚠️ Unknown spell word: {}", word);
         }
     }
 
     if result.is_empty() {
-        eprintln!("[SS9K] ⚠️ Spell mode produced no characters");
+        warn!("⚠️ Spell mode produced no characters");
         return Ok(false);
     }
 
     enigo.text(&result)?;
-    println!("[SS9K] 🔤 Spelled: {}", result);
+    output::typed(&result);
+    info!("🔤 Spelled: {}", privacy::redact(&result));
     Ok(true)
 }
 
@@ -1201,13 +2815,13 @@ pub fn set_key_repeat_ms(ms: u64) {
 fn spawn_hold_thread() {
     if HOLD_THREAD_RUNNING.compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst).is_ok() {
         std::thread::spawn(|| {
-            println!("[SS9K] 🔄 Hold thread started");
+            info!("🔄 Hold thread started");
 
-            // Create our own Enigo instance for this thread
-            let mut enigo = match Enigo::new(&Settings::default()) {
+            // Open our own keyboard for this thread
+            let mut enigo = match Keys::new() {
                 Ok(e) => e,
                 Err(e) => {
-                    eprintln!("[SS9K] ❌ Hold thread failed to create Enigo: {}", e);
+                    error!("❌ Hold thread failed to open the keyboard: {}", e);
                     HOLD_THREAD_RUNNING.store(false, Ordering::SeqCst);
                     return;
                 }
@@ -1229,7 +2843,7 @@ fn spawn_hold_thread() {
                 // Click all held keys together
                 for key in &keys {
                     if let Err(e) = enigo.key(key.clone(), enigo::Direction::Click) {
-                        eprintln!("[SS9K] ⚠️ Hold thread key error: {}", e);
+                        warn!("⚠️ Hold thread key error: {}", e);
                     }
                 }
 
@@ -1237,17 +2851,17 @@ fn spawn_hold_thread() {
             }
 
             HOLD_THREAD_RUNNING.store(false, Ordering::SeqCst);
-            println!("[SS9K] 🔄 Hold thread stopped");
+            info!("🔄 Hold thread stopped");
         });
     }
 }
 
 /// Hold a key down (add to held keys, spawn spam thread)
-pub fn execute_hold(_enigo: &mut Enigo, key_name: &str) -> Result<bool> {
+pub fn execute_hold(_enigo: &mut Keys, key_name: &str) -> Result<bool> {
     let key = match parse_key_name(key_name) {
         Some(k) => k,
         None => {
-            eprintln!("[SS9K] ⚠️ Unknown key to hold: {}", key_name);
+            warn!("⚠️ Unknown key to hold: {}", key_name);
             return Ok(false);
         }
     };
@@ -1260,16 +2874,16 @@ pub fn execute_hold(_enigo: &mut Enigo, key_name: &str) -> Result<bool> {
     // Spawn hold thread if not running
     spawn_hold_thread();
 
-    println!("[SS9K] 🔒 Holding: {}", key_name);
+    info!("🔒 Holding: {}", key_name);
     Ok(true)
 }
 
 /// Release a specific held key (remove from set, thread will stop clicking it)
-pub fn execute_release(_enigo: &mut Enigo, key_name: &str) -> Result<bool> {
+pub fn execute_release(_enigo: &mut Keys, key_name: &str) -> Result<bool> {
     let key = match parse_key_name(key_name) {
         Some(k) => k,
         None => {
-            eprintln!("[SS9K] ⚠️ Unknown key to release: {}", key_name);
+            warn!("⚠️ Unknown key to release: {}", key_name);
             return Ok(false);
         }
     };
@@ -1278,12 +2892,29 @@ pub fn execute_release(_enigo: &mut Enigo, key_name: &str) -> Result<bool> {
         held.remove(&HeldKey(key));
     }
 
-    println!("[SS9K] 🔓 Released: {}", key_name);
+    info!("🔓 Released: {}", key_name);
     Ok(true)
 }
 
+/// Names of the keys "hold" is repeating, sorted
+pub fn held_keys() -> Vec<String> {
+    let mut names: Vec<String> = HELD_KEYS
+        .lock()
+        .map(|held| {
+            held.iter()
+                .map(|key| match key.0 {
+                    EnigoKey::Unicode(c) => c.to_string(),
+                    ref other => format!("{:?}", other).to_lowercase(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
 /// Release all held keys (clear set, thread will exit)
-pub fn execute_release_all(_enigo: &mut Enigo) -> Result<bool> {
+pub fn execute_release_all(_enigo: &mut Keys) -> Result<bool> {
     let count = if let Ok(mut held) = HELD_KEYS.lock() {
         let c = held.len();
         held.clear();
@@ -1293,14 +2924,54 @@ pub fn execute_release_all(_enigo: &mut Enigo) -> Result<bool> {
     };
 
     if count == 0 {
-        println!("[SS9K] 🔓 No keys held");
+        info!("🔓 No keys held");
         return Ok(true);
     }
 
-    println!("[SS9K] 🔓 Released {} key(s)", count);
+    info!("🔓 Released {} key(s)", count);
     Ok(true)
 }
 
+/// Modifiers released on shutdown in case a combo was cut off mid-press
+const MODIFIERS: [EnigoKey; 4] = [EnigoKey::Shift, EnigoKey::Control, EnigoKey::Alt, EnigoKey::Meta];
+
+/// Let go of everything before exiting: clear held keys, wait for the hold
+/// thread to stop, then send key-ups for those keys and every modifier
+/// (works with poisoned locks, so it's safe to call after a panic)
+pub fn release_everything() {
+    let held: Vec<EnigoKey> = HELD_KEYS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .drain()
+        .map(|k| k.0)
+        .collect();
+
+    // The hold thread notices the empty set on its next pass
+    let wait = Duration::from_millis(KEY_REPEAT_MS.load(Ordering::SeqCst) + 200);
+    let start = std::time::Instant::now();
+    while HOLD_THREAD_RUNNING.load(Ordering::SeqCst) && start.elapsed() < wait {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    match Keys::new() {
+        Ok(mut enigo) => {
+            for key in held.into_iter().chain(MODIFIERS) {
+                let _ = enigo.key(key, enigo::Direction::Release);
+            }
+        }
+        Err(e) => warn!("⚠️ Couldn't release keys: {}", e),
+    }
+}
+
+/// On any panic, print it as usual and then release keys (see `release_everything`)
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        release_everything();
+    }));
+}
+
 /// Print the help/command reference
 pub fn print_help() {
     println!();
@@ -1315,9 +2986,28 @@ pub fn print_help() {
     println!("║             [leader] page up, page down                      ║");
     println!("║ EDITING:    [leader] select all, copy, paste, cut, undo, redo║");
     println!("║             [leader] save, find, close tab, new tab          ║");
+    println!("║             [leader] scratch that, scratch word / sentence   ║");
+    println!("║             [leader] correct [wrong] to [right]              ║");
+    println!("║             [leader] again (types your last dictation again) ║");
+    println!("║             [leader] [mode] selection (snake selection...)   ║");
     println!("║ MEDIA:      [leader] play, pause, next, previous, mute       ║");
     println!("║             [leader] volume up, volume down                  ║");
+    println!("║             [leader] volume [N] percent, brightness [N]      ║");
+    println!("║ SYSTEM:     [leader] do not disturb on, do not disturb off   ║");
+    println!("║             [leader] privacy on, privacy off                 ║");
+    println!("║ OUTPUT:     [leader] output clipboard, output type...        ║");
+    println!("║ MOUSE:      [leader] mouse up/down/left/right [N]            ║");
+    println!("║             [leader] click, right click, double click        ║");
+    println!("║             [leader] scroll up/down/left/right [N]           ║");
+    println!("║             [leader] grid, then 1-9 to zoom in, click        ║");
+    println!("║ SPEECH:     [leader] read that (needs tts_backend)           ║");
+    println!("║ CLIPBOARD:  [leader] read clipboard, type clipboard [slowly] ║");
+    println!("║ STATUS:     [leader] status, what mode                       ║");
+    println!("║ DICTATION:  [leader] full stop (ends a VAD paragraph)        ║");
     println!("║ REPETITION: [leader] [cmd] times [N], repeat, repeat [N]     ║");
+    println!("║ TIMING:     [leader] wait [N] seconds / milliseconds         ║");
+    println!("║             [leader] set timer [N] minutes named [X]         ║");
+    println!("║             [leader] list timers, cancel timer [X]           ║");
     println!("╠══════════════════════════════════════════════════════════════╣");
     println!("║ SUBCOMMANDS:                                                 ║");
     println!("║   [leader] shift [X]   - select (shift+arrow, shift+word)    ║");
@@ -1325,12 +3015,18 @@ pub fn print_help() {
     println!("║   [leader] hold [X]    - hold a key (gaming, accessibility)  ║");
     println!("║   [leader] release [X] - release held key(s)                 ║");
     println!("║   [leader] emoji [X]   - insert emoji (smile, fire, etc.)    ║");
+    println!("║   [leader] unicode [X] - any character, by name or code point║");
     println!("║   [leader] punctuation [X] - insert symbol (comma, arrow)    ║");
     println!("║   [leader] insert [X]  - insert snippet from config          ║");
     println!("║   [leader] wrap [X] [text] - wrap text (quotes, parens, etc) ║");
+    println!("║   [leader] to [X] [text] - send to clipboard, file, stdout...║");
+    println!("║   [leader] run and type [X] - type a [commands] output       ║");
     println!("║   [leader] mode [X]    - modes: snake, camel, pascal, kebab, ║");
-    println!("║                          screaming, caps, lower, math, code, ║");
-    println!("║                          alternating, swearing, off          ║");
+    println!("║                          dot, path, screaming, caps, lower,  ║");
+    println!("║                          math, code, alternating, swearing,  ║");
+    println!("║                          vim, emacs, off                     ║");
+    println!("║   [leader] snake [text] - [text] in a mode, just this once   ║");
+    println!("║   [leader] profile [X] - switch [profiles.X] (off = none)    ║");
     println!("╠══════════════════════════════════════════════════════════════╣");
     println!("║ INFO:       [leader] languages - list supported languages      ║");
     println!("║ CONFIG:     ~/.config/ss9k/config.toml                       ║");
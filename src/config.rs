@@ -0,0 +1,1039 @@
+//! Configuration for SS9K
+//!
+//! This module handles:
+//! - The config file schema and defaults
+//! - Finding, creating, and parsing config.toml
+//! - Merging in the files listed in `include` (drop-in directories)
+//! - Command line overrides (`--model`, `--config`...), kept across reloads
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::{info, warn};
+
+use crate::error::ConfigError;
+use crate::migrate::{migrate, CONFIG_VERSION};
+use crate::profiles;
+
+/// Configuration for SS9K
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub config_version: u32, // Config shape version (see migrate)
+    pub include: Vec<String>, // More config files to merge in (globs, relative to this file)
+    pub model: String,
+    pub language: String,
+    pub threads: usize,
+    pub device: String,
+    pub audio_source: String,        // Listen for network audio on tcp://, udp:// or ws:// host:port ("" = microphone)
+    pub audio_source_format: String, // Layout of network audio: encoding:rate:channels
+    pub audio_source_allow: Vec<String>, // IP addresses that may send network audio, besides this machine
+    pub hotkey: String,
+    pub command_hotkey: String, // Alternate hotkey that auto-prefixes with leader word
+    pub abort_hotkey: String,   // Throws away the recording and queued audio, releases held keys
+    pub hotkey_mode: String,
+    pub hotkey_backend: String, // How hotkeys are read: "rdev" (X11, Windows, macOS) or "evdev" (/dev/input)
+    pub hotkey_device: String,  // evdev: a /dev/input path or part of a device name ("" = all keyboards and mice)
+    pub trigger_device: String, // Foot pedal/clicker vendor:product ID whose buttons act as the hotkey ("" = off)
+    pub gamepad_button: String, // Controller button that acts as the hotkey, gamepad feature ("" = off)
+    pub midi_device: String,    // MIDI input port (part of its name), midi feature ("" = off)
+    pub toggle_timeout_secs: u64,
+    pub leader: String,
+    pub leaderless_commands: Vec<String>, // Builtins that work without the leader (e.g. "escape")
+    pub fuzzy_match: String,      // "off", "levenshtein" or "jaro-winkler": near misses of a phrase still run it
+    pub fuzzy_threshold: f64,     // Similarity (0-1) a near miss needs, with fuzzy_match on
+    pub phonetic_match: bool,     // Phrases and built-ins that sound the same still run ("knew tab")
+    pub spelling_alphabet: String, // "nato", "german" or "none": the letter words "command spell" knows
+    pub confirm_timeout_secs: u64, // How long a "say yes to confirm" prompt waits for the answer
+    pub key_repeat_ms: u64,
+    pub processing_timeout_secs: u64, // 0 = no timeout
+    #[serde(default)]
+    pub audio_feedback: bool, // Sound cues on start/stop/command/error
+    pub sound_start: String,   // Cue sound files (empty = generated tone)
+    pub sound_stop: String,
+    pub sound_command: String,
+    pub sound_error: String,
+    pub sound_timer: String,   // When a voice timer ends (plays even with audio_feedback off)
+    pub notifications: String,     // Desktop notifications: "off", "errors", "results", "all"
+    pub http_port: u16,            // Localhost HTTP server port, 0 = off (http feature)
+    pub remote_listen: String,     // host:port for the LAN record trigger ("" = off, http feature)
+    pub remote_token: String,      // Secret every remote request must carry (16+ characters)
+    // MQTT (mqtt feature)
+    pub mqtt_broker: String,       // Broker host ("" = off)
+    pub mqtt_port: u16,
+    pub mqtt_username: String,     // Empty = no login
+    pub mqtt_password: String,
+    pub mqtt_topic: String,        // Topic prefix: <prefix>/state, /transcription, /command
+    // Switch access
+    pub switch_key: String,        // Key/button the switch sends ("" = off)
+    pub switch_short: String,      // Action for a short press
+    pub switch_long: String,       // Action for a long press
+    pub switch_double: String,     // Action for a double press
+    pub switch_long_press_ms: u64, // Hold this long for a long press
+    pub switch_double_press_ms: u64, // Max gap between double presses
+    pub switch_scan_items: Vec<String>, // Actions "scan" steps through
+    // VAD settings
+    pub activation_mode: String,   // "hotkey" (default) or "vad"
+    pub vad_sensitivity: f32,      // 0.0-1.0, higher = more sensitive
+    pub vad_silence_ms: u64,       // Silence duration before processing
+    pub vad_min_speech_ms: u64,    // Minimum speech before valid
+    pub vad_speech_pad_ms: u64,    // Padding added to end of speech
+    pub vad_adaptive_silence: bool, // Adapt silence timeout to your pauses
+    pub vad_silence_min_ms: u64,   // Adaptive timeout lower bound
+    pub vad_silence_max_ms: u64,   // Adaptive timeout upper bound
+    pub vad_paragraph_mode: bool,  // Combine consecutive utterances before typing
+    pub vad_paragraph_gap_ms: u64, // Pause that ends a paragraph
+    pub wake_word: String,         // Wake word for VAD mode (empty = disabled)
+    pub pause_on_screen_share: bool, // Pause VAD listening while a screen share is active
+    pub screen_share_check: String,  // Custom screen share check command (empty = built-in)
+    // Text-to-speech
+    pub tts_backend: String,       // "espeak-ng", "piper", "say", a custom command, or empty = off
+    pub tts_voice: String,         // espeak voice, piper .onnx model path, or say voice
+    // Output
+    pub output_mode: String,       // "type" (default), "clipboard", "stdout", "file", "webhook", "exec:<command>" or "homeassistant"
+    pub output_file: String,       // File the "file" output appends to
+    pub output_webhook: String,    // URL the "webhook" output POSTs to
+    pub clipboard_notify: bool,    // Notify when dictation goes to the clipboard, even with notifications off
+    pub paste_min_chars: usize,    // Paste dictation this long via the clipboard instead of typing it, 0 = never
+    pub auto_capitalize: bool,     // Capitalize typed dictation that starts a sentence (after . ! ? or at the start)
+    pub smart_spacing: bool,       // Put a space between typed dictation and the text typed before it
+    pub remove_fillers: bool,      // Strip "um", "uh", "you know"... from dictation (lists follow `language`)
+    pub filler_words: Vec<String>, // More words and phrases remove_fillers strips wherever they are
+    pub numbers: String,           // Spoken numbers in dictation: "spoken" (as Whisper wrote them), "digits" or "formatted"
+    pub swear_style: String,       // What swearing mode turns a swear into: "grawlix", "asterisks", "first-letter" or "remove"
+    pub swear_words: Vec<String>,  // More words swearing mode censors, on top of the list for `language`
+    pub grawlix: String,           // Symbols the "grawlix" style cycles through
+    pub keyboard_backend: String,  // How keys reach the desktop: "enigo", "wtype", "ydotool" or "uinput"
+    pub homeassistant_url: String, // e.g. "http://homeassistant.local:8123"
+    pub homeassistant_token: String, // Long-lived access token
+    pub homeassistant_agent: String, // Conversation agent id (empty = default)
+    pub homeassistant_speak: bool, // Speak Home Assistant's reply (needs tts_backend)
+    pub captions_file: String,     // Live captions file for OBS (empty = off)
+    pub captions_lines: usize,     // Lines of captions to keep
+    pub captions_clear_secs: u64,  // Clear captions after this long without speech, 0 = never
+    // Presence
+    pub dnd_modes: Vec<String>,    // Modes that turn on do-not-disturb while active
+    // Case modes
+    pub mode_revert: String,       // "never" or "utterance": back to off after one utterance
+    pub mode_timeout_secs: u64,    // Back to off after this long without dictation in the mode, 0 = never
+    // Logging
+    pub dictation_log: String,     // Path to log transcriptions (empty = disabled)
+    pub error_log: String,         // Path to log errors (empty = disabled)
+    pub log_level: String,         // "error".."trace" or a filter; empty = debug if verbose, else info
+    pub log_file: String,          // JSON log file (empty = disabled)
+    pub log_rotation: String,      // "daily", "hourly" or "never"
+    pub save_history: bool,        // Keep every transcription in history_file
+    pub history_file: String,      // JSONL history (empty = <data dir>/ss9k/history.jsonl)
+    pub save_stats: bool,          // Record per-utterance timings/word counts for `ss9k stats`
+    pub privacy: bool,             // Never log/save/notify transcribed text, only its length
+    pub script: String,            // Rhai hooks file (scripting feature), empty = none
+    pub plugins: Vec<String>,      // WebAssembly plugin files (plugins feature), globs relative to this file
+    pub hook_pre_record: String,   // Shell command run when recording starts, empty = none
+    pub hook_post_transcribe: String, // Shell command run with each transcription on stdin, empty = none
+    #[serde(default)]
+    pub commands: HashMap<String, String>,
+    #[serde(default)]
+    pub keybinds: HashMap<String, String>, // Spoken phrase -> key chords, "ctrl+alt+t" or "ctrl+k ctrl+c"
+    #[serde(default)]
+    pub macros: HashMap<String, Vec<String>>, // Spoken phrase -> steps run in order, see commands::parse_step
+    #[serde(default)]
+    pub command_settings: HashMap<String, CommandSettings>, // [command_settings.<phrase>], options for a [commands] entry
+    #[serde(default)]
+    pub priorities: HashMap<String, i64>, // Phrase -> priority when several phrases match (higher wins, default 0)
+    #[serde(default)]
+    pub patterns: Vec<Pattern>, // [[patterns]]: regex over the utterance -> shell, keys or steps
+    #[serde(default)]
+    pub replacements: Vec<Replacement>, // [[replacements]]: regex -> text, rewrites every utterance after [aliases]
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    #[serde(default)]
+    pub command_aliases: HashMap<String, String>, // Your words for built-ins: "zap" = "backspace", "nuke line" = "shift end, backspace"
+    #[serde(default)]
+    pub inserts: HashMap<String, String>,
+    #[serde(default)]
+    pub wrappers: HashMap<String, String>,
+    #[serde(default)]
+    pub punctuation: HashMap<String, String>, // Spoken name -> symbol for "command punctuation <name>", on top of the built-ins
+    #[serde(default)]
+    pub emoji: HashMap<String, String>,       // Spoken name -> emoji for "command emoji <name>", on top of the built-ins
+    #[serde(default)]
+    pub spelling: HashMap<String, String>,    // Spoken word -> one character for "command spell", before spelling_alphabet
+    #[serde(default)]
+    pub profiles: HashMap<String, Profile>, // [profiles.<name>], see profiles
+    #[serde(default)]
+    pub apps: HashMap<String, String>,     // Window class -> profile while it's focused, see apps
+    #[serde(default)]
+    pub midi: HashMap<String, String>,     // "note 60" / "cc 64" -> record, command, abort or "mode <name>"
+    #[serde(default)]
+    pub plugin_settings: HashMap<String, toml::Table>, // [plugin_settings.<name>], handed to the plugin as JSON
+    #[serde(default)]
+    pub security: Security, // [security], limits on what spoken commands may run
+    #[serde(default)]
+    pub verbose: bool,
+}
+
+/// Options for one `[commands]` entry, under `[command_settings.<phrase>]`
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct CommandSettings {
+    pub confirm: bool,                // Ask "say yes to confirm" before running it
+    pub cwd: String,                  // Directory to run it in (supports ~ and $VARS), empty = ss9k's own
+    pub env: HashMap<String, String>, // Extra environment variables for it
+}
+
+/// The `[security]` table, see security
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Security {
+    pub allowed_programs: Vec<String>, // Programs shell commands may start, as written in them (empty = any)
+    pub shell: bool,                   // Run through sh -c (cmd /C on Windows); false = split into words, run the first
+    pub refuse_chars: String,          // Never run a command containing any of these characters
+}
+
+impl Default for Security {
+    fn default() -> Self {
+        Self {
+            allowed_programs: Vec::new(),
+            shell: true,
+            refuse_chars: String::new(),
+        }
+    }
+}
+
+/// A `[[patterns]]` entry: a regex and what to do when it matches
+/// Exactly one of `shell`, `keys` and `steps` is set; `{1}` or `{name}` in it
+/// takes that capture group.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Pattern {
+    #[serde(rename = "match")]
+    pub regex: String,      // Case-insensitive, matched against the whole utterance
+    pub shell: String,      // Shell command, like [commands]
+    pub keys: String,       // Key chords, like [keybinds]
+    pub steps: Vec<String>, // Steps, like [macros]
+}
+
+/// A `[[replacements]]` entry: a regex and what each match of it becomes
+/// `{1}` or `{name}` in `with` takes that capture group.
+#[derive(Debug, Default, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Replacement {
+    #[serde(rename = "match")]
+    pub regex: String, // Case-insensitive, matched anywhere in the utterance
+    pub with: String,  // Text that replaces each match
+}
+
+/// A named set of commands/aliases layered over the top-level ones
+#[derive(Debug, Default, Deserialize, Serialize, Clone)]
+#[serde(default)]
+pub struct Profile {
+    pub mode: String, // Case mode to switch to when selected (empty = leave it)
+    pub commands: HashMap<String, String>,
+    pub aliases: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            config_version: CONFIG_VERSION,
+            include: Vec::new(),
+            model: "small".to_string(),
+            language: "en".to_string(),
+            threads: 4,
+            device: String::new(),
+            audio_source: String::new(), // Microphone
+            audio_source_format: "s16le:16000:1".to_string(),
+            audio_source_allow: Vec::new(), // Only this machine
+            hotkey: "F12".to_string(),
+            command_hotkey: String::new(), // Empty = disabled
+            abort_hotkey: String::new(),   // Empty = disabled
+            hotkey_mode: "hold".to_string(),
+            hotkey_backend: "rdev".to_string(),
+            hotkey_device: String::new(), // Every keyboard and mouse
+            trigger_device: String::new(), // No pedal
+            gamepad_button: String::new(), // No controller
+            midi_device: String::new(),    // No MIDI
+            toggle_timeout_secs: 0,
+            leader: "command".to_string(),
+            leaderless_commands: Vec::new(), // Empty = leader always required
+            fuzzy_match: "off".to_string(),
+            fuzzy_threshold: 0.9,
+            phonetic_match: false,
+            spelling_alphabet: "nato".to_string(),
+            confirm_timeout_secs: 10,
+            key_repeat_ms: 50,
+            processing_timeout_secs: 30, // Default 30s timeout
+            audio_feedback: false,       // Disabled by default
+            sound_start: String::new(),  // Empty = generated tones
+            sound_stop: String::new(),
+            sound_command: String::new(),
+            sound_error: String::new(),
+            sound_timer: String::new(),
+            notifications: "off".to_string(), // Console only by default
+            http_port: 0,                // HTTP server off
+            remote_listen: String::new(), // Remote trigger off
+            remote_token: String::new(),
+            // MQTT defaults
+            mqtt_broker: String::new(),  // Not connected
+            mqtt_port: 1883,
+            mqtt_username: String::new(),
+            mqtt_password: String::new(),
+            mqtt_topic: "ss9k".to_string(),
+            // Switch access defaults
+            switch_key: String::new(),             // No switch
+            switch_short: "record".to_string(),    // Toggle recording
+            switch_long: "release all".to_string(), // Let go of held keys
+            switch_double: "repeat".to_string(),   // Repeat last command
+            switch_long_press_ms: 600,
+            switch_double_press_ms: 350,
+            switch_scan_items: ["record", "repeat", "release all", "enter", "escape"]
+                .iter().map(|s| s.to_string()).collect(),
+            // VAD defaults
+            activation_mode: "hotkey".to_string(), // Default to hotkey mode
+            vad_sensitivity: 0.9,                  // High sensitivity for reliable detection
+            vad_silence_ms: 1000,                  // 1 second - tolerates natural pauses
+            vad_min_speech_ms: 200,                // Filter brief noises
+            vad_speech_pad_ms: 300,                // Pad end of speech to catch trailing words
+            vad_adaptive_silence: false,           // Fixed vad_silence_ms unless enabled
+            vad_silence_min_ms: 400,               // Quick commands still process fast
+            vad_silence_max_ms: 2500,              // Never wait longer than this
+            vad_paragraph_mode: false,             // Type each utterance as it comes
+            vad_paragraph_gap_ms: 3000,            // 3 seconds of quiet types the paragraph
+            wake_word: String::new(),              // Empty = no wake word required
+            pause_on_screen_share: false,          // Opt-in
+            screen_share_check: String::new(),     // Empty = built-in PipeWire check
+            tts_backend: String::new(),            // Off by default
+            tts_voice: String::new(),              // Backend default voice
+            output_mode: "type".to_string(),       // Type at the cursor
+            output_file: String::new(),            // Unset
+            output_webhook: String::new(),         // Unset
+            clipboard_notify: true,                // Say it's ready to paste
+            paste_min_chars: 0,                    // Always type
+            auto_capitalize: false,
+            smart_spacing: false,
+            remove_fillers: false,
+            filler_words: Vec::new(),
+            numbers: "spoken".to_string(),         // Leave them as Whisper wrote them
+            swear_style: "grawlix".to_string(),    // fuck -> @#$%
+            swear_words: Vec::new(),
+            grawlix: "@#$%!&*".to_string(),
+            keyboard_backend: "enigo".to_string(), // X11, Windows, macOS
+            homeassistant_url: String::new(),
+            homeassistant_token: String::new(),
+            homeassistant_agent: String::new(),    // Home Assistant's default agent
+            homeassistant_speak: true,             // Replies are the point
+            captions_file: String::new(),          // No captions
+            captions_lines: 2,
+            captions_clear_secs: 5,
+            dnd_modes: Vec::new(),                 // Empty = never automatic
+            mode_revert: "never".to_string(),      // Modes stay on until changed
+            mode_timeout_secs: 0,
+            // Logging defaults
+            dictation_log: String::new(),          // Empty = disabled
+            error_log: String::new(),              // Empty = disabled
+            log_level: String::new(),              // Follow verbose
+            log_file: String::new(),               // Empty = disabled
+            log_rotation: "daily".to_string(),
+            save_history: true,                    // Opt-out
+            history_file: String::new(),           // Default location
+            save_stats: true,                      // Opt-out (never stores text)
+            privacy: false,                        // Off
+            script: String::new(),                 // No hooks
+            plugins: Vec::new(),                   // None
+            hook_pre_record: String::new(),        // None
+            hook_post_transcribe: String::new(),   // None
+            commands: HashMap::new(),
+            keybinds: HashMap::new(),
+            macros: HashMap::new(),
+            command_settings: HashMap::new(),
+            priorities: HashMap::new(),
+            patterns: Vec::new(),
+            replacements: Vec::new(),
+            aliases: HashMap::new(),
+            command_aliases: HashMap::new(),
+            inserts: HashMap::new(),
+            wrappers: HashMap::new(),
+            punctuation: HashMap::new(),
+            emoji: HashMap::new(),
+            spelling: HashMap::new(),
+            profiles: HashMap::new(),
+            apps: HashMap::new(),
+            midi: HashMap::new(),
+            plugin_settings: HashMap::new(),
+            security: Security::default(),
+            verbose: true,
+        }
+    }
+}
+
+/// Settings given on the command line; they win over the config file
+#[derive(Clone, Debug, Default)]
+pub struct Overrides {
+    pub config: Option<PathBuf>,  // Use this file instead of searching
+    pub model: Option<String>,
+    pub language: Option<String>,
+    pub device: Option<String>,
+    pub hotkey: Option<String>,
+    pub output: Option<String>,   // output_mode
+}
+
+static OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+impl Overrides {
+    /// Make every later load (reloads included) use these; first call wins
+    pub fn install(self) {
+        let _ = OVERRIDES.set(self);
+    }
+
+    fn apply(&self, config: &mut Config) {
+        let fields = [
+            (&self.model, &mut config.model),
+            (&self.language, &mut config.language),
+            (&self.device, &mut config.device),
+            (&self.hotkey, &mut config.hotkey),
+            (&self.output, &mut config.output_mode),
+        ];
+        for (value, field) in fields {
+            if let Some(value) = value {
+                field.clone_from(value);
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Find and load the config (`--config`, else the usual places), creating
+    /// a default one if there's none. Returns the file in use, if any
+    pub fn load() -> (Self, Option<PathBuf>) {
+        let overrides = OVERRIDES.get();
+        let (mut config, path) = Self::find(overrides.and_then(|o| o.config.clone()));
+        if let Some(overrides) = overrides {
+            overrides.apply(&mut config);
+        }
+        (config, path)
+    }
+
+    fn find(explicit: Option<PathBuf>) -> (Self, Option<PathBuf>) {
+        if let Some(path) = explicit {
+            return match Self::load_from(&path) {
+                Ok(config) => {
+                    info!("Loaded config from: {:?}", path);
+                    (config, Some(path))
+                }
+                Err(e) => {
+                    warn!("{}, using defaults", e);
+                    (Self::default(), Some(path))
+                }
+            };
+        }
+
+        let config_paths = [
+            dirs::config_dir().map(|p| p.join("ss9k").join("config.toml")),
+            dirs::home_dir().map(|p| p.join(".ss9k").join("config.toml")),
+            Some(PathBuf::from("config.toml")),
+        ];
+
+        for path in config_paths.into_iter().flatten() {
+            if path.exists() {
+                match Self::load_from(&path) {
+                    Ok(config) => {
+                        info!("Loaded config from: {:?}", path);
+                        return (config, Some(path));
+                    }
+                    Err(e @ ConfigError::Parse { .. }) => {
+                        warn!("{}", e);
+                    }
+                    Err(_) => {}
+                }
+            }
+        }
+
+        // No config found - create one at the default location
+        if let Some(config_dir) = dirs::config_dir() {
+            let ss9k_dir = config_dir.join("ss9k");
+            let config_path = ss9k_dir.join("config.toml");
+
+            // Create directory if needed
+            if let Err(e) = fs::create_dir_all(&ss9k_dir) {
+                warn!("Failed to create config directory: {}", e);
+            } else {
+                // Write default config
+                if let Err(e) = fs::write(&config_path, Self::default_config_content()) {
+                    warn!("Failed to write default config: {}", e);
+                } else {
+                    info!("Created default config at: {:?}", config_path);
+                    info!("Edit this file to customize your settings!");
+                    return (Self::default(), Some(config_path));
+                }
+            }
+        }
+
+        info!("Using default config");
+        (Self::default(), None)
+    }
+
+    fn default_config_content() -> &'static str {
+        r##"# SuperScreecher9000 Configuration
+# Edit this file to customize your settings.
+# Changes are hot-reloaded - no restart needed!
+
+# Config format version - older configs are migrated on load (with a printed diff)
+config_version = 2
+
+# More files to merge into this one, e.g. commands and aliases shared between
+# machines. Globs, relative to this file; settings here win over theirs
+# include = ["commands.d/*.toml"]
+include = []
+
+# Model to use: tiny, base, small, medium, large
+# Larger = more accurate but slower
+# Tip: Use "tiny" or "base" on older/weaker CPUs
+model = "small"
+
+# Language for transcription (ISO 639-1 codes)
+# Say "command languages" or "command language list" for full list
+# Or see: https://github.com/openai/whisper#available-models-and-languages
+language = "en"
+
+# Number of threads for whisper inference
+# More threads = faster on multi-core CPUs
+threads = 4
+
+# Specific audio device name (partial match)
+# Leave empty for auto-detection
+# Example: "Microphone" or "Blue Yeti"
+device = ""
+
+# Take audio from the network instead of a microphone: raw PCM sent to
+# tcp://, udp:// or ws:// (WebSocket, binary messages) on host:port
+# Example: "tcp://127.0.0.1:7777". Leave empty to use a microphone
+audio_source = ""
+# Layout of that audio: encoding (u8, s16le, s32le, f32le):rate:channels
+audio_source_format = "s16le:16000:1"
+# What's heard runs commands, so only this machine may send audio unless
+# the sender's IP address is listed here. Example: ["192.168.1.20"]
+audio_source_allow = []
+
+# Hotkey to trigger recording (dictation mode)
+# Options: F1-F12, ScrollLock, Pause, PrintScreen, Insert, Home, End, PageUp, PageDown, Num0-Num9,
+# Mouse4/Mouse5 (thumb buttons), Mouse_Middle
+# or a combo: Ctrl/Alt/Shift/Super + a key or letter, e.g. "Ctrl+Alt+Space", "Super+D"
+hotkey = "F12"
+
+# Command hotkey - alternate key that auto-prefixes with leader word
+# Use this to speak commands without saying "command" first
+# Example: with command_hotkey = "F11", pressing F11 and saying "enter"
+# is the same as pressing F12 and saying "command enter"
+# Leave empty to disable
+command_hotkey = ""
+
+# Abort hotkey - throws away the recording in progress, everything waiting to
+# be transcribed (and what's being transcribed now), and releases held keys
+# Leave empty to disable
+abort_hotkey = ""
+
+# Hotkey mode: "hold" (release to stop) or "toggle" (press again to stop)
+# Applies to both hotkey and command_hotkey
+hotkey_mode = "hold"
+
+# How hotkeys are read: "rdev" (X11, Windows, macOS) or "evdev" (straight
+# from /dev/input: works on Wayland, needs your user in the input group)
+# Restart SS9K after changing these two
+hotkey_backend = "rdev"
+# evdev only: a /dev/input/event* path or part of a device name ("" = all)
+hotkey_device = ""
+# A foot pedal or clicker whose buttons all act as the hotkey (Linux):
+# its vendor:product ID as lsusb shows it, e.g. "05f3:00ff" ("" = off)
+trigger_device = ""
+# A game controller button that acts as the hotkey (gamepad feature): A, B, X,
+# Y, LB, RB, LT, RT, L3, R3, Back, Start, DPadUp... or a raw code ("" = off)
+gamepad_button = ""
+# A MIDI input (midi feature): part of the port name ("" = off). What its
+# notes and controllers do goes in [midi] below
+midi_device = ""
+
+# Auto-stop timeout for toggle mode (0 = no timeout)
+toggle_timeout_secs = 0
+
+# Leader word for voice commands
+# All commands require this prefix: "command enter", "command emoji smile", etc.
+# Change to whatever feels natural: "voice", "computer", "hey", etc.
+leader = "command"
+
+# Builtins that also work WITHOUT the leader, for emergencies
+# Saying the leader adds latency exactly when you need an immediate abort.
+# Keep this short: anything listed here can't be dictated as plain text.
+# Example: leaderless_commands = ["escape", "scratch that", "release all"]
+leaderless_commands = []
+
+# Run [commands], [keybinds] and [macros] phrases that were nearly heard
+# right ("open emax" for "open emacs"): "off", "levenshtein" or "jaro-winkler"
+# fuzzy_threshold is the similarity needed, 0 to 1 (1 = exact only)
+fuzzy_match = "off"
+fuzzy_threshold = 0.9
+
+# Run phrases and built-ins that sound the same as what was heard
+# ("knew tab" -> "new tab", "cellar" -> "seller"), compared by Metaphone code
+phonetic_match = false
+
+# Letter words for "command spell": "nato" (alpha, bravo...), "german"
+# (Anton, Berta, Cäsar... and Ärger, Ökonom, Übermut, Eszett) or "none"
+spelling_alphabet = "nato"
+
+# Seconds a confirm = true command waits for "yes"
+confirm_timeout_secs = 10
+
+# Key repeat rate for hold mode (milliseconds between key presses)
+# Lower = faster repeat, higher = slower
+# Used when you say "command hold w" to spam a key
+key_repeat_ms = 50
+
+# Processing timeout in seconds (0 = no timeout)
+# If transcription takes longer than this, it will be aborted
+# Useful for weak CPUs that might hang on larger models
+# Tip: If you hit timeouts often, try model = "tiny" or "base"
+processing_timeout_secs = 30
+
+# Verbose logging (processing, resampling, transcription details)
+# Errors always print regardless. Set false once you're comfortable with the tool.
+verbose = true
+
+# Log level: "error", "warn", "info", "debug", "trace", or a filter like
+# "ss9k=debug,zbus=info". Empty = debug with verbose, info without.
+# SS9K_LOG and --log-level override this.
+log_level = ""
+# JSON log file (supports ~), rotated per log_rotation ("daily", "hourly",
+# "never"); the last 7 are kept. Empty = off
+log_file = ""
+log_rotation = "daily"
+
+# Transcription history for `ss9k history show|search|export`: every utterance
+# (time, case mode, command or not, text) is appended to history_file.
+# Empty = ~/.local/share/ss9k/history.jsonl. Set save_history = false to opt out
+save_history = true
+history_file = ""
+
+# Usage statistics for `ss9k stats`: word counts and per-stage timings (no text)
+save_stats = true
+
+# Privacy mode: transcribed text never shows up in the console, log file,
+# history, dictation log or notifications - only its length. Voice toggle:
+# "command privacy on/off" (lasts until the config is reloaded)
+privacy = false
+
+# Rhai script with transform(text) and/or command(text) hooks (needs the
+# scripting feature). Edits are picked up on the next utterance
+# script = "~/.config/ss9k/hooks.rhai"
+script = ""
+
+# WebAssembly plugins to load (needs the plugins feature), globs relative to
+# this file. Settings for one go in [plugin_settings.<file name>] below
+# plugins = ["plugins/*.wasm"]
+plugins = []
+
+# Shell commands run on every recording/utterance (not spoken, unlike
+# [commands]); SS9K doesn't wait for them. hook_pre_record runs when recording
+# starts (VAD: when speech is detected). hook_post_transcribe gets the text on
+# stdin and in $SS9K_TEXT, plus $SS9K_COMMAND (1/0), $SS9K_MODE, $SS9K_PROFILE;
+# privacy mode leaves the text out
+# hook_pre_record = "notify-send ss9k listening"
+# hook_post_transcribe = "cat >> ~/notes/dictation.md"
+hook_pre_record = ""
+hook_post_transcribe = ""
+
+# Audio feedback (sound cues on your speakers)
+# Rising tone when recording starts, falling tone when it stops,
+# a double blip when a command runs or text is typed, low buzz on errors
+audio_feedback = false
+
+# Custom cue sounds (wav/mp3/ogg/flac, supports ~). Empty = generated tone.
+sound_start = ""
+sound_stop = ""
+sound_command = ""
+sound_error = ""
+# Voice timers ("command set timer five minutes") ring even with audio_feedback off
+sound_timer = ""
+
+# Desktop notifications (useful when running as a background service)
+# "off", "errors", "results" (errors + what was typed/run), or "all" (+ recording state)
+notifications = "off"
+
+# Local HTTP server on 127.0.0.1 (builds with --features http), 0 = off
+# Serves Prometheus metrics at /metrics, the control API (/status, /record/start,
+# /record/stop, /mode, /type), and the web UI at / (--features webui).
+# Read at startup.
+http_port = 0
+
+# Remote record trigger (builds with --features http), "" = off. Listens on a
+# LAN address so a phone or smart button can POST /record/start|stop|toggle,
+# with remote_token (16+ characters) as "Authorization: Bearer <token>" or
+# ?token=<token>. Plain HTTP: trusted networks only. Read at startup.
+remote_listen = ""
+remote_token = ""
+
+# MQTT broker (builds with --features mqtt), "" = off. Publishes <topic>/state and
+# <topic>/transcription, runs commands sent to <topic>/command. Read at startup.
+mqtt_broker = ""
+mqtt_port = 1883
+mqtt_username = ""
+mqtt_password = ""
+mqtt_topic = "ss9k"
+
+# Switch access: a single-button switch (HID key or mouse button) as input
+# switch_key: key name, SPACE, ENTER, MOUSE_MIDDLE or MOUSE4-MOUSE9 ("" = off)
+# Actions: "record", "scan", "select", or any builtin command ("repeat", "enter")
+switch_key = ""
+switch_short = "record"
+switch_long = "release all"
+switch_double = "repeat"
+switch_long_press_ms = 600
+switch_double_press_ms = 350
+switch_scan_items = ["record", "repeat", "release all", "enter", "escape"]
+
+# Activation mode: "hotkey" (default) or "vad" (voice activity detection)
+# - hotkey: Press a key to start/stop recording (traditional mode)
+# - vad: Automatically detect when you're speaking (hands-free mode)
+#        In VAD mode, the hotkey toggles listening on/off
+activation_mode = "hotkey"
+
+# VAD settings (only used when activation_mode = "vad")
+# Sensitivity: 0.0-1.0, higher = more sensitive to speech
+vad_sensitivity = 0.9
+# Silence duration (ms) before processing - wait for pause after speech
+vad_silence_ms = 1000
+# Minimum speech duration (ms) before it counts - ignore brief noises
+vad_min_speech_ms = 200
+# Speech padding (ms) - extra time at end to catch trailing words
+vad_speech_pad_ms = 300
+# Adapt the silence timeout to your recent pauses (starts at vad_silence_ms)
+vad_adaptive_silence = false
+vad_silence_min_ms = 400
+vad_silence_max_ms = 2500
+# Paragraph mode: hold dictation until you pause for vad_paragraph_gap_ms
+# (or say "command full stop"), then type it all at once
+vad_paragraph_mode = false
+vad_paragraph_gap_ms = 3000
+
+# Pause VAD listening while a screen share is active (avoids transcribing calls)
+# Built-in check looks for PipeWire video capture (Linux); set screen_share_check
+# to a shell command that exits 0 while sharing to use your own
+pause_on_screen_share = false
+screen_share_check = ""
+
+# Modes that turn on do-not-disturb while active, e.g. ["lower"]
+# DND turns back off when you leave the mode
+dnd_modes = []
+
+# Case modes go back to off on their own: "utterance" after the next thing you
+# dictate ("never" = when you say so), mode_timeout_secs after that long
+# without dictation in the mode (0 = never). Vim and emacs modes stay on.
+mode_revert = "never"
+mode_timeout_secs = 0
+
+# Text-to-speech feedback (eyes-free use): speaks confirmations like "mode snake"
+# and reads back your last dictation on "command read that"
+# Backends: "espeak-ng", "piper" (needs aplay), "say" (macOS), or any shell
+# command that reads text on stdin. Empty = off.
+tts_backend = ""
+# Voice: espeak voice (e.g. "en-us"), piper model path (.onnx), or say voice
+tts_voice = ""
+
+# Where dictation goes: "type" (at the cursor), "clipboard", "stdout" (one line
+# per utterance; use --output stdout so logs go to stderr), "file" (appended to
+# output_file), "webhook" (POSTed as {"text": ...} to output_webhook),
+# "exec:<command>" (one line per utterance on the command's stdin) or
+# "homeassistant" (sent to Home Assistant's conversation API). Leader commands
+# still run locally. "command to clipboard <text>" picks one for one utterance,
+# "command output clipboard" switches until the next config reload
+output_mode = "type"
+output_file = ""
+output_webhook = ""
+# Show a notification when dictation lands on the clipboard (even with
+# notifications = "off")
+clipboard_notify = true
+# Dictation at least this many characters long is pasted (clipboard + Ctrl+V,
+# the old clipboard text is put back after) instead of typed key by key - much
+# faster, and Electron apps don't scramble it. 0 = always type
+paste_min_chars = 0
+# Capitalize typed dictation that starts a sentence: the first word after
+# . ! or ? (even in the last utterance) and the very first one
+auto_capitalize = false
+# Put a space before typed dictation that would run into the last utterance
+# (not after "(", a newline or other whitespace)
+smart_spacing = false
+# Strip fillers from dictation: "um", "uh" anywhere, "like" and "you know"
+# only between commas. The lists follow `language`; filler_words adds more
+remove_fillers = false
+filler_words = []
+# "digits" writes spoken numbers in dictation as digits: "one hundred twenty
+# three" -> 123, "three point five" -> 3.5 (single words below ten stay words);
+# "formatted" also writes money and percentages the way `language` does:
+# "twelve dollars fifty" -> $12.50, "twenty percent" -> 20%
+numbers = "spoken"
+# "command mode swearing": what a swear becomes ("grawlix" @#$%, "asterisks"
+# ****, "first-letter" f*** or "remove"); swear_words adds to the list for
+# `language`, grawlix is the symbols it cycles through
+swear_style = "grawlix"
+swear_words = []
+grawlix = "@#$%!&*"
+# How typing reaches the desktop: "enigo" (X11, Windows, macOS), "wtype"
+# (Wayland on wlroots: Sway, Hyprland), "ydotool" (any Wayland, needs
+# ydotoold) or "uinput" (any Wayland, needs write access to /dev/uinput)
+keyboard_backend = "enigo"
+homeassistant_url = ""
+homeassistant_token = ""
+homeassistant_agent = ""
+homeassistant_speak = true
+
+# Live captions for OBS: the last few dictated lines are kept in this file
+# (Text source -> "Read from file"). Empty = off
+captions_file = ""
+captions_lines = 2
+# Clear the captions after this many seconds without speech, 0 = never
+captions_clear_secs = 5
+
+# Custom voice commands
+# Maps spoken phrase -> shell command
+# Supports $ENV_VAR expansion (e.g., $TERMINAL, $BROWSER, $EDITOR)
+# {name} in a phrase captures the words spoken there: "search for {query}"
+[commands]
+# "open terminal" = "$TERMINAL"
+# "search for {query}" = "xdg-open 'https://duckduckgo.com/?q={query}'"
+# "open browser" = "$BROWSER"
+# "open firefox" = "firefox"
+# "screenshot" = "flameshot gui"
+# "reboot" = "systemctl reboot"
+
+# Per-command options: confirm = true asks "say yes to confirm" first,
+# cwd and env set where it runs and extra environment variables
+# [command_settings."reboot"]
+# confirm = true
+# [command_settings."build project"]
+# cwd = "~/code/app"
+# env = { RUST_LOG = "debug" }
+
+# Voice shortcuts: spoken phrase -> key chords, pressed like a shortcut
+# Several chords in a row go space-separated ("ctrl+k ctrl+c")
+[keybinds]
+# "new tab" = "ctrl+t"
+# "close window" = "alt+f4"
+
+# Several things for one phrase, in order: type "...", key "ctrl+c",
+# wait <ms> (or "wait 2 seconds"), shell "...", type_shell "..." (types the
+# output) and builtin "..." (the words after the leader)
+[macros]
+# "file bug" = ['shell "xdg-open https://github.com/you/app/issues/new"', 'wait 2000', 'type "Steps to reproduce:\n"']
+
+# Which phrase wins when several match (higher first, default 0)
+[priorities]
+# "search {q}" = 10
+
+# Regexes over the whole utterance, with shell, keys or steps; {1} is group 1
+# [[patterns]]
+# match = 'go to line (\d+)'
+# steps = ['key "ctrl+g"', 'type "{1}"', 'key "enter"']
+
+# Regex find and replace over every utterance, after [aliases], in order
+# [[replacements]]
+# match = '\bfor example\b'
+# with = "e.g."
+
+# Aliases for common misrecognitions
+# Maps what whisper hears -> what you meant
+[aliases]
+# "e max" = "emacs"
+# "fire fox" = "firefox"
+
+# Your own names for built-ins ("command zap"); several run in order
+[command_aliases]
+# "zap" = "backspace"
+# "nuke line" = "shift end, backspace"
+
+# Text snippets for quick insertion
+# Say "command insert <name>" to type the snippet
+# Supports placeholders: {date}, {time}, {datetime}, {date:tomorrow}, {shell:cmd}
+# Without an entry, "command insert date next friday" types that date
+[inserts]
+# email = "you@example.com"
+# sig = "Best regards,\nYour Name"
+
+# Text wrappers for quick wrapping
+# Say "command wrap <name> <text>" to wrap text
+# Use | to separate left/right: "parens" = "(|)"
+[wrappers]
+# quotes = '"'
+# parens = "(|)"
+# brackets = "[|]"
+
+# More "command punctuation <name>" symbols; a built-in name here is replaced
+[punctuation]
+# interrobang = "‽"
+# "low quote" = "„"
+
+# More "command emoji <name>" names (other names search all Unicode emoji)
+[emoji]
+# ship = "🚢"
+
+# Your own "command spell" words, each for one character
+[spelling]
+# eszett = "ß"
+
+# Profiles: extra commands/aliases for one kind of work, on top of the ones
+# above. Pick one with --profile <name> or "command profile <name>"
+# ("command profile off" goes back to none)
+# [profiles.gaming]
+# mode = "off"
+# [profiles.gaming.commands]
+# "open steam" = "steam"
+# [profiles.writing]
+# mode = "off"
+# [profiles.writing.aliases]
+# "full stop" = "."
+
+# Apps: switch to a profile while an app is focused, and back when you leave
+# it. Keys are window classes (ss9k ctl status shows the focused one)
+[apps]
+# kitty = "coding"
+# firefox = "writing"
+
+# MIDI mappings for midi_device: "note <n>" or "cc <n>" = "record", "command",
+# "abort" or "mode <name>"
+[midi]
+# "note 60" = "record"
+# "cc 64" = "command"
+
+# Settings handed to a plugin when it loads (keyed by its file name)
+# [plugin_settings.jira]
+# url = "https://example.atlassian.net"
+
+# Limits on what spoken commands can run: only these programs (empty = any),
+# sh -c or not, and characters that refuse a command outright
+[security]
+allowed_programs = []
+shell = true
+refuse_chars = ""
+"##
+    }
+
+    /// Read and parse a config file (command line overrides and the active
+    /// profile applied)
+    pub fn load_from(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        };
+        let mut table: toml::Table = toml::from_str(&contents).map_err(parse_error)?;
+        merge_includes(&mut table, path)?;
+        migrate(&mut table).print(path);
+        let mut config: Self = toml::Value::Table(table).try_into().map_err(parse_error)?;
+        if let Some(overrides) = OVERRIDES.get() {
+            overrides.apply(&mut config);
+        }
+        profiles::apply(&mut config);
+        Ok(config)
+    }
+
+    /// Directories the `include` globs read from (watched for hot reload)
+    pub fn include_dirs(&self, path: &Path) -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = self
+            .include
+            .iter()
+            .filter_map(|pattern| {
+                let pattern = PathBuf::from(shellexpand::tilde(pattern).as_ref());
+                let mut dir = match path.parent() {
+                    Some(dir) if pattern.is_relative() => dir.to_path_buf(),
+                    _ => PathBuf::new(),
+                };
+                for part in pattern.components() {
+                    if part.as_os_str().to_string_lossy().contains(['*', '?', '[']) {
+                        return Some(dir);
+                    }
+                    dir.push(part);
+                }
+                dir.parent().map(Path::to_path_buf)
+            })
+            .map(|dir| if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir })
+            .collect();
+        dirs.sort();
+        dirs.dedup();
+        dirs
+    }
+
+    pub fn model_filename(&self) -> String {
+        format!("ggml-{}.bin", self.model)
+    }
+
+    pub fn model_url(&self) -> String {
+        format!(
+            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin",
+            self.model
+        )
+    }
+}
+
+/// Files a glob matches, in name order (`~` expanded, relative paths taken
+/// from the config file's directory)
+pub(crate) fn glob_files(config_path: &Path, pattern: &str) -> Result<Vec<PathBuf>, glob::PatternError> {
+    // Escape the config's own directory so a "[" in it isn't read as a glob
+    let relative = PathBuf::from(shellexpand::tilde(pattern).as_ref());
+    let full = match config_path.parent() {
+        Some(dir) if relative.is_relative() && !dir.as_os_str().is_empty() => {
+            format!("{}/{}", glob::Pattern::escape(&dir.to_string_lossy()), relative.display())
+        }
+        _ => relative.to_string_lossy().into_owned(),
+    };
+    Ok(glob::glob(&full)?.flatten().collect())
+}
+
+/// Merge the files `include` lists into a config table. The table's own
+/// settings win; between included files, later ones win (each glob's matches
+/// are taken in name order). Included files can't include more files.
+pub(crate) fn merge_includes(table: &mut toml::Table, path: &Path) -> Result<(), ConfigError> {
+    let Some(toml::Value::Array(patterns)) = table.get("include") else {
+        return Ok(());
+    };
+    let patterns: Vec<String> = patterns.iter().filter_map(|p| p.as_str().map(str::to_string)).collect();
+    let own = fs::canonicalize(path).ok();
+    let mut merged = toml::Table::new();
+    for pattern in patterns {
+        let files = glob_files(path, &pattern).map_err(|source| ConfigError::Include { pattern: pattern.clone(), source })?;
+        if files.is_empty() {
+            warn!("⚠️ include \"{}\" matched no files", pattern);
+        }
+        for file in files {
+            // "*.toml" next to config.toml matches config.toml itself
+            if fs::canonicalize(&file).ok() == own {
+                continue;
+            }
+            let contents = fs::read_to_string(&file).map_err(|source| ConfigError::Read { path: file.clone(), source })?;
+            let mut included: toml::Table =
+                toml::from_str(&contents).map_err(|source| ConfigError::Parse { path: file.clone(), source })?;
+            if included.remove("include").is_some() {
+                warn!("⚠️ {:?}: include only works in the main config file, ignoring it", file);
+            }
+            included.remove("config_version");
+            merge_tables(&mut merged, included);
+        }
+    }
+    merge_tables(&mut merged, std::mem::take(table));
+    *table = merged;
+    Ok(())
+}
+
+/// Copy `over` into `base`, replacing values but merging tables key by key
+fn merge_tables(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge_tables(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
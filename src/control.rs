@@ -0,0 +1,270 @@
+//! Remote control for SS9K
+//!
+//! This module handles:
+//! - Start/stop recording requests from other threads (HTTP API, control socket,
+//!   signals)
+//! - Status snapshots to report back
+//! - Mode and profile changes, config reloads and text commands, shared by
+//!   every way of asking for them
+//! - Events (state changes, transcriptions) for integrations to subscribe to
+//!
+//! Recording is owned by the binary (audio stream, hotkey state), so requests
+//! go over a channel it installs at startup and it acts on them like a hotkey.
+
+use arc_swap::ArcSwap;
+use serde::Serialize;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use tracing::info;
+
+use crate::commands::{self, get_case_mode, held_keys, parse_mode_name, set_case_mode, sync_mode_dnd, CaseMode};
+use crate::{apps, check, fillers, history, keyboard, logging, lookups, numbers, output, privacy, profanity, profiles, security, stats, timers};
+use crate::{Config, RECORDING, VAD_LISTENING};
+
+/// What to do with recording (VAD listening, in VAD mode)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RecordRequest {
+    Start,
+    Stop,
+    Toggle,
+}
+
+static HANDLER: OnceLock<Sender<RecordRequest>> = OnceLock::new();
+
+/// Install the receiver of record requests (once, at startup)
+pub fn set_handler(tx: Sender<RecordRequest>) {
+    let _ = HANDLER.set(tx);
+}
+
+/// Send a record request (false = nothing is handling them)
+pub fn request(request: RecordRequest) -> bool {
+    HANDLER.get().is_some_and(|tx| tx.send(request).is_ok())
+}
+
+/// What SS9K is doing right now
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum State {
+    Idle,
+    Recording,  // Hotkey recording running
+    Listening,  // VAD waiting for speech
+    Processing, // Transcribing
+}
+
+impl State {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            State::Idle => "idle",
+            State::Recording => "recording",
+            State::Listening => "listening",
+            State::Processing => "processing",
+        }
+    }
+}
+
+/// Something integrations may want to hear about
+#[derive(Clone, Debug)]
+pub enum Event {
+    State(State),
+    Transcription(String), // Text that was typed or run as a command
+}
+
+static SUBSCRIBERS: Mutex<Vec<Sender<Event>>> = Mutex::new(Vec::new());
+static LAST_STATE: Mutex<State> = Mutex::new(State::Idle);
+
+/// Receive every event published from now on
+pub fn subscribe() -> Receiver<Event> {
+    let (tx, rx) = mpsc::channel();
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.push(tx);
+    }
+    rx
+}
+
+/// Tell subscribers about an event (repeated states are dropped)
+pub fn publish(event: Event) {
+    if let Event::State(state) = event
+        && let Ok(mut last) = LAST_STATE.lock()
+    {
+        if *last == state {
+            return;
+        }
+        *last = state;
+    }
+    if let Ok(mut subscribers) = SUBSCRIBERS.lock() {
+        subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+    }
+}
+
+/// Last published state
+pub fn state() -> State {
+    LAST_STATE.lock().map(|s| *s).unwrap_or(State::Idle)
+}
+
+/// State to go back to once nothing is being transcribed
+pub fn resting_state() -> State {
+    if RECORDING.load(Ordering::SeqCst) {
+        State::Recording
+    } else if VAD_LISTENING.load(Ordering::SeqCst) {
+        State::Listening
+    } else {
+        State::Idle
+    }
+}
+
+/// Snapshot of what SS9K is doing
+#[derive(Clone, Debug, Serialize)]
+pub struct Status {
+    pub mode: String,            // Case mode ("off", "snake"...)
+    pub profile: String,         // Active profile ("" = none)
+    pub app: String,             // Focused app at the last utterance ("" = unknown or no [apps])
+    pub output: String,          // Where dictation goes (output_mode, or "command output <sink>")
+    pub activation_mode: String, // "hotkey" or "vad"
+    pub state: State,            // Last published state
+    pub recording: bool,         // Hotkey recording running
+    pub listening: bool,         // VAD listening
+    pub held_keys: Vec<String>,  // Keys "hold" is repeating
+    pub model: String,           // Whisper model
+    pub language: String,        // Transcription language ("auto" = detect)
+}
+
+/// Current status
+pub fn status(config: &Config) -> Status {
+    Status {
+        mode: format!("{:?}", get_case_mode()).to_lowercase(),
+        profile: profiles::active().unwrap_or_default(),
+        app: apps::focused().unwrap_or_default(),
+        output: output::mode(config),
+        activation_mode: config.activation_mode.clone(),
+        state: state(),
+        recording: RECORDING.load(Ordering::SeqCst),
+        listening: VAD_LISTENING.load(Ordering::SeqCst),
+        held_keys: held_keys(),
+        model: config.model.clone(),
+        language: config.language.clone(),
+    }
+}
+
+/// Switch case mode by name, same as "[leader] mode <name>"
+pub fn set_mode(name: &str, config: &Config) -> Result<CaseMode, String> {
+    let mode = parse_mode_name(name.trim()).ok_or_else(|| format!("Unknown mode '{}'", name.trim()))?;
+    set_case_mode(mode);
+    sync_mode_dnd(&config.dnd_modes);
+    Ok(mode)
+}
+
+/// Order `cycle_mode` steps through
+/// Vim mode isn't in it: landing there by accident would drop dictation.
+const MODE_CYCLE: [CaseMode; 14] = [
+    CaseMode::Off,
+    CaseMode::Snake,
+    CaseMode::Camel,
+    CaseMode::Pascal,
+    CaseMode::Kebab,
+    CaseMode::Dot,
+    CaseMode::Path,
+    CaseMode::Screaming,
+    CaseMode::Caps,
+    CaseMode::Lower,
+    CaseMode::Math,
+    CaseMode::Code,
+    CaseMode::Alternating,
+    CaseMode::Swearing,
+];
+
+/// Switch to the next case mode (wrapping back to off), returning it
+pub fn cycle_mode(config: &Config) -> CaseMode {
+    let current = get_case_mode();
+    let index = MODE_CYCLE.iter().position(|&m| m == current).unwrap_or(0);
+    let mode = MODE_CYCLE[(index + 1) % MODE_CYCLE.len()];
+    set_case_mode(mode);
+    sync_mode_dnd(&config.dnd_modes);
+    mode
+}
+
+/// Re-read the config file now instead of waiting for the watcher
+pub fn reload(config: &ArcSwap<Config>, path: Option<&Path>) -> Result<(), String> {
+    let path = path.ok_or("No config file to reload (running on built-in defaults)")?;
+    let new_config = Config::load_from(path).map_err(|e| e.to_string())?;
+    logging::configure(&new_config);
+    check::warn_problems(&new_config);
+    history::configure(&new_config);
+    stats::configure(&new_config);
+    privacy::configure(&new_config);
+    output::configure(&new_config);
+    keyboard::configure(&new_config);
+    security::configure(&new_config);
+    timers::configure(&new_config);
+    lookups::configure(&new_config);
+    fillers::configure(&new_config);
+    numbers::configure(&new_config);
+    profanity::configure(&new_config);
+    commands::configure(&new_config);
+    #[cfg(feature = "scripting")]
+    crate::scripting::configure(&new_config);
+    #[cfg(feature = "plugins")]
+    crate::plugins::configure(&new_config, Some(path));
+    config.store(Arc::new(new_config));
+    info!("🔄 Config reloaded!");
+    Ok(())
+}
+
+/// Switch profile by name ("off"/"none" = no profile), same as "[leader] profile <name>"
+/// Re-reads the config file so the old profile's commands go away
+pub fn set_profile(name: &str, config: &ArcSwap<Config>, path: Option<&Path>) -> Result<(), String> {
+    let current = config.load();
+    let selected = match name.trim() {
+        "off" | "none" | "default" => None,
+        name => {
+            let Some((name, _)) = profiles::find(&current, name) else {
+                return Err(format!("Unknown profile '{}' (have: {})", name, profiles::names(&current).join(", ")));
+            };
+            Some(name.clone())
+        }
+    };
+    let previous = profiles::active();
+    profiles::set(selected.clone());
+    if let Err(e) = reload(config, path) {
+        profiles::set(previous);
+        return Err(e);
+    }
+
+    let config = config.load();
+    match selected.as_deref().and_then(|name| profiles::find(&config, name)) {
+        Some((name, profile)) => {
+            if !profile.mode.is_empty() {
+                set_mode(&profile.mode, &config)?;
+            }
+            info!("🎭 Profile: {}", name);
+        }
+        None => info!("🎭 Profile: none"),
+    }
+    Ok(())
+}
+
+/// Run a text command (control socket, MQTT), returning the reply payload
+/// Commands: `toggle`, `start`, `stop`, `status`, `mode [name]`, `profile [name]`, `reload`
+pub fn run_command(command: &str, config: &ArcSwap<Config>, config_path: Option<&Path>) -> Result<String, String> {
+    let (name, arg) = command.split_once(' ').unwrap_or((command, ""));
+    let record = |record| {
+        if request(record) { Ok(String::new()) } else { Err("Recording can't be controlled right now".to_string()) }
+    };
+    match (name, arg.trim()) {
+        ("toggle", "") => record(RecordRequest::Toggle),
+        ("start", "") => record(RecordRequest::Start),
+        ("stop", "") => record(RecordRequest::Stop),
+        ("status", "") => serde_json::to_string(&status(&config.load())).map_err(|e| e.to_string()),
+        ("mode", "") => Ok(status(&config.load()).mode),
+        ("mode", name) => {
+            let mode = set_mode(name, &config.load())?;
+            info!("🔌 Mode set to {:?}", mode);
+            Ok(String::new())
+        }
+        ("profile", "") => Ok(profiles::active().unwrap_or_default()),
+        ("profile", name) => set_profile(name, config, config_path).map(|()| String::new()),
+        ("reload", "") => reload(config, config_path).map(|()| String::new()),
+        _ => Err(format!("Unknown command '{}'", command)),
+    }
+}
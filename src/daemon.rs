@@ -0,0 +1,106 @@
+//! Running SS9K as a systemd user service
+//!
+//! This module handles:
+//! - sd_notify: telling systemd we're ready (`Type=notify`)
+//! - Watchdog pings when the unit sets `WatchdogSec`
+//! - The sample unit `ss9k systemd-unit` prints
+//!
+//! sd_notify is one datagram to `$NOTIFY_SOCKET`, so there's no libsystemd
+//! dependency. Outside systemd (no `$NOTIFY_SOCKET`) everything here is a no-op.
+
+use std::path::Path;
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+/// Send a state string ("READY=1", "WATCHDOG=1"...) to systemd, if it's listening
+#[cfg(unix)]
+pub fn notify(state: &str) -> std::io::Result<()> {
+    use std::os::unix::net::UnixDatagram;
+
+    let Some(path) = std::env::var_os("NOTIFY_SOCKET") else {
+        return Ok(());
+    };
+    let socket = UnixDatagram::unbound()?;
+    match path.to_str().and_then(|p| p.strip_prefix('@')) {
+        // Abstract socket (Linux only)
+        Some(name) => {
+            #[cfg(target_os = "linux")]
+            {
+                use std::os::linux::net::SocketAddrExt;
+                let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                socket.send_to_addr(state.as_bytes(), &addr)?;
+            }
+            #[cfg(not(target_os = "linux"))]
+            let _ = name;
+        }
+        None => {
+            socket.send_to(state.as_bytes(), &path)?;
+        }
+    }
+    Ok(())
+}
+
+/// No systemd off Unix
+#[cfg(not(unix))]
+pub fn notify(_state: &str) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Watchdog interval systemd expects pings within (`WatchdogSec`), if any
+pub fn watchdog_interval() -> Option<Duration> {
+    // WATCHDOG_PID, when set, says who the watchdog is meant for
+    if let Ok(pid) = std::env::var("WATCHDOG_PID")
+        && pid.parse() != Ok(std::process::id())
+    {
+        return None;
+    }
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    (usec > 0).then(|| Duration::from_micros(usec))
+}
+
+/// Ping the watchdog at half its interval from a background thread
+pub fn start_watchdog() {
+    let Some(interval) = watchdog_interval() else {
+        return;
+    };
+    info!("🐕 systemd watchdog: pinging every {:?}", interval / 2);
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval / 2);
+        if let Err(e) = notify("WATCHDOG=1") {
+            warn!("⚠️ Watchdog ping failed: {}", e);
+        }
+    });
+}
+
+/// Sample systemd user unit running `exe --daemon`
+pub fn unit_file(exe: &Path) -> String {
+    format!(
+        "\
+# SS9K as a systemd user service
+# Save as ~/.config/systemd/user/ss9k.service, then:
+#   systemctl --user daemon-reload
+#   systemctl --user enable --now ss9k
+# Logs: journalctl --user -u ss9k -f
+
+[Unit]
+Description=SuperScreecher9000 local voice dictation
+PartOf=graphical-session.target
+After=graphical-session.target pipewire.service pulseaudio.service
+
+[Service]
+Type=notify
+ExecStart={} --daemon
+Restart=on-failure
+RestartSec=5
+WatchdogSec=60
+# First start may download the model
+TimeoutStartSec=10min
+SyslogIdentifier=ss9k
+
+[Install]
+WantedBy=graphical-session.target
+",
+        exe.display()
+    )
+}
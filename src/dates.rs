@@ -0,0 +1,213 @@
+//! Spoken dates and times for SS9K
+//!
+//! "command insert date tomorrow" types tomorrow's date, "command insert next
+//! friday" the coming Friday's and "command insert time in two hours" the time
+//! two hours from now. The same phrases work in placeholders, so `[inserts]`
+//! and macro `type` steps can hold `{date:tomorrow}`, `{time:in 90 minutes}`
+//! or `{datetime:next monday at 9 am}`.
+//!
+//! Phrases are a day and then an optional "at <time>":
+//!
+//! - today, tomorrow, yesterday, the day after tomorrow, the day before yesterday
+//! - a weekday: "friday" is the next one (today if it's Friday), "next friday"
+//!   the next one after today, "last friday" the one before today
+//! - next or last week, month or year
+//! - "in three days", "two weeks ago", "an hour from now", in minutes, hours,
+//!   days, weeks, months or years
+//! - "at noon", "at midnight", "at 3 pm", "at 15:30", "at half past four"
+
+use chrono::{Datelike, Days, Local, Months, NaiveDateTime, NaiveTime, TimeDelta, Weekday};
+
+use crate::numbers::parse_integer;
+
+/// Placeholder names and the format each writes
+pub const FORMATS: &[(&str, &str)] = &[("date", "%Y-%m-%d"), ("time", "%H:%M"), ("datetime", "%Y-%m-%d %H:%M")];
+
+const WEEKDAYS: &[(&str, Weekday)] = &[
+    ("monday", Weekday::Mon), ("tuesday", Weekday::Tue), ("wednesday", Weekday::Wed), ("thursday", Weekday::Thu),
+    ("friday", Weekday::Fri), ("saturday", Weekday::Sat), ("sunday", Weekday::Sun),
+];
+
+/// Days from today, for the words that name one
+const DAYS: &[(&str, i64)] = &[
+    ("today", 0), ("now", 0), ("tonight", 0), ("tomorrow", 1), ("yesterday", -1),
+    ("day after tomorrow", 2), ("day before yesterday", -2),
+];
+
+/// A span of time for "in three days", "two weeks ago", "next month"
+#[derive(Clone, Copy)]
+enum Unit {
+    Minutes,
+    Hours,
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
+
+const UNITS: &[(&[&str], Unit)] = &[
+    (&["minute", "minutes", "min", "mins"], Unit::Minutes),
+    (&["hour", "hours"], Unit::Hours),
+    (&["day", "days"], Unit::Days),
+    (&["week", "weeks"], Unit::Weeks),
+    (&["month", "months"], Unit::Months),
+    (&["year", "years"], Unit::Years),
+];
+
+/// The date and time a phrase names, counting from `now`: "tomorrow",
+/// "next friday at 9 am", "in two weeks". None if it isn't one of the phrases
+/// in the module docs.
+pub fn parse(phrase: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let words: Vec<String> = phrase
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty() && !matches!(w.as_str(), "the" | "on"))
+        .collect();
+
+    // "tomorrow at noon": the day, then the time
+    let (day, time) = match words.iter().rposition(|w| w == "at") {
+        Some(at) => (&words[..at], Some(&words[at + 1..])),
+        None => (&words[..], None),
+    };
+    let day: Vec<&str> = day.iter().map(String::as_str).collect();
+    let mut when = if day.is_empty() { now } else { parse_day(&day, now)? };
+    if let Some(time) = time {
+        let time: Vec<&str> = time.iter().map(String::as_str).collect();
+        when = when.date().and_time(parse_time(&time)?);
+    }
+    Some(when)
+}
+
+/// Everything before "at": "tomorrow", "next friday", "in three days"
+fn parse_day(words: &[&str], now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let phrase = words.join(" ");
+    if let Some((_, days)) = DAYS.iter().find(|(name, _)| *name == phrase) {
+        return shift(now, *days, Unit::Days);
+    }
+
+    match words {
+        // "friday", "this friday", "next friday", "last friday"
+        [which @ .., day] if which.len() <= 1 && weekday(day).is_some() => {
+            let target = weekday(day)?.num_days_from_monday() as i64;
+            let today = now.weekday().num_days_from_monday() as i64;
+            let ahead = (target - today).rem_euclid(7);
+            let days = match which {
+                [] | ["this" | "coming"] => ahead,
+                ["next"] if ahead == 0 => 7,
+                ["next"] => ahead,
+                ["last" | "previous"] => -(today - target - 1).rem_euclid(7) - 1,
+                _ => return None,
+            };
+            shift(now, days, Unit::Days)
+        }
+        // "next week", "last month"
+        [which @ ("next" | "last" | "previous"), unit] => shift(now, if *which == "next" { 1 } else { -1 }, unit_named(unit)?),
+        // "in three days", "in an hour"
+        ["in", count @ .., unit] => shift(now, count_of(count)?, unit_named(unit)?),
+        // "two weeks ago", "three days from now", "a month from today"
+        [count @ .., unit, "ago"] => shift(now, -count_of(count)?, unit_named(unit)?),
+        [count @ .., unit, "from", "now" | "today"] => shift(now, count_of(count)?, unit_named(unit)?),
+        _ => None,
+    }
+}
+
+/// After "at": "noon", "3 pm", "15:30", "half past four", "nine thirty"
+fn parse_time(words: &[&str]) -> Option<NaiveTime> {
+    let words: Vec<String> = words.iter().map(|w| w.replace('.', "")).collect();
+    let mut words: Vec<&str> = words.iter().map(String::as_str).filter(|w| *w != "oclock" && *w != "o'clock").collect();
+    match words.as_slice() {
+        ["noon" | "midday"] => return NaiveTime::from_hms_opt(12, 0, 0),
+        ["midnight"] => return NaiveTime::from_hms_opt(0, 0, 0),
+        _ => {}
+    }
+
+    // "3pm" comes through as one word, "3 pm" as two
+    let mut meridiem = None;
+    if let Some(last) = words.last().copied() {
+        for suffix in ["am", "pm"] {
+            if last == suffix {
+                meridiem = Some(suffix);
+                words.pop();
+            } else if let Some(hour) = last.strip_suffix(suffix).filter(|h| !h.is_empty()) {
+                meridiem = Some(suffix);
+                *words.last_mut()? = hour;
+            }
+        }
+    }
+
+    let (hour, minute) = match words.as_slice() {
+        ["half", "past", hour] => (number(&[hour])?, 30),
+        ["quarter", "past", hour] => (number(&[hour])?, 15),
+        ["quarter", "to", hour] => ((number(&[hour])? + 23) % 24, 45),
+        [single] if single.contains(':') => {
+            let (hour, minute) = single.split_once(':')?;
+            (hour.parse().ok()?, minute.parse().ok()?)
+        }
+        [hour] => (number(&[hour])?, 0),
+        // "nine thirty", "nine forty five", "ten oh five"
+        [hour, "oh", minute] => (number(&[hour])?, number(&[minute]).filter(|m| *m < 10)?),
+        [hour, minute @ ..] => (number(&[hour])?, number(minute)?),
+        [] => return None,
+    };
+    let hour = match meridiem {
+        Some(_) if !(1..=12).contains(&hour) => return None,
+        Some("am") => hour % 12,
+        Some(_) => hour % 12 + 12,
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+/// A count in digits or words
+fn number(words: &[&str]) -> Option<u32> {
+    let n = parse_integer(&words.join(" ")).or_else(|| words.concat().parse().ok())?;
+    u32::try_from(n).ok()
+}
+
+/// The count before a unit: "three", "twenty five", "2", or "a" for one
+fn count_of(words: &[&str]) -> Option<i64> {
+    match words {
+        ["a" | "an"] => Some(1),
+        _ => number(words).map(i64::from),
+    }
+}
+
+fn weekday(word: &str) -> Option<Weekday> {
+    WEEKDAYS.iter().find(|(name, _)| *name == word).map(|(_, day)| *day)
+}
+
+fn unit_named(word: &str) -> Option<Unit> {
+    UNITS.iter().find(|(names, _)| names.contains(&word)).map(|(_, unit)| *unit)
+}
+
+/// `now` moved `n` units forward (or back, when negative)
+fn shift(now: NaiveDateTime, n: i64, unit: Unit) -> Option<NaiveDateTime> {
+    match unit {
+        Unit::Minutes => now.checked_add_signed(TimeDelta::try_minutes(n)?),
+        Unit::Hours => now.checked_add_signed(TimeDelta::try_hours(n)?),
+        Unit::Days | Unit::Weeks => {
+            let days = if matches!(unit, Unit::Weeks) { n.checked_mul(7)? } else { n };
+            let by = Days::new(days.unsigned_abs());
+            if days < 0 { now.checked_sub_days(by) } else { now.checked_add_days(by) }
+        }
+        Unit::Months | Unit::Years => {
+            let months = if matches!(unit, Unit::Years) { n.checked_mul(12)? } else { n };
+            let by = Months::new(u32::try_from(months.unsigned_abs()).ok()?);
+            if months < 0 { now.checked_sub_months(by) } else { now.checked_add_months(by) }
+        }
+    }
+}
+
+/// The placeholder for a spoken insert name: "date tomorrow" ->
+/// `{date:tomorrow}`, "time in an hour" -> `{time:in an hour}`, "next friday"
+/// -> `{date:next friday}`, "date" -> `{date}`; None if it doesn't name one
+pub fn placeholder(name: &str) -> Option<String> {
+    let now = Local::now().naive_local();
+    let (format, phrase) = match name.split_once(' ') {
+        Some((first, rest)) if FORMATS.iter().any(|(f, _)| *f == first) => (first, rest),
+        None if FORMATS.iter().any(|(f, _)| *f == name) => return Some(format!("{{{}}}", name)),
+        _ => ("date", name),
+    };
+    parse(phrase, now)?;
+    Some(format!("{{{}:{}}}", format, phrase))
+}
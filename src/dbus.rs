@@ -0,0 +1,121 @@
+//! D-Bus service for SS9K (dbus feature)
+//!
+//! This module handles:
+//! - Owning `org.sqrew.SS9K` on the session bus
+//! - Methods at `/org/sqrew/SS9K`: StartRecording, StopRecording,
+//!   ToggleRecording, SetMode
+//! - Properties: Mode, State
+//! - Signals: TranscriptionReady(text), StateChanged(state)
+//!
+//! GNOME extensions and KDE widgets can call these and watch the signals
+//! instead of polling. Signals are fed from `control` events.
+
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use arc_swap::ArcSwap;
+use tracing::{info, warn};
+use zbus::blocking::connection;
+use zbus::fdo;
+use zbus::names::BusName;
+use zbus::object_server::SignalEmitter;
+
+use crate::control::{self, Event, RecordRequest};
+use crate::error::DbusError;
+use crate::privacy;
+use crate::Config;
+
+/// Well-known bus name
+pub const BUS_NAME: &str = "org.sqrew.SS9K";
+/// Interface name
+pub const INTERFACE: &str = "org.sqrew.SS9K";
+/// Object path
+pub const PATH: &str = "/org/sqrew/SS9K";
+
+struct Service {
+    config: Arc<ArcSwap<Config>>,
+}
+
+impl Service {
+    fn record(&self, request: RecordRequest) -> fdo::Result<()> {
+        if control::request(request) {
+            Ok(())
+        } else {
+            Err(fdo::Error::Failed("Recording can't be controlled right now".to_string()))
+        }
+    }
+}
+
+#[zbus::interface(name = "org.sqrew.SS9K")]
+impl Service {
+    /// Start recording (VAD mode: start listening)
+    fn start_recording(&self) -> fdo::Result<()> {
+        self.record(RecordRequest::Start)
+    }
+
+    /// Stop recording and transcribe (VAD mode: stop listening)
+    fn stop_recording(&self) -> fdo::Result<()> {
+        self.record(RecordRequest::Stop)
+    }
+
+    /// Same as pressing the toggle hotkey
+    fn toggle_recording(&self) -> fdo::Result<()> {
+        self.record(RecordRequest::Toggle)
+    }
+
+    /// Switch case mode ("snake", "off"...)
+    fn set_mode(&self, mode: &str) -> fdo::Result<()> {
+        let mode = control::set_mode(mode, &self.config.load()).map_err(fdo::Error::InvalidArgs)?;
+        info!("🚌 Mode set to {:?}", mode);
+        Ok(())
+    }
+
+    /// Current case mode
+    #[zbus(property)]
+    fn mode(&self) -> String {
+        control::status(&self.config.load()).mode
+    }
+
+    /// "idle", "recording", "listening" or "processing"
+    #[zbus(property)]
+    fn state(&self) -> String {
+        control::state().as_str().to_string()
+    }
+
+    /// Text was typed or run as a command
+    #[zbus(signal)]
+    async fn transcription_ready(emitter: &SignalEmitter<'_>, text: &str) -> zbus::Result<()>;
+
+    /// State changed (same values as the State property)
+    /// (named explicitly: zbus generates `state_changed` for the property)
+    #[zbus(signal, name = "StateChanged")]
+    async fn state_changed_signal(emitter: &SignalEmitter<'_>, state: &str) -> zbus::Result<()>;
+}
+
+/// Connect to the session bus and serve the interface; signals go out from a background thread
+pub fn start(config: Arc<ArcSwap<Config>>) -> Result<JoinHandle<()>, DbusError> {
+    let conn = connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(PATH, Service { config })?
+        .build()?;
+    info!("🚌 D-Bus service: {} at {}", BUS_NAME, PATH);
+
+    let events = control::subscribe();
+    Ok(std::thread::spawn(move || {
+        for event in events {
+            let result = match &event {
+                Event::State(state) => emit(&conn, "StateChanged", state.as_str()),
+                // Any program on the session bus could read it
+                Event::Transcription(_) if privacy::enabled() => continue,
+                Event::Transcription(text) => emit(&conn, "TranscriptionReady", text),
+            };
+            if let Err(e) = result {
+                warn!("⚠️ D-Bus signal failed: {}", e);
+            }
+        }
+    }))
+}
+
+fn emit(conn: &zbus::blocking::Connection, signal: &str, arg: &str) -> zbus::Result<()> {
+    conn.emit_signal(None::<BusName>, PATH, INTERFACE, signal, &(arg,))
+}
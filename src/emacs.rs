@@ -0,0 +1,146 @@
+//! Emacs mode for SS9K
+//!
+//! "command mode emacs" turns editing phrases into Emacs key sequences:
+//! "save buffer" sends `C-x C-s`, "other window" sends `C-x o`, "meta x
+//! magit status" sends `M-x magit-status RET`. The built-in editing
+//! shortcuts ("command copy", "command save") are the Ctrl ones other
+//! programs use, which mean something else in Emacs.
+//!
+//! Emacs has no separate insert mode, so anything that isn't one of these
+//! phrases is typed like normal dictation.
+
+use crate::commands::{parse_count, strip_punct, Keystroke};
+
+/// Phrases with a fixed key sequence, in Emacs key notation
+const COMMANDS: &[(&[&str], &str)] = &[
+    // Files and buffers
+    (&["save buffer", "save file", "save"], "C-x C-s"),
+    (&["save as", "write file"], "C-x C-w"),
+    (&["save all", "save some buffers"], "C-x s"),
+    (&["find file", "open file"], "C-x C-f"),
+    (&["switch buffer", "switch to buffer"], "C-x b"),
+    (&["list buffers"], "C-x C-b"),
+    (&["kill buffer", "close buffer"], "C-x k"),
+    (&["quit emacs", "exit emacs"], "C-x C-c"),
+
+    // Windows
+    (&["other window", "next window"], "C-x o"),
+    (&["split window", "split below", "split window below"], "C-x 2"),
+    (&["split right", "split window right"], "C-x 3"),
+    (&["delete window", "close window"], "C-x 0"),
+    (&["only window", "one window", "delete other windows"], "C-x 1"),
+
+    // Editing
+    (&["cancel", "keyboard quit", "quit"], "C-g"),
+    (&["undo"], "C-x u"),
+    (&["copy", "copy region"], "M-w"),
+    (&["cut", "kill region"], "C-w"),
+    (&["paste", "yank"], "C-y"),
+    (&["yank pop", "paste previous"], "M-y"),
+    (&["set mark", "mark"], "C-SPC"),
+    (&["select all", "mark whole buffer"], "C-x h"),
+    (&["kill line"], "C-k"),
+    (&["kill word"], "M-d"),
+    (&["backward kill word", "kill word back"], "M-DEL"),
+    (&["transpose words"], "M-t"),
+    (&["transpose characters", "transpose chars"], "C-t"),
+    (&["upcase word", "uppercase word"], "M-u"),
+    (&["downcase word", "lowercase word"], "M-l"),
+    (&["capitalize word"], "M-c"),
+    (&["fill paragraph"], "M-q"),
+
+    // Moving around
+    (&["beginning of line", "start of line"], "C-a"),
+    (&["end of line"], "C-e"),
+    (&["beginning of buffer", "top of buffer", "top"], "C-<home>"),
+    (&["end of buffer", "bottom of buffer", "bottom"], "C-<end>"),
+    (&["forward word", "next word"], "M-f"),
+    (&["backward word", "back word", "previous word"], "M-b"),
+    (&["next line"], "C-n"),
+    (&["previous line"], "C-p"),
+    (&["scroll down", "page down"], "C-v"),
+    (&["scroll up", "page up"], "M-v"),
+    (&["recenter", "center"], "C-l"),
+    (&["search", "search again", "next match"], "C-s"),
+    (&["search backward", "previous match"], "C-r"),
+];
+
+/// Spoken forms of M-x, before the command name
+const META_X: &[&str] = &["meta x", "metax", "mx", "m x", "alt x"];
+
+/// Keys Emacs notation names, and the `[keybinds]` name each sends
+const NAMED_KEYS: &[(&str, &str)] = &[
+    ("RET", "return"),
+    ("SPC", "space"),
+    ("TAB", "tab"),
+    ("ESC", "escape"),
+    ("DEL", "backspace"),
+];
+
+/// A phrase's key sequence in Emacs notation ("save buffer" -> `C-x C-s`,
+/// "meta x magit status" -> `M-x magit-status RET`), or None if it isn't an
+/// Emacs phrase and should be typed
+pub fn translate(text: &str) -> Option<String> {
+    let words: Vec<String> = text.split_whitespace().map(strip_punct).filter(|w| !w.is_empty()).collect();
+    let phrase = words.join(" ");
+    if phrase.is_empty() {
+        return None;
+    }
+
+    // "meta x magit status" -> M-x magit-status RET
+    if let Some(command) = META_X.iter().find_map(|m| phrase.strip_prefix(m).and_then(|r| r.strip_prefix(' '))) {
+        return Some(format!("M-x {} RET", command.replace(' ', "-")));
+    }
+    if META_X.contains(&phrase.as_str()) {
+        return Some("M-x".to_string());
+    }
+
+    // "go to line forty" -> M-g g 40 RET
+    let line = ["go to line", "goto line", "jump to line"]
+        .iter()
+        .find_map(|prefix| phrase.strip_prefix(prefix).and_then(|rest| rest.strip_prefix(' ')));
+    if let Some(n) = line.and_then(parse_count) {
+        return Some(format!("M-g g {} RET", n));
+    }
+
+    // "search for foo bar" -> C-s foo SPC bar RET
+    let search = [("search for ", "C-s"), ("search backward for ", "C-r")]
+        .iter()
+        .find_map(|(prefix, key)| phrase.strip_prefix(prefix).map(|rest| (key, rest)));
+    if let Some((key, rest)) = search {
+        return Some(format!("{} {} RET", key, rest.split(' ').collect::<Vec<_>>().join(" SPC ")));
+    }
+
+    COMMANDS.iter().find(|(names, _)| names.contains(&phrase.as_str())).map(|(_, keys)| keys.to_string())
+}
+
+/// The keystrokes Emacs notation stands for: `C-x`, `M-DEL`, `RET` and other
+/// keys become chords, anything else ("o", "magit-status") is typed
+pub fn keystrokes(notation: &str) -> Vec<Keystroke> {
+    notation
+        .split_whitespace()
+        .map(|key| chord(key).map_or_else(|| Keystroke::Text(key.to_string()), Keystroke::Chord))
+        .collect()
+}
+
+/// The `[keybinds]` chord for one key in Emacs notation: "C-x" -> "ctrl+x",
+/// "M-DEL" -> "alt+backspace", "C-<home>" -> "ctrl+home"; None for text
+fn chord(key: &str) -> Option<String> {
+    let mut modifiers = Vec::new();
+    let mut rest = key;
+    while let Some((modifier, after)) = [("C-", "ctrl"), ("M-", "alt"), ("S-", "shift")]
+        .iter()
+        .find_map(|(prefix, modifier)| rest.strip_prefix(prefix).filter(|r| !r.is_empty()).map(|r| (*modifier, r)))
+    {
+        modifiers.push(modifier);
+        rest = after;
+    }
+
+    let named = NAMED_KEYS.iter().find(|(emacs, _)| *emacs == rest).map(|(_, name)| name.to_string());
+    let base = named
+        .or_else(|| rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')).map(str::to_string))
+        .or_else(|| (!modifiers.is_empty() && rest.chars().count() == 1).then(|| rest.to_lowercase()))?;
+    modifiers.push(&base);
+    Some(modifiers.join("+"))
+}
+
@@ -0,0 +1,548 @@
+//! Error types for the SS9K library layer
+//!
+//! Each subsystem has its own error enum so callers (the binary, IPC/HTTP
+//! layers, embedders) can match on what went wrong instead of parsing strings.
+//! `code()` gives a stable machine-readable identifier for each variant.
+
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Audio capture and resampling errors
+#[derive(Debug, Error)]
+pub enum AudioError {
+    #[error("Failed to build input stream: {0}")]
+    BuildStream(#[from] cpal::BuildStreamError),
+    #[error("Unsupported sample format: {0:?}")]
+    UnsupportedFormat(cpal::SampleFormat),
+    #[error("Failed to create resampler: {0}")]
+    ResamplerInit(#[from] rubato::ResamplerConstructionError),
+    #[error("Resample error: {0}")]
+    Resample(#[from] rubato::ResampleError),
+    #[error("Resampler cache lock poisoned")]
+    Poisoned,
+    #[error("Failed to open audio file {path:?}: {source}")]
+    Open { path: PathBuf, source: std::io::Error },
+    #[error("Failed to decode audio file: {0}")]
+    Decode(#[from] symphonia::core::errors::Error),
+    #[error("No audio track found in file")]
+    NoAudioTrack,
+    #[error("Invalid raw audio format '{0}' (expected e.g. s16le:48000:1)")]
+    RawFormat(String),
+    #[error("Invalid audio_source '{0}' (expected tcp://, udp:// or ws:// and host:port)")]
+    Source(String),
+    #[error("Invalid audio_source_allow entry '{0}' (expected an IP address)")]
+    SourceAllow(String),
+    #[error("Failed to listen for audio on {addr}: {source}")]
+    Listen { addr: String, source: std::io::Error },
+    #[error("No audio output device available")]
+    NoOutputDevice,
+    #[error("Failed to get output config: {0}")]
+    OutputConfig(#[from] cpal::DefaultStreamConfigError),
+    #[error("Failed to start stream: {0}")]
+    Play(#[from] cpal::PlayStreamError),
+}
+
+impl AudioError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::BuildStream(_) => "audio.build_stream",
+            Self::UnsupportedFormat(_) => "audio.unsupported_format",
+            Self::ResamplerInit(_) => "audio.resampler_init",
+            Self::Resample(_) => "audio.resample",
+            Self::Poisoned => "audio.poisoned",
+            Self::Open { .. } => "audio.open",
+            Self::Decode(_) => "audio.decode",
+            Self::NoAudioTrack => "audio.no_track",
+            Self::RawFormat(_) => "audio.raw_format",
+            Self::Source(_) => "audio.source",
+            Self::SourceAllow(_) => "audio.source_allow",
+            Self::Listen { .. } => "audio.listen",
+            Self::NoOutputDevice => "audio.no_output",
+            Self::OutputConfig(_) => "audio.output_config",
+            Self::Play(_) => "audio.play",
+        }
+    }
+}
+
+/// Model download and loading errors
+#[derive(Debug, Error)]
+pub enum ModelError {
+    #[error("Model file error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Download error: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Download failed: HTTP {0}")]
+    HttpStatus(reqwest::StatusCode),
+    #[error("Progress bar template error: {0}")]
+    Progress(#[from] indicatif::style::TemplateError),
+    #[error("Failed to load whisper model: {0}")]
+    Load(whisper_rs::WhisperError),
+}
+
+impl ModelError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Io(_) => "model.io",
+            Self::Http(_) => "model.http",
+            Self::HttpStatus(_) => "model.http_status",
+            Self::Progress(_) => "model.progress",
+            Self::Load(_) => "model.load",
+        }
+    }
+}
+
+/// Whisper transcription errors
+#[derive(Debug, Error)]
+pub enum TranscribeError {
+    #[error("Whisper error: {0}")]
+    Whisper(#[from] whisper_rs::WhisperError),
+}
+
+impl TranscribeError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Whisper(_) => "transcribe.whisper",
+        }
+    }
+}
+
+/// Output errors (typing text, pressing keys)
+#[derive(Debug, Error)]
+pub enum OutputError {
+    #[error("Input simulation failed: {0}")]
+    Input(#[from] enigo::InputError),
+    #[error("Failed to connect to input backend: {0}")]
+    Connection(#[from] enigo::NewConError),
+    #[error("Failed to write to stdout: {0}")]
+    Stdout(std::io::Error),
+    #[error("Output command '{command}' failed: {source}")]
+    Exec { command: String, source: std::io::Error },
+    #[error("Clipboard error: {0}")]
+    Clipboard(#[from] arboard::Error),
+    #[error("Failed to append to {path:?}: {source}")]
+    File { path: PathBuf, source: std::io::Error },
+    #[error("Webhook request failed: {0}")]
+    Webhook(#[from] reqwest::Error),
+    #[error("Webhook returned HTTP {0}")]
+    WebhookStatus(reqwest::StatusCode),
+    #[error("The {sink} output needs {key} set in config.toml")]
+    Unset { sink: &'static str, key: &'static str },
+    #[error("Unknown output '{0}' (type, clipboard, stdout, file, webhook, exec:<command>)")]
+    UnknownSink(String),
+    #[error("Output state lock poisoned")]
+    Poisoned,
+    #[error("Can't create a uinput keyboard (needs write access to /dev/uinput): {0}")]
+    Uinput(std::io::Error),
+    #[error(transparent)]
+    Refused(#[from] SecurityError),
+}
+
+impl OutputError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Input(_) => "output.input",
+            Self::Connection(_) => "output.connection",
+            Self::Stdout(_) => "output.stdout",
+            Self::Exec { .. } => "output.exec",
+            Self::Clipboard(_) => "output.clipboard",
+            Self::File { .. } => "output.file",
+            Self::Webhook(_) => "output.webhook",
+            Self::WebhookStatus(_) => "output.webhook_status",
+            Self::Unset { .. } => "output.unset",
+            Self::UnknownSink(_) => "output.unknown_sink",
+            Self::Poisoned => "output.poisoned",
+            Self::Uinput(_) => "output.uinput",
+            Self::Refused(e) => e.code(),
+        }
+    }
+}
+
+/// Shell commands the `[security]` table won't run
+#[derive(Debug, Error)]
+pub enum SecurityError {
+    #[error("Refused '{command}': it contains '{ch}' (security.refuse_chars)")]
+    Char { command: String, ch: char },
+    #[error("Refused '{command}': '{program}' isn't in security.allowed_programs")]
+    Program { command: String, program: String },
+    #[error("Refused '{0}': pipes, ; and $(...) can't be used with security.allowed_programs")]
+    Operators(String),
+    #[error("Refused '{0}': unbalanced quotes")]
+    Quotes(String),
+    #[error("Refused an empty command")]
+    Empty,
+}
+
+impl SecurityError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Char { .. } => "security.char",
+            Self::Program { .. } => "security.program",
+            Self::Operators(_) => "security.operators",
+            Self::Quotes(_) => "security.quotes",
+            Self::Empty => "security.empty",
+        }
+    }
+}
+
+/// Config file errors
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Failed to read config {path:?}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Config parse error in {path:?}: {source}")]
+    Parse { path: PathBuf, source: toml::de::Error },
+    #[error("Bad include pattern \"{pattern}\": {source}")]
+    Include { pattern: String, source: glob::PatternError },
+}
+
+impl ConfigError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Read { .. } => "config.read",
+            Self::Parse { .. } => "config.parse",
+            Self::Include { .. } => "config.include",
+        }
+    }
+}
+
+/// Local HTTP server errors (http feature)
+#[derive(Debug, Error)]
+pub enum HttpError {
+    #[error("Failed to listen on {addr}: {source}")]
+    Bind {
+        addr: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    #[error("remote_token must be at least {0} characters")]
+    WeakToken(usize),
+}
+
+impl HttpError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Bind { .. } => "http.bind",
+            Self::WeakToken(_) => "http.weak_token",
+        }
+    }
+}
+
+/// Control socket errors (Unix)
+#[derive(Debug, Error)]
+pub enum IpcError {
+    #[error("Failed to create control socket {path:?}: {source}")]
+    Bind { path: PathBuf, source: std::io::Error },
+    #[error("Another SS9K is already listening on {0:?}")]
+    InUse(PathBuf),
+    #[error("Couldn't connect to {path:?} (is ss9k running?): {source}")]
+    Connect { path: PathBuf, source: std::io::Error },
+    #[error("Control socket I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    Rejected(String),
+}
+
+impl IpcError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Bind { .. } => "ipc.bind",
+            Self::InUse(_) => "ipc.in_use",
+            Self::Connect { .. } => "ipc.connect",
+            Self::Io(_) => "ipc.io",
+            Self::Rejected(_) => "ipc.rejected",
+        }
+    }
+}
+
+/// Hotkey listener errors (`hotkey_backend = "evdev"`, `trigger_device`)
+#[derive(Debug, Error)]
+pub enum HotkeyError {
+    #[error("No input device matches hotkey_device '{0}' (is your user in the input group?)")]
+    NoDevice(String),
+    #[error("Failed to open input device {path:?}: {source}")]
+    Open { path: PathBuf, source: std::io::Error },
+    #[error("Every input device went away")]
+    Closed,
+    #[error("Invalid trigger_device '{0}' (vendor:product in hex, as lsusb shows it)")]
+    BadId(String),
+    #[error("No input device with ID {0} (is it plugged in, and your user in the input group?)")]
+    NoTrigger(String),
+    #[error("Reading /dev/input is Linux-only")]
+    Unsupported,
+}
+
+impl HotkeyError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::NoDevice(_) => "hotkey.no_device",
+            Self::Open { .. } => "hotkey.open",
+            Self::Closed => "hotkey.closed",
+            Self::BadId(_) => "hotkey.bad_id",
+            Self::NoTrigger(_) => "hotkey.no_trigger",
+            Self::Unsupported => "hotkey.unsupported",
+        }
+    }
+}
+
+/// Signal handling errors (Unix)
+#[derive(Debug, Error)]
+pub enum SignalError {
+    #[error("Failed to install signal handlers: {0}")]
+    Register(#[from] std::io::Error),
+}
+
+impl SignalError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Register(_) => "signal.register",
+        }
+    }
+}
+
+/// D-Bus service errors (dbus feature)
+#[cfg(feature = "dbus")]
+#[derive(Debug, Error)]
+pub enum DbusError {
+    #[error("D-Bus error: {0}")]
+    Bus(#[from] zbus::Error),
+}
+
+#[cfg(feature = "dbus")]
+impl DbusError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Bus(_) => "dbus.bus",
+        }
+    }
+}
+
+/// MIDI input errors (midi feature)
+#[cfg(feature = "midi")]
+#[derive(Debug, Error)]
+pub enum MidiError {
+    #[error("MIDI unavailable: {0}")]
+    Init(#[from] midir::InitError),
+    #[error("No MIDI input matches midi_device '{0}'")]
+    NoPort(String),
+    #[error("Failed to connect to the MIDI input: {0}")]
+    Connect(String),
+}
+
+#[cfg(feature = "midi")]
+impl MidiError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Init(_) => "midi.init",
+            Self::NoPort(_) => "midi.no_port",
+            Self::Connect(_) => "midi.connect",
+        }
+    }
+}
+
+/// MQTT errors (mqtt feature)
+#[derive(Debug, Error)]
+pub enum MqttError {
+    #[error("Invalid mqtt_topic '{0}' (needs a prefix without + or #)")]
+    Topic(String),
+}
+
+impl MqttError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Topic(_) => "mqtt.topic",
+        }
+    }
+}
+
+/// Home Assistant forwarding errors
+#[derive(Debug, Error)]
+pub enum HomeAssistantError {
+    #[error("Home Assistant request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("Home Assistant returned HTTP {0}")]
+    Status(reqwest::StatusCode),
+    #[error("Unexpected Home Assistant response: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+impl HomeAssistantError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Request(_) => "homeassistant.request",
+            Self::Status(_) => "homeassistant.status",
+            Self::Parse(_) => "homeassistant.parse",
+        }
+    }
+}
+
+/// Live caption file errors
+#[derive(Debug, Error)]
+pub enum CaptionsError {
+    #[error("Failed to write captions to {path:?}: {source}")]
+    Write { path: PathBuf, source: std::io::Error },
+}
+
+impl CaptionsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Write { .. } => "captions.write",
+        }
+    }
+}
+
+/// Transcription history file errors
+#[derive(Debug, Error)]
+pub enum HistoryError {
+    #[error("Failed to read history {path:?}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Failed to save history to {path:?}: {source}")]
+    Write { path: PathBuf, source: std::io::Error },
+}
+
+impl HistoryError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Read { .. } => "history.read",
+            Self::Write { .. } => "history.write",
+        }
+    }
+}
+
+/// Usage statistics file errors
+#[derive(Debug, Error)]
+pub enum StatsError {
+    #[error("Failed to read stats {path:?}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Failed to save stats to {path:?}: {source}")]
+    Write { path: PathBuf, source: std::io::Error },
+}
+
+impl StatsError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Read { .. } => "stats.read",
+            Self::Write { .. } => "stats.write",
+        }
+    }
+}
+
+/// Script hook errors (scripting feature)
+#[derive(Debug, Error)]
+pub enum ScriptError {
+    #[error("Failed to read script {path:?}: {source}")]
+    Read { path: PathBuf, source: std::io::Error },
+    #[error("Script error in {path:?}: {message}")]
+    Compile { path: PathBuf, message: String },
+    #[error("Script hook {hook}() failed: {message}")]
+    Run { hook: &'static str, message: String },
+    #[error(transparent)]
+    Output(#[from] OutputError),
+}
+
+impl ScriptError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Read { .. } => "script.read",
+            Self::Compile { .. } => "script.compile",
+            Self::Run { .. } => "script.run",
+            Self::Output(e) => e.code(),
+        }
+    }
+}
+
+/// WebAssembly plugin errors (plugins feature)
+#[derive(Debug, Error)]
+pub enum PluginError {
+    #[error("Can't start the WebAssembly engine for plugins: {0}")]
+    Engine(String),
+    #[error("Can't load plugin {path:?}: {message}")]
+    Load { path: PathBuf, message: String },
+    #[error("Plugin {plugin}: {hook}() failed: {message}")]
+    Call { plugin: String, hook: &'static str, message: String },
+    #[error("Bad plugins pattern \"{pattern}\": {source}")]
+    Pattern { pattern: String, source: glob::PatternError },
+    #[error(transparent)]
+    Output(#[from] OutputError),
+}
+
+impl PluginError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Engine(_) => "plugin.engine",
+            Self::Load { .. } => "plugin.load",
+            Self::Call { .. } => "plugin.call",
+            Self::Pattern { .. } => "plugin.pattern",
+            Self::Output(e) => e.code(),
+        }
+    }
+}
+
+/// Any SS9K library error
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error(transparent)]
+    Audio(#[from] AudioError),
+    #[error(transparent)]
+    Model(#[from] ModelError),
+    #[error(transparent)]
+    Transcribe(#[from] TranscribeError),
+    #[error(transparent)]
+    Output(#[from] OutputError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Http(#[from] HttpError),
+    #[error(transparent)]
+    Ipc(#[from] IpcError),
+    #[error(transparent)]
+    Hotkey(#[from] HotkeyError),
+    #[error(transparent)]
+    Signal(#[from] SignalError),
+    #[error(transparent)]
+    Mqtt(#[from] MqttError),
+    #[error(transparent)]
+    HomeAssistant(#[from] HomeAssistantError),
+    #[error(transparent)]
+    Captions(#[from] CaptionsError),
+    #[error(transparent)]
+    History(#[from] HistoryError),
+    #[error(transparent)]
+    Stats(#[from] StatsError),
+    #[error(transparent)]
+    Script(#[from] ScriptError),
+    #[error(transparent)]
+    Plugin(#[from] PluginError),
+    #[cfg(feature = "dbus")]
+    #[error(transparent)]
+    Dbus(#[from] DbusError),
+    #[cfg(feature = "midi")]
+    #[error(transparent)]
+    Midi(#[from] MidiError),
+}
+
+impl Error {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Audio(e) => e.code(),
+            Self::Model(e) => e.code(),
+            Self::Transcribe(e) => e.code(),
+            Self::Output(e) => e.code(),
+            Self::Config(e) => e.code(),
+            Self::Http(e) => e.code(),
+            Self::Ipc(e) => e.code(),
+            Self::Hotkey(e) => e.code(),
+            Self::Signal(e) => e.code(),
+            Self::Mqtt(e) => e.code(),
+            Self::HomeAssistant(e) => e.code(),
+            Self::Captions(e) => e.code(),
+            Self::History(e) => e.code(),
+            Self::Stats(e) => e.code(),
+            Self::Script(e) => e.code(),
+            Self::Plugin(e) => e.code(),
+            #[cfg(feature = "dbus")]
+            Self::Dbus(e) => e.code(),
+            #[cfg(feature = "midi")]
+            Self::Midi(e) => e.code(),
+        }
+    }
+}
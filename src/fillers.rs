@@ -0,0 +1,186 @@
+//! Filler-word removal for SS9K
+//!
+//! With `remove_fillers = true` dictation loses its hesitations before it's
+//! typed: "So, um, I think, like, we're done" comes out "So, I think, we're
+//! done". Sounds like "um" and "uh" go wherever they are. Words that are
+//! fillers only some of the time ("like", "you know") go only when they're set
+//! off by commas or sentence ends, so "I like it" keeps its "like".
+//!
+//! Each language has its own lists, picked with `language`; `filler_words`
+//! adds your own, removed wherever they are. "auto" uses every language's
+//! lists, but only for fillers that are set off: one language's hesitation
+//! is another's word ("em", "hum", "bah"), so there are none to remove
+//! wherever they are.
+
+use std::sync::RwLock;
+
+use crate::text::find_case_insensitive;
+use crate::Config;
+
+/// Hesitation sounds, removed wherever they are
+const SOUNDS: &[(&str, &[&str])] = &[
+    ("en", &["um", "umm", "uh", "uhh", "uhm", "er", "erm", "hmm"]),
+    ("de", &["äh", "ähm", "öh", "öhm", "hm", "hmm"]),
+    ("fr", &["euh", "heu", "bah", "hum"]),
+    ("es", &["eh", "ehm", "em", "mmm"]),
+    ("it", &["ehm", "eh", "uhm", "mmm"]),
+    ("nl", &["eh", "ehm", "uh", "uhm"]),
+    ("pt", &["hã", "hum", "éh", "ahn"]),
+];
+
+/// Words that are fillers only when set off by commas ("it was, like, huge")
+const INTERJECTIONS: &[(&str, &[&str])] = &[
+    ("en", &["like", "you know", "i mean", "kind of", "sort of"]),
+    ("de", &["halt", "sozusagen", "quasi", "weißt du"]),
+    ("fr", &["genre", "tu vois", "tu sais", "quoi", "bon"]),
+    ("es", &["o sea", "este", "pues", "bueno", "sabes"]),
+    ("it", &["cioè", "tipo", "diciamo", "insomma"]),
+    ("nl", &["zeg maar", "weet je", "nou"]),
+    ("pt", &["tipo", "sabe", "né"]),
+];
+
+/// Punctuation that ends a clause, so a filler after it is set off
+const CLAUSE_ENDS: &[char] = &[',', '.', '!', '?', ';', ':', '…', '—'];
+
+/// The fillers removed from dictation
+#[derive(Debug, Clone, Default)]
+pub struct Fillers {
+    anywhere: Vec<String>,
+    set_off: Vec<String>,
+}
+
+impl Fillers {
+    /// The default lists for `language` plus your own words
+    /// With "auto" every language's sounds and interjections need setting off.
+    pub fn for_language(language: &str, extra: &[String]) -> Self {
+        let lists = |table: &[(&str, &[&str])]| -> Vec<String> {
+            table
+                .iter()
+                .filter(|(lang, _)| language == "auto" || lang.eq_ignore_ascii_case(language))
+                .flat_map(|(_, words)| words.iter().map(|w| w.to_string()))
+                .collect()
+        };
+        let mut set_off = lists(INTERJECTIONS);
+        let mut anywhere = Vec::new();
+        if language == "auto" {
+            set_off.extend(lists(SOUNDS));
+        } else {
+            anywhere = lists(SOUNDS);
+        }
+        anywhere.extend(extra.iter().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()));
+        Fillers { anywhere, set_off }
+    }
+
+    /// True when there's nothing to remove (no list for the language, no `filler_words`)
+    pub fn is_empty(&self) -> bool {
+        self.anywhere.is_empty() && self.set_off.is_empty()
+    }
+
+    /// The text without its fillers, with the commas around them tidied up and
+    /// a capital moved to the next word when a sentence started with one
+    pub fn remove(&self, text: &str) -> String {
+        let mut out = text.to_string();
+        let mut removed = false;
+        while let Some(range) = self.next_filler(&out) {
+            out = cut(&out, range);
+            removed = true;
+        }
+        // "Um." on its own leaves nothing worth typing
+        if removed && !out.chars().any(char::is_alphanumeric) {
+            return String::new();
+        }
+        out
+    }
+
+    /// The first filler in `text`; the longer one when two start at the same place
+    fn next_filler(&self, text: &str) -> Option<std::ops::Range<usize>> {
+        let anywhere = self.anywhere.iter().map(|w| (w, false));
+        let set_off = self.set_off.iter().map(|w| (w, true));
+        anywhere
+            .chain(set_off)
+            .flat_map(|(word, needs_commas)| {
+                find_case_insensitive(text, word)
+                    .into_iter()
+                    .filter(move |r| is_whole_word(text, r) && (!needs_commas || is_set_off(text, r)))
+            })
+            .min_by_key(|r| (r.start, std::cmp::Reverse(r.end)))
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '\'' | '’' | '-')
+}
+
+fn is_whole_word(text: &str, range: &std::ops::Range<usize>) -> bool {
+    !text[..range.start].chars().next_back().is_some_and(is_word_char)
+        && !text[range.end..].chars().next().is_some_and(is_word_char)
+}
+
+/// A filler between commas, or at the start or end of a sentence
+fn is_set_off(text: &str, range: &std::ops::Range<usize>) -> bool {
+    let before = text[..range.start].trim_end();
+    let after = &text[range.end..];
+    (before.is_empty() || before.ends_with(CLAUSE_ENDS))
+        && (after.trim().is_empty() || after.starts_with(CLAUSE_ENDS))
+}
+
+/// Remove one filler and the comma after it
+fn cut(text: &str, range: std::ops::Range<usize>) -> String {
+    let filler = &text[range.clone()];
+    let before_raw = &text[..range.start];
+    // Leading whitespace stays, it separates this utterance from the last one
+    let mut before = if before_raw.trim().is_empty() { before_raw } else { before_raw.trim_end() };
+    let starts_sentence = before.trim().is_empty() || before.ends_with(['.', '!', '?']);
+
+    let mut after = &text[range.end..];
+    if let Some(rest) = after.strip_prefix("...") {
+        after = rest.trim_start_matches('.');
+    } else if let Some(rest) = after.strip_prefix([',', '…']) {
+        after = rest;
+    }
+    let mut after = after.trim_start();
+
+    // "there, um." -> "there."
+    if after.is_empty() || after.starts_with(['.', '!', '?', ';', ':']) {
+        before = before.trim_end_matches(',').trim_end();
+    }
+    // "Hello. Um. Goodbye." -> "Hello. Goodbye."
+    if (before.trim().is_empty() || before.ends_with(['.', '!', '?'])) && after.starts_with(['.', '!', '?']) {
+        after = after[1..].trim_start();
+    }
+
+    let mut after = after.to_string();
+    if starts_sentence
+        && filler.chars().next().is_some_and(char::is_uppercase)
+        && let Some(first) = after.chars().next()
+    {
+        after.replace_range(..first.len_utf8(), &first.to_uppercase().to_string());
+    }
+
+    let joined = before.trim().is_empty() || after.is_empty() || after.starts_with(CLAUSE_ENDS);
+    if joined {
+        format!("{}{}", before, after)
+    } else {
+        format!("{} {}", before, after)
+    }
+}
+
+static FILLERS: RwLock<Option<Fillers>> = RwLock::new(None);
+
+/// Apply `remove_fillers`, `filler_words` and `language` (startup and config reloads)
+pub fn configure(config: &Config) {
+    let fillers = config
+        .remove_fillers
+        .then(|| Fillers::for_language(&config.language, &config.filler_words));
+    if let Ok(mut current) = FILLERS.write() {
+        *current = fillers;
+    }
+}
+
+/// Dictation with its fillers removed, or as it is with `remove_fillers` off
+pub fn strip(text: &str) -> String {
+    match FILLERS.read().ok().as_deref().and_then(Option::as_ref) {
+        Some(fillers) => fillers.remove(text),
+        None => text.to_string(),
+    }
+}
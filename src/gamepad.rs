@@ -0,0 +1,62 @@
+//! Game controller push-to-talk for SS9K (gamepad feature)
+//!
+//! This module handles:
+//! - Watching every connected controller through gilrs and passing presses
+//!   and releases of `gamepad_button` on, so a shoulder button or paddle can
+//!   act as the hotkey
+//!
+//! Controllers plugged in later are picked up. `gamepad_button` is read on
+//! every press, so changing it needs no restart. Buttons gilrs has no name
+//! for go by their raw code, which the debug log shows on each press.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use gilrs::{EventType, Gilrs};
+use tracing::{debug, info, warn};
+
+use crate::hotkeys::{parse_gamepad_button, GamepadButton};
+use crate::Config;
+
+/// Watch controllers in the background; `on(true)` when `gamepad_button`
+/// goes down, `on(false)` when it comes back up
+pub fn start(config: Arc<ArcSwap<Config>>, on: impl Fn(bool) + Send + 'static) {
+    std::thread::spawn(move || {
+        let mut gilrs = match Gilrs::new() {
+            Ok(gilrs) => gilrs,
+            Err(e) => {
+                warn!("⚠️ Game controllers unavailable: {}", e);
+                return;
+            }
+        };
+        for (_, pad) in gilrs.gamepads() {
+            info!("🎮 Controller: {}", pad.name());
+        }
+        loop {
+            let Some(event) = gilrs.next_event_blocking(Some(Duration::from_secs(1))) else {
+                continue;
+            };
+            let (button, code, down) = match event.event {
+                EventType::ButtonPressed(button, code) => (button, code, true),
+                EventType::ButtonReleased(button, code) => (button, code, false),
+                EventType::Connected => {
+                    info!("🎮 Controller connected: {}", gilrs.gamepad(event.id).name());
+                    continue;
+                }
+                _ => continue,
+            };
+            if down {
+                debug!("🎮 {:?} pressed (code {})", button, code.into_u32());
+            }
+            let ours = match parse_gamepad_button(&config.load().gamepad_button) {
+                Some(GamepadButton::Named(name)) => format!("{:?}", button) == name,
+                Some(GamepadButton::Code(wanted)) => code.into_u32() == wanted,
+                None => false,
+            };
+            if ours {
+                on(down);
+            }
+        }
+    });
+}
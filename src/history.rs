@@ -0,0 +1,200 @@
+//! Transcription history for SS9K
+//!
+//! This module handles:
+//! - The last few utterances in memory, for the web UI
+//! - Every utterance appended to `history.jsonl` in the data dir (unless
+//!   `save_history = false`), one JSON object per line
+//! - Reading it back for `ss9k history show|search|export`
+//!
+//! Append-only: nothing is rewritten, so a crash can't lose old entries.
+//! Delete the file to forget everything. On Unix it's created readable by
+//! you only.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{LazyLock, Mutex};
+use tracing::warn;
+
+use crate::commands::get_case_mode;
+use crate::error::HistoryError;
+use crate::{privacy, Config};
+
+/// How many utterances to keep in memory
+const MAX_ENTRIES: usize = 50;
+
+/// One transcribed utterance
+#[derive(Clone, Debug, Serialize)]
+pub struct Entry {
+    pub time: String,  // Local time, HH:MM:SS
+    pub text: String,  // What Whisper heard
+    pub command: bool, // Ran as a command instead of being typed
+}
+
+/// One line of the history file
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    pub timestamp: String, // Local time, RFC 3339
+    pub mode: String,      // Case mode at the time ("off", "snake"...)
+    pub command: bool,     // Ran as a command instead of being typed
+    pub text: String,
+}
+
+static HISTORY: LazyLock<Mutex<VecDeque<Entry>>> =
+    LazyLock::new(|| Mutex::new(VecDeque::with_capacity(MAX_ENTRIES)));
+static FILE: Mutex<Option<PathBuf>> = Mutex::new(None); // None = not saving
+static WRITE_FAILED: AtomicBool = AtomicBool::new(false); // Warn once, not per utterance
+
+/// Default history file: `<data dir>/ss9k/history.jsonl`
+pub fn default_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("ss9k").join("history.jsonl")
+}
+
+/// Where the config says history goes (`history_file`, else the default)
+pub fn path(config: &Config) -> PathBuf {
+    if config.history_file.is_empty() {
+        default_path()
+    } else {
+        PathBuf::from(shellexpand::tilde(&config.history_file).as_ref())
+    }
+}
+
+/// Apply `save_history` / `history_file` (startup and config reloads)
+pub fn configure(config: &Config) {
+    if let Ok(mut file) = FILE.lock() {
+        *file = config.save_history.then(|| path(config));
+    }
+}
+
+/// Remember an utterance (and save it, if enabled); privacy mode keeps nothing
+pub fn record(text: &str, command: bool) {
+    if privacy::enabled() {
+        return;
+    }
+    let now = chrono::Local::now();
+    if let Ok(mut history) = HISTORY.lock() {
+        if history.len() == MAX_ENTRIES {
+            history.pop_front();
+        }
+        history.push_back(Entry {
+            time: now.format("%H:%M:%S").to_string(),
+            text: text.to_string(),
+            command,
+        });
+    }
+
+    let Some(path) = FILE.lock().ok().and_then(|f| f.clone()) else {
+        return;
+    };
+    let record = Record {
+        timestamp: now.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        mode: format!("{:?}", get_case_mode()).to_lowercase(),
+        command,
+        text: text.to_string(),
+    };
+    match append(&path, &record) {
+        Ok(()) => WRITE_FAILED.store(false, Ordering::Relaxed),
+        Err(e) if !WRITE_FAILED.swap(true, Ordering::Relaxed) => warn!("⚠️ {}", e),
+        Err(_) => {}
+    }
+}
+
+fn append(path: &Path, record: &Record) -> Result<(), HistoryError> {
+    let write_error = |source| HistoryError::Write { path: path.to_path_buf(), source };
+    let line = serde_json::to_string(record).map_err(|e| write_error(e.into()))?;
+    privacy::append_private(path, &line).map_err(write_error)
+}
+
+/// Recent utterances, newest first
+pub fn recent() -> Vec<Entry> {
+    HISTORY.lock().map(|h| h.iter().rev().cloned().collect()).unwrap_or_default()
+}
+
+/// Read the history file, oldest first (a missing file is an empty history)
+/// Lines that don't parse (a torn write) are skipped
+pub fn load(path: &Path) -> Result<Vec<Record>, HistoryError> {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(HistoryError::Read { path: path.to_path_buf(), source }),
+    };
+    let mut records = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|source| HistoryError::Read { path: path.to_path_buf(), source })?;
+        if let Ok(record) = serde_json::from_str(&line) {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Records whose text contains `query` (case-insensitive)
+pub fn search<'a>(records: &'a [Record], query: &str) -> Vec<&'a Record> {
+    let query = query.to_lowercase();
+    records.iter().filter(|r| r.text.to_lowercase().contains(&query)).collect()
+}
+
+/// How `ss9k history export` writes records
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Json,
+    Csv,
+    Text,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            "text" | "txt" => Ok(Self::Text),
+            other => Err(format!("Unknown export format '{}' (expected jsonl, json, csv or text)", other)),
+        }
+    }
+}
+
+/// One record as a readable line: `2026-01-31 14:02:11 [snake] text` (commands marked)
+pub fn format_line(record: &Record) -> String {
+    let time = record.timestamp.get(..19).unwrap_or(&record.timestamp).replace('T', " ");
+    let marker = if record.command { " (command)" } else { "" };
+    format!("{} [{}]{} {}", time, record.mode, marker, record.text)
+}
+
+/// Quote a CSV field when it needs it
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Render records in an export format
+pub fn export<'a>(records: impl IntoIterator<Item = &'a Record>, format: ExportFormat) -> String {
+    let records: Vec<&Record> = records.into_iter().collect();
+    match format {
+        ExportFormat::Jsonl => records
+            .iter()
+            .filter_map(|r| serde_json::to_string(r).ok())
+            .map(|line| line + "\n")
+            .collect(),
+        ExportFormat::Json => serde_json::to_string_pretty(&records).unwrap_or_default() + "\n",
+        ExportFormat::Csv => {
+            let mut out = String::from("timestamp,mode,command,text\n");
+            for r in records {
+                let _ = writeln!(out, "{},{},{},{}", r.timestamp, csv_field(&r.mode), r.command, csv_field(&r.text));
+            }
+            out
+        }
+        ExportFormat::Text => records.iter().map(|r| format_line(r) + "\n").collect(),
+    }
+}
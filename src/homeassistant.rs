@@ -0,0 +1,78 @@
+//! Home Assistant forwarding for SS9K
+//!
+//! This module handles:
+//! - Sending dictation to Home Assistant's conversation API instead of typing it
+//!   (`output_mode = "homeassistant"`)
+//! - Pulling the spoken reply out of the response
+//! - Keeping the conversation id so follow-ups ("and the kitchen?") work
+//!
+//! Leader commands still run locally, so you can switch back or change
+//! modes without Home Assistant understanding you.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::error::HomeAssistantError;
+
+/// Give up on Home Assistant after this long
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Conversation to continue (Home Assistant expires it on its side)
+static CONVERSATION_ID: Mutex<Option<String>> = Mutex::new(None);
+
+#[derive(Deserialize)]
+struct ConversationResult {
+    response: ConversationResponse,
+    conversation_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ConversationResponse {
+    speech: Option<Speech>,
+}
+
+#[derive(Deserialize)]
+struct Speech {
+    plain: Option<PlainSpeech>,
+}
+
+#[derive(Deserialize)]
+struct PlainSpeech {
+    speech: String,
+}
+
+/// Send text to `<url>/api/conversation/process` and return what Home Assistant said
+/// `language` is passed through unless it's "auto"; empty `agent_id` = default agent
+pub fn process(url: &str, token: &str, agent_id: &str, text: &str, language: &str) -> Result<String, HomeAssistantError> {
+    let endpoint = format!("{}/api/conversation/process", url.trim_end_matches('/'));
+    let mut body = serde_json::json!({ "text": text });
+    if language != "auto" {
+        body["language"] = language.into();
+    }
+    if !agent_id.is_empty() {
+        body["agent_id"] = agent_id.into();
+    }
+    if let Some(id) = CONVERSATION_ID.lock().ok().and_then(|id| id.clone()) {
+        body["conversation_id"] = id.into();
+    }
+
+    let response = reqwest::blocking::Client::builder()
+        .timeout(TIMEOUT)
+        .build()?
+        .post(&endpoint)
+        .bearer_auth(token)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()?;
+    if !response.status().is_success() {
+        return Err(HomeAssistantError::Status(response.status()));
+    }
+    let result: ConversationResult = serde_json::from_str(&response.text()?)?;
+
+    if let Ok(mut id) = CONVERSATION_ID.lock() {
+        *id = result.conversation_id;
+    }
+    Ok(result.response.speech.and_then(|s| s.plain).map(|p| p.speech).unwrap_or_default())
+}
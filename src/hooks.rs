@@ -0,0 +1,71 @@
+//! Hook commands for SS9K
+//!
+//! This module handles:
+//! - `hook_pre_record`: a shell command run when recording starts (VAD mode:
+//!   when speech is detected)
+//! - `hook_post_transcribe`: a shell command run after each transcription,
+//!   with the text on stdin and in `SS9K_TEXT`
+//!
+//! Unlike `[commands]` these aren't spoken; they run on every recording or
+//! utterance. They run in the background and SS9K never waits for them. In
+//! privacy mode the text is left out (`SS9K_TEXT` is empty, stdin is closed).
+
+use std::io::Write;
+use std::process::Stdio;
+
+use tracing::{debug, warn};
+
+use crate::commands::get_case_mode;
+use crate::{privacy, profiles, security, Config};
+
+/// Run `hook_pre_record`, if set
+pub fn pre_record(config: &Config) {
+    run("hook_pre_record", &config.hook_pre_record, None, &[]);
+}
+
+/// Run `hook_post_transcribe` for an utterance, if set
+/// `command` = it runs as a command rather than being typed
+pub fn post_transcribe(config: &Config, text: &str, command: bool) {
+    let text = (!privacy::enabled()).then_some(text);
+    let env = [
+        ("SS9K_TEXT", text.unwrap_or_default().to_string()),
+        ("SS9K_COMMAND", if command { "1" } else { "0" }.to_string()),
+        ("SS9K_MODE", format!("{:?}", get_case_mode()).to_lowercase()),
+        ("SS9K_PROFILE", profiles::active().unwrap_or_default()),
+    ];
+    run("hook_post_transcribe", &config.hook_post_transcribe, text, &env);
+}
+
+/// Start `cmd` through the shell without waiting; `stdin` is written to it
+fn run(name: &'static str, cmd: &str, stdin: Option<&str>, env: &[(&str, String)]) {
+    if cmd.trim().is_empty() {
+        return;
+    }
+    let mut command = security::shell(cmd);
+    command
+        .env("SS9K_HOOK", name)
+        .envs(env.iter().map(|(k, v)| (k, v)))
+        .stdin(if stdin.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::null());
+
+    debug!("🪝 Running {}", name);
+    let stdin = stdin.map(str::to_string);
+    std::thread::spawn(move || {
+        let mut child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                warn!("⚠️ {} failed to start: {}", name, e);
+                return;
+            }
+        };
+        if let (Some(text), Some(mut pipe)) = (stdin, child.stdin.take()) {
+            // A hook that doesn't read stdin closes it early; that's fine
+            let _ = writeln!(pipe, "{}", text);
+        }
+        match child.wait() {
+            Ok(status) if !status.success() => warn!("⚠️ {} exited with {}", name, status),
+            Ok(_) => {}
+            Err(e) => warn!("⚠️ {} failed: {}", name, e),
+        }
+    });
+}
@@ -0,0 +1,321 @@
+//! Hotkey names for SS9K
+//!
+//! This module handles:
+//! - Turning `hotkey` / `command_hotkey` names into rdev keys or mouse
+//!   buttons, alone ("F12", "Mouse4") or with modifiers ("Ctrl+Alt+Space",
+//!   "Super+D")
+//! - Keeping track of which modifiers are held, so a combo only fires with
+//!   exactly its own modifiers down
+//! - Turning `switch_key` into a key or mouse button
+//! - Parsing `trigger_device` IDs ("05f3:00ff") and `gamepad_button` names
+//! - Parsing `[midi]` entries: which note or controller does what
+//!
+//! Shared by the listener in main and by config validation (`ss9k check`).
+
+use rdev::{Button, EventType, Key as RdevKey};
+
+/// Modifiers held with a hotkey (left and right count the same)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool, // Super / Windows / Cmd
+}
+
+/// A key or mouse button, as hotkeys and the switch show up
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Key(RdevKey),
+    Button(Button),
+}
+
+/// A hotkey: a key or button, and the modifiers that have to be down with it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Hotkey {
+    pub input: Input,
+    pub modifiers: Modifiers,
+}
+
+impl Hotkey {
+    /// A key or button on its own
+    pub fn new(input: Input) -> Self {
+        Self { input, modifiers: Modifiers::default() }
+    }
+
+    /// Whether pressing `input` with `held` down fires this hotkey
+    pub fn pressed(&self, input: Input, held: Modifiers) -> bool {
+        self.input == input && self.modifiers == held
+    }
+}
+
+/// Back and forward thumb buttons ("Mouse4", "Mouse5") as rdev reports them
+#[cfg(target_os = "windows")]
+const THUMB_BUTTONS: [u8; 2] = [1, 2]; // XButton1, XButton2
+#[cfg(not(target_os = "windows"))]
+const THUMB_BUTTONS: [u8; 2] = [8, 9]; // X11 buttons (4-7 are the wheel)
+
+/// Parse a hotkey string: a key or mouse button on its own, or modifiers
+/// and a key joined with '+' ("Ctrl+Alt+Space", "Super+D", "Shift+F12")
+pub fn parse_hotkey(s: &str) -> Option<Hotkey> {
+    let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+    let key = parts.pop()?;
+    let input = parse_key(key).map(Input::Key).or_else(|| parse_button(key).map(Input::Button));
+    if parts.is_empty() {
+        return input.map(Hotkey::new);
+    }
+    let mut modifiers = Modifiers::default();
+    for part in parts {
+        match part.to_uppercase().as_str() {
+            "CTRL" | "CONTROL" => modifiers.ctrl = true,
+            "ALT" | "OPTION" => modifiers.alt = true,
+            "SHIFT" => modifiers.shift = true,
+            "SUPER" | "META" | "WIN" | "WINDOWS" | "CMD" | "COMMAND" => modifiers.meta = true,
+            _ => return None,
+        }
+    }
+    // Keys that would be typed all the time are fine once a modifier is held
+    let input = input.or_else(|| parse_combo_key(key).map(Input::Key))?;
+    Some(Hotkey { input, modifiers })
+}
+
+/// Keys that only make a hotkey together with a modifier
+fn parse_combo_key(s: &str) -> Option<RdevKey> {
+    let upper = s.to_uppercase();
+    let key = match upper.as_str() {
+        "SPACE" => RdevKey::Space,
+        "ENTER" | "RETURN" => RdevKey::Return,
+        "TAB" => RdevKey::Tab,
+        "ESC" | "ESCAPE" => RdevKey::Escape,
+        "BACKSPACE" => RdevKey::Backspace,
+        "A" => RdevKey::KeyA,
+        "B" => RdevKey::KeyB,
+        "C" => RdevKey::KeyC,
+        "D" => RdevKey::KeyD,
+        "E" => RdevKey::KeyE,
+        "F" => RdevKey::KeyF,
+        "G" => RdevKey::KeyG,
+        "H" => RdevKey::KeyH,
+        "I" => RdevKey::KeyI,
+        "J" => RdevKey::KeyJ,
+        "K" => RdevKey::KeyK,
+        "L" => RdevKey::KeyL,
+        "M" => RdevKey::KeyM,
+        "N" => RdevKey::KeyN,
+        "O" => RdevKey::KeyO,
+        "P" => RdevKey::KeyP,
+        "Q" => RdevKey::KeyQ,
+        "R" => RdevKey::KeyR,
+        "S" => RdevKey::KeyS,
+        "T" => RdevKey::KeyT,
+        "U" => RdevKey::KeyU,
+        "V" => RdevKey::KeyV,
+        "W" => RdevKey::KeyW,
+        "X" => RdevKey::KeyX,
+        "Y" => RdevKey::KeyY,
+        "Z" => RdevKey::KeyZ,
+        "0" => RdevKey::Num0,
+        "1" => RdevKey::Num1,
+        "2" => RdevKey::Num2,
+        "3" => RdevKey::Num3,
+        "4" => RdevKey::Num4,
+        "5" => RdevKey::Num5,
+        "6" => RdevKey::Num6,
+        "7" => RdevKey::Num7,
+        "8" => RdevKey::Num8,
+        "9" => RdevKey::Num9,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Modifier keys currently down, updated from every key event
+#[derive(Debug, Default)]
+pub struct HeldModifiers {
+    ctrl: [bool; 2], // Left, right
+    alt: [bool; 2],
+    shift: [bool; 2],
+    meta: [bool; 2],
+}
+
+impl HeldModifiers {
+    /// Note a modifier going down or up (other events are ignored)
+    pub fn update(&mut self, event: &EventType) {
+        let (key, down) = match *event {
+            EventType::KeyPress(key) => (key, true),
+            EventType::KeyRelease(key) => (key, false),
+            _ => return,
+        };
+        match key {
+            RdevKey::ControlLeft => self.ctrl[0] = down,
+            RdevKey::ControlRight => self.ctrl[1] = down,
+            RdevKey::Alt => self.alt[0] = down,
+            RdevKey::AltGr => self.alt[1] = down,
+            RdevKey::ShiftLeft => self.shift[0] = down,
+            RdevKey::ShiftRight => self.shift[1] = down,
+            RdevKey::MetaLeft => self.meta[0] = down,
+            RdevKey::MetaRight => self.meta[1] = down,
+            _ => {}
+        }
+    }
+
+    /// Which modifiers are down right now
+    pub fn current(&self) -> Modifiers {
+        Modifiers {
+            ctrl: self.ctrl.contains(&true),
+            alt: self.alt.contains(&true),
+            shift: self.shift.contains(&true),
+            meta: self.meta.contains(&true),
+        }
+    }
+}
+
+/// Parse a single key name into an rdev::Key
+fn parse_key(s: &str) -> Option<RdevKey> {
+    match s.to_uppercase().as_str() {
+        "F1" => Some(RdevKey::F1),
+        "F2" => Some(RdevKey::F2),
+        "F3" => Some(RdevKey::F3),
+        "F4" => Some(RdevKey::F4),
+        "F5" => Some(RdevKey::F5),
+        "F6" => Some(RdevKey::F6),
+        "F7" => Some(RdevKey::F7),
+        "F8" => Some(RdevKey::F8),
+        "F9" => Some(RdevKey::F9),
+        "F10" => Some(RdevKey::F10),
+        "F11" => Some(RdevKey::F11),
+        "F12" => Some(RdevKey::F12),
+        "SCROLLLOCK" | "SCROLL_LOCK" | "SCROLL" => Some(RdevKey::ScrollLock),
+        "PAUSE" | "BREAK" => Some(RdevKey::Pause),
+        "PRINTSCREEN" | "PRINT_SCREEN" | "PRTSC" => Some(RdevKey::PrintScreen),
+        "INSERT" | "INS" => Some(RdevKey::Insert),
+        "HOME" => Some(RdevKey::Home),
+        "END" => Some(RdevKey::End),
+        "PAGEUP" | "PAGE_UP" | "PGUP" => Some(RdevKey::PageUp),
+        "PAGEDOWN" | "PAGE_DOWN" | "PGDN" => Some(RdevKey::PageDown),
+        "NUM0" | "NUMPAD0" => Some(RdevKey::Kp0),
+        "NUM1" | "NUMPAD1" => Some(RdevKey::Kp1),
+        "NUM2" | "NUMPAD2" => Some(RdevKey::Kp2),
+        "NUM3" | "NUMPAD3" => Some(RdevKey::Kp3),
+        "NUM4" | "NUMPAD4" => Some(RdevKey::Kp4),
+        "NUM5" | "NUMPAD5" => Some(RdevKey::Kp5),
+        "NUM6" | "NUMPAD6" => Some(RdevKey::Kp6),
+        "NUM7" | "NUMPAD7" => Some(RdevKey::Kp7),
+        "NUM8" | "NUMPAD8" => Some(RdevKey::Kp8),
+        "NUM9" | "NUMPAD9" => Some(RdevKey::Kp9),
+        _ => None,
+    }
+}
+
+/// Parse a mouse button: MOUSE_MIDDLE, MOUSE4/MOUSE5 (the thumb buttons),
+/// or MOUSE6..MOUSE9 (extra buttons, numbered as X11 does)
+fn parse_button(s: &str) -> Option<Button> {
+    let upper = s.trim().to_uppercase();
+    match upper.as_str() {
+        "MOUSE_MIDDLE" | "MIDDLE_CLICK" => Some(Button::Middle),
+        "MOUSE4" | "MOUSE_BACK" => Some(Button::Unknown(THUMB_BUTTONS[0])),
+        "MOUSE5" | "MOUSE_FORWARD" => Some(Button::Unknown(THUMB_BUTTONS[1])),
+        _ => match upper.strip_prefix("MOUSE").and_then(|n| n.parse::<u8>().ok()) {
+            Some(n @ 6..=9) => Some(Button::Unknown(n)),
+            _ => None,
+        },
+    }
+}
+
+/// Parse switch_key: any hotkey name, SPACE/ENTER, MOUSE_MIDDLE, or MOUSE4..MOUSE9
+pub fn parse_switch(s: &str) -> Option<Input> {
+    let upper = s.trim().to_uppercase();
+    match upper.as_str() {
+        "" => None,
+        "SPACE" => Some(Input::Key(RdevKey::Space)),
+        "ENTER" | "RETURN" => Some(Input::Key(RdevKey::Return)),
+        _ => parse_button(&upper).map(Input::Button).or_else(|| parse_key(&upper).map(Input::Key)),
+    }
+}
+
+/// Parse a USB vendor:product ID in hex, as lsusb shows it ("05f3:00ff")
+pub fn parse_device_id(s: &str) -> Option<(u16, u16)> {
+    let (vendor, product) = s.trim().split_once(':')?;
+    Some((u16::from_str_radix(vendor, 16).ok()?, u16::from_str_radix(product, 16).ok()?))
+}
+
+/// Gamepad buttons by their gilrs names, with the names people know them by
+const GAMEPAD_BUTTONS: &[(&str, &[&str])] = &[
+    ("South", &["A", "CROSS"]),
+    ("East", &["B", "CIRCLE"]),
+    ("West", &["X", "SQUARE"]),
+    ("North", &["Y", "TRIANGLE"]),
+    ("C", &[]),
+    ("Z", &[]),
+    ("LeftTrigger", &["LB", "L1"]),
+    ("RightTrigger", &["RB", "R1"]),
+    ("LeftTrigger2", &["LT", "L2"]),
+    ("RightTrigger2", &["RT", "R2"]),
+    ("Select", &["BACK", "VIEW", "SHARE"]),
+    ("Start", &["MENU", "OPTIONS"]),
+    ("Mode", &["GUIDE", "HOME", "PS"]),
+    ("LeftThumb", &["L3"]),
+    ("RightThumb", &["R3"]),
+    ("DPadUp", &[]),
+    ("DPadDown", &[]),
+    ("DPadLeft", &[]),
+    ("DPadRight", &[]),
+];
+
+/// A game controller button: a gilrs name, or a raw code for buttons gilrs
+/// doesn't name (back paddles)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GamepadButton {
+    Named(&'static str),
+    Code(u32),
+}
+
+/// Parse `gamepad_button`: a name ("LB", "South", "DPadUp") or a raw code
+pub fn parse_gamepad_button(s: &str) -> Option<GamepadButton> {
+    let upper = s.trim().to_uppercase();
+    if let Ok(code) = upper.parse::<u32>() {
+        return Some(GamepadButton::Code(code));
+    }
+    GAMEPAD_BUTTONS
+        .iter()
+        .find(|(name, aliases)| name.to_uppercase() == upper || aliases.contains(&upper.as_str()))
+        .map(|(name, _)| GamepadButton::Named(name))
+}
+
+/// A MIDI message a `[midi]` entry listens for (any channel)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MidiMessage {
+    Note(u8), // Down on note-on, up on note-off
+    Cc(u8),   // Down at 64 and over, up below (how sustain pedals work)
+}
+
+/// Parse a `[midi]` key: "note 60" or "cc 64"
+pub fn parse_midi_message(s: &str) -> Option<MidiMessage> {
+    let (kind, number) = s.trim().split_once(char::is_whitespace)?;
+    let number = number.trim().parse::<u8>().ok().filter(|n| *n < 128)?;
+    match kind.to_lowercase().as_str() {
+        "note" => Some(MidiMessage::Note(number)),
+        "cc" => Some(MidiMessage::Cc(number)),
+        _ => None,
+    }
+}
+
+/// What a `[midi]` entry does
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MidiAction {
+    Record,       // Acts as the hotkey
+    Command,      // Acts as command_hotkey
+    Abort,        // Acts as abort_hotkey
+    Mode(String), // Switches case mode
+}
+
+/// Parse a `[midi]` value: "record", "command", "abort" or "mode <name>"
+pub fn parse_midi_action(s: &str) -> Option<MidiAction> {
+    let s = s.trim().to_lowercase();
+    match s.as_str() {
+        "record" => Some(MidiAction::Record),
+        "command" => Some(MidiAction::Command),
+        "abort" => Some(MidiAction::Abort),
+        _ => s.strip_prefix("mode ").map(|name| MidiAction::Mode(name.trim().to_string())),
+    }
+}
@@ -0,0 +1,178 @@
+//! Local HTTP server for SS9K (http feature)
+//!
+//! This module handles:
+//! - Listening on 127.0.0.1 only (never reachable from the network)
+//! - `GET /metrics` - Prometheus metrics (see `metrics`)
+//! - The control API: `GET /status`, `POST /record/start|stop|toggle`,
+//!   `GET|POST /mode`, `POST /type`
+//! - The web UI and its API when built with the webui feature (see `webui`)
+//!
+//! Runs on its own thread with a blocking server; requests are tiny and rare.
+//!
+//! `/type` injects keystrokes, so the control API (reads included) needs a
+//! localhost Host header (stops DNS rebinding) and no foreign Origin (stops
+//! other web pages talking to us). curl, scripts and StreamDeck plugins
+//! pass both checks without trying. `/metrics` is left open for scrapers.
+
+use std::io::{Cursor, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use arc_swap::ArcSwap;
+use crate::keyboard::Keys;
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{info, warn};
+
+use crate::commands::type_text;
+use crate::control::{self, RecordRequest};
+use crate::error::HttpError;
+use crate::metrics;
+use crate::Config;
+
+/// Largest request body we accept
+const MAX_BODY: u64 = 1024 * 1024;
+
+/// Response with an in-memory body
+pub(crate) type HttpResponse = Response<Cursor<Vec<u8>>>;
+
+/// What request handlers can see
+#[derive(Clone)]
+pub struct Context {
+    /// Live config (hot-reloaded)
+    pub config: Arc<ArcSwap<Config>>,
+    /// File the config was loaded from, for edits (None = built-in defaults)
+    pub config_path: Option<PathBuf>,
+    /// Port we're listening on, to check the Host header
+    pub port: u16,
+}
+
+/// Start the server on 127.0.0.1:`port` in a background thread
+pub fn start(port: u16, config: Arc<ArcSwap<Config>>, config_path: Option<PathBuf>) -> Result<JoinHandle<()>, HttpError> {
+    let addr = format!("127.0.0.1:{}", port);
+    let server = Server::http(&addr).map_err(|source| HttpError::Bind { addr: addr.clone(), source })?;
+    info!("🌐 HTTP server listening on http://{}", addr);
+    let ctx = Context { config, config_path, port };
+
+    Ok(std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let response = route(&mut request, &ctx)
+                .unwrap_or_else(|| Response::from_string("Not found\n").with_status_code(404));
+            if let Err(e) = request.respond(response) {
+                warn!("⚠️ HTTP response failed: {}", e);
+            }
+        }
+    }))
+}
+
+/// Pick a response for a request (None = 404)
+fn route(request: &mut Request, ctx: &Context) -> Option<HttpResponse> {
+    #[cfg(feature = "webui")]
+    if let Some(response) = crate::webui::route(request, ctx) {
+        return Some(response);
+    }
+    let response = match (request.method(), request.url()) {
+        (Method::Get, "/metrics") => Response::from_string(metrics::render())
+            .with_header(header("Content-Type", "text/plain; version=0.0.4")),
+        (Method::Get, "/status") => local(request, ctx, || json(&control::status(&ctx.config.load()))),
+        (Method::Get, "/mode") => local(request, ctx, || {
+            json(&serde_json::json!({ "mode": control::status(&ctx.config.load()).mode }))
+        }),
+        (Method::Post, "/record/start") => control_response(record(request, ctx, RecordRequest::Start)),
+        (Method::Post, "/record/stop") => control_response(record(request, ctx, RecordRequest::Stop)),
+        (Method::Post, "/record/toggle") => control_response(record(request, ctx, RecordRequest::Toggle)),
+        (Method::Post, "/mode") => control_response(set_mode(request, ctx)),
+        (Method::Post, "/type") => control_response(type_body(request, ctx)),
+        _ => return None,
+    };
+    Some(response)
+}
+
+/// Turn a control handler's result into a response, logging rejections
+fn control_response(result: Result<(), (u16, String)>) -> HttpResponse {
+    match result {
+        Ok(()) => json(&serde_json::json!({ "ok": true })),
+        Err((status, message)) => {
+            warn!("⚠️ HTTP request rejected: {}", message);
+            Response::from_string(message).with_status_code(status)
+        }
+    }
+}
+
+/// Answer a read-only request only if it passes `check_local` like the
+/// writes: status says what you're doing, which other pages shouldn't see
+fn local(request: &Request, ctx: &Context, respond: impl FnOnce() -> HttpResponse) -> HttpResponse {
+    match check_local(request, ctx.port) {
+        Ok(()) => respond(),
+        Err(rejected) => control_response(Err(rejected)),
+    }
+}
+
+/// POST /record/*: start, stop or toggle recording like the hotkey would
+fn record(request: &Request, ctx: &Context, record: RecordRequest) -> Result<(), (u16, String)> {
+    check_local(request, ctx.port)?;
+    if !control::request(record) {
+        return Err((503, "Recording can't be controlled right now".to_string()));
+    }
+    Ok(())
+}
+
+/// POST /mode: body is a mode name, same as "[leader] mode <name>"
+fn set_mode(request: &mut Request, ctx: &Context) -> Result<(), (u16, String)> {
+    check_local(request, ctx.port)?;
+    let mode = control::set_mode(&read_body(request)?, &ctx.config.load()).map_err(|e| (400, e))?;
+    info!("🌐 Mode set to {:?}", mode);
+    Ok(())
+}
+
+/// POST /type: type the body as if it had been dictated (current case mode applies)
+fn type_body(request: &mut Request, ctx: &Context) -> Result<(), (u16, String)> {
+    check_local(request, ctx.port)?;
+    let text = read_body(request)?;
+    if text.is_empty() {
+        return Err((400, "Nothing to type".to_string()));
+    }
+    let mut enigo = Keys::new().map_err(|e| (500, format!("Keyboard init error: {}", e)))?;
+    type_text(&mut enigo, &text).map_err(|e| (500, format!("Type error: {}", e)))
+}
+
+/// Reject control requests that may not come from this machine:
+/// the Host must be localhost, and a browser Origin must be our own page
+pub(crate) fn check_local(request: &Request, port: u16) -> Result<(), (u16, String)> {
+    let hosts = [format!("127.0.0.1:{}", port), format!("localhost:{}", port)];
+    let header_value = |name: &'static str| {
+        request.headers().iter().find(|h| h.field.equiv(name)).map(|h| h.value.to_string())
+    };
+    if !header_value("Host").is_some_and(|host| hosts.contains(&host)) {
+        return Err((403, "Only accepted from localhost".to_string()));
+    }
+    if let Some(origin) = header_value("Origin")
+        && !hosts.iter().any(|host| origin == format!("http://{}", host))
+    {
+        return Err((403, format!("Not accepted from {}", origin)));
+    }
+    Ok(())
+}
+
+/// Read a request body as text (up to MAX_BODY)
+pub(crate) fn read_body(request: &mut Request) -> Result<String, (u16, String)> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_BODY)
+        .read_to_string(&mut body)
+        .map_err(|e| (400, format!("Failed to read request: {}", e)))?;
+    Ok(body)
+}
+
+/// Serialize a value as a JSON response
+pub(crate) fn json(value: &impl Serialize) -> HttpResponse {
+    Response::from_string(serde_json::to_string(value).unwrap_or_default())
+        .with_header(header("Content-Type", "application/json"))
+}
+
+/// Build a header from static strings
+pub(crate) fn header(name: &'static str, value: &'static str) -> Header {
+    Header::from_bytes(name, value).expect("static header")
+}
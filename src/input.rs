@@ -0,0 +1,275 @@
+//! Hotkeys read straight from /dev/input for SS9K
+//!
+//! This module handles:
+//! - `hotkey_backend = "evdev"`: reading key and button presses from the
+//!   kernel's input devices instead of through X11, so the hotkey, the
+//!   command hotkey and the switch work on Wayland (GNOME, KDE, Sway,
+//!   Hyprland...) and on the console
+//! - `hotkey_device`: which devices to read ("" = every keyboard and mouse,
+//!   a /dev/input path, or part of a device name as `evtest` lists them)
+//! - `trigger_device`: a USB foot pedal, presentation clicker or other HID
+//!   device, picked by vendor:product ID, whose buttons all act as the hotkey
+//!   (with either hotkey backend)
+//!
+//! Presses come out as rdev events, so main's callback handles both
+//! backends the same way. Reading /dev/input needs the user in the `input`
+//! group. Devices plugged in after startup aren't picked up.
+
+#[cfg(target_os = "linux")]
+pub use linux::{listen, watch_trigger};
+
+#[cfg(not(target_os = "linux"))]
+pub use other::{listen, watch_trigger};
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::collections::HashSet;
+    use std::sync::mpsc;
+    use std::time::SystemTime;
+
+    use evdev::{Device, EventSummary, KeyCode};
+    use rdev::{Button, Event, EventType, Key as RdevKey};
+    use tracing::{debug, info, warn};
+
+    use crate::error::HotkeyError;
+    use crate::hotkeys::parse_device_id;
+
+    /// Read the devices `device` picks and pass presses to `callback` until
+    /// they're all gone
+    pub fn listen(device: &str, mut callback: impl FnMut(Event)) -> Result<(), HotkeyError> {
+        let devices = open(device)?;
+        let (tx, rx) = mpsc::channel();
+        for (path, mut device) in devices {
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                loop {
+                    let events = match device.fetch_events() {
+                        Ok(events) => events,
+                        Err(e) => {
+                            warn!("⚠️ Stopped reading {}: {}", path, e);
+                            return;
+                        }
+                    };
+                    for event in events {
+                        // value: 0 = up, 1 = down, 2 = auto-repeat (ignored)
+                        let EventSummary::Key(_, code, value @ 0..=1) = event.destructure() else {
+                            continue;
+                        };
+                        if let Some(event_type) = event_type(code, value == 1)
+                            && tx.send(event_type).is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+            });
+        }
+        drop(tx);
+        for event_type in rx {
+            callback(Event { time: SystemTime::now(), name: None, event_type });
+        }
+        Err(HotkeyError::Closed)
+    }
+
+    /// Read the devices with the vendor:product ID `id` in the background and
+    /// call `on(true)` when one of their buttons goes down, `on(false)` when
+    /// the last one comes back up
+    pub fn watch_trigger(id: &str, on: impl Fn(bool) + Clone + Send + 'static) -> Result<(), HotkeyError> {
+        let (vendor, product) = parse_device_id(id).ok_or_else(|| HotkeyError::BadId(id.to_string()))?;
+        let mut found = false;
+        for (path, mut device) in evdev::enumerate() {
+            let input_id = device.input_id();
+            if input_id.vendor() != vendor || input_id.product() != product {
+                continue;
+            }
+            found = true;
+            info!("🦶 Trigger: {} ({})", path.display(), device.name().unwrap_or("unnamed"));
+            // Pedals often send a letter or page-down; keep it away from apps
+            if let Err(e) = device.grab() {
+                warn!("⚠️ Couldn't take {} for SS9K alone ({}), apps will see its keys too", path.display(), e);
+            }
+            let on = on.clone();
+            std::thread::spawn(move || {
+                let mut down = HashSet::new();
+                loop {
+                    let events = match device.fetch_events() {
+                        Ok(events) => events,
+                        Err(e) => {
+                            warn!("⚠️ Stopped reading trigger {}: {}", path.display(), e);
+                            return;
+                        }
+                    };
+                    for event in events {
+                        let EventSummary::Key(_, code, value @ 0..=1) = event.destructure() else {
+                            continue;
+                        };
+                        let was_down = !down.is_empty();
+                        if value == 1 {
+                            down.insert(code);
+                        } else {
+                            down.remove(&code);
+                        }
+                        let is_down = !down.is_empty();
+                        if is_down != was_down {
+                            on(is_down);
+                        }
+                    }
+                }
+            });
+        }
+        if !found {
+            return Err(HotkeyError::NoTrigger(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Open the devices `device` picks: a path, part of a name, or "" for
+    /// anything with keys or buttons
+    fn open(device: &str) -> Result<Vec<(String, Device)>, HotkeyError> {
+        let device = device.trim();
+        if device.starts_with('/') {
+            let opened = Device::open(device).map_err(|source| HotkeyError::Open { path: device.into(), source })?;
+            info!("⌨️ Reading hotkeys from {} ({})", device, opened.name().unwrap_or("unnamed"));
+            return Ok(vec![(device.to_string(), opened)]);
+        }
+
+        let wanted = device.to_lowercase();
+        let mut devices = Vec::new();
+        for (path, opened) in evdev::enumerate() {
+            let name = opened.name().unwrap_or_default().to_string();
+            let matches = if wanted.is_empty() {
+                // Keyboards, mice and switches; not power buttons, lid switches...
+                opened.supported_keys().is_some_and(|keys| {
+                    keys.contains(KeyCode::KEY_SPACE) || keys.contains(KeyCode::BTN_MIDDLE) || keys.contains(KeyCode::KEY_F12)
+                })
+            } else {
+                name.to_lowercase().contains(&wanted)
+            };
+            // Our own virtual keyboard ("uinput" keyboard backend)
+            if !matches || name == "SS9K virtual keyboard" {
+                continue;
+            }
+            info!("⌨️ Reading hotkeys from {} ({})", path.display(), name);
+            devices.push((path.display().to_string(), opened));
+        }
+        debug!("⌨️ {} input device(s) opened", devices.len());
+        if devices.is_empty() {
+            return Err(HotkeyError::NoDevice(device.to_string()));
+        }
+        Ok(devices)
+    }
+
+    /// The rdev event for a key or button going down or up (None = a key
+    /// no hotkey or switch can be)
+    fn event_type(code: KeyCode, down: bool) -> Option<EventType> {
+        let key = match code {
+            KeyCode::KEY_F1 => RdevKey::F1,
+            KeyCode::KEY_F2 => RdevKey::F2,
+            KeyCode::KEY_F3 => RdevKey::F3,
+            KeyCode::KEY_F4 => RdevKey::F4,
+            KeyCode::KEY_F5 => RdevKey::F5,
+            KeyCode::KEY_F6 => RdevKey::F6,
+            KeyCode::KEY_F7 => RdevKey::F7,
+            KeyCode::KEY_F8 => RdevKey::F8,
+            KeyCode::KEY_F9 => RdevKey::F9,
+            KeyCode::KEY_F10 => RdevKey::F10,
+            KeyCode::KEY_F11 => RdevKey::F11,
+            KeyCode::KEY_F12 => RdevKey::F12,
+            KeyCode::KEY_SCROLLLOCK => RdevKey::ScrollLock,
+            KeyCode::KEY_PAUSE => RdevKey::Pause,
+            KeyCode::KEY_SYSRQ => RdevKey::PrintScreen,
+            KeyCode::KEY_INSERT => RdevKey::Insert,
+            KeyCode::KEY_HOME => RdevKey::Home,
+            KeyCode::KEY_END => RdevKey::End,
+            KeyCode::KEY_PAGEUP => RdevKey::PageUp,
+            KeyCode::KEY_PAGEDOWN => RdevKey::PageDown,
+            KeyCode::KEY_KP0 => RdevKey::Kp0,
+            KeyCode::KEY_KP1 => RdevKey::Kp1,
+            KeyCode::KEY_KP2 => RdevKey::Kp2,
+            KeyCode::KEY_KP3 => RdevKey::Kp3,
+            KeyCode::KEY_KP4 => RdevKey::Kp4,
+            KeyCode::KEY_KP5 => RdevKey::Kp5,
+            KeyCode::KEY_KP6 => RdevKey::Kp6,
+            KeyCode::KEY_KP7 => RdevKey::Kp7,
+            KeyCode::KEY_KP8 => RdevKey::Kp8,
+            KeyCode::KEY_KP9 => RdevKey::Kp9,
+            KeyCode::KEY_SPACE => RdevKey::Space,
+            KeyCode::KEY_ENTER => RdevKey::Return,
+            KeyCode::KEY_TAB => RdevKey::Tab,
+            KeyCode::KEY_ESC => RdevKey::Escape,
+            KeyCode::KEY_BACKSPACE => RdevKey::Backspace,
+            KeyCode::KEY_A => RdevKey::KeyA,
+            KeyCode::KEY_B => RdevKey::KeyB,
+            KeyCode::KEY_C => RdevKey::KeyC,
+            KeyCode::KEY_D => RdevKey::KeyD,
+            KeyCode::KEY_E => RdevKey::KeyE,
+            KeyCode::KEY_F => RdevKey::KeyF,
+            KeyCode::KEY_G => RdevKey::KeyG,
+            KeyCode::KEY_H => RdevKey::KeyH,
+            KeyCode::KEY_I => RdevKey::KeyI,
+            KeyCode::KEY_J => RdevKey::KeyJ,
+            KeyCode::KEY_K => RdevKey::KeyK,
+            KeyCode::KEY_L => RdevKey::KeyL,
+            KeyCode::KEY_M => RdevKey::KeyM,
+            KeyCode::KEY_N => RdevKey::KeyN,
+            KeyCode::KEY_O => RdevKey::KeyO,
+            KeyCode::KEY_P => RdevKey::KeyP,
+            KeyCode::KEY_Q => RdevKey::KeyQ,
+            KeyCode::KEY_R => RdevKey::KeyR,
+            KeyCode::KEY_S => RdevKey::KeyS,
+            KeyCode::KEY_T => RdevKey::KeyT,
+            KeyCode::KEY_U => RdevKey::KeyU,
+            KeyCode::KEY_V => RdevKey::KeyV,
+            KeyCode::KEY_W => RdevKey::KeyW,
+            KeyCode::KEY_X => RdevKey::KeyX,
+            KeyCode::KEY_Y => RdevKey::KeyY,
+            KeyCode::KEY_Z => RdevKey::KeyZ,
+            KeyCode::KEY_0 => RdevKey::Num0,
+            KeyCode::KEY_1 => RdevKey::Num1,
+            KeyCode::KEY_2 => RdevKey::Num2,
+            KeyCode::KEY_3 => RdevKey::Num3,
+            KeyCode::KEY_4 => RdevKey::Num4,
+            KeyCode::KEY_5 => RdevKey::Num5,
+            KeyCode::KEY_6 => RdevKey::Num6,
+            KeyCode::KEY_7 => RdevKey::Num7,
+            KeyCode::KEY_8 => RdevKey::Num8,
+            KeyCode::KEY_9 => RdevKey::Num9,
+            // Modifiers, for combos like Ctrl+Alt+Space
+            KeyCode::KEY_LEFTCTRL => RdevKey::ControlLeft,
+            KeyCode::KEY_RIGHTCTRL => RdevKey::ControlRight,
+            KeyCode::KEY_LEFTALT => RdevKey::Alt,
+            KeyCode::KEY_RIGHTALT => RdevKey::AltGr,
+            KeyCode::KEY_LEFTSHIFT => RdevKey::ShiftLeft,
+            KeyCode::KEY_RIGHTSHIFT => RdevKey::ShiftRight,
+            KeyCode::KEY_LEFTMETA => RdevKey::MetaLeft,
+            KeyCode::KEY_RIGHTMETA => RdevKey::MetaRight,
+            // Mouse buttons, numbered the way X11 (and so switch_key) does
+            KeyCode::BTN_MIDDLE => return Some(button(Button::Middle, down)),
+            KeyCode::BTN_SIDE => return Some(button(Button::Unknown(8), down)),
+            KeyCode::BTN_EXTRA => return Some(button(Button::Unknown(9), down)),
+            _ => return None,
+        };
+        Some(if down { EventType::KeyPress(key) } else { EventType::KeyRelease(key) })
+    }
+
+    fn button(button: Button, down: bool) -> EventType {
+        if down { EventType::ButtonPress(button) } else { EventType::ButtonRelease(button) }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod other {
+    use rdev::Event;
+
+    use crate::error::HotkeyError;
+
+    /// /dev/input is Linux-only
+    pub fn listen(_device: &str, _callback: impl FnMut(Event)) -> Result<(), HotkeyError> {
+        Err(HotkeyError::Unsupported)
+    }
+
+    /// /dev/input is Linux-only
+    pub fn watch_trigger(_id: &str, _on: impl Fn(bool) + Clone + Send + 'static) -> Result<(), HotkeyError> {
+        Err(HotkeyError::Unsupported)
+    }
+}
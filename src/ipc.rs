@@ -0,0 +1,100 @@
+//! Control socket for SS9K (Unix only)
+//!
+//! This module handles:
+//! - A Unix socket a running instance listens on (`$XDG_RUNTIME_DIR/ss9k.sock`),
+//!   which also keeps a second instance from starting
+//! - The client side `ss9k ctl` uses to talk to it
+//!
+//! One command per connection, one line each way. Commands are the ones
+//! `control::run_command` knows; replies are `ok`, `ok <payload>`, or
+//! `error: <message>`.
+//!
+//! The socket is created owner-only (0600), so other users can't type
+//! into your session through it.
+
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use arc_swap::ArcSwap;
+use tracing::{info, warn};
+
+use crate::control;
+use crate::error::IpcError;
+use crate::Config;
+
+/// Where the socket lives: the runtime dir if there is one, else the temp dir
+pub fn socket_path() -> PathBuf {
+    dirs::runtime_dir().unwrap_or_else(std::env::temp_dir).join("ss9k.sock")
+}
+
+/// Claim the control socket; `IpcError::InUse` means another SS9K is running
+/// Done early at startup so a second instance stops before loading a model
+pub fn bind() -> Result<UnixListener, IpcError> {
+    let path = socket_path();
+    if path.exists() {
+        // A socket nobody answers is left over from a crash
+        if UnixStream::connect(&path).is_ok() {
+            return Err(IpcError::InUse(path));
+        }
+        let _ = std::fs::remove_file(&path);
+    }
+    let listener = UnixListener::bind(&path).map_err(|source| IpcError::Bind { path: path.clone(), source })?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|source| IpcError::Bind { path: path.clone(), source })?;
+    info!("🔌 Control socket: {:?}", path);
+    Ok(listener)
+}
+
+/// Answer commands on a bound control socket in a background thread
+pub fn start(listener: UnixListener, config: Arc<ArcSwap<Config>>, config_path: Option<PathBuf>) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(s) => s,
+                Err(e) => {
+                    warn!("⚠️ Control socket error: {}", e);
+                    continue;
+                }
+            };
+            if let Err(e) = serve(stream, &config, config_path.as_deref()) {
+                warn!("⚠️ Control socket error: {}", e);
+            }
+        }
+    })
+}
+
+/// Read one command, write one reply
+fn serve(stream: UnixStream, config: &ArcSwap<Config>, config_path: Option<&Path>) -> std::io::Result<()> {
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    if line.trim().is_empty() {
+        return Ok(()); // Connected and left (another instance checking if we're alive)
+    }
+    let reply = match control::run_command(line.trim(), config, config_path) {
+        Ok(payload) if payload.is_empty() => "ok".to_string(),
+        Ok(payload) => format!("ok {}", payload),
+        Err(message) => {
+            warn!("⚠️ Control command '{}' failed: {}", line.trim(), message);
+            format!("error: {}", message)
+        }
+    };
+    writeln!(&stream, "{}", reply)
+}
+
+/// Send a command to a running instance and return its reply payload
+pub fn send(command: &str) -> Result<String, IpcError> {
+    let path = socket_path();
+    let stream = UnixStream::connect(&path).map_err(|source| IpcError::Connect { path, source })?;
+    writeln!(&stream, "{}", command)?;
+    let mut reply = String::new();
+    BufReader::new(&stream).read_line(&mut reply)?;
+    let reply = reply.trim();
+    match reply.strip_prefix("error: ") {
+        Some(message) => Err(IpcError::Rejected(message.to_string())),
+        None => Ok(reply.strip_prefix("ok").unwrap_or(reply).trim().to_string()),
+    }
+}
@@ -0,0 +1,468 @@
+//! Keyboard backends for SS9K
+//!
+//! This module handles:
+//! - `keyboard_backend`: which way typing and key presses reach the desktop
+//!   - "enigo" (default): X11, Windows and macOS through enigo
+//!   - "wtype": Wayland's virtual-keyboard protocol through the `wtype` tool
+//!     (wlroots compositors: Sway, Hyprland, river...)
+//!   - "ydotool": the kernel's uinput through `ydotool` and its `ydotoold`
+//!     daemon (any compositor, GNOME and KDE included)
+//!   - "uinput": a virtual keyboard SS9K creates itself in /dev/uinput (any
+//!     compositor, no daemon; needs write access to /dev/uinput)
+//! - `Keys`: one of those, behind enigo's own `Keyboard` and `Mouse` traits so
+//!   the rest of SS9K calls `text`, `key` and `button` the same way whichever
+//!   is picked
+//!
+//! ydotool and uinput send key codes, so text goes through a US layout: they
+//! type ASCII, not emoji. wtype types anything but can't hold a key down
+//! between commands ("command hold w"). The mouse works with enigo and
+//! ydotool; wtype and uinput are keyboard-only.
+
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use enigo::{Axis, Button, Coordinate, Direction, Enigo, InputError, InputResult, Key, Keyboard, Mouse, Settings};
+use tracing::warn;
+
+use crate::error::OutputError;
+use crate::Config;
+
+/// Backend names `keyboard_backend` takes
+pub const BACKENDS: &[&str] = &["enigo", "wtype", "ydotool", "uinput"];
+
+/// Index into `BACKENDS` picked by the config
+static BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// Apply `keyboard_backend` (startup and config reloads)
+pub fn configure(config: &Config) {
+    let index = BACKENDS.iter().position(|b| *b == config.keyboard_backend).unwrap_or_else(|| {
+        warn!("⚠️ Unknown keyboard_backend '{}', using enigo", config.keyboard_backend);
+        0
+    });
+    BACKEND.store(index as u8, Ordering::SeqCst);
+}
+
+/// The configured keyboard
+pub enum Keys {
+    Enigo(Enigo),
+    Wtype { held: Vec<&'static str> }, // Modifiers down ("ctrl"), applied to the next key
+    Ydotool,
+    Uinput,
+}
+
+impl Keys {
+    /// Open the configured backend
+    pub fn new() -> Result<Self, OutputError> {
+        match BACKENDS[usize::from(BACKEND.load(Ordering::SeqCst))] {
+            "wtype" => Ok(Self::Wtype { held: Vec::new() }),
+            "ydotool" => Ok(Self::Ydotool),
+            "uinput" => {
+                uinput::open()?;
+                Ok(Self::Uinput)
+            }
+            _ => Ok(Self::Enigo(Enigo::new(&Settings::default())?)),
+        }
+    }
+
+    /// Press/release key codes in order (ydotool and uinput)
+    fn send_codes(&mut self, events: &[(u16, bool)]) -> InputResult<()> {
+        match self {
+            Self::Uinput => uinput::send(events),
+            _ => {
+                let args: Vec<String> = events.iter().map(|(code, down)| format!("{}:{}", code, u8::from(*down))).collect();
+                run("ydotool", &["key"], &args)
+            }
+        }
+    }
+}
+
+impl Keyboard for Keys {
+    fn fast_text(&mut self, text: &str) -> InputResult<Option<()>> {
+        match self {
+            Self::Enigo(enigo) => enigo.fast_text(text),
+            Self::Wtype { .. } => run("wtype", &["--"], &[text.to_string()]).map(Some),
+            Self::Ydotool => run("ydotool", &["type", "--"], &[text.to_string()]).map(Some),
+            Self::Uinput => {
+                let mut events = Vec::new();
+                for c in text.chars() {
+                    let (code, shift) = char_code(c)
+                        .ok_or_else(|| InputError::Mapping(format!("'{}' can't be typed with the uinput backend (US layout ASCII only)", c)))?;
+                    events.extend(clicked(code, shift));
+                }
+                self.send_codes(&events).map(Some)
+            }
+        }
+    }
+
+    fn key(&mut self, key: Key, direction: Direction) -> InputResult<()> {
+        match self {
+            Self::Enigo(enigo) => enigo.key(key, direction),
+            Self::Wtype { held } => {
+                if let Some(modifier) = wtype_modifier(key) {
+                    match direction {
+                        Direction::Press => held.push(modifier),
+                        Direction::Release => held.retain(|m| *m != modifier),
+                        Direction::Click => {}
+                    }
+                    return Ok(());
+                }
+                let (name, plain) = match key {
+                    Key::Unicode(c) => (c.to_string(), held.is_empty()),
+                    key => (wtype_keysym(key).ok_or_else(|| unmapped(key, "the wtype"))?.to_string(), false),
+                };
+                // A lone character is just text; with modifiers it's a keysym ("v" for ctrl+v)
+                if plain && direction == Direction::Click {
+                    return run("wtype", &["--"], &[name]);
+                }
+                let flag = match direction {
+                    Direction::Click => "-k",
+                    Direction::Press => "-P",
+                    Direction::Release => "-p",
+                };
+                let mut args = Vec::new();
+                for modifier in held.iter() {
+                    args.extend(["-M".to_string(), modifier.to_string()]);
+                }
+                args.extend([flag.to_string(), name]);
+                for modifier in held.iter().rev() {
+                    args.extend(["-m".to_string(), modifier.to_string()]);
+                }
+                run("wtype", &[], &args)
+            }
+            Self::Ydotool | Self::Uinput => {
+                let (code, shift) = match key {
+                    Key::Unicode(c) => char_code(c),
+                    key => key_code(key).map(|code| (code, false)),
+                }
+                .ok_or_else(|| unmapped(key, "this"))?;
+                let events = match direction {
+                    Direction::Click => clicked(code, shift),
+                    Direction::Press => vec![(code, true)],
+                    Direction::Release => vec![(code, false)],
+                };
+                self.send_codes(&events)
+            }
+        }
+    }
+
+    fn raw(&mut self, keycode: u16, direction: Direction) -> InputResult<()> {
+        match self {
+            Self::Enigo(enigo) => enigo.raw(keycode, direction),
+            Self::Wtype { .. } => Err(InputError::InvalidInput("raw key codes aren't supported by wtype")),
+            Self::Ydotool | Self::Uinput => {
+                let events = match direction {
+                    Direction::Click => vec![(keycode, true), (keycode, false)],
+                    Direction::Press => vec![(keycode, true)],
+                    Direction::Release => vec![(keycode, false)],
+                };
+                self.send_codes(&events)
+            }
+        }
+    }
+}
+
+impl Mouse for Keys {
+    fn button(&mut self, button: Button, direction: Direction) -> InputResult<()> {
+        match self {
+            Self::Enigo(enigo) => enigo.button(button, direction),
+            Self::Ydotool => {
+                // ydotool click takes the button in the low bits, 0x40 = down and 0x80 = up
+                let code = match button {
+                    Button::Left => 0x00,
+                    Button::Right => 0x01,
+                    Button::Middle => 0x02,
+                    _ => return Err(InputError::InvalidInput("ydotool clicks left, right and middle only")),
+                };
+                let bits = match direction {
+                    Direction::Click => 0xC0,
+                    Direction::Press => 0x40,
+                    Direction::Release => 0x80,
+                };
+                run("ydotool", &["click"], &[format!("0x{:02X}", code | bits)])
+            }
+            Self::Wtype { .. } | Self::Uinput => Err(no_mouse()),
+        }
+    }
+
+    fn move_mouse(&mut self, x: i32, y: i32, coordinate: Coordinate) -> InputResult<()> {
+        match self {
+            Self::Enigo(enigo) => enigo.move_mouse(x, y, coordinate),
+            Self::Ydotool => {
+                let absolute: &[&str] = if coordinate == Coordinate::Abs { &["mousemove", "--absolute"] } else { &["mousemove"] };
+                run("ydotool", absolute, &["-x".to_string(), x.to_string(), "-y".to_string(), y.to_string()])
+            }
+            Self::Wtype { .. } | Self::Uinput => Err(no_mouse()),
+        }
+    }
+
+    fn scroll(&mut self, length: i32, axis: Axis) -> InputResult<()> {
+        match self {
+            Self::Enigo(enigo) => enigo.scroll(length, axis),
+            // The wheel turns the other way round: positive is up
+            Self::Ydotool => {
+                let (x, y) = if axis == Axis::Horizontal { (length, 0) } else { (0, -length) };
+                run("ydotool", &["mousemove", "--wheel"], &["-x".to_string(), x.to_string(), "-y".to_string(), y.to_string()])
+            }
+            Self::Wtype { .. } | Self::Uinput => Err(no_mouse()),
+        }
+    }
+
+    fn main_display(&self) -> InputResult<(i32, i32)> {
+        match self {
+            Self::Enigo(enigo) => enigo.main_display(),
+            _ => Err(InputError::InvalidInput("only the enigo keyboard backend knows the screen size")),
+        }
+    }
+
+    fn location(&self) -> InputResult<(i32, i32)> {
+        match self {
+            Self::Enigo(enigo) => enigo.location(),
+            _ => Err(InputError::InvalidInput("only the enigo keyboard backend knows where the pointer is")),
+        }
+    }
+}
+
+fn no_mouse() -> InputError {
+    InputError::InvalidInput("the mouse needs keyboard_backend \"enigo\" or \"ydotool\"")
+}
+
+fn unmapped(key: Key, backend: &str) -> InputError {
+    InputError::Mapping(format!("{:?} isn't supported by {} keyboard backend", key, backend))
+}
+
+/// Run a typing tool, logging what it printed if it failed
+fn run(program: &'static str, args: &[&str], more: &[String]) -> InputResult<()> {
+    let output = Command::new(program).args(args).args(more).stdin(Stdio::null()).output().map_err(|e| {
+        warn!("⚠️ Can't run {}: {}", program, e);
+        InputError::Simulate("keyboard backend tool couldn't be started")
+    })?;
+    if !output.status.success() {
+        warn!("⚠️ {} failed: {}", program, String::from_utf8_lossy(&output.stderr).trim());
+        return Err(InputError::Simulate("keyboard backend tool failed"));
+    }
+    Ok(())
+}
+
+// Linux input event codes (linux/input-event-codes.h)
+const KEY_LEFTSHIFT: u16 = 42;
+
+/// Press and release `code`, with shift held around it if `shift`
+fn clicked(code: u16, shift: bool) -> Vec<(u16, bool)> {
+    if shift {
+        vec![(KEY_LEFTSHIFT, true), (code, true), (code, false), (KEY_LEFTSHIFT, false)]
+    } else {
+        vec![(code, true), (code, false)]
+    }
+}
+
+/// Key code for a named key
+fn key_code(key: Key) -> Option<u16> {
+    Some(match key {
+        Key::Escape => 1,
+        Key::Backspace => 14,
+        Key::Tab => 15,
+        Key::Return => 28,
+        Key::Control => 29,
+        Key::Shift => KEY_LEFTSHIFT,
+        Key::Alt => 56,
+        Key::Space => 57,
+        Key::CapsLock => 58,
+        Key::F1 => 59,
+        Key::F2 => 60,
+        Key::F3 => 61,
+        Key::F4 => 62,
+        Key::F5 => 63,
+        Key::F6 => 64,
+        Key::F7 => 65,
+        Key::F8 => 66,
+        Key::F9 => 67,
+        Key::F10 => 68,
+        Key::F11 => 87,
+        Key::F12 => 88,
+        Key::Home => 102,
+        Key::UpArrow => 103,
+        Key::PageUp => 104,
+        Key::LeftArrow => 105,
+        Key::RightArrow => 106,
+        Key::End => 107,
+        Key::DownArrow => 108,
+        Key::PageDown => 109,
+        #[cfg(not(target_os = "macos"))]
+        Key::Insert => 110,
+        Key::Delete => 111,
+        Key::VolumeMute => 113,
+        Key::VolumeDown => 114,
+        Key::VolumeUp => 115,
+        Key::Meta => 125,
+        Key::MediaNextTrack => 163,
+        Key::MediaPlayPause => 164,
+        Key::MediaPrevTrack => 165,
+        _ => return None,
+    })
+}
+
+/// Key code for a character on a US layout, and whether it needs shift
+fn char_code(c: char) -> Option<(u16, bool)> {
+    const LETTERS: [u16; 26] = [
+        30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22, 47, 17, 45, 21, 44,
+    ];
+    const SHIFTED_DIGITS: &str = ")!@#$%^&*(";
+    if c.is_ascii_lowercase() {
+        return Some((LETTERS[usize::from(c as u8 - b'a')], false));
+    }
+    if c.is_ascii_uppercase() {
+        return Some((LETTERS[usize::from(c as u8 - b'A')], true));
+    }
+    // 1-9 are codes 2-10, 0 is 11
+    let digit = |d: u8| if d == 0 { 11 } else { 1 + u16::from(d) };
+    if let Some(d) = c.to_digit(10) {
+        return Some((digit(d as u8), false));
+    }
+    if let Some(d) = SHIFTED_DIGITS.find(c) {
+        return Some((digit(d as u8), true));
+    }
+    let (code, shift) = match c {
+        ' ' => (57, false),
+        '\n' => (28, false),
+        '\t' => (15, false),
+        '-' => (12, false),
+        '_' => (12, true),
+        '=' => (13, false),
+        '+' => (13, true),
+        '[' => (26, false),
+        '{' => (26, true),
+        ']' => (27, false),
+        '}' => (27, true),
+        ';' => (39, false),
+        ':' => (39, true),
+        '\'' => (40, false),
+        '"' => (40, true),
+        '`' => (41, false),
+        '~' => (41, true),
+        '\\' => (43, false),
+        '|' => (43, true),
+        ',' => (51, false),
+        '<' => (51, true),
+        '.' => (52, false),
+        '>' => (52, true),
+        '/' => (53, false),
+        '?' => (53, true),
+        _ => return None,
+    };
+    Some((code, shift))
+}
+
+/// wtype's name for a modifier (-M/-m)
+fn wtype_modifier(key: Key) -> Option<&'static str> {
+    match key {
+        Key::Control => Some("ctrl"),
+        Key::Shift => Some("shift"),
+        Key::Alt => Some("alt"),
+        Key::Meta => Some("logo"),
+        _ => None,
+    }
+}
+
+/// XKB keysym name for a named key
+fn wtype_keysym(key: Key) -> Option<&'static str> {
+    Some(match key {
+        Key::Escape => "Escape",
+        Key::Backspace => "BackSpace",
+        Key::Tab => "Tab",
+        Key::Return => "Return",
+        Key::Space => "space",
+        Key::CapsLock => "Caps_Lock",
+        Key::F1 => "F1",
+        Key::F2 => "F2",
+        Key::F3 => "F3",
+        Key::F4 => "F4",
+        Key::F5 => "F5",
+        Key::F6 => "F6",
+        Key::F7 => "F7",
+        Key::F8 => "F8",
+        Key::F9 => "F9",
+        Key::F10 => "F10",
+        Key::F11 => "F11",
+        Key::F12 => "F12",
+        Key::Home => "Home",
+        Key::End => "End",
+        Key::UpArrow => "Up",
+        Key::DownArrow => "Down",
+        Key::LeftArrow => "Left",
+        Key::RightArrow => "Right",
+        Key::PageUp => "Prior",
+        Key::PageDown => "Next",
+        #[cfg(not(target_os = "macos"))]
+        Key::Insert => "Insert",
+        Key::Delete => "Delete",
+        Key::VolumeMute => "XF86AudioMute",
+        Key::VolumeDown => "XF86AudioLowerVolume",
+        Key::VolumeUp => "XF86AudioRaiseVolume",
+        Key::MediaNextTrack => "XF86AudioNext",
+        Key::MediaPlayPause => "XF86AudioPlay",
+        Key::MediaPrevTrack => "XF86AudioPrev",
+        _ => return None,
+    })
+}
+
+/// SS9K's own virtual keyboard in /dev/uinput
+#[cfg(target_os = "linux")]
+mod uinput {
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use enigo::{InputError, InputResult};
+    use evdev::uinput::VirtualDevice;
+    use evdev::{AttributeSet, KeyCode, KeyEvent};
+    use tracing::{info, warn};
+
+    use crate::error::OutputError;
+
+    /// Kept for the whole run: every new device makes the compositor set up a keyboard
+    static DEVICE: Mutex<Option<VirtualDevice>> = Mutex::new(None);
+
+    /// Create the device, once
+    pub fn open() -> Result<(), OutputError> {
+        let mut device = DEVICE.lock().map_err(|_| OutputError::Poisoned)?;
+        if device.is_some() {
+            return Ok(());
+        }
+        let keys: AttributeSet<KeyCode> = (1..=248).map(KeyCode).collect();
+        let created = VirtualDevice::builder()
+            .and_then(|b| b.name("SS9K virtual keyboard").with_keys(&keys))
+            .and_then(|b| b.build())
+            .map_err(OutputError::Uinput)?;
+        info!("⌨️ Created uinput keyboard");
+        // Keys sent before the compositor has picked the device up are lost
+        std::thread::sleep(Duration::from_millis(300));
+        *device = Some(created);
+        Ok(())
+    }
+
+    pub fn send(events: &[(u16, bool)]) -> InputResult<()> {
+        let mut device = DEVICE.lock().map_err(|_| InputError::Simulate("uinput device lock poisoned"))?;
+        let device = device.as_mut().ok_or(InputError::Simulate("uinput device isn't open"))?;
+        for (code, down) in events {
+            device.emit(&[*KeyEvent::new(KeyCode(*code), i32::from(*down))]).map_err(|e| {
+                warn!("⚠️ uinput write failed: {}", e);
+                InputError::Simulate("uinput write failed")
+            })?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod uinput {
+    use enigo::{InputError, InputResult};
+
+    use crate::error::OutputError;
+
+    pub fn open() -> Result<(), OutputError> {
+        Err(OutputError::Uinput(std::io::Error::new(std::io::ErrorKind::Unsupported, "uinput is Linux-only")))
+    }
+
+    pub fn send(_events: &[(u16, bool)]) -> InputResult<()> {
+        Err(InputError::Simulate("uinput is Linux-only"))
+    }
+}
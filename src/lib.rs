@@ -0,0 +1,85 @@
+//! SuperScreecher9000 library
+//!
+//! The audio, transcription, command, and config layers behind the `ss9k`
+//! binary. Embedders can drive these directly; errors are typed per
+//! subsystem (see [`error`]).
+
+pub mod apps;
+pub mod audio;
+pub mod captions;
+pub mod check;
+pub mod commands;
+pub mod config;
+pub mod control;
+pub mod daemon;
+pub mod dates;
+#[cfg(feature = "dbus")]
+pub mod dbus;
+pub mod emacs;
+pub mod error;
+pub mod fillers;
+#[cfg(feature = "gamepad")]
+pub mod gamepad;
+pub mod history;
+pub mod homeassistant;
+pub mod hooks;
+pub mod hotkeys;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod input;
+#[cfg(unix)]
+pub mod ipc;
+pub mod keyboard;
+pub mod logging;
+pub mod lookups;
+pub mod metrics;
+#[cfg(feature = "midi")]
+pub mod midi;
+pub mod migrate;
+pub mod model;
+pub mod mouse;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+pub mod netmic;
+pub mod notifications;
+pub mod overlay;
+pub mod numbers;
+pub mod output;
+pub mod paragraph;
+#[cfg(feature = "plugins")]
+pub mod plugins;
+pub mod privacy;
+pub mod profanity;
+pub mod profiles;
+#[cfg(feature = "http")]
+pub mod remote;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod security;
+#[cfg(unix)]
+pub mod signals;
+pub mod sounds;
+pub mod stats;
+pub mod subtitles;
+pub mod switch;
+pub mod system;
+pub mod text;
+pub mod timers;
+pub mod tts;
+pub mod vim;
+#[cfg(feature = "webui")]
+pub mod webui;
+#[cfg(feature = "vad")]
+pub mod vad;
+
+use std::sync::atomic::{AtomicBool, AtomicU64};
+
+pub use config::Config;
+pub use error::Error;
+
+// Recording state
+pub static RECORDING: AtomicBool = AtomicBool::new(false); // True while a hotkey recording is running
+pub static ABORTS: AtomicU64 = AtomicU64::new(0); // Bumped by abort_hotkey; clips queued before it are dropped, waits and captured commands end
+
+// VAD state
+pub static VAD_LISTENING: AtomicBool = AtomicBool::new(false); // True when VAD is actively listening
@@ -0,0 +1,157 @@
+//! Logging for SS9K
+//!
+//! This module handles:
+//! - Console output: `[SS9K] <message>`, warnings and errors on stderr
+//! - The log level: `SS9K_LOG` env var, then `--log-level`, then `log_level`
+//!   in the config (empty = debug with `verbose`, else info)
+//! - An optional JSON log file (`log_file`) rotated per `log_rotation`
+//!
+//! Everything else logs through `tracing` macros. The level follows config
+//! reloads; the log file is opened once, at startup.
+
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use tracing::{info, warn, Event, Level, Subscriber};
+use tracing_appender::rolling::{RollingFileAppender, RollingWriter, Rotation};
+use tracing_subscriber::filter::{filter_fn, EnvFilter};
+use tracing_subscriber::fmt::format::{self, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::writer::{MakeWriterExt, OptionalWriter};
+use tracing_subscriber::fmt::{FmtContext, MakeWriter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{reload, Layer, Registry};
+
+use crate::Config;
+
+/// Env var that overrides every other level setting
+pub const ENV_VAR: &str = "SS9K_LOG";
+
+/// Rotated log files to keep
+const KEEP_FILES: usize = 7;
+
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+static OVERRIDE: OnceLock<String> = OnceLock::new(); // From env or --log-level
+static FILE: OnceLock<RollingFileAppender> = OnceLock::new();
+
+/// `[SS9K] message key=value` like the rest of SS9K's output
+struct Console;
+
+impl<S, N> FormatEvent<S, N> for Console
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, ctx: &FmtContext<'_, S, N>, mut writer: format::Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        write!(writer, "[SS9K] ")?;
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
+
+/// Writes to the log file once one is open, nowhere before
+struct FileWriter;
+
+impl<'a> MakeWriter<'a> for FileWriter {
+    type Writer = OptionalWriter<RollingWriter<'a>>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        FILE.get().map_or_else(OptionalWriter::none, |file| OptionalWriter::some(file.make_writer()))
+    }
+}
+
+/// Turn a level name into a filter for SS9K (dependencies stay at warn);
+/// anything with `=` or `,` is taken as a full filter directive
+fn parse_filter(level: &str) -> Result<EnvFilter, String> {
+    let directive = if level.contains(['=', ',']) {
+        level.to_string()
+    } else {
+        format!("warn,ss9k={}", level)
+    };
+    EnvFilter::try_new(&directive).map_err(|e| format!("Invalid log level '{}': {}", level, e))
+}
+
+/// Start logging; call once, first thing (the config isn't loaded yet, so
+/// this uses `SS9K_LOG` / `cli_level` or info until `configure`)
+/// `stderr_only` keeps stdout clean for commands that print data there
+pub fn init(cli_level: Option<&str>, stderr_only: bool) {
+    let level = std::env::var(ENV_VAR).ok().filter(|l| !l.is_empty()).or(cli_level.map(str::to_string));
+    let (filter, problem) = match level.as_deref().map(parse_filter) {
+        Some(Ok(filter)) => (filter, None),
+        Some(Err(e)) => (parse_filter("info").unwrap_or_default(), Some(e)),
+        None => (parse_filter("info").unwrap_or_default(), None),
+    };
+    if let Some(level) = level {
+        let _ = OVERRIDE.set(level);
+    }
+    let (filter, handle) = reload::Layer::new(filter);
+    let _ = FILTER.set(handle);
+
+    let console = tracing_subscriber::fmt::layer()
+        .event_format(Console)
+        .with_writer(std::io::stderr.with_max_level(if stderr_only { Level::TRACE } else { Level::WARN }).or_else(std::io::stdout));
+    let file = tracing_subscriber::fmt::layer()
+        .json()
+        .with_current_span(true)
+        .with_span_list(false)
+        .with_writer(FileWriter)
+        .with_filter(filter_fn(|_| FILE.get().is_some()));
+    let _ = tracing_subscriber::registry().with(filter).with(console).with(file).try_init();
+
+    if let Some(problem) = problem {
+        warn!("⚠️ {}", problem);
+    }
+}
+
+/// Apply the config: level (unless overridden) and, the first time, `log_file`
+pub fn configure(config: &Config) {
+    if OVERRIDE.get().is_none() {
+        let level = match config.log_level.as_str() {
+            "" if config.verbose => "debug",
+            "" => "info",
+            level => level,
+        };
+        match parse_filter(level) {
+            Ok(filter) => {
+                if let Some(handle) = FILTER.get() {
+                    let _ = handle.reload(filter);
+                }
+            }
+            Err(e) => warn!("⚠️ {}", e),
+        }
+    }
+
+    if !config.log_file.is_empty() && FILE.get().is_none() {
+        let path = shellexpand::tilde(&config.log_file).into_owned();
+        match open(Path::new(&path), &config.log_rotation) {
+            Ok(file) => {
+                let _ = FILE.set(file);
+                info!("📜 Logging to {} ({} rotation)", path, config.log_rotation);
+            }
+            Err(e) => warn!("⚠️ Can't open log file {}: {}", path, e),
+        }
+    }
+}
+
+/// Open a rotating appender: `dir/name.log` becomes `dir/name.<date>.log`
+fn open(path: &Path, rotation: &str) -> Result<RollingFileAppender, String> {
+    let rotation = match rotation {
+        "hourly" => Rotation::HOURLY,
+        "daily" => Rotation::DAILY,
+        "never" => Rotation::NEVER,
+        other => return Err(format!("unknown log_rotation '{}' (expected hourly, daily or never)", other)),
+    };
+    let dir = path.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let prefix = path.file_stem().and_then(|s| s.to_str()).ok_or("log_file needs a file name")?;
+    let mut builder = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(prefix)
+        .max_log_files(KEEP_FILES);
+    if let Some(extension) = path.extension().and_then(|s| s.to_str()) {
+        builder = builder.filename_suffix(extension);
+    }
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    builder.build(dir).map_err(|e| e.to_string())
+}
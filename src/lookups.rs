@@ -1,190 +1,304 @@
 //! Lookup tables and data-driven functions for SS9K
 //!
 //! This module contains the "data" parts of SS9K:
-//! - Punctuation symbol lookup
-//! - Emoji lookup
-//! - NATO phonetic alphabet / word-to-char mapping
-//! - Key name parsing for hold/release
+//! - Punctuation symbol lookup (built-in table plus `[punctuation]`)
+//! - Emoji lookup (`[emoji]`, the built-in table, then any Unicode emoji by name)
+//! - Unicode characters by name or code point
+//! - Spelling alphabets (NATO, German, `[spelling]`) / word-to-char mapping
+//! - Key name parsing for hold/release and key chords
 
-use anyhow::Result;
-use enigo::{Enigo, Key as EnigoKey, Keyboard};
+use enigo::{Key as EnigoKey, Keyboard};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::error::OutputError;
+use crate::keyboard::Keys;
+use crate::output;
+use crate::privacy;
+use crate::text;
+use crate::Config;
+
+type Result<T> = std::result::Result<T, OutputError>;
+
+/// Spoken names for "command punctuation <name>" and the symbol each types
+/// Includes common Whisper mishearings; `[punctuation]` in config adds to
+/// and overrides these.
+pub const PUNCTUATION: &[(&[&str], &str)] = &[
+    // Basic punctuation
+    (&["period", "dot", "full stop", "point"], "."),
+    (&["comma", "coma"], ","),
+    (&["question", "question mark"], "?"),
+    (&["exclamation", "exclamation mark", "bang", "exclamation point"], "!"),
+    (&["colon", "colin", "cologne"], ":"),
+    (&["semicolon", "semi colon", "semi colin", "semicolin"], ";"),
+    (&["ellipsis", "ellipses", "dot dot dot"], "..."),
+
+    // Quotes
+    (&["quote", "double quote", "quotes", "quotation"], "\""),
+    (&["single quote", "apostrophe", "apostrophy"], "'"),
+    (&["backtick", "grave", "back tick", "back tic", "backtic"], "`"),
+
+    // Brackets
+    (&["open paren", "left paren", "open parenthesis", "open parentheses"], "("),
+    (&["close paren", "right paren", "close parenthesis", "close parentheses"], ")"),
+    (&["open bracket", "left bracket", "open square"], "["),
+    (&["close bracket", "right bracket", "close square"], "]"),
+    (&["open brace", "left brace", "open curly", "open curley"], "{"),
+    (&["close brace", "right brace", "close curly", "close curley"], "}"),
+    (&["less than", "open angle", "left angle", "left chevron"], "<"),
+    (&["greater than", "close angle", "right angle", "right chevron"], ">"),
+
+    // Math/symbols
+    (&["plus", "positive"], "+"),
+    (&["minus", "dash", "hyphen", "negative"], "-"),
+    (&["equals", "equal", "equal sign", "equals sign"], "="),
+    (&["underscore", "under score", "underline"], "_"),
+    (&["asterisk", "star", "asterix", "astrix", "asterisks"], "*"),
+    (&["slash", "forward slash", "forwardslash"], "/"),
+    (&["backslash", "back slash", "backward slash"], "\\"),
+    (&["pipe", "bar", "vertical bar", "vertical line"], "|"),
+    (&["caret", "carrot", "karet", "carret", "hat"], "^"),
+    (&["tilde", "tilda", "tildy", "squiggle"], "~"),
+    (&["percent", "percentage", "per cent"], "%"),
+    (&["ampersand", "and sign", "and symbol"], "&"),
+    (&["at", "at sign", "at symbol"], "@"),
+    (&["hash", "hashtag", "pound", "number sign", "hash tag", "octothorpe"], "#"),
+    (&["dollar", "dollar sign", "dollars"], "$"),
+
+    // Programming
+    (&["arrow", "fat arrow", "thick arrow", "rocket"], "=>"),
+    (&["thin arrow", "skinny arrow", "dash arrow", "hyphen arrow"], "->"),
+    (&["double colon", "scope", "colon colon", "colin colin"], "::"),
+    (&["double equals", "equals equals", "equal equal"], "=="),
+    (&["not equals", "not equal", "bang equals", "exclamation equals"], "!="),
+    (&["less than or equal", "less equal", "less or equal"], "<="),
+    (&["greater than or equal", "greater equal", "greater or equal"], ">="),
+    (&["plus equals", "plus equal"], "+="),
+    (&["minus equals", "minus equal", "dash equals"], "-="),
+    (&["and and", "double and", "ampersand ampersand"], "&&"),
+    (&["or or", "double or", "pipe pipe", "double pipe"], "||"),
+];
+
+/// A spoken name as the tables spell it: lowercase, single spaces
+fn spoken_name(name: &str) -> String {
+    name.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// The entry in a config table for a spoken name, if there is one
+fn custom_entry<'a, V>(custom: &'a HashMap<String, V>, name: &str) -> Option<&'a V> {
+    custom.iter().find(|(spoken, _)| spoken_name(spoken) == name).map(|(_, value)| value)
+}
+
+/// The symbol for a punctuation name: `[punctuation]` first, then the built-ins
+pub fn punctuation_symbol(name: &str, custom: &HashMap<String, String>) -> Option<String> {
+    let name = spoken_name(name);
+    custom_entry(custom, &name)
+        .cloned()
+        .or_else(|| PUNCTUATION.iter().find(|(names, _)| names.contains(&name.as_str())).map(|(_, symbol)| symbol.to_string()))
+}
 
 /// Execute punctuation insertion
-/// Includes common Whisper mishearings for robustness
-pub fn execute_punctuation(enigo: &mut Enigo, punct: &str) -> Result<bool> {
-    let symbol = match punct {
-        // Basic punctuation
-        "period" | "dot" | "full stop" | "point" => ".",
-        "comma" | "coma" => ",",
-        "question" | "question mark" => "?",
-        "exclamation" | "exclamation mark" | "bang" | "exclamation point" => "!",
-        "colon" | "colin" | "cologne" => ":",
-        "semicolon" | "semi colon" | "semi colin" | "semicolin" => ";",
-        "ellipsis" | "ellipses" | "dot dot dot" => "...",
-
-        // Quotes
-        "quote" | "double quote" | "quotes" | "quotation" => "\"",
-        "single quote" | "apostrophe" | "apostrophy" => "'",
-        "backtick" | "grave" | "back tick" | "back tic" | "backtic" => "`",
-
-        // Brackets
-        "open paren" | "left paren" | "open parenthesis" | "open parentheses" => "(",
-        "close paren" | "right paren" | "close parenthesis" | "close parentheses" => ")",
-        "open bracket" | "left bracket" | "open square" => "[",
-        "close bracket" | "right bracket" | "close square" => "]",
-        "open brace" | "left brace" | "open curly" | "open curley" => "{",
-        "close brace" | "right brace" | "close curly" | "close curley" => "}",
-        "less than" | "open angle" | "left angle" | "left chevron" => "<",
-        "greater than" | "close angle" | "right angle" | "right chevron" => ">",
-
-        // Math/symbols
-        "plus" | "positive" => "+",
-        "minus" | "dash" | "hyphen" | "negative" => "-",
-        "equals" | "equal" | "equal sign" | "equals sign" => "=",
-        "underscore" | "under score" | "underline" => "_",
-        "asterisk" | "star" | "asterix" | "astrix" | "asterisks" => "*",
-        "slash" | "forward slash" | "forwardslash" => "/",
-        "backslash" | "back slash" | "backward slash" => "\\",
-        "pipe" | "bar" | "vertical bar" | "vertical line" => "|",
-        "caret" | "carrot" | "karet" | "carret" | "hat" => "^",
-        "tilde" | "tilda" | "tildy" | "squiggle" => "~",
-        "percent" | "percentage" | "per cent" => "%",
-        "ampersand" | "and sign" | "and symbol" => "&",
-        "at" | "at sign" | "at symbol" => "@",
-        "hash" | "hashtag" | "pound" | "number sign" | "hash tag" | "octothorpe" => "#",
-        "dollar" | "dollar sign" | "dollars" => "$",
-
-        // Programming
-        "arrow" | "fat arrow" | "thick arrow" | "rocket" => "=>",
-        "thin arrow" | "skinny arrow" | "dash arrow" | "hyphen arrow" => "->",
-        "double colon" | "scope" | "colon colon" | "colin colin" => "::",
-        "double equals" | "equals equals" | "equal equal" => "==",
-        "not equals" | "not equal" | "bang equals" | "exclamation equals" => "!=",
-        "less than or equal" | "less equal" | "less or equal" => "<=",
-        "greater than or equal" | "greater equal" | "greater or equal" => ">=",
-        "plus equals" | "plus equal" => "+=",
-        "minus equals" | "minus equal" | "dash equals" => "-=",
-        "and and" | "double and" | "ampersand ampersand" => "&&",
-        "or or" | "double or" | "pipe pipe" | "double pipe" => "||",
-
-        _ => {
-            eprintln!("[SS9K] ⚠️ Unknown punctuation: {}", punct);
-            return Ok(false);
-        }
+pub fn execute_punctuation(enigo: &mut Keys, punct: &str, custom: &HashMap<String, String>) -> Result<bool> {
+    let Some(symbol) = punctuation_symbol(punct, custom) else {
+        warn!("⚠️ Unknown punctuation: {}", punct);
+        return Ok(false);
     };
 
-    enigo.text(symbol)?;
-    println!("[SS9K] ✏️ Punctuation: {}", symbol);
+    enigo.text(&symbol)?;
+    output::typed(&symbol);
+    info!("✏️ Punctuation: {}", symbol);
     Ok(true)
 }
 
+/// Spoken names for "command emoji <name>" and the emoji each types
+/// `[emoji]` in config adds to and overrides these; anything else is looked
+/// up among every Unicode emoji (see `emoji_for`).
+pub const EMOJI: &[(&[&str], &str)] = &[
+    // Faces
+    (&["smile", "happy"], "😊"),
+    (&["laugh", "lol", "laughing"], "😂"),
+    (&["joy"], "🤣"),
+    (&["wink"], "😉"),
+    (&["love", "heart eyes"], "😍"),
+    (&["cool", "sunglasses"], "😎"),
+    (&["think", "thinking", "hmm"], "🤔"),
+    (&["cry", "sad", "crying"], "😭"),
+    (&["angry", "mad"], "😠"),
+    (&["skull", "dead"], "💀"),
+    (&["eye roll", "roll eyes"], "🙄"),
+    (&["shush", "quiet"], "🤫"),
+    (&["mind blown", "exploding head"], "🤯"),
+    (&["clown"], "🤡"),
+    (&["nerd"], "🤓"),
+    (&["sick", "ill"], "🤢"),
+    (&["scream"], "😱"),
+
+    // Gestures
+    (&["thumbs up", "thumb up", "yes"], "👍"),
+    (&["thumbs down", "thumb down", "no"], "👎"),
+    (&["clap", "clapping"], "👏"),
+    (&["wave", "hi", "bye"], "👋"),
+    (&["shrug"], "🤷"),
+    (&["facepalm", "face palm"], "🤦"),
+    (&["pray", "please", "thanks"], "🙏"),
+    (&["muscle", "strong", "flex"], "💪"),
+    (&["point up"], "☝️"),
+    (&["point right"], "👉"),
+    (&["point left"], "👈"),
+    (&["point down"], "👇"),
+    (&["ok", "okay"], "👌"),
+    (&["peace", "victory"], "✌️"),
+    (&["rock", "metal"], "🤘"),
+    (&["middle finger", "fuck you"], "🖕"),
+
+    // Hearts & love
+    (&["heart", "red heart"], "❤️"),
+    (&["blue heart"], "💙"),
+    (&["green heart"], "💚"),
+    (&["yellow heart"], "💛"),
+    (&["purple heart"], "💜"),
+    (&["black heart"], "🖤"),
+    (&["white heart"], "🤍"),
+    (&["orange heart"], "🧡"),
+    (&["broken heart"], "💔"),
+    (&["sparkling heart"], "💖"),
+    (&["kiss"], "😘"),
+
+    // Animals
+    (&["dog", "wag"], "🐕"),
+    (&["cat"], "🐈"),
+    (&["crab", "rust"], "🦀"),
+    (&["snake"], "🐍"),
+    (&["bug", "beetle"], "🐛"),
+    (&["butterfly"], "🦋"),
+    (&["unicorn"], "🦄"),
+    (&["dragon"], "🐉"),
+    (&["shark"], "🦈"),
+    (&["whale"], "🐋"),
+    (&["octopus"], "🐙"),
+
+    // Objects & symbols
+    (&["fire", "lit"], "🔥"),
+    (&["star", "gold star"], "⭐"),
+    (&["sparkles", "sparkle"], "✨"),
+    (&["lightning", "zap"], "⚡"),
+    (&["poop", "shit"], "💩"),
+    (&["100", "hundred"], "💯"),
+    (&["check", "checkmark"], "✅"),
+    (&["x", "cross"], "❌"),
+    (&["warning"], "⚠️"),
+    (&["question"], "❓"),
+    (&["exclamation"], "❗"),
+    (&["pin", "pushpin"], "📌"),
+    (&["bulb", "idea", "lightbulb"], "💡"),
+    (&["gear", "settings"], "⚙️"),
+    (&["rocket"], "🚀"),
+    (&["trophy"], "🏆"),
+    (&["medal"], "🏅"),
+    (&["crown"], "👑"),
+    (&["money", "cash"], "💰"),
+    (&["gem", "diamond"], "💎"),
+    (&["gift", "present"], "🎁"),
+    (&["party", "celebrate"], "🎉"),
+    (&["balloon"], "🎈"),
+    (&["beer", "cheers"], "🍺"),
+    (&["coffee"], "☕"),
+    (&["pizza"], "🍕"),
+    (&["taco"], "🌮"),
+];
+
+/// How close a name has to be to a Unicode emoji's name or shortcode for the
+/// fuzzy fallback to pick it
+const EMOJI_SIMILARITY: f64 = 0.88;
+
+/// The emoji for a spoken name: `[emoji]` first, then the built-in table, then
+/// every Unicode emoji by shortcode ("thumbs up" -> :thumbs_up:) or name, and
+/// last the nearest of those ("pizzza" -> 🍕)
+pub fn emoji_for(name: &str, custom: &HashMap<String, String>) -> Option<String> {
+    let name = spoken_name(name);
+    if let Some(emoji) = custom_entry(custom, &name) {
+        return Some(emoji.clone());
+    }
+    if let Some((_, emoji)) = EMOJI.iter().find(|(names, _)| names.contains(&name.as_str())) {
+        return Some(emoji.to_string());
+    }
+    if let Some(emoji) = emojis::get_by_shortcode(&name.replace(' ', "_")) {
+        return Some(emoji.as_str().to_string());
+    }
+
+    // Shortcodes and names spelled the way they'd be said
+    let spoken = |emoji: &'static emojis::Emoji| {
+        emoji.shortcodes().map(|code| code.replace(['_', '-'], " ")).chain([emoji.name().to_lowercase()])
+    };
+    if let Some(emoji) = emojis::iter().find(|e| spoken(e).any(|n| n == name)) {
+        return Some(emoji.as_str().to_string());
+    }
+    emojis::iter()
+        .filter_map(|e| spoken(e).map(|n| text::jaro_winkler(&n, &name)).reduce(f64::max).map(|score| (e, score)))
+        .filter(|(_, score)| *score >= EMOJI_SIMILARITY)
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(emoji, _)| emoji.as_str().to_string())
+}
+
 /// Execute emoji insertion
-pub fn execute_emoji(enigo: &mut Enigo, name: &str) -> Result<bool> {
-    let emoji = match name {
-        // Faces
-        "smile" | "happy" => "😊",
-        "laugh" | "lol" | "laughing" => "😂",
-        "joy" => "🤣",
-        "wink" => "😉",
-        "love" | "heart eyes" => "😍",
-        "cool" | "sunglasses" => "😎",
-        "think" | "thinking" | "hmm" => "🤔",
-        "cry" | "sad" | "crying" => "😭",
-        "angry" | "mad" => "😠",
-        "skull" | "dead" => "💀",
-        "eye roll" | "roll eyes" => "🙄",
-        "shush" | "quiet" => "🤫",
-        "mind blown" | "exploding head" => "🤯",
-        "clown" => "🤡",
-        "nerd" => "🤓",
-        "sick" | "ill" => "🤢",
-        "scream" => "😱",
-
-        // Gestures
-        "thumbs up" | "thumb up" | "yes" => "👍",
-        "thumbs down" | "thumb down" | "no" => "👎",
-        "clap" | "clapping" => "👏",
-        "wave" | "hi" | "bye" => "👋",
-        "shrug" => "🤷",
-        "facepalm" | "face palm" => "🤦",
-        "pray" | "please" | "thanks" => "🙏",
-        "muscle" | "strong" | "flex" => "💪",
-        "point up" => "☝️",
-        "point right" => "👉",
-        "point left" => "👈",
-        "point down" => "👇",
-        "ok" | "okay" => "👌",
-        "peace" | "victory" => "✌️",
-        "rock" | "metal" => "🤘",
-        "middle finger" | "fuck you" => "🖕",
-
-        // Hearts & love
-        "heart" | "red heart" => "❤️",
-        "blue heart" => "💙",
-        "green heart" => "💚",
-        "yellow heart" => "💛",
-        "purple heart" => "💜",
-        "black heart" => "🖤",
-        "white heart" => "🤍",
-        "orange heart" => "🧡",
-        "broken heart" => "💔",
-        "sparkling heart" => "💖",
-        "kiss" => "😘",
-
-        // Animals
-        "dog" | "wag" => "🐕",
-        "cat" => "🐈",
-        "crab" | "rust" => "🦀",
-        "snake" => "🐍",
-        "bug" | "beetle" => "🐛",
-        "butterfly" => "🦋",
-        "unicorn" => "🦄",
-        "dragon" => "🐉",
-        "shark" => "🦈",
-        "whale" => "🐋",
-        "octopus" => "🐙",
-
-        // Objects & symbols
-        "fire" | "lit" => "🔥",
-        "star" | "gold star" => "⭐",
-        "sparkles" | "sparkle" => "✨",
-        "lightning" | "zap" => "⚡",
-        "poop" | "shit" => "💩",
-        "100" | "hundred" => "💯",
-        "check" | "checkmark" => "✅",
-        "x" | "cross" => "❌",
-        "warning" => "⚠️",
-        "question" => "❓",
-        "exclamation" => "❗",
-        "pin" | "pushpin" => "📌",
-        "bulb" | "idea" | "lightbulb" => "💡",
-        "gear" | "settings" => "⚙️",
-        "rocket" => "🚀",
-        "trophy" => "🏆",
-        "medal" => "🏅",
-        "crown" => "👑",
-        "money" | "cash" => "💰",
-        "gem" | "diamond" => "💎",
-        "gift" | "present" => "🎁",
-        "party" | "celebrate" => "🎉",
-        "balloon" => "🎈",
-        "beer" | "cheers" => "🍺",
-        "coffee" => "☕",
-        "pizza" => "🍕",
-        "taco" => "🌮",
-
-        _ => {
-            eprintln!("[SS9K] ⚠️ Unknown emoji: {}", name);
-            return Ok(false);
+pub fn execute_emoji(enigo: &mut Keys, name: &str, custom: &HashMap<String, String>) -> Result<bool> {
+    let Some(emoji) = emoji_for(name, custom) else {
+        warn!("⚠️ Unknown emoji: {}", name);
+        return Ok(false);
+    };
+
+    enigo.text(&emoji)?;
+    output::typed(&emoji);
+    info!("😀 Emoji: {}", emoji);
+    Ok(true)
+}
+
+/// The character "command unicode <X>" names: a Unicode character name
+/// ("snowman", "en dash", "greek small letter lambda") or a hex code point,
+/// spoken a digit at a time ("two six zero three", "two six oh three") or
+/// heard as one ("2603", "u+2603"). Control characters are left out.
+pub fn unicode_char(spoken: &str) -> Option<char> {
+    let spoken = spoken_name(spoken);
+    unicode_names2::character(&spoken)
+        .or_else(|| {
+            let hex = code_point_digits(&spoken)?;
+            u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32)
+        })
+        .filter(|ch| !ch.is_control())
+}
+
+/// "u plus two six oh three" -> "2603"; None unless every word is hex
+/// (digits, number words, a-f or their spelling alphabet words)
+fn code_point_digits(spoken: &str) -> Option<String> {
+    let spoken = ["u+", "u plus ", "code point "].iter().find_map(|p| spoken.strip_prefix(p)).unwrap_or(spoken);
+    let mut hex = String::new();
+    for word in spoken.split_whitespace() {
+        if word.chars().all(|c| c.is_ascii_hexdigit()) {
+            hex.push_str(word);
+        } else if word == "oh" {
+            hex.push('0');
+        } else {
+            hex.push(word_to_char(word).filter(char::is_ascii_hexdigit)?);
         }
+    }
+    (!hex.is_empty() && hex.len() <= 6).then_some(hex)
+}
+
+/// Execute "command unicode <X>": type the character it names
+pub fn execute_unicode(enigo: &mut Keys, spoken: &str) -> Result<bool> {
+    let Some(ch) = unicode_char(spoken) else {
+        warn!("⚠️ Unknown Unicode character: {}", privacy::redact(spoken));
+        return Ok(false);
     };
 
-    enigo.text(emoji)?;
-    println!("[SS9K] 😀 Emoji: {}", emoji);
+    enigo.text(&ch.to_string())?;
+    output::typed(&ch.to_string());
+    info!("✏️ Unicode: {} (U+{:04X})", ch, ch as u32);
     Ok(true)
 }
 
-/// Parse a key name to an EnigoKey (for hold/release functionality)
+/// Parse a key name to an EnigoKey (for hold/release and `[keybinds]` chords)
 pub fn parse_key_name(name: &str) -> Option<EnigoKey> {
     match name.to_lowercase().as_str() {
         // Letters (common for gaming: WASD)
@@ -215,6 +329,18 @@ pub fn parse_key_name(name: &str) -> Option<EnigoKey> {
         "y" => Some(EnigoKey::Unicode('y')),
         "z" => Some(EnigoKey::Unicode('z')),
 
+        // Digits (workspaces, tabs: "super+1", "alt+2")
+        "0" => Some(EnigoKey::Unicode('0')),
+        "1" => Some(EnigoKey::Unicode('1')),
+        "2" => Some(EnigoKey::Unicode('2')),
+        "3" => Some(EnigoKey::Unicode('3')),
+        "4" => Some(EnigoKey::Unicode('4')),
+        "5" => Some(EnigoKey::Unicode('5')),
+        "6" => Some(EnigoKey::Unicode('6')),
+        "7" => Some(EnigoKey::Unicode('7')),
+        "8" => Some(EnigoKey::Unicode('8')),
+        "9" => Some(EnigoKey::Unicode('9')),
+
         // Modifiers
         "shift" => Some(EnigoKey::Shift),
         "control" | "ctrl" => Some(EnigoKey::Control),
@@ -233,45 +359,136 @@ pub fn parse_key_name(name: &str) -> Option<EnigoKey> {
         "tab" => Some(EnigoKey::Tab),
         "escape" | "esc" => Some(EnigoKey::Escape),
         "backspace" => Some(EnigoKey::Backspace),
+        "delete" | "del" => Some(EnigoKey::Delete),
+        "home" => Some(EnigoKey::Home),
+        "end" => Some(EnigoKey::End),
+        "pageup" | "page up" => Some(EnigoKey::PageUp),
+        "pagedown" | "page down" => Some(EnigoKey::PageDown),
+
+        // Function keys
+        "f1" => Some(EnigoKey::F1),
+        "f2" => Some(EnigoKey::F2),
+        "f3" => Some(EnigoKey::F3),
+        "f4" => Some(EnigoKey::F4),
+        "f5" => Some(EnigoKey::F5),
+        "f6" => Some(EnigoKey::F6),
+        "f7" => Some(EnigoKey::F7),
+        "f8" => Some(EnigoKey::F8),
+        "f9" => Some(EnigoKey::F9),
+        "f10" => Some(EnigoKey::F10),
+        "f11" => Some(EnigoKey::F11),
+        "f12" => Some(EnigoKey::F12),
 
         _ => None,
     }
 }
 
-/// Map a word to a single character (NATO, raw letter, number word, or raw digit)
-pub fn word_to_char(word: &str) -> Option<char> {
-    // NATO phonetic alphabet
-    let nato = match word {
-        "alpha" | "alfa" => Some('a'),
-        "bravo" => Some('b'),
-        "charlie" => Some('c'),
-        "delta" => Some('d'),
-        "echo" => Some('e'),
-        "foxtrot" => Some('f'),
-        "golf" => Some('g'),
-        "hotel" => Some('h'),
-        "india" => Some('i'),
-        "juliet" | "juliett" => Some('j'),
-        "kilo" => Some('k'),
-        "lima" => Some('l'),
-        "mike" => Some('m'),
-        "november" => Some('n'),
-        "oscar" => Some('o'),
-        "papa" => Some('p'),
-        "quebec" => Some('q'),
-        "romeo" => Some('r'),
-        "sierra" => Some('s'),
-        "tango" => Some('t'),
-        "uniform" => Some('u'),
-        "victor" => Some('v'),
-        "whiskey" => Some('w'),
-        "xray" | "x-ray" => Some('x'),
-        "yankee" => Some('y'),
-        "zulu" => Some('z'),
+/// The NATO phonetic alphabet, for "command spell" (`spelling_alphabet = "nato"`)
+pub const NATO: &[(&[&str], char)] = &[
+    (&["alpha", "alfa"], 'a'),
+    (&["bravo"], 'b'),
+    (&["charlie"], 'c'),
+    (&["delta"], 'd'),
+    (&["echo"], 'e'),
+    (&["foxtrot"], 'f'),
+    (&["golf"], 'g'),
+    (&["hotel"], 'h'),
+    (&["india"], 'i'),
+    (&["juliet", "juliett"], 'j'),
+    (&["kilo"], 'k'),
+    (&["lima"], 'l'),
+    (&["mike"], 'm'),
+    (&["november"], 'n'),
+    (&["oscar"], 'o'),
+    (&["papa"], 'p'),
+    (&["quebec"], 'q'),
+    (&["romeo"], 'r'),
+    (&["sierra"], 's'),
+    (&["tango"], 't'),
+    (&["uniform"], 'u'),
+    (&["victor"], 'v'),
+    (&["whiskey"], 'w'),
+    (&["xray", "x-ray"], 'x'),
+    (&["yankee"], 'y'),
+    (&["zulu"], 'z'),
+];
+
+/// The German spelling alphabet (DIN 5009), umlauts and ß included
+/// (`spelling_alphabet = "german"`)
+pub const GERMAN: &[(&[&str], char)] = &[
+    (&["anton"], 'a'),
+    (&["berta", "bertha"], 'b'),
+    (&["cäsar", "caesar"], 'c'),
+    (&["dora"], 'd'),
+    (&["emil"], 'e'),
+    (&["friedrich"], 'f'),
+    (&["gustav"], 'g'),
+    (&["heinrich"], 'h'),
+    (&["ida"], 'i'),
+    (&["julius"], 'j'),
+    (&["kaufmann", "konrad"], 'k'),
+    (&["ludwig"], 'l'),
+    (&["martha", "marta"], 'm'),
+    (&["nordpol"], 'n'),
+    (&["otto"], 'o'),
+    (&["paula"], 'p'),
+    (&["quelle"], 'q'),
+    (&["richard"], 'r'),
+    (&["samuel", "siegfried"], 's'),
+    (&["theodor"], 't'),
+    (&["ulrich"], 'u'),
+    (&["viktor"], 'v'),
+    (&["wilhelm"], 'w'),
+    (&["xanthippe"], 'x'),
+    (&["ypsilon"], 'y'),
+    (&["zacharias", "zeppelin"], 'z'),
+    (&["ärger", "aerger"], 'ä'),
+    (&["ökonom", "oekonom"], 'ö'),
+    (&["übermut", "uebermut"], 'ü'),
+    (&["eszett"], 'ß'),
+];
+
+/// The built-in spelling alphabet `spelling_alphabet` names ("none" has no words)
+pub fn spelling_alphabet(name: &str) -> Option<&'static [(&'static [&'static str], char)]> {
+    match name {
+        "nato" => Some(NATO),
+        "german" => Some(GERMAN),
+        "none" => Some(&[]),
         _ => None,
-    };
-    if nato.is_some() {
-        return nato;
+    }
+}
+
+/// `spelling_alphabet` and `[spelling]`, set by `configure`
+static SPELLING: RwLock<Option<(String, HashMap<String, String>)>> = RwLock::new(None);
+
+/// Apply `spelling_alphabet` and `[spelling]` (startup and config reloads)
+pub fn configure(config: &Config) {
+    if let Ok(mut spelling) = SPELLING.write() {
+        *spelling = Some((config.spelling_alphabet.clone(), config.spelling.clone()));
+    }
+}
+
+/// Map a word to a single character with the configured spelling alphabet
+/// (see `spelled_char`); NATO until `configure` runs
+pub fn word_to_char(word: &str) -> Option<char> {
+    let spelling = SPELLING.read().ok().and_then(|s| s.clone());
+    let (alphabet, custom) = spelling.unwrap_or_else(|| ("nato".to_string(), HashMap::new()));
+    spelled_char(word, spelling_alphabet(&alphabet).unwrap_or(NATO), &custom)
+}
+
+/// Map a word to a single character: a `[spelling]` word, the spelling
+/// alphabet, a number word, a punctuation word, or a raw letter or digit
+/// `[spelling]` values that aren't exactly one character are skipped.
+pub fn spelled_char(word: &str, alphabet: &[(&[&str], char)], custom: &HashMap<String, String>) -> Option<char> {
+    let word = spoken_name(word);
+    let word = word.as_str();
+
+    let mut chars = custom_entry(custom, word).map(|value| value.chars());
+    if let Some(ch) = chars.as_mut().and_then(|c| c.next().filter(|_| c.next().is_none())) {
+        return Some(ch);
+    }
+    if let Some((_, ch)) = alphabet.iter().find(|(words, _)| words.contains(&word)) {
+        return Some(*ch);
     }
 
     // Number words
@@ -323,16 +540,10 @@ pub fn word_to_char(word: &str) -> Option<char> {
         return punct;
     }
 
-    // Raw single letter (a-z)
-    if word.len() == 1 {
-        let ch = word.chars().next()?;
-        if ch.is_ascii_alphabetic() {
-            return Some(ch.to_ascii_lowercase());
-        }
-        if ch.is_ascii_digit() {
-            return Some(ch);
-        }
+    // Raw single letter (a-z, ä, é...) or digit
+    let mut chars = word.chars();
+    match (chars.next(), chars.next()) {
+        (Some(ch), None) if ch.is_alphabetic() || ch.is_ascii_digit() => Some(ch),
+        _ => None,
     }
-
-    None
 }
@@ -1,63 +1,85 @@
-mod audio;
-mod commands;
-mod lookups;
-mod model;
-mod vad;
-
 use anyhow::Result;
 use arc_swap::ArcSwap;
+use clap::{Parser, Subcommand};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use enigo::{Enigo, Settings};
+use enigo::Keyboard;
 use notify::{recommended_watcher, RecursiveMode, Watcher};
 use rdev::{listen, Event, EventType, Key as RdevKey};
-use serde::Deserialize;
-use std::collections::HashMap;
-use std::fs::{self, OpenOptions};
+use std::fs::OpenOptions;
 use std::io::Write;
-use std::path::PathBuf;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::mpsc;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
-use whisper_rs::{WhisperContext, WhisperContextParameters};
-
-use audio::{build_stream, build_stream_with_vad, is_microphone, resample_audio, transcribe, AudioBuffer, CALLBACK_COUNT, WHISPER_SAMPLE_RATE};
-use commands::{execute_command, print_help, set_key_repeat_ms};
-use model::{download_model, get_model_install_path, get_model_path};
-use vad::{Vad, VadEvent, VadState, VAD_SAMPLE_RATE};
+use tracing::{debug, error, info, info_span, warn};
+use whisper_rs::WhisperContext;
+
+use ss9k::apps;
+use ss9k::audio::{build_stream_for_format, decode_file, is_microphone, resample_audio, spawn_raw_reader, transcribe, transcribe_segments, AudioBuffer, RawFormat, CALLBACK_COUNT, WHISPER_SAMPLE_RATE};
+#[cfg(feature = "vad")]
+use ss9k::audio::{build_stream_with_vad_for_format, StreamingResampler};
+use ss9k::check;
+use ss9k::commands::{self, execute_builtin_command, execute_command, install_panic_hook, parse_action, print_help, Phrases, release_everything, set_key_repeat_ms, sync_mode_dnd, type_text, Action};
+use ss9k::control::{self, Event as ControlEvent, RecordRequest, State};
+use ss9k::daemon;
+use ss9k::error::AudioError;
+#[cfg(unix)]
+use ss9k::error::IpcError;
+use ss9k::fillers;
+use ss9k::history::{self, ExportFormat};
+use ss9k::logging;
+use ss9k::lookups;
+use ss9k::netmic::{self, NetSource};
+use ss9k::homeassistant;
+use ss9k::hooks;
+use ss9k::hotkeys::{parse_hotkey, parse_switch, HeldModifiers, Hotkey, Input};
+use ss9k::input;
+use ss9k::keyboard::{self, Keys};
+use ss9k::metrics;
+use ss9k::model::{download_model, get_model_install_path, get_model_path, load_model};
+use ss9k::notifications::{notify, Kind};
+use ss9k::numbers;
+use ss9k::output;
+use ss9k::paragraph::Paragraph;
+use ss9k::privacy;
+use ss9k::profanity;
+use ss9k::profiles;
+#[cfg(feature = "plugins")]
+use ss9k::plugins;
+#[cfg(feature = "scripting")]
+use ss9k::scripting;
+use ss9k::security;
+use ss9k::timers;
+use ss9k::sounds::{play_cue, Cue};
+use ss9k::stats;
+use ss9k::subtitles::{self, OutputFormat};
+use ss9k::switch::{Gesture, Release, Scanner, SwitchGestures};
+#[cfg(feature = "vad")]
+use ss9k::system;
+use ss9k::tts;
+#[cfg(feature = "vad")]
+use ss9k::vad::{AdaptiveSilence, Vad, VadEvent, VadState, VAD_SAMPLE_RATE};
+use ss9k::config::Overrides;
+use ss9k::{Config, ABORTS, RECORDING, VAD_LISTENING};
 
 // Recording state
-static RECORDING: AtomicBool = AtomicBool::new(false);
 static RECORDING_SESSION: AtomicU64 = AtomicU64::new(0);
 static COMMAND_MODE: AtomicBool = AtomicBool::new(false); // True if recording was started with command_hotkey
 
-// VAD state
-static VAD_LISTENING: AtomicBool = AtomicBool::new(false); // True when VAD is actively listening
-
 /// Audio message for the processor thread
 enum AudioMessage {
     /// Audio from hotkey mode - needs resampling from native rate
     NeedsResampling(Vec<f32>),
     /// Audio from VAD mode - already at 16kHz
+    #[cfg_attr(not(feature = "vad"), allow(dead_code))]
     AlreadyResampled(Vec<f32>),
     /// Wake word check - quick transcribe first ~1.2s and check for wake word
+    #[cfg_attr(not(feature = "vad"), allow(dead_code))]
     WakeWordCheck(Vec<f32>),
 }
 
-/// System beep for audio feedback (single beep)
-fn beep() {
-    print!("\x07");
-    use std::io::Write;
-    let _ = std::io::stdout().flush();
-}
-
-/// Double beep for completion feedback
-fn beep_done() {
-    beep();
-    std::thread::sleep(Duration::from_millis(100));
-    beep();
-}
-
 /// Get current timestamp string
 fn timestamp() -> String {
     chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string()
@@ -65,7 +87,7 @@ fn timestamp() -> String {
 
 /// Log a transcription to the dictation log file
 fn log_dictation(path: &str, text: &str) {
-    if path.is_empty() { return; }
+    if path.is_empty() || privacy::enabled() { return; }
     let expanded = shellexpand::tilde(path);
     if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(expanded.as_ref()) {
         let _ = writeln!(file, "[{}] {}", timestamp(), text);
@@ -73,10 +95,13 @@ fn log_dictation(path: &str, text: &str) {
 }
 
 /// Log an error to both stderr and the error log file
-fn log_error(path: &str, message: &str) {
-    eprintln!("[SS9K] ❌ {}", message);
-    if path.is_empty() { return; }
-    let expanded = shellexpand::tilde(path);
+fn log_error(cfg: &Config, message: &str) {
+    error!("❌ {}", message);
+    metrics::error();
+    play_cue(cfg, Cue::Error);
+    notify(cfg, Kind::Error, message);
+    if cfg.error_log.is_empty() { return; }
+    let expanded = shellexpand::tilde(&cfg.error_log);
     if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(expanded.as_ref()) {
         let _ = writeln!(file, "[{}] [ERROR] {}", timestamp(), message);
     }
@@ -84,7 +109,7 @@ fn log_error(path: &str, message: &str) {
 
 /// Log a warning to both stderr and the error log file
 fn log_warn(path: &str, message: &str) {
-    eprintln!("[SS9K] ⚠️ {}", message);
+    warn!("⚠️ {}", message);
     if path.is_empty() { return; }
     let expanded = shellexpand::tilde(path);
     if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(expanded.as_ref()) {
@@ -92,367 +117,784 @@ fn log_warn(path: &str, message: &str) {
     }
 }
 
-/// Configuration for SS9K
-#[derive(Debug, Deserialize, Clone)]
-#[serde(default)]
-pub struct Config {
-    pub model: String,
-    pub language: String,
-    pub threads: usize,
-    pub device: String,
-    pub hotkey: String,
-    pub command_hotkey: String, // Alternate hotkey that auto-prefixes with leader word
-    pub hotkey_mode: String,
-    pub toggle_timeout_secs: u64,
-    pub leader: String,
-    pub key_repeat_ms: u64,
-    pub processing_timeout_secs: u64, // 0 = no timeout
-    #[serde(default)]
-    pub audio_feedback: bool, // Beep on start/stop listening
-    // VAD settings
-    pub activation_mode: String,   // "hotkey" (default) or "vad"
-    pub vad_sensitivity: f32,      // 0.0-1.0, higher = more sensitive
-    pub vad_silence_ms: u64,       // Silence duration before processing
-    pub vad_min_speech_ms: u64,    // Minimum speech before valid
-    pub vad_speech_pad_ms: u64,    // Padding added to end of speech
-    pub wake_word: String,         // Wake word for VAD mode (empty = disabled)
-    // Logging
-    pub dictation_log: String,     // Path to log transcriptions (empty = disabled)
-    pub error_log: String,         // Path to log errors (empty = disabled)
-    #[serde(default)]
-    pub commands: HashMap<String, String>,
-    #[serde(default)]
-    pub aliases: HashMap<String, String>,
-    #[serde(default)]
-    pub inserts: HashMap<String, String>,
-    #[serde(default)]
-    pub wrappers: HashMap<String, String>,
-    #[serde(default)]
-    pub verbose: bool,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            model: "small".to_string(),
-            language: "en".to_string(),
-            threads: 4,
-            device: String::new(),
-            hotkey: "F12".to_string(),
-            command_hotkey: String::new(), // Empty = disabled
-            hotkey_mode: "hold".to_string(),
-            toggle_timeout_secs: 0,
-            leader: "command".to_string(),
-            key_repeat_ms: 50,
-            processing_timeout_secs: 30, // Default 30s timeout
-            audio_feedback: false,       // Disabled by default
-            // VAD defaults
-            activation_mode: "hotkey".to_string(), // Default to hotkey mode
-            vad_sensitivity: 0.9,                  // High sensitivity for reliable detection
-            vad_silence_ms: 1000,                  // 1 second - tolerates natural pauses
-            vad_min_speech_ms: 200,                // Filter brief noises
-            vad_speech_pad_ms: 300,                // Pad end of speech to catch trailing words
-            wake_word: String::new(),              // Empty = no wake word required
-            // Logging defaults
-            dictation_log: String::new(),          // Empty = disabled
-            error_log: String::new(),              // Empty = disabled
-            commands: HashMap::new(),
-            aliases: HashMap::new(),
-            inserts: HashMap::new(),
-            wrappers: HashMap::new(),
-            verbose: true,
+/// Switch came up: run long/double gestures now, short ones once no second press follows
+fn switch_released(gestures: &Arc<Mutex<SwitchGestures>>, action: &Arc<impl Fn(Gesture) + Send + Sync + 'static>) {
+    let Ok(mut g) = gestures.lock() else { return };
+    match g.release(std::time::Instant::now()) {
+        Release::Gesture(gesture) => {
+            let action = action.clone();
+            std::thread::spawn(move || action(gesture));
         }
+        Release::Pending(id) => {
+            let window = g.double_press_window();
+            let gestures = gestures.clone();
+            let action = action.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(window);
+                let gesture = gestures.lock().ok().and_then(|mut g| g.resolve(id));
+                if let Some(gesture) = gesture {
+                    action(gesture);
+                }
+            });
+        }
+        Release::None => {}
     }
 }
 
-/// Parse a hotkey string into an rdev::Key
-fn parse_hotkey(s: &str) -> Option<RdevKey> {
-    match s.to_uppercase().as_str() {
-        "F1" => Some(RdevKey::F1),
-        "F2" => Some(RdevKey::F2),
-        "F3" => Some(RdevKey::F3),
-        "F4" => Some(RdevKey::F4),
-        "F5" => Some(RdevKey::F5),
-        "F6" => Some(RdevKey::F6),
-        "F7" => Some(RdevKey::F7),
-        "F8" => Some(RdevKey::F8),
-        "F9" => Some(RdevKey::F9),
-        "F10" => Some(RdevKey::F10),
-        "F11" => Some(RdevKey::F11),
-        "F12" => Some(RdevKey::F12),
-        "SCROLLLOCK" | "SCROLL_LOCK" | "SCROLL" => Some(RdevKey::ScrollLock),
-        "PAUSE" | "BREAK" => Some(RdevKey::Pause),
-        "PRINTSCREEN" | "PRINT_SCREEN" | "PRTSC" => Some(RdevKey::PrintScreen),
-        "INSERT" | "INS" => Some(RdevKey::Insert),
-        "HOME" => Some(RdevKey::Home),
-        "END" => Some(RdevKey::End),
-        "PAGEUP" | "PAGE_UP" | "PGUP" => Some(RdevKey::PageUp),
-        "PAGEDOWN" | "PAGE_DOWN" | "PGDN" => Some(RdevKey::PageDown),
-        "NUM0" | "NUMPAD0" => Some(RdevKey::Kp0),
-        "NUM1" | "NUMPAD1" => Some(RdevKey::Kp1),
-        "NUM2" | "NUMPAD2" => Some(RdevKey::Kp2),
-        "NUM3" | "NUMPAD3" => Some(RdevKey::Kp3),
-        "NUM4" | "NUMPAD4" => Some(RdevKey::Kp4),
-        "NUM5" | "NUMPAD5" => Some(RdevKey::Kp5),
-        "NUM6" | "NUMPAD6" => Some(RdevKey::Kp6),
-        "NUM7" | "NUMPAD7" => Some(RdevKey::Kp7),
-        "NUM8" | "NUMPAD8" => Some(RdevKey::Kp8),
-        "NUM9" | "NUMPAD9" => Some(RdevKey::Kp9),
-        _ => None,
-    }
+/// Whether VAD activation is configured and compiled in
+fn vad_enabled(cfg: &Config) -> bool {
+    cfg!(feature = "vad") && cfg.activation_mode == "vad"
 }
 
-impl Config {
-    pub fn load() -> (Self, Option<PathBuf>) {
-        let config_paths = [
-            dirs::config_dir().map(|p| p.join("ss9k").join("config.toml")),
-            dirs::home_dir().map(|p| p.join(".ss9k").join("config.toml")),
-            Some(PathBuf::from("config.toml")),
-        ];
-
-        for path in config_paths.into_iter().flatten() {
-            if path.exists() {
-                if let Ok(contents) = fs::read_to_string(&path) {
-                    match toml::from_str(&contents) {
-                        Ok(config) => {
-                            println!("[SS9K] Loaded config from: {:?}", path);
-                            return (config, Some(path));
+/// Spawn the VAD processor thread, returning the sender for native-rate mono audio
+/// Audio is resampled to 16kHz, run through Silero VAD, and complete utterances
+/// are sent to the processor as `AudioMessage::AlreadyResampled`
+#[cfg(feature = "vad")]
+fn start_vad(
+    sample_rate: u32,
+    config: Arc<ArcSwap<Config>>,
+    audio_tx: mpsc::Sender<(u64, AudioMessage)>,
+    wake_word_rx: mpsc::Receiver<bool>,
+) -> mpsc::Sender<Vec<f32>> {
+    info!("🎤 VAD mode enabled");
+
+    // Create VAD audio channel
+    let (vad_audio_tx, vad_audio_rx) = mpsc::channel::<Vec<f32>>();
+
+    // Screen share checks run programs, too slow for the audio loop
+    system::watch_screen_share(config.clone());
+
+    // Spawn VAD processor thread
+    {
+        std::thread::spawn(move || {
+            let cfg = config.load();
+            info!("🎤 VAD thread starting (sensitivity: {}, silence: {}ms, min_speech: {}ms, pad: {}ms)",
+                     cfg.vad_sensitivity, cfg.vad_silence_ms, cfg.vad_min_speech_ms, cfg.vad_speech_pad_ms);
+
+            // Initialize VAD
+            let mut vad = match Vad::new(cfg.vad_sensitivity, cfg.vad_silence_ms, cfg.vad_min_speech_ms, cfg.vad_speech_pad_ms) {
+                Ok(v) => v,
+                Err(e) => {
+                    error!("❌ Failed to initialize VAD: {}", e);
+                    return;
+                }
+            };
+
+            if cfg.vad_adaptive_silence {
+                vad.set_adaptive_silence(Some(AdaptiveSilence::new(cfg.vad_silence_ms, cfg.vad_silence_min_ms, cfg.vad_silence_max_ms)));
+                info!("⏱️ Adaptive silence enabled ({}-{}ms)", cfg.vad_silence_min_ms, cfg.vad_silence_max_ms);
+            }
+
+            // Enable wake word mode if configured
+            if !cfg.wake_word.is_empty() {
+                vad.set_wake_word_enabled(true);
+                info!("🗣️ Wake word mode enabled: '{}'", cfg.wake_word);
+            }
+
+            // Persistent resampler for the native -> 16kHz VAD stream
+            let mut resampler = match StreamingResampler::new(sample_rate, VAD_SAMPLE_RATE) {
+                Ok(r) => r,
+                Err(e) => {
+                    error!("❌ Failed to initialize VAD resampler: {}", e);
+                    return;
+                }
+            };
+
+            // Buffer for accumulating audio to resample
+            let mut native_buffer: Vec<f32> = Vec::new();
+
+            // Screen share pause state (the watcher thread does the checking)
+            let mut share_paused = false;
+
+            // Mid-utterance (a pause and more speech doesn't rerun hook_pre_record)
+            let mut in_utterance = false;
+
+            // abort_hotkey throws away the utterance in progress
+            let mut aborts_seen = ABORTS.load(Ordering::SeqCst);
+
+            // Process audio chunks
+            for chunk in vad_audio_rx {
+                let aborts = ABORTS.load(Ordering::SeqCst);
+                if aborts != aborts_seen {
+                    aborts_seen = aborts;
+                    vad.abort_utterance();
+                    native_buffer.clear();
+                    resampler.reset();
+                    in_utterance = false;
+                }
+                // Check for wake word results (non-blocking)
+                while let Ok(wake_word_found) = wake_word_rx.try_recv() {
+                    if !wake_word_found {
+                        // Wake word not found - abort current utterance
+                        debug!("❌ Wake word not detected, aborting utterance");
+                        vad.abort_utterance();
+                        native_buffer.clear();
+                        resampler.reset();
+                    } else {
+                        debug!("✅ Wake word confirmed, continuing...");
+                    }
+                }
+
+                // Reload config for hot-reload support
+                let cfg = config.load();
+
+                // Check if we should be listening
+                if !VAD_LISTENING.load(Ordering::SeqCst) {
+                    // Not listening - reset VAD state if needed
+                    if vad.state() != VadState::Idle {
+                        vad.stop_listening();
+                        native_buffer.clear();
+                        resampler.reset();
+                    }
+                    continue;
+                }
+
+                // Pause while a screen share is active
+                if cfg.pause_on_screen_share {
+                    let sharing = system::screen_sharing();
+                    if sharing != share_paused {
+                        share_paused = sharing;
+                        if sharing {
+                            info!("🖥️ Screen share detected, VAD paused");
+                        } else {
+                            info!("🖥️ Screen share ended, VAD resuming");
+                        }
+                    }
+                } else {
+                    share_paused = false;
+                }
+                if share_paused {
+                    if vad.state() != VadState::Idle {
+                        vad.stop_listening();
+                        native_buffer.clear();
+                        resampler.reset();
+                    }
+                    continue;
+                }
+
+                // Start listening if not already
+                if vad.state() == VadState::Idle {
+                    vad.start_listening();
+                    native_buffer.clear();
+                    resampler.reset();
+                    play_cue(&cfg, Cue::Start);
+                    notify(&cfg, Kind::State, "Listening");
+                    control::publish(ControlEvent::State(State::Listening));
+                    info!("🎤 VAD listening...");
+                }
+
+                // Accumulate audio
+                native_buffer.extend_from_slice(&chunk);
+
+                // Resample when we have enough samples
+                // Resample in chunks to avoid latency
+                let min_chunk = (sample_rate as usize) / 10; // 100ms chunks
+                while native_buffer.len() >= min_chunk {
+                    let to_resample: Vec<f32> = native_buffer.drain(..min_chunk).collect();
+
+                    // Resample to 16kHz for VAD (stream state carries across chunks)
+                    match resampler.process(&to_resample) {
+                        Ok(resampled) => {
+                            // Feed to VAD
+                            let events = vad.feed(&resampled);
+
+                            for event in events {
+                                match event {
+                                    VadEvent::StateChanged(state) => {
+                                        match state {
+                                            VadState::Speaking => {
+                                                debug!("🗣️ Speech detected!");
+                                                if !in_utterance {
+                                                    in_utterance = true;
+                                                    hooks::pre_record(&cfg);
+                                                }
+                                            }
+                                            VadState::SilenceDetected => {
+                                                debug!("🤫 Silence detected, waiting {}ms...", vad.silence_timeout_ms());
+                                            }
+                                            VadState::Listening => {
+                                                debug!("👂 Listening for speech...");
+                                                in_utterance = false;
+                                            }
+                                            _ => {}
+                                        }
+                                    }
+                                    VadEvent::WakeWordCheckReady(audio) => {
+                                        debug!("🔍 Sending {:.2}s for wake word check...", audio.len() as f32 / VAD_SAMPLE_RATE as f32);
+                                        // Send for async wake word check
+                                        if let Err(e) = audio_tx.send((ABORTS.load(Ordering::SeqCst), AudioMessage::WakeWordCheck(audio))) {
+                                            error!("❌ Failed to send wake word check: {}", e);
+                                        } else {
+                                            metrics::enqueued();
+                                        }
+                                    }
+                                    VadEvent::ReadyToProcess(audio) => {
+                                        let cfg = config.load();
+                                        let duration = audio.len() as f32 / VAD_SAMPLE_RATE as f32;
+                                        info!("📤 VAD: Sending {:.2}s of speech for transcription", duration);
+
+                                        // Clear native buffer to start fresh for next utterance
+                                        native_buffer.clear();
+
+                                        // Send already-resampled audio to processor
+                                        if let Err(e) = audio_tx.send((ABORTS.load(Ordering::SeqCst), AudioMessage::AlreadyResampled(audio))) {
+                                            error!("❌ Failed to send VAD audio: {}", e);
+                                        } else {
+                                            metrics::enqueued();
+                                            play_cue(&cfg, Cue::Stop);
+                                            notify(&cfg, Kind::State, "Processing...");
+                                            control::publish(ControlEvent::State(State::Processing));
+                                        }
+                                    }
+                                }
+                            }
                         }
                         Err(e) => {
-                            eprintln!("[SS9K] Config parse error in {:?}: {}", path, e);
+                            debug!("⚠️ VAD resample error: {}", e);
                         }
                     }
                 }
             }
-        }
+            info!("🎤 VAD thread exiting");
+        });
+    }
 
-        // No config found - create one at the default location
-        if let Some(config_dir) = dirs::config_dir() {
-            let ss9k_dir = config_dir.join("ss9k");
-            let config_path = ss9k_dir.join("config.toml");
+    vad_audio_tx
+}
 
-            // Create directory if needed
-            if let Err(e) = fs::create_dir_all(&ss9k_dir) {
-                eprintln!("[SS9K] Failed to create config directory: {}", e);
+/// Type out a pending paragraph (paragraph mode), if there is one
+fn flush_paragraph(paragraph: &mut Paragraph, cfg: &Config) {
+    let Some(text) = paragraph.take() else {
+        return;
+    };
+    set_key_repeat_ms(cfg.key_repeat_ms);
+    match Keys::new() {
+        Ok(mut enigo) => {
+            if let Err(e) = type_text(&mut enigo, &text) {
+                log_error(cfg, &format!("Type error: {}", e));
             } else {
-                // Write default config
-                if let Err(e) = fs::write(&config_path, Self::default_config_content()) {
-                    eprintln!("[SS9K] Failed to write default config: {}", e);
-                } else {
-                    println!("[SS9K] Created default config at: {:?}", config_path);
-                    println!("[SS9K] Edit this file to customize your settings!");
-                    return (Self::default(), Some(config_path));
-                }
+                play_cue(cfg, Cue::Command);
+                notify(cfg, Kind::Result, &privacy::redact(&text));
+                control::publish(ControlEvent::Transcription(text));
+            }
+        }
+        Err(e) => log_error(cfg, &format!("Keyboard init error: {}", e)),
+    }
+}
+
+/// Send dictation to Home Assistant's conversation API and speak the reply
+fn forward_to_homeassistant(text: &str, cfg: &Config) {
+    if cfg.homeassistant_url.is_empty() {
+        log_error(cfg, "output_mode is \"homeassistant\" but homeassistant_url is empty");
+        return;
+    }
+    info!("🏠 Asking Home Assistant: {}", privacy::redact(text));
+    match homeassistant::process(&cfg.homeassistant_url, &cfg.homeassistant_token, &cfg.homeassistant_agent, text, &cfg.language) {
+        Ok(reply) => {
+            history::record(text, true);
+            play_cue(cfg, Cue::Command);
+            control::publish(ControlEvent::Transcription(text.to_string()));
+            if reply.is_empty() {
+                return;
+            }
+            info!("🏠 Home Assistant: {}", reply);
+            notify(cfg, Kind::Result, &reply);
+            if cfg.homeassistant_speak {
+                tts::configure(&cfg.tts_backend, &cfg.tts_voice);
+                tts::say(&reply);
             }
         }
+        Err(e) => log_error(cfg, &e.to_string()),
+    }
+}
+
+/// Find the configured model (downloading it if missing) and load it
+fn load_configured_model(config: &Config) -> Result<WhisperContext> {
+    let model_filename = config.model_filename();
+    let mut model_path = get_model_path(&model_filename);
+
+    if !model_path.exists() {
+        info!("Model '{}' not found locally", config.model);
+        let install_path = get_model_install_path(&model_filename);
+        info!("Will download to: {:?}", install_path);
 
-        println!("[SS9K] Using default config");
-        (Self::default(), None)
+        download_model(&config.model_url(), &install_path)?;
+        model_path = install_path;
     }
 
-    fn default_config_content() -> &'static str {
-        r##"# SuperScreecher9000 Configuration
-# Edit this file to customize your settings.
-# Changes are hot-reloaded - no restart needed!
-
-# Model to use: tiny, base, small, medium, large
-# Larger = more accurate but slower
-# Tip: Use "tiny" or "base" on older/weaker CPUs
-model = "small"
-
-# Language for transcription (ISO 639-1 codes)
-# Say "command languages" or "command language list" for full list
-# Or see: https://github.com/openai/whisper#available-models-and-languages
-language = "en"
-
-# Number of threads for whisper inference
-# More threads = faster on multi-core CPUs
-threads = 4
-
-# Specific audio device name (partial match)
-# Leave empty for auto-detection
-# Example: "Microphone" or "Blue Yeti"
-device = ""
-
-# Hotkey to trigger recording (dictation mode)
-# Options: F1-F12, ScrollLock, Pause, PrintScreen, Insert, Home, End, PageUp, PageDown, Num0-Num9
-hotkey = "F12"
-
-# Command hotkey - alternate key that auto-prefixes with leader word
-# Use this to speak commands without saying "command" first
-# Example: with command_hotkey = "F11", pressing F11 and saying "enter"
-# is the same as pressing F12 and saying "command enter"
-# Leave empty to disable
-command_hotkey = ""
-
-# Hotkey mode: "hold" (release to stop) or "toggle" (press again to stop)
-# Applies to both hotkey and command_hotkey
-hotkey_mode = "hold"
-
-# Auto-stop timeout for toggle mode (0 = no timeout)
-toggle_timeout_secs = 0
-
-# Leader word for voice commands
-# All commands require this prefix: "command enter", "command emoji smile", etc.
-# Change to whatever feels natural: "voice", "computer", "hey", etc.
-leader = "command"
-
-# Key repeat rate for hold mode (milliseconds between key presses)
-# Lower = faster repeat, higher = slower
-# Used when you say "command hold w" to spam a key
-key_repeat_ms = 50
-
-# Processing timeout in seconds (0 = no timeout)
-# If transcription takes longer than this, it will be aborted
-# Useful for weak CPUs that might hang on larger models
-# Tip: If you hit timeouts often, try model = "tiny" or "base"
-processing_timeout_secs = 30
-
-# Verbose logging (processing, resampling, transcription details)
-# Errors always print regardless. Set false once you're comfortable with the tool.
-verbose = true
-
-# Audio feedback (system beep)
-# Single beep when recording starts, double beep when transcription completes
-audio_feedback = false
-
-# Activation mode: "hotkey" (default) or "vad" (voice activity detection)
-# - hotkey: Press a key to start/stop recording (traditional mode)
-# - vad: Automatically detect when you're speaking (hands-free mode)
-#        In VAD mode, the hotkey toggles listening on/off
-activation_mode = "hotkey"
-
-# VAD settings (only used when activation_mode = "vad")
-# Sensitivity: 0.0-1.0, higher = more sensitive to speech
-vad_sensitivity = 0.9
-# Silence duration (ms) before processing - wait for pause after speech
-vad_silence_ms = 1000
-# Minimum speech duration (ms) before it counts - ignore brief noises
-vad_min_speech_ms = 200
-# Speech padding (ms) - extra time at end to catch trailing words
-vad_speech_pad_ms = 300
-
-# Custom voice commands
-# Maps spoken phrase -> shell command
-# Supports $ENV_VAR expansion (e.g., $TERMINAL, $BROWSER, $EDITOR)
-[commands]
-# "open terminal" = "$TERMINAL"
-# "open browser" = "$BROWSER"
-# "open firefox" = "firefox"
-# "screenshot" = "flameshot gui"
-
-# Aliases for common misrecognitions
-# Maps what whisper hears -> what you meant
-[aliases]
-# "e max" = "emacs"
-# "fire fox" = "firefox"
-
-# Text snippets for quick insertion
-# Say "command insert <name>" to type the snippet
-# Supports placeholders: {date}, {time}, {datetime}, {shell:cmd}
-[inserts]
-# email = "you@example.com"
-# sig = "Best regards,\nYour Name"
-
-# Text wrappers for quick wrapping
-# Say "command wrap <name> <text>" to wrap text
-# Use | to separate left/right: "parens" = "(|)"
-[wrappers]
-# quotes = '"'
-# parens = "(|)"
-# brackets = "[|]"
-"##
+    info!("Loading whisper model from: {:?}", model_path);
+    Ok(load_model(&model_path)?)
+}
+
+/// Where captured audio comes from
+enum AudioInput {
+    /// A cpal input device
+    Device(cpal::Device, cpal::SupportedStreamConfig),
+    /// Raw PCM from stdin or the network
+    Raw(RawSource, RawFormat),
+}
+
+/// Where raw PCM comes from
+enum RawSource {
+    /// Piped into stdin (`--stdin-audio`)
+    Stdin,
+    /// Sent to `audio_source` from this machine or an `audio_source_allow` address
+    Network(NetSource, Vec<IpAddr>),
+}
+
+/// Start reading a raw source in the background, handing `sink` mono chunks
+fn spawn_raw(source: RawSource, format: RawFormat, sink: impl FnMut(&[f32]) + Send + 'static) -> Result<(), AudioError> {
+    match source {
+        RawSource::Stdin => {
+            spawn_raw_reader(std::io::stdin(), format, sink);
+            Ok(())
+        }
+        RawSource::Network(source, allow) => netmic::listen(&source, allow, format, sink),
     }
+}
 
-    pub fn load_from(path: &PathBuf) -> Option<Self> {
-        if let Ok(contents) = fs::read_to_string(path) {
-            match toml::from_str(&contents) {
-                Ok(config) => Some(config),
-                Err(e) => {
-                    eprintln!("[SS9K] Config reload error: {}", e);
-                    None
+/// Find the configured (or best-guess) microphone and its default config
+fn open_input_device(cfg: &Config) -> Result<(cpal::Device, cpal::SupportedStreamConfig)> {
+    let host = cpal::default_host();
+    info!("Host: {:?}", host.id());
+
+    let device = if !cfg.device.is_empty() {
+        let device_name = cfg.device.clone();
+        host.input_devices()?
+            .find(|d| d.name().map(|n| n.contains(&device_name)).unwrap_or(false))
+            .or_else(|| {
+                warn!("Configured device '{}' not found, using default", device_name);
+                host.default_input_device()
+            })
+    } else {
+        host.input_devices()?
+            .find(|d| d.name().map(|n| is_microphone(&n)).unwrap_or(false))
+            .or_else(|| host.default_input_device())
+    }.expect("No input device available");
+    info!("Device: {}", device.name()?);
+
+    let audio_config = device.default_input_config()?;
+    info!("Audio config: {:?}", audio_config);
+
+    Ok((device, audio_config))
+}
+
+/// SuperScreecher9000 - local voice dictation that types wherever your cursor is
+///
+/// With no subcommand, runs the dictation daemon. Flags override config.toml
+/// (and keep overriding it across hot reloads).
+#[derive(Parser)]
+#[command(name = "ss9k", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Config file to use instead of the usual locations
+    #[arg(long, short, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Whisper model: tiny, base, small, medium, large-v3... (".en" for English-only)
+    #[arg(long, short, global = true)]
+    model: Option<String>,
+
+    /// Spoken language code ("en", "de"...) or "auto"
+    #[arg(long, short, global = true)]
+    language: Option<String>,
+
+    /// Input device (any part of its name)
+    #[arg(long, short, value_name = "NAME")]
+    device: Option<String>,
+
+    /// Recording hotkey (F12, ScrollLock, MOUSE_MIDDLE...)
+    #[arg(long, value_name = "KEY")]
+    hotkey: Option<String>,
+
+    /// Start with a [profiles.<name>] profile active
+    #[arg(long, short, value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Only print warnings and errors (no banner or command list)
+    #[arg(long, short, global = true)]
+    quiet: bool,
+
+    /// Log level or filter, e.g. "debug" or "ss9k=debug,zbus=info" (SS9K_LOG wins)
+    #[arg(long, global = true, value_name = "LEVEL")]
+    log_level: Option<String>,
+
+    /// Running as a service: no banner, sd_notify, keep going without hotkeys
+    #[arg(long)]
+    daemon: bool,
+
+    /// Where dictation goes: type, stdout, exec:<command> or homeassistant
+    #[arg(long, value_name = "MODE")]
+    output: Option<String>,
+
+    /// Read raw PCM from stdin instead of a microphone
+    #[arg(long, value_name = "ENCODING:RATE:CHANNELS", num_args = 0..=1, default_missing_value = "s16le:16000:1")]
+    stdin_audio: Option<RawFormat>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Transcribe an audio file (wav, mp3, ogg, flac)
+    Transcribe {
+        file: PathBuf,
+        /// Type the text at the cursor instead of printing it
+        #[arg(long = "type", short)]
+        type_output: bool,
+        /// Output format: text, srt, vtt, json (default: from --output extension, else text)
+        #[arg(long, short)]
+        format: Option<OutputFormat>,
+        /// Write to a file instead of printing
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+    /// Control a running instance: toggle, start, stop, status, mode [name], profile [name], reload
+    Ctl {
+        #[arg(required = true, num_args = 1.., value_name = "COMMAND")]
+        args: Vec<String>,
+    },
+    /// Browse and export transcription history
+    #[command(subcommand)]
+    History(HistoryCommand),
+    /// Words, commands and stage latencies per day or week
+    Stats {
+        /// daily or weekly
+        #[arg(default_value = "daily")]
+        period: stats::Period,
+        /// How many days/weeks to show (default 14/8)
+        limit: Option<usize>,
+    },
+    /// Print a systemd user unit for running `ss9k --daemon`
+    SystemdUnit,
+    /// Check the config file and print every problem found in it
+    Check,
+}
+
+#[derive(Subcommand)]
+enum HistoryCommand {
+    /// Print the last N transcriptions
+    Show {
+        #[arg(default_value_t = 20)]
+        count: usize,
+    },
+    /// Print transcriptions containing some text (case-insensitive)
+    Search {
+        #[arg(required = true, num_args = 1..)]
+        query: Vec<String>,
+    },
+    /// Write the whole history (default jsonl to stdout)
+    Export {
+        /// jsonl, json, csv, text (default: from --output extension, else jsonl)
+        #[arg(long, short)]
+        format: Option<ExportFormat>,
+        /// Write to a file instead of printing
+        #[arg(long, short, value_name = "PATH")]
+        output: Option<PathBuf>,
+    },
+}
+
+/// The format a file extension implies (subs.srt -> srt), if any
+fn format_from_extension<F: std::str::FromStr>(output: Option<&Path>) -> Option<F> {
+    output?.extension()?.to_str()?.parse().ok()
+}
+
+/// `ss9k transcribe <file> [--type] [--format F] [--output PATH]`: transcribe an audio file
+fn run_transcribe(file: &Path, type_output: bool, format: Option<OutputFormat>, output: Option<&Path>) -> Result<()> {
+    let format = format.or_else(|| format_from_extension(output)).unwrap_or_default();
+    if type_output && format != OutputFormat::Text {
+        anyhow::bail!("--type only works with text output");
+    }
+
+    let (config, _) = Config::load();
+    let ctx = load_configured_model(&config)?;
+
+    let (samples, sample_rate) = decode_file(file)?;
+    let duration = samples.len() as f32 / sample_rate.max(1) as f32;
+    info!("🎵 Decoded {:?}: {:.1}s at {}Hz", file, duration, sample_rate);
+
+    let resampled = resample_audio(&samples, sample_rate, WHISPER_SAMPLE_RATE)?;
+    let start_time = std::time::Instant::now();
+    let segments = transcribe_segments(&ctx, &resampled, &config)?;
+    info!("⏱️ Transcribed in {:.1}s", start_time.elapsed().as_secs_f32());
+
+    let rendered = subtitles::render(&segments, format);
+    if type_output {
+        info!("⌨️ Typing in 3s - focus the target window");
+        std::thread::sleep(Duration::from_secs(3));
+        let mut enigo = Keys::new()?;
+        enigo.text(&rendered)?;
+    } else if let Some(output) = output {
+        std::fs::write(output, &rendered)?;
+        info!("💾 Wrote {:?} ({} segments)", output, segments.len());
+    } else {
+        println!("{}", rendered);
+    }
+
+    Ok(())
+}
+
+/// `ss9k history show [N] | search <text> | export [--format F] [--output PATH]`
+fn run_history(command: HistoryCommand) -> Result<()> {
+    let (config, _) = Config::load();
+    let path = history::path(&config);
+    let records = history::load(&path)?;
+
+    match command {
+        HistoryCommand::Show { count } => {
+            for record in &records[records.len().saturating_sub(count)..] {
+                println!("{}", history::format_line(record));
+            }
+        }
+        HistoryCommand::Search { query } => {
+            for record in history::search(&records, &query.join(" ")) {
+                println!("{}", history::format_line(record));
+            }
+        }
+        HistoryCommand::Export { format, output } => {
+            let format = format.or_else(|| format_from_extension(output.as_deref())).unwrap_or_default();
+            let rendered = history::export(&records, format);
+            match output {
+                Some(output) => {
+                    std::fs::write(&output, rendered)?;
+                    info!("💾 Exported {} entries to {:?}", records.len(), output);
                 }
+                None => print!("{}", rendered),
             }
-        } else {
-            None
         }
     }
+    if records.is_empty() {
+        info!("📭 No history yet in {:?}", path);
+    }
+    Ok(())
+}
+
+/// `ss9k stats [daily|weekly] [N]`: words, commands and stage latencies per day/week
+fn run_stats(period: stats::Period, limit: Option<usize>) -> Result<()> {
+    let limit = limit.unwrap_or(if period == stats::Period::Daily { 14 } else { 8 });
+
+    let path = stats::default_path();
+    let samples = stats::load(&path)?;
+    if samples.is_empty() {
+        info!("📭 No stats yet in {:?}", path);
+        return Ok(());
+    }
+    print!("{}", stats::report(&samples, period, limit));
+    Ok(())
+}
 
-    pub fn model_filename(&self) -> String {
-        format!("ggml-{}.bin", self.model)
+/// `ss9k check`: list config problems with their lines, fail if there are any
+fn run_check() -> Result<()> {
+    let (_, path) = Config::load();
+    let Some(path) = path else {
+        println!("No config file, using built-in defaults");
+        return Ok(());
+    };
+    let problems = check::check_file(&path)?;
+    if problems.is_empty() {
+        println!("✅ {}: no problems found", path.display());
+        return Ok(());
     }
+    let contents = std::fs::read_to_string(&path)?;
+    print!("{}", check::render(&path, &contents, &problems));
+    println!("❌ {} problem(s) in {}", problems.len(), path.display());
+    std::process::exit(1);
+}
 
-    pub fn model_url(&self) -> String {
-        format!(
-            "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-{}.bin",
-            self.model
-        )
+/// `ss9k ctl <command>`: control a running instance over its socket
+fn run_ctl(args: &[String]) -> Result<()> {
+    #[cfg(unix)]
+    {
+        let reply = ss9k::ipc::send(&args.join(" "))?;
+        if !reply.is_empty() {
+            println!("{}", reply);
+        }
+        Ok(())
     }
+    #[cfg(not(unix))]
+    anyhow::bail!("ss9k ctl needs a Unix control socket; use the HTTP control API (http feature) instead")
 }
 
 fn main() -> Result<()> {
-    let (config, config_path) = Config::load();
-    println!("[SS9K] Model: {}, Language: {}, Threads: {}",
-             config.model, config.language, config.threads);
+    // A crash mid-combo must not leave Ctrl/Shift held down
+    install_panic_hook();
+    let cli = Cli::parse();
+    // Subcommands print results on stdout, so their logs go to stderr
+    let prints_data = matches!(cli.command, Some(Command::Transcribe { .. } | Command::History(_) | Command::Stats { .. }))
+        || cli.output.as_deref() == Some("stdout");
+    let log_level = cli.log_level.as_deref().or(cli.quiet.then_some("warn"));
+    logging::init(log_level, prints_data);
+    if let Some(path) = &cli.config
+        && !path.is_file()
+    {
+        anyhow::bail!("Config file {:?} not found", path);
+    }
+    Overrides {
+        config: cli.config,
+        model: cli.model,
+        language: cli.language,
+        device: cli.device,
+        hotkey: cli.hotkey,
+        output: cli.output,
+    }
+    .install();
 
-    if parse_hotkey(&config.hotkey).is_none() {
-        eprintln!("[SS9K] Unknown hotkey '{}', will default to F12", config.hotkey);
+    match cli.command {
+        Some(Command::Transcribe { file, type_output, format, output }) => {
+            return run_transcribe(&file, type_output, format, output.as_deref());
+        }
+        Some(Command::Ctl { args }) => return run_ctl(&args),
+        Some(Command::History(command)) => return run_history(command),
+        Some(Command::Stats { period, limit }) => return run_stats(period, limit),
+        Some(Command::Check) => return run_check(),
+        Some(Command::SystemdUnit) => {
+            print!("{}", daemon::unit_file(&std::env::current_exe()?));
+            return Ok(());
+        }
+        None => {}
     }
+    let stdin_format = cli.stdin_audio;
+    // --daemon: running as a service (no banner, sd_notify, keep going without hotkeys)
+    let daemon = cli.daemon;
+
+    // One instance at a time: a second one would grab the hotkey and type everything twice
+    #[cfg(unix)]
+    let control_socket = match ss9k::ipc::bind() {
+        Ok(listener) => Some(listener),
+        Err(IpcError::InUse(path)) => {
+            anyhow::bail!("SS9K is already running (control socket {:?}). Use `ss9k ctl toggle` to control it.", path)
+        }
+        Err(e) => {
+            warn!("⚠️ Control socket not started: {}", e);
+            None
+        }
+    };
+
+    profiles::set(cli.profile.clone());
+    let (config, config_path) = Config::load();
+    logging::configure(&config);
+    check::warn_problems(&config);
+    if let Some(name) = &cli.profile
+        && let Some((name, profile)) = profiles::find(&config, name)
+    {
+        info!("🎭 Profile: {}", name);
+        if !profile.mode.is_empty() && let Err(e) = control::set_mode(&profile.mode, &config) {
+            warn!("⚠️ Profile '{}': {}", name, e);
+        }
+    }
+    history::configure(&config);
+    stats::configure(&config);
+    output::configure(&config);
+    keyboard::configure(&config);
+    output::configure(&config);
+    security::configure(&config);
+    timers::configure(&config);
+    lookups::configure(&config);
+    fillers::configure(&config);
+    numbers::configure(&config);
+    profanity::configure(&config);
+    commands::configure(&config);
+    #[cfg(feature = "scripting")]
+    scripting::configure(&config);
+    #[cfg(not(feature = "scripting"))]
+    if !config.script.is_empty() {
+        warn!("⚠️ Built without the 'scripting' feature, ignoring script");
+    }
+    #[cfg(feature = "plugins")]
+    plugins::configure(&config, config_path.as_deref());
+    #[cfg(not(feature = "plugins"))]
+    if !config.plugins.is_empty() {
+        warn!("⚠️ Built without the 'plugins' feature, ignoring plugins");
+    }
+    info!("Model: {}, Language: {}, Threads: {}",
+          config.model, config.language, config.threads);
+
 
-    println!("=================================");
-    println!("   SuperScreecher9000 v0.14.0");
-    println!("   Press {} to screech", config.hotkey);
-    println!("=================================");
+    // With stdout output, stdout is for transcriptions only
+    if !daemon && !cli.quiet && config.output_mode != "stdout" {
+        println!("=================================");
+        println!("   SuperScreecher9000 v0.14.0");
+        println!("   Press {} to screech", config.hotkey);
+        println!("=================================");
 
-    print_help();
+        print_help();
+    }
 
     if config.activation_mode == "vad" {
-        println!("[SS9K] Activation: VAD (voice activity detection)");
-        println!("[SS9K] Hotkey: {} (toggles VAD listening)", config.hotkey);
-        println!("[SS9K] VAD: sensitivity={}, silence={}ms, min_speech={}ms",
-                 config.vad_sensitivity, config.vad_silence_ms, config.vad_min_speech_ms);
+        info!("Activation: VAD (voice activity detection)");
+        info!("Hotkey: {} (toggles VAD listening)", config.hotkey);
+        info!("VAD: sensitivity={}, silence={}ms, min_speech={}ms",
+              config.vad_sensitivity, config.vad_silence_ms, config.vad_min_speech_ms);
     } else {
-        println!("[SS9K] Activation: hotkey ({})", config.hotkey_mode);
-        println!("[SS9K] Hotkey: {} (mode: {})", config.hotkey, config.hotkey_mode);
+        info!("Activation: hotkey ({})", config.hotkey_mode);
+        info!("Hotkey: {} (mode: {})", config.hotkey, config.hotkey_mode);
     }
     if !config.command_hotkey.is_empty() {
-        println!("[SS9K] Command hotkey: {} (auto-prefixes '{}')", config.command_hotkey, config.leader);
+        info!("Command hotkey: {} (auto-prefixes '{}')", config.command_hotkey, config.leader);
     }
     if !config.commands.is_empty() {
-        println!("[SS9K] Custom commands: {} loaded", config.commands.len());
+        info!("Custom commands: {} loaded", config.commands.len());
     }
     if !config.aliases.is_empty() {
-        println!("[SS9K] Aliases: {} loaded", config.aliases.len());
+        info!("Aliases: {} loaded", config.aliases.len());
     }
     if !config.inserts.is_empty() {
-        println!("[SS9K] Inserts: {} loaded", config.inserts.len());
+        info!("Inserts: {} loaded", config.inserts.len());
     }
     if !config.wrappers.is_empty() {
-        println!("[SS9K] Wrappers: {} loaded", config.wrappers.len());
+        info!("Wrappers: {} loaded", config.wrappers.len());
     }
 
-    // Check if model exists, download if not
-    let model_filename = config.model_filename();
-    let mut model_path = get_model_path(&model_filename);
-
-    if !model_path.exists() {
-        println!("[SS9K] Model '{}' not found locally", config.model);
-        let install_path = get_model_install_path(&model_filename);
-        println!("[SS9K] Will download to: {:?}", install_path);
+    let ctx = Arc::new(load_configured_model(&config)?);
+    let config = Arc::new(ArcSwap::from_pointee(config));
+    info!("Model loaded!");
 
-        download_model(&config.model_url(), &install_path)?;
-        model_path = install_path;
+    let http_port = config.load().http_port;
+    #[cfg(feature = "http")]
+    if http_port != 0 && let Err(e) = ss9k::http::start(http_port, config.clone(), config_path.clone()) {
+        warn!("⚠️ HTTP server not started: {}", e);
+    }
+    #[cfg(not(feature = "http"))]
+    if http_port != 0 {
+        warn!("⚠️ Built without the 'http' feature, ignoring http_port");
+    }
+    let remote_listen = config.load().remote_listen.clone();
+    #[cfg(feature = "http")]
+    if !remote_listen.is_empty() && let Err(e) = ss9k::remote::start(&remote_listen, config.clone()) {
+        warn!("⚠️ Remote trigger not started: {}", e);
+    }
+    #[cfg(not(feature = "http"))]
+    if !remote_listen.is_empty() {
+        warn!("⚠️ Built without the 'http' feature, ignoring remote_listen");
+    }
+    #[cfg(feature = "dbus")]
+    if let Err(e) = ss9k::dbus::start(config.clone()) {
+        warn!("⚠️ D-Bus service not started: {}", e);
+    }
+    let mqtt_enabled = !config.load().mqtt_broker.is_empty();
+    #[cfg(feature = "mqtt")]
+    if mqtt_enabled && let Err(e) = ss9k::mqtt::start(config.clone(), config_path.clone()) {
+        warn!("⚠️ MQTT not started: {}", e);
+    }
+    #[cfg(not(feature = "mqtt"))]
+    if mqtt_enabled {
+        warn!("⚠️ Built without the 'mqtt' feature, ignoring mqtt_broker");
+    }
+    let captions_file = config.load().captions_file.clone();
+    if !captions_file.is_empty() {
+        let path = PathBuf::from(shellexpand::tilde(&captions_file).as_ref());
+        if let Err(e) = ss9k::captions::start(path, config.clone()) {
+            warn!("⚠️ Live captions not started: {}", e);
+        }
+    }
+    #[cfg(unix)]
+    if let Some(listener) = control_socket {
+        ss9k::ipc::start(listener, config.clone(), config_path.clone());
+    }
+    #[cfg(unix)]
+    if let Err(e) = ss9k::signals::start(config.clone(), config_path.clone()) {
+        warn!("⚠️ Signal controls not installed: {}", e);
     }
-
-    // Load whisper model
-    println!("[SS9K] Loading whisper model from: {:?}", model_path);
-    let ctx = WhisperContext::new_with_params(
-        model_path.to_str().expect("Invalid model path"),
-        WhisperContextParameters::default()
-    ).expect("Failed to load whisper model");
-    let ctx = Arc::new(ctx);
-    let config = Arc::new(ArcSwap::from_pointee(config));
-    println!("[SS9K] Model loaded!");
 
     // Set up config hot-reload
     if let Some(ref path) = config_path {
@@ -463,281 +905,211 @@ fn main() -> Result<()> {
             let mut watcher = match recommended_watcher(tx) {
                 Ok(w) => w,
                 Err(e) => {
-                    eprintln!("[SS9K] Failed to create config watcher: {}", e);
+                    warn!("Failed to create config watcher: {}", e);
                     return;
                 }
             };
             if let Err(e) = watcher.watch(&watch_path, RecursiveMode::NonRecursive) {
-                eprintln!("[SS9K] Failed to watch config file: {}", e);
+                warn!("Failed to watch config file: {}", e);
                 return;
             }
-            println!("[SS9K] 👀 Watching config for changes: {:?}", watch_path);
+            info!("👀 Watching config for changes: {:?}", watch_path);
+
+            // Included files' directories too, so adding or removing a drop-in reloads
+            let mut include_dirs = Vec::new();
+            let mut watch_includes = |watcher: &mut notify::RecommendedWatcher, config: &Config| {
+                for dir in config.include_dirs(&watch_path) {
+                    if !include_dirs.contains(&dir) && watcher.watch(&dir, RecursiveMode::NonRecursive).is_ok() {
+                        info!("👀 Watching included files in {:?}", dir);
+                        include_dirs.push(dir);
+                    }
+                }
+            };
+            watch_includes(&mut watcher, &config_for_watcher.load());
 
             for event in rx {
                 if let Ok(event) = event {
-                    if event.kind.is_modify() {
+                    let drop_in_changed = (event.kind.is_create() || event.kind.is_remove())
+                        && event.paths.iter().any(|p| *p != watch_path);
+                    if event.kind.is_modify() || drop_in_changed {
                         std::thread::sleep(Duration::from_millis(100));
-                        if let Some(new_config) = Config::load_from(&watch_path) {
-                            config_for_watcher.store(Arc::new(new_config));
-                            println!("[SS9K] 🔄 Config reloaded!");
+                        if let Err(e) = control::reload(&config_for_watcher, Some(&watch_path)) {
+                            warn!("Config reload error: {}", e);
                         }
+                        watch_includes(&mut watcher, &config_for_watcher.load());
                     }
                 }
             }
         });
     }
 
-    let host = cpal::default_host();
-    println!("[SS9K] Host: {:?}", host.id());
-
-    // Find microphone device
+    // Pick the audio source: piped raw PCM, the network or a microphone
     let cfg = config.load();
-    let device = if !cfg.device.is_empty() {
-        let device_name = cfg.device.clone();
-        host.input_devices()?
-            .find(|d| d.name().map(|n| n.contains(&device_name)).unwrap_or(false))
-            .or_else(|| {
-                eprintln!("[SS9K] Configured device '{}' not found, using default", device_name);
-                host.default_input_device()
-            })
-    } else {
-        host.input_devices()?
-            .find(|d| d.name().map(|n| is_microphone(&n)).unwrap_or(false))
-            .or_else(|| host.default_input_device())
-    }.expect("No input device available");
-    println!("[SS9K] Device: {}", device.name()?);
-
-    let audio_config = device.default_input_config()?;
-    println!("[SS9K] Audio config: {:?}", audio_config);
-
-    let sample_rate = audio_config.sample_rate().0;
-    let channels = audio_config.channels() as usize;
+    let (input, sample_rate, channels) = match stdin_format {
+        Some(format) => {
+            info!("Input: stdin ({})", format);
+            (AudioInput::Raw(RawSource::Stdin, format), format.rate, format.channels)
+        }
+        None if !cfg.audio_source.is_empty() => {
+            let source: NetSource = cfg.audio_source.parse()?;
+            let format: RawFormat = cfg.audio_source_format.parse()?;
+            let allow = netmic::parse_allow(&cfg.audio_source_allow)?;
+            info!("Input: {} ({})", source, format);
+            (AudioInput::Raw(RawSource::Network(source, allow), format), format.rate, format.channels)
+        }
+        None => {
+            let (device, audio_config) = open_input_device(&cfg)?;
+            let sample_rate = audio_config.sample_rate().0;
+            let channels = audio_config.channels() as usize;
+            (AudioInput::Device(device, audio_config), sample_rate, channels)
+        }
+    };
 
-    let is_vad_mode = cfg.activation_mode == "vad";
+    if cfg.activation_mode == "vad" && !cfg!(feature = "vad") {
+        warn!("⚠️ Built without the 'vad' feature, using hotkey mode");
+    }
+    let is_vad_mode = vad_enabled(&cfg);
 
     // Shared state
     let audio_buffer: AudioBuffer = Arc::new(Mutex::new(Vec::new()));
     let recording_arc = Arc::new(AtomicBool::new(false));
 
     // Create audio channel for processor
-    let (audio_tx, audio_rx) = mpsc::channel::<AudioMessage>();
+    // Each clip goes with the ABORTS count it was queued under
+    let (audio_tx, audio_rx) = mpsc::channel::<(u64, AudioMessage)>();
 
     // Create wake word result channel (processor -> VAD thread)
+    #[cfg_attr(not(feature = "vad"), allow(unused_variables))]
     let (wake_word_tx, wake_word_rx) = mpsc::channel::<bool>();
 
     // Build stream based on activation mode
-    let stream = if is_vad_mode {
-        println!("[SS9K] 🎤 VAD mode enabled");
-
-        // Create VAD audio channel
-        let (vad_audio_tx, vad_audio_rx) = mpsc::channel::<Vec<f32>>();
-
-        // Build VAD stream
-        let err_fn = |err| eprintln!("[SS9K] Stream error: {}", err);
-        let stream = match audio_config.sample_format() {
-            cpal::SampleFormat::I8 => build_stream_with_vad::<i8>(&device, &audio_config.clone().into(), vad_audio_tx.clone(), channels, err_fn)?,
-            cpal::SampleFormat::I16 => build_stream_with_vad::<i16>(&device, &audio_config.clone().into(), vad_audio_tx.clone(), channels, err_fn)?,
-            cpal::SampleFormat::I32 => build_stream_with_vad::<i32>(&device, &audio_config.clone().into(), vad_audio_tx.clone(), channels, err_fn)?,
-            cpal::SampleFormat::F32 => build_stream_with_vad::<f32>(&device, &audio_config.clone().into(), vad_audio_tx, channels, err_fn)?,
-            format => {
-                eprintln!("[SS9K] Unsupported sample format: {:?}", format);
-                return Ok(());
-            }
-        };
-
-        // Spawn VAD processor thread
-        {
-            let audio_tx = audio_tx.clone();
-            let config = config.clone();
-            let wake_word_rx = wake_word_rx; // Move receiver to VAD thread
-            std::thread::spawn(move || {
-                let cfg = config.load();
-                println!("[SS9K] 🎤 VAD thread starting (sensitivity: {}, silence: {}ms, min_speech: {}ms, pad: {}ms)",
-                         cfg.vad_sensitivity, cfg.vad_silence_ms, cfg.vad_min_speech_ms, cfg.vad_speech_pad_ms);
-
-                // Initialize VAD
-                let mut vad = match Vad::new(cfg.vad_sensitivity, cfg.vad_silence_ms, cfg.vad_min_speech_ms, cfg.vad_speech_pad_ms) {
-                    Ok(v) => v,
-                    Err(e) => {
-                        eprintln!("[SS9K] ❌ Failed to initialize VAD: {}", e);
-                        return;
-                    }
-                };
-
-                // Enable wake word mode if configured
-                if !cfg.wake_word.is_empty() {
-                    vad.set_wake_word_enabled(true);
-                    println!("[SS9K] 🗣️ Wake word mode enabled: '{}'", cfg.wake_word);
-                }
-
-                // Buffer for accumulating audio to resample
-                let mut native_buffer: Vec<f32> = Vec::new();
-
-                // Process audio chunks
-                for chunk in vad_audio_rx {
-                    // Check for wake word results (non-blocking)
-                    while let Ok(wake_word_found) = wake_word_rx.try_recv() {
-                        if !wake_word_found {
-                            // Wake word not found - abort current utterance
-                            let cfg = config.load();
-                            if cfg.verbose {
-                                println!("[SS9K] ❌ Wake word not detected, aborting utterance");
-                            }
-                            vad.abort_utterance();
-                            native_buffer.clear();
-                        } else {
-                            let cfg = config.load();
-                            if cfg.verbose {
-                                println!("[SS9K] ✅ Wake word confirmed, continuing...");
-                            }
+    let err_fn = |err| warn!("Stream error: {}", err);
+    let stream = match (is_vad_mode, input) {
+        #[cfg(feature = "vad")]
+        (true, input) => {
+            let vad_audio_tx = start_vad(sample_rate, config.clone(), audio_tx.clone(), wake_word_rx);
+            match input {
+                AudioInput::Raw(source, format) => {
+                    spawn_raw(source, format, move |mono| {
+                        if VAD_LISTENING.load(Ordering::SeqCst) {
+                            let _ = vad_audio_tx.send(mono.to_vec());
                         }
-                    }
-
-                    // Reload config for hot-reload support
-                    let cfg = config.load();
-
-                    // Check if we should be listening
-                    if !VAD_LISTENING.load(Ordering::SeqCst) {
-                        // Not listening - reset VAD state if needed
-                        if vad.state() != VadState::Idle {
-                            vad.stop_listening();
-                            native_buffer.clear();
-                        }
-                        continue;
-                    }
-
-                    // Start listening if not already
-                    if vad.state() == VadState::Idle {
-                        vad.start_listening();
-                        native_buffer.clear();
-                        if cfg.audio_feedback { beep(); }
-                        println!("[SS9K] 🎤 VAD listening...");
-                    }
-
-                    // Accumulate audio
-                    native_buffer.extend_from_slice(&chunk);
-
-                    // Resample when we have enough samples
-                    // Resample in chunks to avoid latency
-                    let min_chunk = (sample_rate as usize) / 10; // 100ms chunks
-                    while native_buffer.len() >= min_chunk {
-                        let to_resample: Vec<f32> = native_buffer.drain(..min_chunk).collect();
-
-                        // Resample to 16kHz for VAD
-                        match resample_audio(&to_resample, sample_rate, VAD_SAMPLE_RATE) {
-                            Ok(resampled) => {
-                                // Feed to VAD
-                                let events = vad.feed(&resampled);
-
-                                for event in events {
-                                    match event {
-                                        VadEvent::StateChanged(state) => {
-                                            let cfg = config.load();
-                                            match state {
-                                                VadState::Speaking => {
-                                                    if cfg.verbose {
-                                                        println!("[SS9K] 🗣️ Speech detected!");
-                                                    }
-                                                }
-                                                VadState::SilenceDetected => {
-                                                    if cfg.verbose {
-                                                        println!("[SS9K] 🤫 Silence detected, waiting...");
-                                                    }
-                                                }
-                                                VadState::Listening => {
-                                                    if cfg.verbose {
-                                                        println!("[SS9K] 👂 Listening for speech...");
-                                                    }
-                                                }
-                                                _ => {}
-                                            }
-                                        }
-                                        VadEvent::WakeWordCheckReady(audio) => {
-                                            let cfg = config.load();
-                                            if cfg.verbose {
-                                                let duration = audio.len() as f32 / VAD_SAMPLE_RATE as f32;
-                                                println!("[SS9K] 🔍 Sending {:.2}s for wake word check...", duration);
-                                            }
-                                            // Send for async wake word check
-                                            if let Err(e) = audio_tx.send(AudioMessage::WakeWordCheck(audio)) {
-                                                eprintln!("[SS9K] ❌ Failed to send wake word check: {}", e);
-                                            }
-                                        }
-                                        VadEvent::ReadyToProcess(audio) => {
-                                            let cfg = config.load();
-                                            let duration = audio.len() as f32 / VAD_SAMPLE_RATE as f32;
-                                            println!("[SS9K] 📤 VAD: Sending {:.2}s of speech for transcription", duration);
-
-                                            // Clear native buffer to start fresh for next utterance
-                                            native_buffer.clear();
-
-                                            // Send already-resampled audio to processor
-                                            if let Err(e) = audio_tx.send(AudioMessage::AlreadyResampled(audio)) {
-                                                eprintln!("[SS9K] ❌ Failed to send VAD audio: {}", e);
-                                            } else if cfg.audio_feedback {
-                                                beep_done();
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                if cfg.verbose {
-                                    eprintln!("[SS9K] ⚠️ VAD resample error: {}", e);
-                                }
-                            }
+                    })?;
+                    None
+                }
+                AudioInput::Device(device, audio_config) => {
+                    match build_stream_with_vad_for_format(&device, &audio_config, vad_audio_tx, channels, err_fn) {
+                        Ok(stream) => Some(stream),
+                        Err(e @ AudioError::UnsupportedFormat(_)) => {
+                            warn!("{}", e);
+                            return Ok(());
                         }
+                        Err(e) => return Err(e.into()),
                     }
                 }
-                println!("[SS9K] 🎤 VAD thread exiting");
-            });
+            }
         }
-
-        stream
-    } else {
-        // Hotkey mode - use existing stream
-        let buffer_clone = audio_buffer.clone();
-        let recording_for_stream = recording_arc.clone();
-        let err_fn = |err| eprintln!("[SS9K] Stream error: {}", err);
-
-        match audio_config.sample_format() {
-            cpal::SampleFormat::I8 => build_stream::<i8>(&device, &audio_config.into(), buffer_clone, channels, recording_for_stream, err_fn)?,
-            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &audio_config.into(), buffer_clone, channels, recording_for_stream.clone(), err_fn)?,
-            cpal::SampleFormat::I32 => build_stream::<i32>(&device, &audio_config.into(), buffer_clone, channels, recording_for_stream.clone(), err_fn)?,
-            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &audio_config.into(), buffer_clone, channels, recording_for_stream.clone(), err_fn)?,
-            format => {
-                eprintln!("[SS9K] Unsupported sample format: {:?}", format);
-                return Ok(());
+        (_, AudioInput::Raw(source, format)) => {
+            // Hotkey mode - keep the source drained, buffer only while recording
+            let buffer = audio_buffer.clone();
+            let recording = recording_arc.clone();
+            spawn_raw(source, format, move |mono| {
+                if recording.load(Ordering::SeqCst) && let Ok(mut buf) = buffer.lock() {
+                    buf.extend_from_slice(mono);
+                }
+            })?;
+            None
+        }
+        (_, AudioInput::Device(device, audio_config)) => {
+            // Hotkey mode - use existing stream
+            let buffer_clone = audio_buffer.clone();
+            let recording_for_stream = recording_arc.clone();
+
+            match build_stream_for_format(&device, &audio_config, buffer_clone, channels, recording_for_stream, err_fn) {
+                Ok(stream) => Some(stream),
+                Err(e @ AudioError::UnsupportedFormat(_)) => {
+                    warn!("{}", e);
+                    return Ok(());
+                }
+                Err(e) => return Err(e.into()),
             }
         }
     };
 
-    stream.play()?;
+    if let Some(stream) = &stream {
+        stream.play()?;
+    }
     if is_vad_mode {
-        println!("[SS9K] Stream playing. Press {} to toggle VAD listening...", cfg.hotkey);
+        info!("Stream playing. Press {} to toggle VAD listening...", cfg.hotkey);
     } else {
-        println!("[SS9K] Stream playing. Press {} to record...", cfg.hotkey);
+        info!("Stream playing. Press {} to record...", cfg.hotkey);
     }
 
     // Spawn processor thread
     {
         let ctx = ctx.clone();
         let config = config.clone();
+        let config_path = config_path.clone();
         let wake_word_tx = wake_word_tx; // Move sender to processor thread
-        std::thread::spawn(move || {
-            println!("[SS9K] 🔧 Processor thread started");
-            for audio_msg in audio_rx {
+        let processor = std::thread::spawn(move || {
+            info!("🔧 Processor thread started");
+            // Paragraph mode: dictation waiting for a long enough pause
+            let mut paragraph = Paragraph::default();
+            let mut clip = 0u64; // Numbers the per-clip log span
+            let mut aborts_seen = ABORTS.load(Ordering::SeqCst);
+            loop {
+                // Aborted: the paragraph waiting to be typed goes too
+                let aborts_now = ABORTS.load(Ordering::SeqCst);
+                if aborts_now != aborts_seen {
+                    aborts_seen = aborts_now;
+                    if paragraph.take().is_some() {
+                        debug!("🗑️ Dropped the pending paragraph");
+                    }
+                }
+                // Everything queued is done: back to recording/listening/idle
+                if control::state() == State::Processing && metrics::queue_depth() <= 0 {
+                    control::publish(ControlEvent::State(control::resting_state()));
+                }
+                let gap = Duration::from_millis(config.load().vad_paragraph_gap_ms);
+                let (aborts, audio_msg) = match paragraph.time_left(gap) {
+                    Some(left) => match audio_rx.recv_timeout(left) {
+                        Ok(msg) => msg,
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            if ABORTS.load(Ordering::SeqCst) == aborts_seen {
+                                flush_paragraph(&mut paragraph, &config.load());
+                            }
+                            continue;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match audio_rx.recv() {
+                        Ok(msg) => msg,
+                        Err(_) => break,
+                    },
+                };
+                metrics::dequeued();
+                if aborts != ABORTS.load(Ordering::SeqCst) {
+                    debug!("🗑️ Dropped a clip queued before the abort");
+                    continue;
+                }
+                clip += 1;
+                let kind = match audio_msg {
+                    AudioMessage::WakeWordCheck(_) => "wake_word",
+                    _ => "speech",
+                };
+                let _span = info_span!("clip", id = clip, kind).entered();
+                // The focused app may pick a different profile; switch before reading the config
+                if kind == "speech" {
+                    apps::follow(&config, config_path.as_deref());
+                }
                 let cfg = config.load();
-                let verbose = cfg.verbose;
                 let timeout_secs = cfg.processing_timeout_secs;
 
                 let start_time = std::time::Instant::now();
 
                 // Handle wake word check separately (early return)
                 if let AudioMessage::WakeWordCheck(audio_data) = audio_msg {
-                    if verbose {
-                        println!("[SS9K] 🔍 Checking for wake word '{}'...", cfg.wake_word);
-                    }
+                    debug!("🔍 Checking for wake word '{}'...", cfg.wake_word);
 
                     // Quick transcription of the audio
                     match transcribe(&ctx, &audio_data, &cfg) {
@@ -746,12 +1118,10 @@ fn main() -> Result<()> {
                             let wake_lower = cfg.wake_word.to_lowercase();
                             let found = check_lower.contains(&wake_lower);
 
-                            if verbose {
-                                if found {
-                                    println!("[SS9K] ✅ Wake word '{}' found in: \"{}\"", cfg.wake_word, check_text.trim());
-                                } else {
-                                    println!("[SS9K] ❌ Wake word '{}' not found in: \"{}\"", cfg.wake_word, check_text.trim());
-                                }
+                            if found {
+                                debug!("✅ Wake word '{}' found in: \"{}\"", cfg.wake_word, privacy::redact(check_text.trim()));
+                            } else {
+                                debug!("❌ Wake word '{}' not found in: \"{}\"", cfg.wake_word, privacy::redact(check_text.trim()));
                             }
 
                             // Send result back to VAD thread
@@ -772,26 +1142,20 @@ fn main() -> Result<()> {
                 // Get resampled audio based on message type
                 let resampled = match audio_msg {
                     AudioMessage::NeedsResampling(audio_data) => {
-                        if verbose {
-                            println!("[SS9K] 🔄 Processing {} samples...", audio_data.len());
-                        }
+                        debug!("🔄 Processing {} samples...", audio_data.len());
                         match resample_audio(&audio_data, sample_rate, WHISPER_SAMPLE_RATE) {
                             Ok(r) => {
-                                if verbose {
-                                    println!("[SS9K] 🔄 Resampled to {} samples at 16kHz", r.len());
-                                }
+                                debug!("🔄 Resampled to {} samples at 16kHz", r.len());
                                 r
                             }
                             Err(e) => {
-                                log_error(&cfg.error_log, &format!("Resample error: {}", e));
+                                log_error(&cfg, &format!("Resample error: {}", e));
                                 continue;
                             }
                         }
                     }
                     AudioMessage::AlreadyResampled(audio_data) => {
-                        if verbose {
-                            println!("[SS9K] 🔄 Processing {} pre-resampled samples...", audio_data.len());
-                        }
+                        debug!("🔄 Processing {} pre-resampled samples...", audio_data.len());
                         audio_data
                     }
                     AudioMessage::WakeWordCheck(_) => {
@@ -799,8 +1163,10 @@ fn main() -> Result<()> {
                         unreachable!()
                     }
                 };
+                let resample_ms = start_time.elapsed().as_millis() as u64;
 
                 // Wake word check for VAD mode
+                let wake_word_start = std::time::Instant::now();
                 if is_vad_audio && !cfg.wake_word.is_empty() {
                     // Check first ~1.2s for wake word
                     let check_samples = (WHISPER_SAMPLE_RATE as f32 * 1.2) as usize;
@@ -810,9 +1176,7 @@ fn main() -> Result<()> {
                         &resampled[..]
                     };
 
-                    if verbose {
-                        println!("[SS9K] 🔍 Checking for wake word '{}'...", cfg.wake_word);
-                    }
+                    debug!("🔍 Checking for wake word '{}'...", cfg.wake_word);
 
                     // Quick transcription of first segment
                     match transcribe(&ctx, check_audio, &cfg) {
@@ -820,14 +1184,10 @@ fn main() -> Result<()> {
                             let check_lower = check_text.to_lowercase();
                             let wake_lower = cfg.wake_word.to_lowercase();
                             if !check_lower.contains(&wake_lower) {
-                                if verbose {
-                                    println!("[SS9K] ❌ Wake word '{}' not found in: \"{}\"", cfg.wake_word, check_text.trim());
-                                }
+                                debug!("❌ Wake word '{}' not found in: \"{}\"", cfg.wake_word, privacy::redact(check_text.trim()));
                                 continue; // Skip this utterance
                             }
-                            if verbose {
-                                println!("[SS9K] ✅ Wake word detected!");
-                            }
+                            debug!("✅ Wake word detected!");
                         }
                         Err(e) => {
                             log_warn(&cfg.error_log, &format!("Wake word check failed: {}", e));
@@ -836,7 +1196,10 @@ fn main() -> Result<()> {
                     }
                 }
 
+                let wake_word_ms = wake_word_start.elapsed().as_millis() as u64;
+
                 // Run transcription with optional timeout
+                let transcribe_start = std::time::Instant::now();
                 let transcribe_result = if timeout_secs > 0 {
                     // Spawn transcription in a thread and wait with timeout
                     let (tx, rx) = mpsc::channel();
@@ -854,11 +1217,14 @@ fn main() -> Result<()> {
                         Err(mpsc::RecvTimeoutError::Timeout) => {
                             let elapsed = start_time.elapsed().as_secs_f32();
                             log_warn(&cfg.error_log, &format!("TIMEOUT: Processing exceeded {}s limit (ran for {:.1}s). Tip: Try a smaller model (tiny/base) or increase processing_timeout_secs", timeout_secs, elapsed));
+                            play_cue(&cfg, Cue::Error);
+                            notify(&cfg, Kind::Error, &format!("Processing timed out after {:.1}s", elapsed));
+                            metrics::error();
                             COMMAND_MODE.store(false, Ordering::SeqCst); // Reset command mode
                             continue;
                         }
                         Err(mpsc::RecvTimeoutError::Disconnected) => {
-                            log_error(&cfg.error_log, "Transcription thread crashed");
+                            log_error(&cfg, "Transcription thread crashed");
                             COMMAND_MODE.store(false, Ordering::SeqCst);
                             continue;
                         }
@@ -868,10 +1234,31 @@ fn main() -> Result<()> {
                     transcribe(&ctx, &resampled, &cfg)
                 };
 
+                // Aborted while it was being transcribed
+                if aborts != ABORTS.load(Ordering::SeqCst) {
+                    debug!("🗑️ Dropped a transcription started before the abort");
+                    COMMAND_MODE.store(false, Ordering::SeqCst);
+                    continue;
+                }
+
                 let elapsed = start_time.elapsed().as_secs_f32();
+                let mut sample = stats::Sample {
+                    timestamp: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+                    model: cfg.model.clone(),
+                    audio_secs: resampled.len() as f32 / WHISPER_SAMPLE_RATE as f32,
+                    resample_ms,
+                    wake_word_ms,
+                    transcribe_ms: transcribe_start.elapsed().as_millis() as u64,
+                    output_ms: 0,
+                    words: 0,
+                    command: false,
+                };
 
                 match transcribe_result {
                     Ok(text) => {
+                        metrics::utterance();
+                        metrics::observe_transcribe(elapsed as f64);
+
                         // If command_hotkey was used, prepend the leader word
                         let text = if COMMAND_MODE.load(Ordering::SeqCst) {
                             COMMAND_MODE.store(false, Ordering::SeqCst); // Reset for next recording
@@ -897,33 +1284,167 @@ fn main() -> Result<()> {
                             text
                         };
 
-                        if verbose {
-                            println!("[SS9K] 📝 Transcription ({:.1}s): {}", elapsed, text);
-                        }
+                        // Script hook, then plugins: rewrite the text before anything else sees it
+                        #[cfg(feature = "scripting")]
+                        let text = match scripting::transform(&text) {
+                            Ok(text) => text,
+                            Err(e) => {
+                                log_error(&cfg, &e.to_string());
+                                text
+                            }
+                        };
+                        #[cfg(feature = "plugins")]
+                        let text = {
+                            let mut text = text;
+                            if let Err(e) = plugins::transform(&mut text) {
+                                log_error(&cfg, &e.to_string());
+                            }
+                            text
+                        };
+
+                        debug!("📝 Transcription ({:.1}s): {}", elapsed, privacy::redact(&text));
 
                         // Log to dictation log if configured
                         log_dictation(&cfg.dictation_log, &text);
 
+                        if !text.is_empty() {
+                            let action = parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases);
+                            hooks::post_transcribe(&cfg, &text, !matches!(action, Action::Type(_)));
+                        }
+
+                        // Script hook and plugins: they may handle the utterance themselves
+                        #[cfg(any(feature = "scripting", feature = "plugins"))]
+                        if !text.is_empty() {
+                            #[cfg(feature = "scripting")]
+                            let handled = scripting::command(&text).map_err(|e| e.to_string());
+                            #[cfg(not(feature = "scripting"))]
+                            let handled = Ok(false);
+                            #[cfg(feature = "plugins")]
+                            let handled = match handled {
+                                Ok(false) => plugins::command(&text, &cfg).map_err(|e| e.to_string()),
+                                other => other,
+                            };
+                            match handled {
+                                Ok(true) => {
+                                    sample.command = true;
+                                    stats::record(&sample);
+                                    history::record(&text, true);
+                                    play_cue(&cfg, Cue::Command);
+                                    control::publish(ControlEvent::Transcription(text.clone()));
+                                    continue;
+                                }
+                                Ok(false) => {}
+                                Err(e) => log_error(&cfg, &e),
+                            }
+                        }
+
+                        // Home Assistant output: dictation goes there, commands still run here
+                        let output_mode = output::mode(&cfg);
+                        if output_mode == "homeassistant" && !text.is_empty()
+                            && let Action::Type(aliased) = parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases)
+                        {
+                            forward_to_homeassistant(&aliased, &cfg);
+                            sample.command = true;
+                            stats::record(&sample);
+                            continue;
+                        }
+
+                        // Other output sinks: dictation goes there without the keyboard, commands still run here
+                        if output::is_sink_mode(&output_mode) && !text.is_empty()
+                            && let Action::Type(aliased) = parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases)
+                        {
+                            let output_start = std::time::Instant::now();
+                            match output::open(&output_mode, &cfg).and_then(|mut sink| sink.write(&aliased)) {
+                                Ok(()) => {
+                                    sample.output_ms = output_start.elapsed().as_millis() as u64;
+                                    sample.words = aliased.split_whitespace().count();
+                                    stats::record(&sample);
+                                    history::record(&text, false);
+                                    play_cue(&cfg, Cue::Command);
+                                    notify(&cfg, Kind::Result, &privacy::redact(&text));
+                                    control::publish(ControlEvent::Transcription(text.clone()));
+                                }
+                                Err(e) => log_error(&cfg, &e.to_string()),
+                            }
+                            continue;
+                        }
+
+                        // Paragraph mode: hold dictation, flush before anything else runs
+                        if is_vad_audio && cfg.vad_paragraph_mode && !text.is_empty() {
+                            match parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases) {
+                                Action::Type(aliased) => {
+                                    history::record(&text, false);
+                                    sample.words = aliased.split_whitespace().count();
+                                    stats::record(&sample);
+                                    paragraph.push(&aliased);
+                                    debug!("📄 Added to paragraph (types after {}ms pause)", cfg.vad_paragraph_gap_ms);
+                                    continue;
+                                }
+                                Action::Builtin(cmd) if cmd == "full stop" => {
+                                    paragraph.end_sentence();
+                                    flush_paragraph(&mut paragraph, &cfg);
+                                    continue;
+                                }
+                                _ => flush_paragraph(&mut paragraph, &cfg),
+                            }
+                        }
+
+                        // "[leader] profile <name>" swaps the whole config, so it's handled here
+                        if let Action::Builtin(cmd) = parse_action(&text, &cfg.leader, &cfg.leaderless_commands, &Phrases::of(&cfg), &cfg.aliases)
+                            && let Some(name) = cmd.strip_prefix("profile ")
+                        {
+                            match control::set_profile(name, &config, config_path.as_deref()) {
+                                Ok(()) => {
+                                    sample.command = true;
+                                    stats::record(&sample);
+                                    history::record(&text, true);
+                                    play_cue(&cfg, Cue::Command);
+                                    tts::configure(&cfg.tts_backend, &cfg.tts_voice);
+                                    tts::say(&format!("profile {}", name));
+                                }
+                                Err(e) => log_error(&cfg, &e),
+                            }
+                            continue;
+                        }
+
                         if !text.is_empty() {
                             // Update key repeat rate from config
                             set_key_repeat_ms(cfg.key_repeat_ms);
+                            tts::configure(&cfg.tts_backend, &cfg.tts_voice);
 
-                            match Enigo::new(&Settings::default()) {
+                            match Keys::new() {
                                 Ok(mut enigo) => {
-                                    if let Err(e) = execute_command(&mut enigo, &text, &cfg.leader, &cfg.commands, &cfg.aliases, &cfg.inserts, &cfg.wrappers) {
-                                        log_error(&cfg.error_log, &format!("Command/Type error: {}", e));
-                                    } else if cfg.audio_feedback {
-                                        beep_done();
+                                    let output_start = std::time::Instant::now();
+                                    match execute_command(&mut enigo, &text, &cfg) {
+                                        Ok(handled) => {
+                                            sample.output_ms = output_start.elapsed().as_millis() as u64;
+                                            sample.words = if handled { 0 } else { text.split_whitespace().count() };
+                                            sample.command = handled;
+                                            stats::record(&sample);
+                                            history::record(&text, handled);
+                                            play_cue(&cfg, Cue::Command);
+                                            notify(&cfg, Kind::Result, &privacy::redact(&text));
+                                            control::publish(ControlEvent::Transcription(text.clone()));
+                                        }
+                                        Err(e) => log_error(&cfg, &format!("Command/Type error: {}", e)),
                                     }
+                                    sync_mode_dnd(&cfg.dnd_modes);
                                 }
-                                Err(e) => log_error(&cfg.error_log, &format!("Enigo init error: {}", e)),
+                                Err(e) => log_error(&cfg, &format!("Keyboard init error: {}", e)),
                             }
                         }
                     }
-                    Err(e) => log_error(&cfg.error_log, &format!("Transcription error ({:.1}s): {}", elapsed, e)),
+                    Err(e) => log_error(&cfg, &format!("Transcription error ({:.1}s): {}", elapsed, e)),
                 }
             }
-            println!("[SS9K] 🔧 Processor thread exiting");
+            info!("🔧 Processor thread exiting");
+        });
+        // Without the processor nothing gets typed; exit (keys were released by the panic hook)
+        std::thread::spawn(move || {
+            if processor.join().is_err() {
+                error!("❌ Processor thread crashed, exiting");
+                std::process::exit(101);
+            }
         });
     }
 
@@ -934,12 +1455,13 @@ fn main() -> Result<()> {
     let send_audio = {
         let buffer = buffer_for_kb.clone();
         let tx = audio_tx.clone();
+        let config = config.clone();
         Arc::new(move || {
             let audio_data = if let Ok(buf) = buffer.lock() {
                 let duration = buf.len() as f32 / sample_rate as f32;
                 let callbacks = CALLBACK_COUNT.load(Ordering::SeqCst);
-                println!(
-                    "[SS9K] 🛑 Stopped. {} samples ({:.2}s), {} callbacks",
+                info!(
+                    "🛑 Stopped. {} samples ({:.2}s), {} callbacks",
                     buf.len(), duration, callbacks
                 );
                 buf.clone()
@@ -948,125 +1470,380 @@ fn main() -> Result<()> {
             };
 
             if !audio_data.is_empty() {
-                if let Err(e) = tx.send(AudioMessage::NeedsResampling(audio_data)) {
-                    eprintln!("[SS9K] ❌ Failed to queue audio: {}", e);
+                if let Err(e) = tx.send((ABORTS.load(Ordering::SeqCst), AudioMessage::NeedsResampling(audio_data))) {
+                    error!("❌ Failed to queue audio: {}", e);
                 } else {
-                    println!("[SS9K] 📤 Audio queued for processing");
+                    metrics::enqueued();
+                    info!("📤 Audio queued for processing");
+                    let cfg = config.load();
+                    play_cue(&cfg, Cue::Stop);
+                    notify(&cfg, Kind::State, "Processing...");
+                    control::publish(ControlEvent::State(State::Processing));
+                    return;
                 }
             }
+            control::publish(ControlEvent::State(control::resting_state()));
         })
     };
 
-    let send_audio_for_timeout = send_audio.clone();
-    let config_for_timeout = config_for_kb.clone();
-    let recording_for_timeout = recording_for_kb.clone();
-
-    let callback = move |event: Event| {
-        let cfg = config_for_kb.load();
-        let current_hotkey = parse_hotkey(&cfg.hotkey).unwrap_or(RdevKey::F12);
-        let command_hotkey = parse_hotkey(&cfg.command_hotkey); // None if empty/invalid
-        let is_toggle_mode = cfg.hotkey_mode == "toggle";
-        let toggle_timeout = cfg.toggle_timeout_secs;
-        let is_vad_mode = cfg.activation_mode == "vad";
-
-        // Check if this key is one of our hotkeys
-        let is_dictation_key = |key: RdevKey| key == current_hotkey;
-        let is_command_key = |key: RdevKey| command_hotkey.map_or(false, |ck| key == ck);
-        let is_our_hotkey = |key: RdevKey| is_dictation_key(key) || is_command_key(key);
-
-        match event.event_type {
-            EventType::KeyPress(key) if is_our_hotkey(key) => {
-                // VAD mode: hotkey toggles listening
-                if is_vad_mode {
-                    let was_listening = VAD_LISTENING.load(Ordering::SeqCst);
-                    VAD_LISTENING.store(!was_listening, Ordering::SeqCst);
-
-                    if was_listening {
-                        println!("[SS9K] 🔇 VAD listening stopped");
-                    } else {
-                        println!("[SS9K] 🎤 VAD listening started (press {} to stop)", cfg.hotkey);
-                    }
-                    return;
+    // Toggle recording (or VAD listening) - toggle-mode hotkeys and switch devices
+    // `stop_hint` names what stops it again, for the log line
+    let toggle_recording = {
+        let buffer = buffer_for_kb.clone();
+        let recording = recording_for_kb.clone();
+        let send_audio = send_audio.clone();
+        let config = config.clone();
+        Arc::new(move |command_mode: bool, stop_hint: &str| {
+            let cfg = config.load();
+
+            // VAD mode: toggles listening
+            if vad_enabled(&cfg) {
+                let was_listening = VAD_LISTENING.load(Ordering::SeqCst);
+                VAD_LISTENING.store(!was_listening, Ordering::SeqCst);
+
+                if was_listening {
+                    info!("🔇 VAD listening stopped");
+                    notify(&cfg, Kind::State, "Listening stopped");
+                    control::publish(ControlEvent::State(State::Idle));
+                } else {
+                    info!("🎤 VAD listening started (press {} to stop)", stop_hint);
+                    control::publish(ControlEvent::State(State::Listening));
                 }
+                return;
+            }
 
-                // Hotkey mode: original behavior
-                let using_command_key = is_command_key(key);
-                if is_toggle_mode {
-                    if recording_for_kb.load(Ordering::SeqCst) {
-                        recording_for_kb.store(false, Ordering::SeqCst);
+            if recording.load(Ordering::SeqCst) {
+                recording.store(false, Ordering::SeqCst);
+                RECORDING.store(false, Ordering::SeqCst);
+                send_audio();
+                return;
+            }
+
+            if let Ok(mut buf) = buffer.lock() {
+                buf.clear();
+            }
+            CALLBACK_COUNT.store(0, Ordering::SeqCst);
+
+            let session_id = RECORDING_SESSION.fetch_add(1, Ordering::SeqCst) + 1;
+            recording.store(true, Ordering::SeqCst);
+            RECORDING.store(true, Ordering::SeqCst);
+            COMMAND_MODE.store(command_mode, Ordering::SeqCst);
+
+            let toggle_timeout = cfg.toggle_timeout_secs;
+            play_cue(&cfg, Cue::Start);
+            hooks::pre_record(&cfg);
+            notify(&cfg, Kind::State, "Recording...");
+            control::publish(ControlEvent::State(State::Recording));
+            if toggle_timeout > 0 {
+                info!("🎙️ Recording... ({} to stop, or {}s timeout)", stop_hint, toggle_timeout);
+
+                let send_audio_timeout = send_audio.clone();
+                let config_timeout = config.clone();
+                let recording_timeout = recording.clone();
+                std::thread::spawn(move || {
+                    std::thread::sleep(Duration::from_secs(toggle_timeout));
+
+                    if RECORDING_SESSION.load(Ordering::SeqCst) == session_id
+                       && recording_timeout.load(Ordering::SeqCst) {
+                        let cfg = config_timeout.load();
+                        info!("⏱️ Timeout reached! (was recording with {})", cfg.hotkey);
+                        recording_timeout.store(false, Ordering::SeqCst);
                         RECORDING.store(false, Ordering::SeqCst);
-                        send_audio();
-                    } else {
-                        if let Ok(mut buf) = buffer_for_kb.lock() {
-                            buf.clear();
-                        }
-                        CALLBACK_COUNT.store(0, Ordering::SeqCst);
-
-                        let session_id = RECORDING_SESSION.fetch_add(1, Ordering::SeqCst) + 1;
-                        recording_for_kb.store(true, Ordering::SeqCst);
-                        RECORDING.store(true, Ordering::SeqCst);
-                        COMMAND_MODE.store(using_command_key, Ordering::SeqCst);
-
-                        let hotkey_name = if using_command_key { cfg.command_hotkey.clone() } else { cfg.hotkey.clone() };
-                        if cfg.audio_feedback { beep(); }
-                        if toggle_timeout > 0 {
-                            println!("[SS9K] 🎙️ Recording... ({} to stop, or {}s timeout)", hotkey_name, toggle_timeout);
-
-                            let send_audio_timeout = send_audio_for_timeout.clone();
-                            let config_timeout = config_for_timeout.clone();
-                            let recording_timeout = recording_for_timeout.clone();
-                            std::thread::spawn(move || {
-                                std::thread::sleep(Duration::from_secs(toggle_timeout));
-
-                                if RECORDING_SESSION.load(Ordering::SeqCst) == session_id
-                                   && recording_timeout.load(Ordering::SeqCst) {
-                                    let cfg = config_timeout.load();
-                                    println!("[SS9K] ⏱️ Timeout reached! (was recording with {})", cfg.hotkey);
-                                    recording_timeout.store(false, Ordering::SeqCst);
-                                    RECORDING.store(false, Ordering::SeqCst);
-                                    send_audio_timeout();
-                                }
-                            });
-                        } else {
-                            println!("[SS9K] 🎙️ Recording... (press {} again to stop)", hotkey_name);
-                        }
+                        send_audio_timeout();
                     }
+                });
+            } else {
+                info!("🎙️ Recording... (press {} again to stop)", stop_hint);
+            }
+        })
+    };
+
+    // Remote control (HTTP API, control socket): start/stop like the toggle hotkey
+    {
+        let (control_tx, control_rx) = mpsc::channel::<RecordRequest>();
+        control::set_handler(control_tx);
+        let toggle_recording = toggle_recording.clone();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            for request in control_rx {
+                let active = if vad_enabled(&config.load()) {
+                    VAD_LISTENING.load(Ordering::SeqCst)
                 } else {
-                    if !recording_for_kb.load(Ordering::SeqCst) {
-                        if let Ok(mut buf) = buffer_for_kb.lock() {
-                            buf.clear();
-                        }
-                        CALLBACK_COUNT.store(0, Ordering::SeqCst);
-                        recording_for_kb.store(true, Ordering::SeqCst);
-                        RECORDING.store(true, Ordering::SeqCst);
-                        COMMAND_MODE.store(using_command_key, Ordering::SeqCst);
-                        if cfg.audio_feedback { beep(); }
-                        if using_command_key {
-                            println!("[SS9K] 🎙️ Recording (command mode)...");
-                        } else {
-                            println!("[SS9K] 🎙️ Recording...");
-                        }
-                    }
+                    RECORDING.load(Ordering::SeqCst)
+                };
+                let toggle = match request {
+                    RecordRequest::Start => !active,
+                    RecordRequest::Stop => active,
+                    RecordRequest::Toggle => true,
+                };
+                if toggle {
+                    toggle_recording(false, "stop");
                 }
             }
-            EventType::KeyRelease(key) if is_our_hotkey(key) => {
-                // VAD mode doesn't use key release
-                if is_vad_mode {
+        });
+    }
+
+    // Switch access: gestures map to actions, "scan"/"select" step through a list
+    let switch_action = {
+        let toggle_recording = toggle_recording.clone();
+        let config = config.clone();
+        let scanner = Arc::new(Mutex::new(Scanner::default()));
+        Arc::new(move |gesture: Gesture| {
+            let cfg = config.load();
+            let action = match gesture {
+                Gesture::Short => &cfg.switch_short,
+                Gesture::Long => &cfg.switch_long,
+                Gesture::Double => &cfg.switch_double,
+            }
+            .trim()
+            .to_lowercase();
+            tts::configure(&cfg.tts_backend, &cfg.tts_voice);
+            debug!("🔘 Switch {:?} press: {}", gesture, action);
+
+            let action = match action.as_str() {
+                "" => return,
+                "scan" => {
+                    let Ok(mut scanner) = scanner.lock() else { return };
+                    if let Some(item) = scanner.next(&cfg.switch_scan_items) {
+                        info!("🔘 Scan: {}", item);
+                        tts::say(item);
+                    }
                     return;
                 }
+                "select" => {
+                    let Ok(scanner) = scanner.lock() else { return };
+                    match scanner.current(&cfg.switch_scan_items) {
+                        Some(item) if item != "scan" && item != "select" => item.to_lowercase(),
+                        _ => return,
+                    }
+                }
+                _ => action,
+            };
 
-                if !is_toggle_mode {
-                    if recording_for_kb.load(Ordering::SeqCst) {
-                        recording_for_kb.store(false, Ordering::SeqCst);
-                        RECORDING.store(false, Ordering::SeqCst);
-                        send_audio();
+            if action == "record" {
+                toggle_recording(false, "switch");
+                return;
+            }
+            // Anything else is a builtin command ("repeat", "release all", "enter"...)
+            set_key_repeat_ms(cfg.key_repeat_ms);
+            match Keys::new() {
+                Ok(mut enigo) => {
+                    if let Err(e) = execute_builtin_command(&mut enigo, &action) {
+                        log_error(&cfg, &format!("Switch action '{}' failed: {}", action, e));
                     }
                 }
+                Err(e) => log_error(&cfg, &format!("Keyboard init error: {}", e)),
+            }
+        })
+    };
+    let switch_gestures = {
+        let cfg = config.load();
+        Arc::new(Mutex::new(SwitchGestures::new(cfg.switch_long_press_ms, cfg.switch_double_press_ms)))
+    };
+    if let Some(input) = parse_switch(&config.load().switch_key) {
+        info!("🔘 Switch access enabled ({:?})", input);
+    }
+
+    // A hotkey going down: VAD mode toggles listening, toggle mode starts or
+    // stops recording, hold mode starts recording (`name` says what was pressed)
+    let hotkey_down = {
+        let toggle_recording = toggle_recording.clone();
+        let buffer = buffer_for_kb.clone();
+        let recording = recording_for_kb.clone();
+        let config = config.clone();
+        Arc::new(move |using_command_key: bool, name: &str| {
+            let cfg = config.load();
+            if vad_enabled(&cfg) || cfg.hotkey_mode == "toggle" {
+                toggle_recording(using_command_key, name);
+                return;
+            }
+
+            // Hold mode: record while the key (or button) is down
+            if !recording.load(Ordering::SeqCst) {
+                if let Ok(mut buf) = buffer.lock() {
+                    buf.clear();
+                }
+                CALLBACK_COUNT.store(0, Ordering::SeqCst);
+                recording.store(true, Ordering::SeqCst);
+                RECORDING.store(true, Ordering::SeqCst);
+                COMMAND_MODE.store(using_command_key, Ordering::SeqCst);
+                play_cue(&cfg, Cue::Start);
+                hooks::pre_record(&cfg);
+                notify(&cfg, Kind::State, if using_command_key { "Recording (command mode)..." } else { "Recording..." });
+                control::publish(ControlEvent::State(State::Recording));
+                if using_command_key {
+                    info!("🎙️ Recording (command mode)...");
+                } else {
+                    info!("🎙️ Recording...");
+                }
+            }
+        })
+    };
+
+    // A hotkey coming back up: stops a hold-mode recording
+    let hotkey_up = {
+        let recording = recording_for_kb.clone();
+        let send_audio = send_audio.clone();
+        let config = config.clone();
+        Arc::new(move || {
+            let cfg = config.load();
+            // VAD and toggle mode don't use key release
+            if vad_enabled(&cfg) || cfg.hotkey_mode == "toggle" {
+                return;
+            }
+            if recording.load(Ordering::SeqCst) {
+                recording.store(false, Ordering::SeqCst);
+                RECORDING.store(false, Ordering::SeqCst);
+                send_audio();
+            }
+        })
+    };
+
+    // abort_hotkey: drop the recording, the queue and the clip being
+    // transcribed, and let go of held keys
+    let abort = {
+        let buffer = buffer_for_kb.clone();
+        let recording = recording_for_kb.clone();
+        let config = config.clone();
+        Arc::new(move || {
+            ABORTS.fetch_add(1, Ordering::SeqCst);
+            recording.store(false, Ordering::SeqCst);
+            RECORDING.store(false, Ordering::SeqCst);
+            COMMAND_MODE.store(false, Ordering::SeqCst);
+            if let Ok(mut buf) = buffer.lock() {
+                buf.clear();
+            }
+            // Waits for the hold thread to stop, so not on the listener thread
+            std::thread::spawn(release_everything);
+            let cfg = config.load();
+            info!("🛑 Aborted: recording and queued audio thrown away");
+            play_cue(&cfg, Cue::Error);
+            notify(&cfg, Kind::State, "Aborted");
+            control::publish(ControlEvent::State(control::resting_state()));
+        })
+    };
+
+    // trigger_device: a pedal or clicker that acts as the hotkey
+    let trigger_device = config.load().trigger_device.clone();
+    if !trigger_device.is_empty() {
+        let (hotkey_down, hotkey_up) = (hotkey_down.clone(), hotkey_up.clone());
+        let on = move |down: bool| if down { hotkey_down(false, "the trigger") } else { hotkey_up() };
+        if let Err(e) = input::watch_trigger(&trigger_device, on) {
+            warn!("⚠️ {}", e);
+        }
+    }
+
+    // gamepad_button: a controller button that acts as the hotkey
+    #[cfg(feature = "gamepad")]
+    if !config.load().gamepad_button.is_empty() {
+        let (hotkey_down, hotkey_up) = (hotkey_down.clone(), hotkey_up.clone());
+        let on = move |down: bool| if down { hotkey_down(false, "the gamepad button") } else { hotkey_up() };
+        ss9k::gamepad::start(config.clone(), on);
+    }
+    #[cfg(not(feature = "gamepad"))]
+    if !config.load().gamepad_button.is_empty() {
+        warn!("⚠️ Built without the 'gamepad' feature, ignoring gamepad_button");
+    }
+
+    // midi_device: notes and controllers mapped in [midi]; the connection
+    // lives until the listener returns
+    #[cfg(feature = "midi")]
+    let _midi = if config.load().midi_device.is_empty() {
+        None
+    } else {
+        use ss9k::hotkeys::MidiAction;
+        let (hotkey_down, hotkey_up, abort) = (hotkey_down.clone(), hotkey_up.clone(), abort.clone());
+        let modes = config.clone();
+        let on = move |action: &MidiAction, down: bool| match (action, down) {
+            (MidiAction::Record, true) => hotkey_down(false, "the MIDI pedal"),
+            (MidiAction::Command, true) => hotkey_down(true, "the MIDI pedal"),
+            (MidiAction::Record | MidiAction::Command, false) => hotkey_up(),
+            (MidiAction::Abort, true) => abort(),
+            (MidiAction::Mode(name), true) => match control::set_mode(name, &modes.load()) {
+                Ok(mode) => info!("🎹 Mode set to {:?}", mode),
+                Err(e) => warn!("⚠️ MIDI: {}", e),
+            },
+            (MidiAction::Abort | MidiAction::Mode(_), false) => {}
+        };
+        match ss9k::midi::start(config.clone(), on) {
+            Ok(connection) => Some(connection),
+            Err(e) => {
+                warn!("⚠️ {}", e);
+                None
+            }
+        }
+    };
+    #[cfg(not(feature = "midi"))]
+    if !config.load().midi_device.is_empty() || !config.load().midi.is_empty() {
+        warn!("⚠️ Built without the 'midi' feature, ignoring midi_device and [midi]");
+    }
+
+    let mut modifiers = HeldModifiers::default();
+    let callback = move |event: Event| {
+        let cfg = config_for_kb.load();
+        let current_hotkey = parse_hotkey(&cfg.hotkey).unwrap_or(Hotkey::new(Input::Key(RdevKey::F12)));
+        let command_hotkey = parse_hotkey(&cfg.command_hotkey); // None if empty/invalid
+        let abort_hotkey = parse_hotkey(&cfg.abort_hotkey);
+        let switch_input = parse_switch(&cfg.switch_key);
+        modifiers.update(&event.event_type);
+        let held = modifiers.current();
+
+        // Keys and mouse buttons alike; moves and the wheel are never ours
+        let (input, down) = match event.event_type {
+            EventType::KeyPress(key) => (Input::Key(key), true),
+            EventType::KeyRelease(key) => (Input::Key(key), false),
+            EventType::ButtonPress(button) => (Input::Button(button), true),
+            EventType::ButtonRelease(button) => (Input::Button(button), false),
+            _ => return,
+        };
+
+        // Check if this is one of our hotkeys (presses need exactly the
+        // combo's modifiers down; releases only the key, whatever was let go first)
+        let is_dictation_key = |input: Input| current_hotkey.pressed(input, held);
+        let is_command_key = |input: Input| command_hotkey.is_some_and(|ck| ck.pressed(input, held));
+        let is_our_hotkey = |input: Input| is_dictation_key(input) || is_command_key(input);
+        let is_our_key = |input: Input| input == current_hotkey.input || command_hotkey.is_some_and(|ck| input == ck.input);
+
+        match (input, down) {
+            // Switch device (before hotkeys, in case it sends the same key)
+            (input, true) if switch_input == Some(input) => {
+                if let Ok(mut gestures) = switch_gestures.lock() {
+                    gestures.press(std::time::Instant::now());
+                }
+            }
+            (input, false) if switch_input == Some(input) => {
+                switch_released(&switch_gestures, &switch_action);
+            }
+            (input, true) if abort_hotkey.is_some_and(|h| h.pressed(input, held)) => abort(),
+            (input, true) if is_our_hotkey(input) => {
+                let using_command_key = is_command_key(input);
+                hotkey_down(using_command_key, if using_command_key { &cfg.command_hotkey } else { &cfg.hotkey });
             }
+            (input, false) if is_our_key(input) => hotkey_up(),
             _ => {}
         }
     };
 
-    listen(callback).map_err(|e| anyhow::anyhow!("Listen error: {:?}", e))?;
+    if let Err(e) = daemon::notify("READY=1") {
+        warn!("⚠️ sd_notify failed: {}", e);
+    }
+    daemon::start_watchdog();
+
+    // rdev goes through X11 (not there on Wayland); evdev reads /dev/input itself
+    let cfg = config.load();
+    let listened = match cfg.hotkey_backend.as_str() {
+        "evdev" => input::listen(&cfg.hotkey_device, callback).map_err(|e| e.to_string()),
+        _ => listen(callback).map_err(|e| format!("{:?}", e)),
+    };
+    if let Err(e) = listened {
+        if !daemon {
+            anyhow::bail!("Listen error: {}", e);
+        }
+        // Services often can't grab global hotkeys (Wayland); remote control still works
+        warn!("⚠️ Hotkeys unavailable ({}), use ss9k ctl, signals or the control API", e);
+        loop {
+            std::thread::park();
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,90 @@
+//! Local performance metrics for SS9K
+//!
+//! This module handles:
+//! - Counters for utterances and errors
+//! - A histogram of transcription time
+//! - The depth of the audio queue waiting on the processor
+//! - Rendering all of it in Prometheus text format
+//!
+//! Nothing here is ever sent anywhere. The numbers only leave the process
+//! when something on this machine scrapes `/metrics` (http feature).
+
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+/// Upper bounds (seconds) of the transcribe_seconds histogram buckets
+const TRANSCRIBE_BUCKETS: [f64; 8] = [0.25, 0.5, 1.0, 2.0, 5.0, 10.0, 30.0, 60.0];
+
+static UTTERANCES: AtomicU64 = AtomicU64::new(0);
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static QUEUE_DEPTH: AtomicI64 = AtomicI64::new(0);
+static TRANSCRIBE_COUNT: AtomicU64 = AtomicU64::new(0);
+static TRANSCRIBE_SUM_MICROS: AtomicU64 = AtomicU64::new(0);
+static TRANSCRIBE_BUCKET_COUNTS: [AtomicU64; 8] = [const { AtomicU64::new(0) }; 8];
+
+/// Count a transcribed utterance
+pub fn utterance() {
+    UTTERANCES.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an error (transcription failure, timeout, command error...)
+pub fn error() {
+    ERRORS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Audio was queued for the processor
+pub fn enqueued() {
+    QUEUE_DEPTH.fetch_add(1, Ordering::Relaxed);
+}
+
+/// The processor picked up queued audio
+pub fn dequeued() {
+    QUEUE_DEPTH.fetch_sub(1, Ordering::Relaxed);
+}
+
+/// Audio clips waiting for the processor
+pub fn queue_depth() -> i64 {
+    QUEUE_DEPTH.load(Ordering::Relaxed)
+}
+
+/// Record how long a transcription took
+pub fn observe_transcribe(seconds: f64) {
+    TRANSCRIBE_COUNT.fetch_add(1, Ordering::Relaxed);
+    TRANSCRIBE_SUM_MICROS.fetch_add((seconds * 1_000_000.0) as u64, Ordering::Relaxed);
+    if let Some(i) = TRANSCRIBE_BUCKETS.iter().position(|&le| seconds <= le) {
+        TRANSCRIBE_BUCKET_COUNTS[i].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// All metrics in Prometheus text exposition format
+pub fn render() -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP ss9k_utterances_total Utterances transcribed.");
+    let _ = writeln!(out, "# TYPE ss9k_utterances_total counter");
+    let _ = writeln!(out, "ss9k_utterances_total {}", UTTERANCES.load(Ordering::Relaxed));
+
+    let _ = writeln!(out, "# HELP ss9k_errors_total Errors (transcription, timeouts, commands).");
+    let _ = writeln!(out, "# TYPE ss9k_errors_total counter");
+    let _ = writeln!(out, "ss9k_errors_total {}", ERRORS.load(Ordering::Relaxed));
+
+    let _ = writeln!(out, "# HELP ss9k_queue_depth Audio clips waiting to be transcribed.");
+    let _ = writeln!(out, "# TYPE ss9k_queue_depth gauge");
+    let _ = writeln!(out, "ss9k_queue_depth {}", QUEUE_DEPTH.load(Ordering::Relaxed).max(0));
+
+    let _ = writeln!(out, "# HELP ss9k_transcribe_seconds Time spent transcribing each utterance.");
+    let _ = writeln!(out, "# TYPE ss9k_transcribe_seconds histogram");
+    // Prometheus buckets are cumulative
+    let mut cumulative = 0;
+    for (le, count) in TRANSCRIBE_BUCKETS.iter().zip(&TRANSCRIBE_BUCKET_COUNTS) {
+        cumulative += count.load(Ordering::Relaxed);
+        let _ = writeln!(out, "ss9k_transcribe_seconds_bucket{{le=\"{}\"}} {}", le, cumulative);
+    }
+    let total = TRANSCRIBE_COUNT.load(Ordering::Relaxed);
+    let _ = writeln!(out, "ss9k_transcribe_seconds_bucket{{le=\"+Inf\"}} {}", total);
+    let sum = TRANSCRIBE_SUM_MICROS.load(Ordering::Relaxed) as f64 / 1_000_000.0;
+    let _ = writeln!(out, "ss9k_transcribe_seconds_sum {}", sum);
+    let _ = writeln!(out, "ss9k_transcribe_seconds_count {}", total);
+
+    out
+}
@@ -0,0 +1,88 @@
+//! MIDI triggers for SS9K (midi feature)
+//!
+//! This module handles:
+//! - Connecting to the MIDI input port `midi_device` names (part of the name,
+//!   ignoring case), so a pedalboard or pad controller can drive SS9K
+//! - Turning note and controller messages into the actions `[midi]` maps
+//!   them to: recording like the hotkey or the command hotkey, aborting, and
+//!   switching case mode
+//!
+//! A controller (CC) counts as pressed at 64 and over, like a sustain pedal;
+//! faders and expression pedals send a stream of values, so only crossing
+//! 64 is a press or a release.
+//!
+//! `[midi]` is read on every message, so mappings hot-reload; a different
+//! `midi_device` needs a restart. Every message is logged at debug level,
+//! which is the easy way to find a pedal's note or CC number.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use midir::{Ignore, MidiInput, MidiInputConnection};
+use tracing::{debug, info};
+
+use crate::error::MidiError;
+use crate::hotkeys::{parse_midi_action, parse_midi_message, MidiAction, MidiMessage};
+use crate::Config;
+
+/// Connect to `midi_device` and call `on(action, down)` for each mapped
+/// message; SS9K listens for as long as the connection is kept
+pub fn start(
+    config: Arc<ArcSwap<Config>>,
+    on: impl Fn(&MidiAction, bool) + Send + 'static,
+) -> Result<MidiInputConnection<Decoder>, MidiError> {
+    let wanted = config.load().midi_device.trim().to_lowercase();
+    let mut input = MidiInput::new("ss9k")?;
+    input.ignore(Ignore::All);
+    let ports = input.ports();
+    let (port, name) = ports
+        .iter()
+        .filter_map(|port| input.port_name(port).ok().map(|name| (port, name)))
+        .find(|(_, name)| name.to_lowercase().contains(&wanted))
+        .ok_or_else(|| MidiError::NoPort(wanted.clone()))?;
+    info!("🎹 MIDI input: {}", name);
+
+    let handle = move |_: u64, bytes: &[u8], decoder: &mut Decoder| {
+        let Some((message, down)) = decoder.decode(bytes) else {
+            return;
+        };
+        debug!("🎹 {:?} {}", message, if down { "down" } else { "up" });
+        let config = config.load();
+        for (key, action) in &config.midi {
+            if parse_midi_message(key) == Some(message)
+                && let Some(action) = parse_midi_action(action)
+            {
+                on(&action, down);
+            }
+        }
+    };
+    input.connect(port, "ss9k-trigger", handle, Decoder::default()).map_err(|e| MidiError::Connect(e.to_string()))
+}
+
+/// Turns messages into presses and releases, remembering where each
+/// controller was so a sweep through its values counts once
+#[derive(Debug, Default)]
+pub struct Decoder {
+    controllers: HashMap<(u8, u8), bool>, // (channel, CC number) -> down
+}
+
+impl Decoder {
+    /// A note or controller message, and whether it means down or up; None
+    /// for other messages and for controller values on the same side of 64
+    pub fn decode(&mut self, bytes: &[u8]) -> Option<(MidiMessage, bool)> {
+        let [status, number, value, ..] = *bytes else {
+            return None;
+        };
+        match status & 0xF0 {
+            0x90 => Some((MidiMessage::Note(number), value > 0)), // Velocity 0 = note-off
+            0x80 => Some((MidiMessage::Note(number), false)),
+            0xB0 => {
+                let down = value >= 64;
+                let was = self.controllers.insert((status & 0x0F, number), down).unwrap_or(false);
+                (was != down).then_some((MidiMessage::Cc(number), down))
+            }
+            _ => None,
+        }
+    }
+}
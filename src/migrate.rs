@@ -0,0 +1,160 @@
+//! Config versioning and migrations for SS9K
+//!
+//! This module handles:
+//! - `config_version`: which shape a config file was written for
+//! - Upgrading older shapes in memory, one version at a time
+//! - Describing every change as diff lines so nothing changes silently
+//! - Flagging keys SS9K doesn't know (typos, or renamed in a newer release)
+//!
+//! Migrations run on the parsed TOML before it's deserialized, so a renamed
+//! key keeps its value instead of quietly falling back to the default. The
+//! file itself is never rewritten (that would lose comments); the printed
+//! diff says what to change.
+//!
+//! Versions:
+//! - 1: no `config_version` key (every release before versioning)
+//! - 2: `config_version` added; custom commands no longer start with the leader
+
+use toml::{Table, Value};
+use tracing::{info, warn};
+
+use crate::Config;
+
+/// Config shape this build writes and expects
+pub const CONFIG_VERSION: u32 = 2;
+
+/// Keys renamed between versions: (version that renamed it, old key, new key)
+/// Applied when migrating from an older version, keeping the user's value
+const RENAMED_KEYS: &[(u32, &str, &str)] = &[];
+
+/// What happened while migrating a config
+#[derive(Debug, Default, PartialEq)]
+pub struct MigrationReport {
+    /// Version the file was written for
+    pub from_version: u32,
+    /// Diff lines: "- old" / "+ new"
+    pub changes: Vec<String>,
+    /// Problems that migration can't fix (unknown keys, newer version)
+    pub warnings: Vec<String>,
+}
+
+impl MigrationReport {
+    /// Nothing to tell the user
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.warnings.is_empty()
+    }
+
+    /// Print the report (no-op if empty)
+    pub fn print(&self, path: &std::path::Path) {
+        if self.is_empty() {
+            return;
+        }
+        if !self.changes.is_empty() {
+            info!(
+                "🔧 {:?} is config version {}, migrated to {}:",
+                path, self.from_version, CONFIG_VERSION
+            );
+            for line in &self.changes {
+                info!("   {}", line);
+            }
+            info!("🔧 Apply these changes to the file to stop seeing this");
+        }
+        for warning in &self.warnings {
+            warn!("⚠️ {}", warning);
+        }
+    }
+}
+
+/// Upgrade a parsed config table to CONFIG_VERSION in place
+pub fn migrate(table: &mut Table) -> MigrationReport {
+    let from_version = match table.get("config_version") {
+        Some(Value::Integer(v)) => u32::try_from(*v).unwrap_or(1),
+        _ => 1,
+    };
+    let mut report = MigrationReport { from_version, ..Default::default() };
+
+    if from_version > CONFIG_VERSION {
+        report.warnings.push(format!(
+            "Config version {} is newer than this build understands ({}); some settings may be ignored",
+            from_version, CONFIG_VERSION
+        ));
+    }
+
+    for (version, old, new) in RENAMED_KEYS {
+        if from_version < *version {
+            rename_key(table, old, new, &mut report.changes);
+        }
+    }
+    if from_version < 2 {
+        strip_leader_from_commands(table, &mut report.changes);
+    }
+    if from_version < CONFIG_VERSION {
+        table.insert("config_version".to_string(), Value::Integer(CONFIG_VERSION as i64));
+        report.changes.push(format!("+ config_version = {}", CONFIG_VERSION));
+    }
+
+    report.warnings.extend(unknown_keys(table).into_iter().map(|key| {
+        format!("Unknown config key '{}' (ignored - typo, or renamed in a newer release?)", key)
+    }));
+    report
+}
+
+/// Move `old` to `new`, unless the file already sets `new`
+fn rename_key(table: &mut Table, old: &str, new: &str, changes: &mut Vec<String>) {
+    let Some(value) = table.remove(old) else {
+        return;
+    };
+    changes.push(format!("- {} = {}", old, value));
+    if table.contains_key(new) {
+        changes.push(format!("  ({} already set, keeping it)", new));
+    } else {
+        changes.push(format!("+ {} = {}", new, value));
+        table.insert(new.to_string(), value);
+    }
+}
+
+/// v1 -> v2: before the leader word, custom commands were sometimes written
+/// with it ("command open browser"). The leader is now checked first, so those
+/// phrases can never match; drop the leader so saying the phrase works again
+fn strip_leader_from_commands(table: &mut Table, changes: &mut Vec<String>) {
+    let leader = match table.get("leader") {
+        Some(Value::String(l)) => l.to_lowercase(),
+        _ => Config::default().leader,
+    };
+    let prefix = format!("{} ", leader);
+    let Some(Value::Table(commands)) = table.get_mut("commands") else {
+        return;
+    };
+
+    let prefixed: Vec<String> = commands
+        .keys()
+        .filter(|phrase| phrase.get(..prefix.len()).is_some_and(|head| head.to_lowercase() == prefix))
+        .cloned()
+        .collect();
+    for phrase in prefixed {
+        let stripped = phrase[prefix.len()..].trim().to_string();
+        if stripped.is_empty() {
+            continue;
+        }
+        let Some(cmd) = commands.remove(&phrase) else {
+            continue;
+        };
+        changes.push(format!("- [commands] \"{}\" = {}", phrase, cmd));
+        if commands.contains_key(&stripped) {
+            changes.push(format!("  ([commands] \"{}\" already set, keeping it)", stripped));
+        } else {
+            changes.push(format!("+ [commands] \"{}\" = {}", stripped, cmd));
+            commands.insert(stripped, cmd);
+        }
+    }
+}
+
+/// Top-level keys that aren't Config fields
+pub(crate) fn unknown_keys(table: &Table) -> Vec<String> {
+    let Ok(Value::Table(known)) = Value::try_from(Config::default()) else {
+        return Vec::new();
+    };
+    let mut unknown: Vec<String> = table.keys().filter(|k| !known.contains_key(*k)).cloned().collect();
+    unknown.sort();
+    unknown
+}
@@ -3,15 +3,18 @@
 //! Handles downloading Whisper models from HuggingFace and
 //! finding model files across multiple locations.
 
-use anyhow::Result;
 use indicatif::{ProgressBar, ProgressStyle};
 use std::fs::{self, File};
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::info;
+use whisper_rs::{WhisperContext, WhisperContextParameters};
+
+use crate::error::ModelError;
 
 /// Download a model from HuggingFace with progress bar
-pub fn download_model(url: &str, dest: &PathBuf) -> Result<()> {
-    println!("[SS9K] Downloading model from: {}", url);
+pub fn download_model(url: &str, dest: &PathBuf) -> Result<(), ModelError> {
+    info!("Downloading model from: {}", url);
 
     if let Some(parent) = dest.parent() {
         fs::create_dir_all(parent)?;
@@ -20,7 +23,7 @@ pub fn download_model(url: &str, dest: &PathBuf) -> Result<()> {
     let response = reqwest::blocking::get(url)?;
 
     if !response.status().is_success() {
-        anyhow::bail!("Download failed: HTTP {}", response.status());
+        return Err(ModelError::HttpStatus(response.status()));
     }
 
     let total_size = response.content_length().unwrap_or(0);
@@ -43,11 +46,19 @@ pub fn download_model(url: &str, dest: &PathBuf) -> Result<()> {
     }
 
     pb.finish_with_message("Download complete!");
-    println!("[SS9K] Model saved to: {:?}", dest);
+    info!("Model saved to: {:?}", dest);
 
     Ok(())
 }
 
+/// Load a whisper model from disk
+pub fn load_model(path: &Path) -> Result<WhisperContext, ModelError> {
+    let path_str = path.to_str().ok_or_else(|| {
+        ModelError::Io(std::io::Error::new(std::io::ErrorKind::InvalidInput, "model path is not valid UTF-8"))
+    })?;
+    WhisperContext::new_with_params(path_str, WhisperContextParameters::default()).map_err(ModelError::Load)
+}
+
 /// Get the preferred model install location
 pub fn get_model_install_path(model_name: &str) -> PathBuf {
     dirs::data_dir()
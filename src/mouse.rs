@@ -0,0 +1,295 @@
+//! Voice mouse control for SS9K
+//!
+//! "command mouse up 200" moves the pointer 200 pixels, "command click",
+//! "right click" and "double click" press buttons, and "command scroll down
+//! three" turns the wheel three notches. A move or scroll without an amount
+//! goes a default step. The pointer goes through the keyboard backend, so it
+//! works with `keyboard_backend = "enigo"` and `"ydotool"`.
+//!
+//! "command grid" splits the screen into a 3×3 grid numbered like a phone
+//! keypad and puts the pointer in the middle. Saying a number (no leader
+//! needed) zooms into that cell and moves the pointer to its middle, so
+//! "five three" lands in the top right of the middle cell; "click" (or
+//! "right click", "double click") clicks there and closes the grid, and
+//! "cancel" closes it without clicking. On X11 the grid's lines and numbers
+//! are drawn over the screen (see `overlay`); elsewhere the pointer shows
+//! where you are, and a notification shows the numbering.
+
+use std::sync::Mutex;
+
+use enigo::{Axis, Button, Coordinate, Direction, InputResult, Mouse};
+
+use crate::commands::{parse_count, parse_number_word};
+use crate::keyboard::Keys;
+use crate::overlay;
+
+/// Pixels "mouse left" moves without a distance
+pub const STEP: i32 = 50;
+
+/// Wheel notches "scroll down" turns without a count
+pub const SCROLL_STEP: i32 = 3;
+
+/// Something to do with the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pointer {
+    /// Move by this many pixels (right and down are positive)
+    Move { dx: i32, dy: i32 },
+    /// Press a button this many times in a row
+    Click { button: Button, times: usize },
+    /// Turn the wheel (down and right are positive)
+    Scroll { length: i32, axis: Axis },
+}
+
+/// A pointer command: "mouse up 200", "move mouse left", "click", "right
+/// click", "double click", "scroll down three"; None if it isn't one
+pub fn parse(cmd: &str) -> Option<Pointer> {
+    let click = |button, times| Some(Pointer::Click { button, times });
+    match cmd.trim() {
+        "click" | "left click" => return click(Button::Left, 1),
+        "right click" => return click(Button::Right, 1),
+        "middle click" => return click(Button::Middle, 1),
+        "double click" => return click(Button::Left, 2),
+        "triple click" => return click(Button::Left, 3),
+        _ => {}
+    }
+
+    if let Some(rest) = cmd.strip_prefix("mouse ").or_else(|| cmd.strip_prefix("move mouse ")) {
+        let (direction, amount) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+        let n = amount_or(amount, STEP)?;
+        let (dx, dy) = match direction {
+            "up" => (0, -n),
+            "down" => (0, n),
+            "left" => (-n, 0),
+            "right" => (n, 0),
+            _ => return None,
+        };
+        return Some(Pointer::Move { dx, dy });
+    }
+
+    let rest = cmd.strip_prefix("scroll ")?;
+    let (direction, amount) = rest.trim().split_once(' ').unwrap_or((rest.trim(), ""));
+    let n = amount_or(amount, SCROLL_STEP)?;
+    let (length, axis) = match direction {
+        "up" => (-n, Axis::Vertical),
+        "down" => (n, Axis::Vertical),
+        "left" => (-n, Axis::Horizontal),
+        "right" => (n, Axis::Horizontal),
+        _ => return None,
+    };
+    Some(Pointer::Scroll { length, axis })
+}
+
+/// "200", "two hundred pixels", "three"; `default` when nothing was said
+fn amount_or(amount: &str, default: i32) -> Option<i32> {
+    let amount = amount.trim();
+    let amount = ["pixels", "pixel", "px", "times", "notches", "clicks"]
+        .iter()
+        .find_map(|unit| amount.strip_suffix(unit))
+        .unwrap_or(amount)
+        .trim();
+    if amount.is_empty() {
+        return Some(default);
+    }
+    parse_count(amount).and_then(|n| i32::try_from(n).ok())
+}
+
+/// Carry out a pointer command
+pub fn run(keys: &mut Keys, pointer: Pointer) -> InputResult<()> {
+    match pointer {
+        Pointer::Move { dx, dy } => keys.move_mouse(dx, dy, Coordinate::Rel),
+        Pointer::Click { button, times } => {
+            for _ in 0..times {
+                keys.button(button, Direction::Click)?;
+            }
+            Ok(())
+        }
+        Pointer::Scroll { length, axis } => keys.scroll(length, axis),
+    }
+}
+
+/// A part of the screen the grid covers, in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Grid {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Grid {
+    /// Cell 1-9 of the 3×3 grid over this area, numbered left to right, top
+    /// to bottom
+    pub fn cell(&self, n: u8) -> Option<Grid> {
+        if !(1..=9).contains(&n) {
+            return None;
+        }
+        let (row, col) = (i32::from(n - 1) / 3, i32::from(n - 1) % 3);
+        let left = self.x + self.width * col / 3;
+        let top = self.y + self.height * row / 3;
+        Some(Grid {
+            x: left,
+            y: top,
+            width: self.x + self.width * (col + 1) / 3 - left,
+            height: self.y + self.height * (row + 1) / 3 - top,
+        })
+    }
+
+    /// The middle of the area, where the pointer goes
+    pub fn center(&self) -> (i32, i32) {
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// What the overlay draws, as filled rectangles: the lines between and
+    /// around the cells, and each cell's number in seven-segment style
+    pub fn overlay(&self) -> Vec<Grid> {
+        const LINE: i32 = 2;
+        let mut rects = Vec::new();
+        for k in 0..=3 {
+            let x = (self.x + self.width * k / 3 - LINE / 2).clamp(self.x, self.x + self.width - LINE.min(self.width));
+            let y = (self.y + self.height * k / 3 - LINE / 2).clamp(self.y, self.y + self.height - LINE.min(self.height));
+            rects.push(Grid { x, y: self.y, width: LINE.min(self.width), height: self.height });
+            rects.push(Grid { x: self.x, y, width: self.width, height: LINE.min(self.height) });
+        }
+        for n in 1..=9 {
+            if let Some(cell) = self.cell(n) {
+                rects.extend(digit(n, cell));
+            }
+        }
+        rects
+    }
+}
+
+/// Segments a-g (top, top right, bottom right, bottom, bottom left, top
+/// left, middle) lit for each digit
+const SEGMENTS: [[bool; 7]; 10] = [
+    [true, true, true, true, true, true, false],
+    [false, true, true, false, false, false, false],
+    [true, true, false, true, true, false, true],
+    [true, true, true, true, false, false, true],
+    [false, true, true, false, false, true, true],
+    [true, false, true, true, false, true, true],
+    [true, false, true, true, true, true, true],
+    [true, true, true, false, false, false, false],
+    [true, true, true, true, true, true, true],
+    [true, true, true, true, false, true, true],
+];
+
+/// `n` drawn in the middle of `cell`, a quarter of its size; nothing if
+/// the cell is too small to read it
+fn digit(n: u8, cell: Grid) -> Vec<Grid> {
+    let height = (cell.width.min(cell.height) / 4).min(80);
+    if height < 10 {
+        return Vec::new();
+    }
+    let (width, thick) = (height / 2, (height / 8).max(2));
+    let (cx, cy) = cell.center();
+    let (left, top) = (cx - width / 2, cy - height / 2);
+    let (right, middle, bottom) = (left + width - thick, top + (height - thick) / 2, top + height - thick);
+    let half = height / 2;
+    let segments = [
+        Grid { x: left, y: top, width, height: thick },
+        Grid { x: right, y: top, width: thick, height: half },
+        Grid { x: right, y: top + half, width: thick, height: height - half },
+        Grid { x: left, y: bottom, width, height: thick },
+        Grid { x: left, y: top + half, width: thick, height: height - half },
+        Grid { x: left, y: top, width: thick, height: half },
+        Grid { x: left, y: middle, width, height: thick },
+    ];
+    SEGMENTS[usize::from(n % 10)]
+        .iter()
+        .zip(segments)
+        .filter(|(lit, _)| **lit)
+        .map(|(_, segment)| segment)
+        .collect()
+}
+
+/// An utterance while the grid is up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridStep {
+    /// Zoom into these cells, one after the other ("five three")
+    Zoom(Vec<u8>),
+    /// Click where the pointer is and close the grid
+    Click(Pointer),
+    /// Close the grid without clicking
+    Close,
+}
+
+/// The area the grid covers, while it's up
+static GRID: Mutex<Option<Grid>> = Mutex::new(None);
+
+/// Whether the grid is up, so numbers and "click" go to it
+pub fn grid_active() -> bool {
+    GRID.lock().is_ok_and(|grid| grid.is_some())
+}
+
+/// What an utterance does to the grid: "five", "5 3", "click", "cancel";
+/// None for anything else, which is handled as usual
+pub fn grid_step(text: &str) -> Option<GridStep> {
+    let text = text.trim();
+    if matches!(text, "cancel" | "close" | "close grid" | "grid off" | "escape" | "stop") {
+        return Some(GridStep::Close);
+    }
+    if let Some(click @ Pointer::Click { .. }) = parse(text) {
+        return Some(GridStep::Click(click));
+    }
+    // "53" comes through as one word, "five three" as two
+    let mut cells = Vec::new();
+    for word in text.split_whitespace() {
+        if word.chars().all(|c| c.is_ascii_digit()) {
+            cells.extend(word.bytes().map(|b| b - b'0'));
+        } else {
+            cells.push(u8::try_from(parse_number_word(word)?).ok()?);
+        }
+    }
+    (!cells.is_empty() && cells.iter().all(|n| (1..=9).contains(n))).then_some(GridStep::Zoom(cells))
+}
+
+/// Put the grid over the whole screen, with the pointer in the middle
+pub fn open_grid(keys: &mut Keys) -> InputResult<Grid> {
+    let (width, height) = keys.main_display()?;
+    let grid = Grid { x: 0, y: 0, width, height };
+    let (x, y) = grid.center();
+    keys.move_mouse(x, y, Coordinate::Abs)?;
+    set_grid(Some(grid));
+    Ok(grid)
+}
+
+/// Take the grid down
+pub fn close_grid() {
+    set_grid(None);
+}
+
+/// Remember the area the grid covers and redraw the overlay for it
+fn set_grid(grid: Option<Grid>) {
+    if let Ok(mut current) = GRID.lock() {
+        *current = grid;
+    }
+    overlay::show(grid);
+}
+
+/// Carry out a grid step; the area the grid covers afterwards, None once it's closed
+pub fn run_grid(keys: &mut Keys, step: &GridStep) -> InputResult<Option<Grid>> {
+    let Some(mut grid) = GRID.lock().ok().and_then(|grid| *grid) else {
+        return Ok(None);
+    };
+    match step {
+        GridStep::Zoom(cells) => {
+            for &n in cells {
+                grid = grid.cell(n).unwrap_or(grid);
+            }
+            let (x, y) = grid.center();
+            keys.move_mouse(x, y, Coordinate::Abs)?;
+            set_grid(Some(grid));
+            Ok(Some(grid))
+        }
+        GridStep::Click(click) => {
+            close_grid();
+            run(keys, *click)?;
+            Ok(None)
+        }
+        GridStep::Close => {
+            close_grid();
+            Ok(None)
+        }
+    }
+}
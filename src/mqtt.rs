@@ -0,0 +1,113 @@
+//! MQTT integration for SS9K (mqtt feature)
+//!
+//! This module handles:
+//! - Connecting to the broker in `mqtt_broker` (reconnecting when it drops)
+//! - Publishing state and transcriptions from `control` events
+//! - Running commands sent to the command topic (same ones as `ss9k ctl`)
+//!
+//! Topics, under the `mqtt_topic` prefix ("ss9k" by default):
+//! - `ss9k/availability` - "online" / "offline" (retained, offline is the last will)
+//! - `ss9k/state` - "idle", "recording", "listening", "processing" (retained)
+//! - `ss9k/transcription` - text that was typed or run as a command
+//! - `ss9k/command` - subscribed: "toggle", "start", "stop", "mode snake"...
+//! - `ss9k/reply` - what the last command answered
+//!
+//! Plain TCP only. Transcriptions leave the machine, so point this at a
+//! broker you trust.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use rumqttc::{Client, Event as MqttEvent, LastWill, MqttOptions, Packet, QoS};
+use tracing::{info, warn};
+
+use crate::control::{self, Event};
+use crate::error::MqttError;
+use crate::privacy;
+use crate::Config;
+
+/// Wait between reconnect attempts
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Connect to the configured broker; the connection runs on background threads
+pub fn start(config: Arc<ArcSwap<Config>>, config_path: Option<PathBuf>) -> Result<JoinHandle<()>, MqttError> {
+    let cfg = config.load();
+    let prefix = cfg.mqtt_topic.trim_end_matches('/').to_string();
+    if prefix.is_empty() || prefix.contains(['+', '#']) {
+        return Err(MqttError::Topic(cfg.mqtt_topic.clone()));
+    }
+    let topic = {
+        let prefix = prefix.clone();
+        move |name: &str| format!("{}/{}", prefix, name)
+    };
+    let (host, port) = (cfg.mqtt_broker.clone(), cfg.mqtt_port);
+
+    let mut options = MqttOptions::new(format!("ss9k-{}", std::process::id()), host.clone(), port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(topic("availability"), "offline", QoS::AtLeastOnce, true));
+    if !cfg.mqtt_username.is_empty() {
+        options.set_credentials(cfg.mqtt_username.clone(), cfg.mqtt_password.clone());
+    }
+    let (client, mut connection) = Client::new(options, 64);
+
+    // Events out: state and transcriptions
+    {
+        let client = client.clone();
+        let (state_topic, transcription_topic) = (topic("state"), topic("transcription"));
+        let events = control::subscribe();
+        std::thread::spawn(move || {
+            for event in events {
+                let result = match event {
+                    Event::State(state) => client.publish(&state_topic, QoS::AtLeastOnce, true, state.as_str()),
+                    // It would leave the machine
+                    Event::Transcription(_) if privacy::enabled() => continue,
+                    Event::Transcription(text) => client.publish(&transcription_topic, QoS::AtLeastOnce, false, text),
+                };
+                if let Err(e) = result {
+                    warn!("⚠️ MQTT publish failed: {}", e);
+                }
+            }
+        });
+    }
+
+    // Connection and commands in; never block here (try_* only), it drives the connection
+    Ok(std::thread::spawn(move || {
+        let command_topic = topic("command");
+        let mut connected = true; // Log the first failure, then once per outage
+        for notification in connection.iter() {
+            match notification {
+                Ok(MqttEvent::Incoming(Packet::ConnAck(_))) => {
+                    connected = true;
+                    info!("📡 MQTT connected to {}:{} ({}/...)", host, port, prefix);
+                    let _ = client.try_subscribe(&command_topic, QoS::AtLeastOnce);
+                    let _ = client.try_publish(topic("availability"), QoS::AtLeastOnce, true, "online");
+                    let _ = client.try_publish(topic("state"), QoS::AtLeastOnce, true, control::state().as_str());
+                }
+                Ok(MqttEvent::Incoming(Packet::Publish(publish))) if publish.topic == command_topic => {
+                    let command = String::from_utf8_lossy(&publish.payload).trim().to_string();
+                    let reply = match control::run_command(&command, &config, config_path.as_deref()) {
+                        Ok(payload) if payload.is_empty() => "ok".to_string(),
+                        Ok(payload) => payload,
+                        Err(message) => {
+                            warn!("⚠️ MQTT command '{}' failed: {}", command, message);
+                            format!("error: {}", message)
+                        }
+                    };
+                    let _ = client.try_publish(topic("reply"), QoS::AtMostOnce, false, reply);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    if connected {
+                        warn!("⚠️ MQTT connection to {}:{} failed: {} (retrying every {}s)",
+                                  host, port, e, RETRY_DELAY.as_secs());
+                        connected = false;
+                    }
+                    std::thread::sleep(RETRY_DELAY);
+                }
+            }
+        }
+    }))
+}
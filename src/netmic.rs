@@ -0,0 +1,204 @@
+//! Network microphone for SS9K
+//!
+//! This module handles:
+//! - Listening on `audio_source` for raw PCM sent from another device (a
+//!   phone app, a Raspberry Pi with the mic), so the machine transcribing
+//!   doesn't need one attached
+//! - TCP (a plain byte stream), UDP (datagrams, read as they arrive) and
+//!   WebSocket (binary messages)
+//!
+//! The audio goes through the same reader as `--stdin-audio`, in the layout
+//! `audio_source_format` gives. One TCP or WebSocket sender at a time; the
+//! next one is taken when it disconnects.
+//!
+//! What's heard can run commands, so audio is only taken from this machine
+//! and the addresses in `audio_source_allow`; other senders are turned away
+//! (TCP, WebSocket) or their datagrams dropped (UDP).
+
+use std::collections::HashSet;
+use std::fmt;
+use std::io::{self, Read};
+use std::net::{IpAddr, TcpListener, TcpStream, UdpSocket};
+use std::str::FromStr;
+
+use tracing::{info, warn};
+use tungstenite::{Message, WebSocket};
+
+use crate::audio::{read_raw, RawFormat};
+use crate::error::AudioError;
+
+/// Where network audio arrives: `tcp://`, `udp://` or `ws://` plus host:port
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NetSource {
+    Tcp(String),
+    Udp(String),
+    WebSocket(String),
+}
+
+impl FromStr for NetSource {
+    type Err = AudioError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AudioError::Source(s.to_string());
+        let (scheme, addr) = s.trim().split_once("://").ok_or_else(invalid)?;
+        // host:port, with a port that parses
+        let port = addr.rsplit_once(':').and_then(|(_, port)| port.parse::<u16>().ok());
+        if port.is_none() {
+            return Err(invalid());
+        }
+        let addr = addr.to_string();
+        match scheme.to_lowercase().as_str() {
+            "tcp" => Ok(Self::Tcp(addr)),
+            "udp" => Ok(Self::Udp(addr)),
+            "ws" => Ok(Self::WebSocket(addr)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for NetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "tcp://{}", addr),
+            Self::Udp(addr) => write!(f, "udp://{}", addr),
+            Self::WebSocket(addr) => write!(f, "ws://{}", addr),
+        }
+    }
+}
+
+/// `audio_source_allow` as addresses
+pub fn parse_allow(list: &[String]) -> Result<Vec<IpAddr>, AudioError> {
+    list.iter()
+        .map(|entry| entry.trim().parse().map_err(|_| AudioError::SourceAllow(entry.clone())))
+        .collect()
+}
+
+/// Whether `peer` may send audio: this machine, or a listed address
+fn allowed(peer: IpAddr, allow: &[IpAddr]) -> bool {
+    let peer = peer.to_canonical();
+    peer.is_loopback() || allow.iter().any(|ip| ip.to_canonical() == peer)
+}
+
+/// Bind `source` and read senders on a background thread, handing `sink`
+/// mono chunks like `spawn_raw_reader`
+/// Binding happens before this returns, so a taken port is an error here.
+pub fn listen(
+    source: &NetSource,
+    allow: Vec<IpAddr>,
+    format: RawFormat,
+    mut sink: impl FnMut(&[f32]) + Send + 'static,
+) -> Result<(), AudioError> {
+    let listen_error = |addr: &str, source| AudioError::Listen { addr: addr.to_string(), source };
+    match source {
+        NetSource::Udp(addr) => {
+            let socket = UdpSocket::bind(addr).map_err(|e| listen_error(addr, e))?;
+            info!("📡 Listening for audio on udp://{}", addr);
+            std::thread::spawn(move || read_raw(Datagrams::new(socket, allow), format, &mut sink));
+        }
+        NetSource::Tcp(addr) | NetSource::WebSocket(addr) => {
+            let listener = TcpListener::bind(addr).map_err(|e| listen_error(addr, e))?;
+            info!("📡 Listening for audio on {}", source);
+            let websocket = matches!(source, NetSource::WebSocket(_));
+            std::thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            warn!("⚠️ Audio connection failed: {}", e);
+                            continue;
+                        }
+                    };
+                    let Ok(peer) = stream.peer_addr() else { continue };
+                    if !allowed(peer.ip(), &allow) {
+                        warn!("⚠️ Turned away audio from {} (not in audio_source_allow)", peer.ip());
+                        continue;
+                    }
+                    info!("📡 Audio sender connected: {}", peer);
+                    if websocket {
+                        match tungstenite::accept(stream) {
+                            Ok(ws) => read_raw(Messages::new(ws), format, &mut sink),
+                            Err(e) => warn!("⚠️ WebSocket handshake with {} failed: {}", peer, e),
+                        }
+                    } else {
+                        read_raw(stream, format, &mut sink);
+                    }
+                    info!("📡 Audio sender disconnected: {}", peer);
+                }
+            });
+        }
+    }
+    Ok(())
+}
+
+/// UDP datagrams from allowed senders read as one byte stream
+struct Datagrams {
+    socket: UdpSocket,
+    allow: Vec<IpAddr>,
+    /// Senders already warned about, so a stream of them logs once
+    refused: HashSet<IpAddr>,
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+}
+
+impl Datagrams {
+    fn new(socket: UdpSocket, allow: Vec<IpAddr>) -> Self {
+        Self { socket, allow, refused: HashSet::new(), buf: vec![0; 65536], start: 0, end: 0 }
+    }
+}
+
+impl Read for Datagrams {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        // Empty datagrams would look like the end of the stream
+        while self.start == self.end {
+            let (len, peer) = self.socket.recv_from(&mut self.buf)?;
+            if !allowed(peer.ip(), &self.allow) {
+                if self.refused.insert(peer.ip()) {
+                    warn!("⚠️ Dropping audio from {} (not in audio_source_allow)", peer.ip());
+                }
+                continue;
+            }
+            self.end = len;
+            self.start = 0;
+        }
+        let n = out.len().min(self.end - self.start);
+        out[..n].copy_from_slice(&self.buf[self.start..self.start + n]);
+        self.start += n;
+        Ok(n)
+    }
+}
+
+/// Binary WebSocket messages read as one byte stream; ends when the sender closes
+struct Messages {
+    ws: WebSocket<TcpStream>,
+    pending: Vec<u8>,
+    start: usize,
+}
+
+impl Messages {
+    fn new(ws: WebSocket<TcpStream>) -> Self {
+        Self { ws, pending: Vec::new(), start: 0 }
+    }
+}
+
+impl Read for Messages {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        while self.start == self.pending.len() {
+            match self.ws.read() {
+                Ok(Message::Binary(data)) => {
+                    self.pending = data.to_vec();
+                    self.start = 0;
+                }
+                Ok(Message::Close(_)) => return Ok(0),
+                Ok(_) => {} // Text, and pings (tungstenite answers those)
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => return Ok(0),
+                Err(tungstenite::Error::Io(e)) => return Err(e),
+                Err(e) => return Err(io::Error::other(e)),
+            }
+        }
+        let n = out.len().min(self.pending.len() - self.start);
+        out[..n].copy_from_slice(&self.pending[self.start..self.start + n]);
+        self.start += n;
+        Ok(n)
+    }
+}
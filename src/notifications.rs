@@ -0,0 +1,69 @@
+//! Desktop notifications for SS9K
+//!
+//! This module handles:
+//! - Recording state (started/stopped listening, audio sent)
+//! - Transcription results (what was typed or which command ran)
+//! - Errors
+//!
+//! Console output is invisible when ss9k runs as a background service, so
+//! these go through the desktop's notification daemon (libnotify on Linux).
+//! `notifications` in config picks how chatty it is.
+
+use notify_rust::{Notification, Timeout};
+use tracing::warn;
+
+use crate::Config;
+
+/// What a notification is about, from least to most chatty
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Kind {
+    Error,  // Something failed
+    Result, // Transcription typed or command executed
+    State,  // Recording/listening started or stopped
+}
+
+/// Most chatty kind a `notifications` setting lets through (None = off)
+fn max_kind(setting: &str) -> Option<Kind> {
+    match setting.trim().to_lowercase().as_str() {
+        "errors" => Some(Kind::Error),
+        "results" => Some(Kind::Result),
+        "all" => Some(Kind::State),
+        _ => None, // "off", "" and anything unrecognized
+    }
+}
+
+/// Show a desktop notification in the background, if the configured level allows it
+pub fn notify(config: &Config, kind: Kind, body: &str) {
+    if max_kind(&config.notifications).is_none_or(|max| kind > max) {
+        return;
+    }
+    show(kind, body);
+}
+
+/// Show a desktop notification in the background whatever `notifications`
+/// says (for things asked for separately, like `clipboard_notify`)
+pub fn show(kind: Kind, body: &str) {
+    let summary = if kind == Kind::Error { "SS9K error" } else { "SS9K" };
+    let body = body.to_string();
+
+    // Talking to the notification daemon can block, keep it off the caller's thread
+    std::thread::spawn(move || {
+        let mut notification = Notification::new();
+        notification
+            .appname("ss9k")
+            .summary(summary)
+            .body(&body)
+            .icon(if kind == Kind::Error { "dialog-error" } else { "audio-input-microphone" })
+            .timeout(match kind {
+                Kind::State => Timeout::Milliseconds(1500), // Gone before the next one
+                _ => Timeout::Default,
+            });
+        #[cfg(all(unix, not(target_os = "macos")))]
+        if kind == Kind::Error {
+            notification.urgency(notify_rust::Urgency::Critical);
+        }
+        if let Err(e) = notification.show() {
+            warn!("⚠️ Notification failed: {}", e);
+        }
+    });
+}
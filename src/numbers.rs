@@ -0,0 +1,574 @@
+//! Spoken numbers for SS9K
+//!
+//! Whisper writes most numbers as digits, but not all of them: "one hundred
+//! twenty three", "three point five", "negative seven" and "two thousand
+//! twenty six" still come through as words now and then, and they're what
+//! people say after "times" or in math mode. This parses them, for counts
+//! and levels in commands, math mode, and dictation with `numbers = "digits"`
+//! or `"formatted"`, which writes money and percentages too ("$12.50", "20%").
+//!
+//! Hyphens are fine ("twenty-five"), and so is "a" or "and" where English
+//! puts them ("a hundred and five"). A word that can't continue a number
+//! ends it, so "one two three" is three numbers.
+//!
+//! Ordinals and fractions count too. In text "twenty first" is `21st` and
+//! "three quarters" is `¾` (`5/16` without a character of its own); in math
+//! mode they're `21` and `3/4`, and "two and a half" is `(2 + 1/2)`.
+
+use std::sync::RwLock;
+
+use crate::Config;
+
+const UNITS: &[(&str, u64)] = &[
+    ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4), ("five", 5), ("six", 6), ("seven", 7),
+    ("eight", 8), ("nine", 9), ("ten", 10), ("eleven", 11), ("twelve", 12), ("thirteen", 13),
+    ("fourteen", 14), ("fifteen", 15), ("sixteen", 16), ("seventeen", 17), ("eighteen", 18), ("nineteen", 19),
+];
+
+const TENS: &[(&str, u64)] = &[
+    ("twenty", 20), ("thirty", 30), ("forty", 40), ("fourty", 40), ("fifty", 50),
+    ("sixty", 60), ("seventy", 70), ("eighty", 80), ("ninety", 90),
+];
+
+const SCALES: &[(&str, u64)] = &[
+    ("thousand", 1_000), ("million", 1_000_000), ("billion", 1_000_000_000), ("trillion", 1_000_000_000_000),
+];
+
+/// Digit words after "point": "three point oh five" -> 3.05
+const DIGITS: &[(&str, char)] = &[
+    ("zero", '0'), ("oh", '0'), ("o", '0'), ("one", '1'), ("two", '2'), ("three", '3'), ("four", '4'),
+    ("five", '5'), ("six", '6'), ("seven", '7'), ("eight", '8'), ("nine", '9'),
+];
+
+const ORDINAL_UNITS: &[(&str, u64)] = &[
+    ("first", 1), ("second", 2), ("third", 3), ("fourth", 4), ("fifth", 5), ("sixth", 6), ("seventh", 7),
+    ("eighth", 8), ("ninth", 9), ("tenth", 10), ("eleventh", 11), ("twelfth", 12), ("thirteenth", 13),
+    ("fourteenth", 14), ("fifteenth", 15), ("sixteenth", 16), ("seventeenth", 17), ("eighteenth", 18),
+    ("nineteenth", 19),
+];
+
+const ORDINAL_TENS: &[(&str, u64)] = &[
+    ("twentieth", 20), ("thirtieth", 30), ("fortieth", 40), ("fiftieth", 50),
+    ("sixtieth", 60), ("seventieth", 70), ("eightieth", 80), ("ninetieth", 90),
+];
+
+/// Ordinals that multiply what comes before them: "two hundredth" -> 200th
+const ORDINAL_SCALES: &[(&str, u64)] = &[
+    ("hundredth", 100), ("thousandth", 1_000), ("millionth", 1_000_000), ("billionth", 1_000_000_000),
+];
+
+/// Fraction denominators, one and more: "one third", "two thirds"
+const DENOMINATORS: &[(&str, &str, u64)] = &[
+    ("half", "halves", 2), ("third", "thirds", 3), ("quarter", "quarters", 4), ("fourth", "fourths", 4),
+    ("fifth", "fifths", 5), ("sixth", "sixths", 6), ("seventh", "sevenths", 7), ("eighth", "eighths", 8),
+    ("ninth", "ninths", 9), ("tenth", "tenths", 10), ("twelfth", "twelfths", 12), ("sixteenth", "sixteenths", 16),
+    ("hundredth", "hundredths", 100), ("thousandth", "thousandths", 1_000),
+];
+
+/// Fractions with a character of their own
+const VULGAR: &[((u64, u64), char)] = &[
+    ((1, 2), '½'), ((1, 3), '⅓'), ((2, 3), '⅔'), ((1, 4), '¼'), ((3, 4), '¾'), ((1, 5), '⅕'), ((2, 5), '⅖'),
+    ((3, 5), '⅗'), ((4, 5), '⅘'), ((1, 6), '⅙'), ((5, 6), '⅚'), ((1, 7), '⅐'), ((1, 8), '⅛'), ((3, 8), '⅜'),
+    ((5, 8), '⅝'), ((7, 8), '⅞'), ((1, 9), '⅑'), ((1, 10), '⅒'),
+];
+
+/// A number read from the start of some words
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spoken {
+    pub negative: bool,
+    pub whole: u64,
+    pub fraction: String,           // Digits after the point, empty for none
+    pub ratio: Option<(u64, u64)>,  // A fraction on top of `whole`: "two and a half" is 2 and (1, 2)
+    pub ordinal: bool,              // "third" rather than "three"
+    pub words: usize,               // How many words it took
+}
+
+impl Spoken {
+    /// The number as it's written in text: "123", "-3.14", "23rd", "¾", "2½", "5/16"
+    pub fn text(&self) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        match self.ratio {
+            Some(ratio) => {
+                let whole = if self.whole > 0 { self.whole.to_string() } else { String::new() };
+                match VULGAR.iter().find(|(r, _)| *r == ratio) {
+                    Some((_, glyph)) => format!("{}{}{}", sign, whole, glyph),
+                    None if whole.is_empty() => format!("{}{}/{}", sign, ratio.0, ratio.1),
+                    None => format!("{}{} {}/{}", sign, whole, ratio.0, ratio.1),
+                }
+            }
+            None if self.ordinal => format!("{}{}{}", sign, self.whole, ordinal_suffix(self.whole)),
+            None => self.math(),
+        }
+    }
+
+    /// The number as math mode writes it: "123", "-3.14", "3" for "third",
+    /// "3/4", and "(2 + 1/2)" so a mixed number keeps together
+    pub fn math(&self) -> String {
+        let sign = if self.negative { "-" } else { "" };
+        match self.ratio {
+            Some((n, d)) if self.whole > 0 => format!("{}({} + {}/{})", sign, self.whole, n, d),
+            Some((n, d)) => format!("{}{}/{}", sign, n, d),
+            None if self.fraction.is_empty() => format!("{}{}", sign, self.whole),
+            None => format!("{}{}.{}", sign, self.whole, self.fraction),
+        }
+    }
+
+    /// The number as a float, for amounts like waits
+    pub fn value(&self) -> f64 {
+        let fraction = format!("0.{}", self.fraction).parse::<f64>().unwrap_or(0.0);
+        let ratio = self.ratio.map_or(0.0, |(n, d)| n as f64 / d as f64);
+        let value = self.whole as f64 + fraction + ratio;
+        if self.negative { -value } else { value }
+    }
+}
+
+/// "st", "nd", "rd" or "th" for an ordinal in digits
+pub fn ordinal_suffix(n: u64) -> &'static str {
+    match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+/// What the last word of a number was, which decides what can follow it
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    Nothing,
+    Unit,  // one ... nineteen, or a tens word with its unit
+    Tens,  // twenty ... ninety on its own
+    Hundred,
+    Scale(u64),
+    Digits,
+}
+
+/// A word lowercased, without punctuation around it or hyphens in it
+fn clean(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).replace('-', "").to_lowercase()
+}
+
+fn lookup(table: &[(&str, u64)], word: &str) -> Option<u64> {
+    table.iter().find(|(name, _)| *name == word).map(|(_, n)| *n)
+}
+
+/// "fiftyfive" (a hyphen taken out) -> 55, and with `units` of ordinals, "twentyfirst" -> 21
+fn compound(word: &str, units: &[(&str, u64)]) -> Option<u64> {
+    TENS.iter().find_map(|&(name, tens)| {
+        let unit = lookup(units, word.strip_prefix(name)?).filter(|u| (1..10).contains(u))?;
+        Some(tens + unit)
+    })
+}
+
+/// A word that can come after "and" in a number: "a hundred and five",
+/// "one thousand and twenty", "one hundred and first"
+fn ends_a_group(word: &str) -> bool {
+    [UNITS, TENS, ORDINAL_UNITS, ORDINAL_TENS].iter().any(|table| lookup(table, word).is_some())
+        || compound(word, UNITS).is_some()
+        || compound(word, ORDINAL_UNITS).is_some()
+}
+
+/// The denominator `word` names after a numerator of `n`: "quarters" is 4 after
+/// anything, "quarter" only after one
+fn denominator(word: &str, n: u64) -> Option<u64> {
+    DENOMINATORS.iter().find_map(|&(one, many, d)| (word == many || (word == one && n == 1)).then_some(d))
+}
+
+/// The number the first words spell out, if they start with one
+/// Stops at the first word that can't continue it, so "five apples" is 5
+/// taking one word.
+pub fn parse_prefix(words: &[&str]) -> Option<Spoken> {
+    let words: Vec<String> = words.iter().map(|w| clean(w)).collect();
+    let mut i = 0;
+    let negative = words.first().is_some_and(|w| w == "negative");
+    if negative {
+        i += 1;
+    }
+
+    let (mut total, mut group, mut last) = (0u64, 0u64, Last::Nothing);
+    let (mut ratio, mut ordinal) = (None, false);
+    let mut taken = i;
+    while let Some(word) = words.get(i) {
+        let next = words.get(i + 1).map(String::as_str);
+        let multiplier = next.is_some_and(|n| n == "hundred" || lookup(SCALES, n).is_some() || denominator(n, 1).is_some());
+        let unit = lookup(UNITS, word);
+        let counted = matches!(last, Last::Unit | Last::Tens | Last::Digits) || group == 1;
+
+        // "three quarters", "a half"
+        if counted && let Some(d) = denominator(word, total + group) {
+            ratio = Some((total + group, d));
+            (total, group) = (0, 0);
+            i += 1;
+            taken = i;
+            break;
+        }
+        // "two and a half", "one and three quarters"
+        if word == "and" && last != Last::Nothing {
+            let rest: Vec<&str> = words[i + 1..].iter().map(String::as_str).collect();
+            if let Some(part) = parse_prefix(&rest).filter(|p| p.ratio.is_some() && p.whole == 0 && !p.negative) {
+                ratio = part.ratio;
+                taken = i + 1 + part.words;
+                break;
+            }
+        }
+
+        match word.as_str() {
+            // "a hundred", "a thousand", "a half"
+            "a" | "an" if last == Last::Nothing && multiplier => group = 1,
+            // "a hundred and five", "one hundred and first"
+            "and" if matches!(last, Last::Hundred | Last::Scale(_)) && next.is_some_and(ends_a_group) => {}
+            "hundred" if (matches!(last, Last::Unit | Last::Tens | Last::Digits) || group == 1) && group < 100 => {
+                group *= 100;
+                last = Last::Hundred;
+            }
+            _ if lookup(SCALES, word).is_some() => {
+                let scale = lookup(SCALES, word)?;
+                let smaller = match last {
+                    Last::Scale(previous) => scale < previous,
+                    _ => true,
+                };
+                if !smaller || group == 0 {
+                    break;
+                }
+                total = total.checked_add(group.checked_mul(scale)?)?;
+                group = 0;
+                last = Last::Scale(scale);
+            }
+            // "two hundredth", "the thousandth time"
+            _ if lookup(ORDINAL_SCALES, word).is_some() => {
+                let scale = lookup(ORDINAL_SCALES, word)?;
+                let fits = match last {
+                    Last::Nothing | Last::Unit | Last::Tens | Last::Digits => true,
+                    Last::Hundred => scale > 100,
+                    Last::Scale(_) => false,
+                };
+                if !fits {
+                    break;
+                }
+                total = total.checked_add(group.max(1).checked_mul(scale)?)?;
+                group = 0;
+                ordinal = true;
+            }
+            _ if word.chars().all(|c| c.is_ascii_digit()) && !word.is_empty() && last == Last::Nothing => {
+                group = word.parse().ok()?;
+                last = Last::Digits;
+            }
+            _ => {
+                let ordinal_unit = lookup(ORDINAL_UNITS, word);
+                let step = match last {
+                    Last::Nothing | Last::Hundred | Last::Scale(_) => unit
+                        .or_else(|| compound(word, UNITS))
+                        .map(|n| (n, Last::Unit, false))
+                        .or_else(|| lookup(TENS, word).map(|n| (n, Last::Tens, false)))
+                        .or_else(|| {
+                            let n = ordinal_unit.or_else(|| lookup(ORDINAL_TENS, word)).or_else(|| compound(word, ORDINAL_UNITS))?;
+                            Some((n, Last::Unit, true))
+                        }),
+                    // "twenty five", "twenty first"; not "thirty second", which is
+                    // nearly always a length of time
+                    Last::Tens => unit
+                        .filter(|u| (1..10).contains(u))
+                        .map(|n| (n, Last::Unit, false))
+                        .or_else(|| {
+                            let n = ordinal_unit.filter(|u| (1..10).contains(u) && word != "second")?;
+                            Some((n, Last::Unit, true))
+                        }),
+                    Last::Unit | Last::Digits => None,
+                };
+                let Some((n, kind, is_ordinal)) = step else { break };
+                group += n;
+                last = kind;
+                ordinal = is_ordinal;
+            }
+        }
+        i += 1;
+        // An ordinal ends the number
+        if ordinal {
+            taken = i;
+            break;
+        }
+        if !matches!(word.as_str(), "a" | "an" | "and") {
+            taken = i;
+        }
+    }
+    if last == Last::Nothing && !ordinal && ratio.is_none() {
+        return None;
+    }
+    let whole = total.checked_add(group)?;
+
+    // "three point one four"
+    let mut fraction = String::new();
+    if !ordinal && ratio.is_none() && words.get(taken).is_some_and(|w| w == "point" || w == "dot") {
+        let digits: String = words[taken + 1..]
+            .iter()
+            .map_while(|w| DIGITS.iter().find(|(name, _)| name == w).map(|(_, d)| *d))
+            .collect();
+        if !digits.is_empty() {
+            taken += 1 + digits.len();
+            fraction = digits;
+        }
+    }
+    Some(Spoken { negative, whole, fraction, ratio, ordinal, words: taken })
+}
+
+/// A whole number said in full: "one hundred twenty three" -> 123, "negative seven" -> -7
+/// None if any word isn't part of it, or it's an ordinal or has a fraction.
+pub fn parse_integer(s: &str) -> Option<i64> {
+    let words: Vec<&str> = s.split_whitespace().collect();
+    let spoken = parse_prefix(&words)
+        .filter(|n| n.words == words.len() && n.fraction.is_empty() && n.ratio.is_none() && !n.ordinal)?;
+    let whole = i64::try_from(spoken.whole).ok()?;
+    Some(if spoken.negative { -whole } else { whole })
+}
+
+/// A number said in full, fractions too: "three point five" -> 3.5,
+/// "two and a half" -> 2.5, "1.5" -> 1.5
+pub fn parse_decimal(s: &str) -> Option<f64> {
+    if let Ok(n) = s.trim().parse::<f64>() {
+        return Some(n);
+    }
+    let words: Vec<&str> = s.split_whitespace().collect();
+    parse_prefix(&words).filter(|n| n.words == words.len() && !n.ordinal).map(|n| n.value())
+}
+
+/// Spoken numbers in text as digits: "I waited twenty five minutes" ->
+/// "I waited 25 minutes", "the twenty first" -> "the 21st", "three quarters"
+/// -> "¾". Lone words below ten stay words ("one of them", "third"),
+/// as style guides have it, unless `small` is set.
+/// Whitespace and the punctuation around each number are kept.
+pub fn to_digits(text: &str, small: bool) -> String {
+    let spans = word_spans(text);
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < spans.len() {
+        let words: Vec<&str> = spans[i..].iter().map(|r| &text[r.clone()]).collect();
+        // A number ends at punctuation after one of its words ("five, six")
+        let upto = words.iter().position(|w| w.ends_with(|c: char| !c.is_alphanumeric())).map_or(words.len(), |p| p + 1);
+        let number = parse_prefix(&words[..upto])
+            .filter(|n| small || n.words > 1 || n.whole >= 10 || !n.fraction.is_empty())
+            // "a quarter past five" reads better than "¼ past five"
+            .filter(|n| !(n.ratio.is_some() && n.whole == 0 && matches!(clean(words[0]).as_str(), "a" | "an")))
+            .filter(|n| !words[..n.words].iter().all(|w| w.chars().all(|c| c.is_ascii_digit() || !c.is_alphanumeric())));
+        match number {
+            Some(number) => {
+                let (first, last) = (&spans[i], &spans[i + number.words - 1]);
+                let leading = &text[first.clone()];
+                let lead = &leading[..leading.len() - leading.trim_start_matches(|c: char| !c.is_alphanumeric()).len()];
+                let trailing = &text[last.clone()];
+                let trail = &trailing[trailing.trim_end_matches(|c: char| !c.is_alphanumeric()).len()..];
+                out.push_str(&text[copied..first.start]);
+                out.push_str(lead);
+                out.push_str(&number.text());
+                out.push_str(trail);
+                copied = last.end;
+                i += number.words;
+            }
+            None => i += 1,
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Currency words and their symbols
+const CURRENCIES: &[(&[&str], &str)] = &[
+    (&["dollar", "dollars", "bucks"], "$"),
+    (&["euro", "euros"], "€"),
+    (&["pound", "pounds", "quid"], "£"),
+    (&["yen"], "¥"),
+    (&["rupee", "rupees"], "₹"),
+];
+
+/// Ways to say "percent", in the languages with a `Locale`
+const PERCENT: &[&[&str]] = &[
+    &["percent"], &["per", "cent"], &["prozent"], &["pour", "cent"], &["por", "ciento"],
+    &["per", "cento"], &["procent"], &["por", "cento"],
+];
+
+/// Words for the hundredths after an amount: "twelve dollars fifty cents"
+const CENTS: &[&str] = &["cent", "cents", "penny", "pence"];
+
+/// How amounts are written in a language, for `numbers = "formatted"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    pub decimal: char,        // "12.50" or "12,50"
+    pub symbol_after: bool,   // "$12.50" or "12,50 €"
+    pub percent_space: bool,  // "20%" or "20 %"
+}
+
+/// Language code, decimal separator, currency symbol after, space before %
+const LOCALES: &[(&str, char, bool, bool)] = &[
+    ("en", '.', false, false),
+    ("de", ',', true, true),
+    ("fr", ',', true, true),
+    ("es", ',', true, true),
+    ("it", ',', true, false),
+    ("nl", ',', false, false),
+    ("pt", ',', true, false),
+];
+
+impl Locale {
+    pub const ENGLISH: Locale = Locale { decimal: '.', symbol_after: false, percent_space: false };
+
+    /// The conventions for `language`; English ones for "auto" and languages without a table entry
+    pub fn for_language(language: &str) -> Self {
+        LOCALES
+            .iter()
+            .find(|(lang, ..)| lang.eq_ignore_ascii_case(language))
+            .map_or(Locale::ENGLISH, |&(_, decimal, symbol_after, percent_space)| Locale { decimal, symbol_after, percent_space })
+    }
+
+    /// The separator between thousands, which is whichever the decimal one isn't
+    fn group(&self) -> char {
+        if self.decimal == ',' { '.' } else { ',' }
+    }
+
+    /// An amount written in digits, "1,250.5" or "1.250,5", as its whole part
+    /// (separators kept) and the digits after the decimal separator
+    fn amount<'a>(&self, word: &'a str) -> Option<(&'a str, &'a str)> {
+        let (whole, fraction) = word.split_once(self.decimal).unwrap_or((word, ""));
+        let whole_ok = whole.starts_with(|c: char| c.is_ascii_digit())
+            && whole.chars().all(|c| c.is_ascii_digit() || c == self.group());
+        (whole_ok && fraction.chars().all(|c| c.is_ascii_digit())).then_some((whole, fraction))
+    }
+}
+
+/// Money and percentages in text written the way `locale` writes them:
+/// "12 dollars 50" -> "$12.50", "5 euros" -> "5 €" in German, "20 percent"
+/// -> "20%". Amounts have to be in digits already (see `to_digits`); other
+/// words are left as they are.
+pub fn format_amounts(text: &str, locale: &Locale) -> String {
+    let spans = word_spans(text);
+    let word = |i: usize| spans.get(i).map(|r: &std::ops::Range<usize>| &text[r.clone()]);
+    let bare = |i: usize| word(i).map(clean).unwrap_or_default();
+    let punctuated = |i: usize| word(i).is_some_and(|w| w.ends_with(|c: char| !c.is_alphanumeric()));
+
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < spans.len() {
+        let first = &text[spans[i].clone()];
+        let lead_len = first.len() - first.trim_start_matches(|c: char| !c.is_alphanumeric() && c != '-').len();
+        let core = first[lead_len..].trim_end_matches(|c: char| !c.is_alphanumeric());
+        let (negative, digits) = match core.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", core),
+        };
+        // Words below ten are still words after `to_digits`: "five percent"
+        let spelled = parse_integer(digits).filter(|n| *n >= 0).map(|n| n.to_string());
+        let digits = spelled.as_deref().unwrap_or(digits);
+        let amount = locale.amount(digits).filter(|_| !punctuated(i));
+
+        // "12 dollars", "12 dollars 50", "12 dollars and 50 cents"
+        let currency = CURRENCIES.iter().find(|(names, _)| names.contains(&bare(i + 1).as_str()));
+        let formatted = match (amount, currency) {
+            (Some((whole, fraction)), Some((_, symbol))) => {
+                let mut end = i + 1;
+                let mut cents = (!fraction.is_empty()).then(|| fraction.to_string());
+                if cents.is_none() && !punctuated(end) {
+                    let at = if bare(end + 1) == "and" && !punctuated(end + 1) { end + 2 } else { end + 1 };
+                    let hundredths = word(at)
+                        .map(|w| w.trim_end_matches(|c: char| !c.is_alphanumeric()))
+                        .and_then(|w| parse_integer(w).or_else(|| w.parse().ok()))
+                        .filter(|n| (0..100).contains(n));
+                    let named = CENTS.contains(&bare(at + 1).as_str()) && !punctuated(at);
+                    let last = word(at + 1).is_none() || punctuated(at);
+                    if let Some(hundredths) = hundredths.filter(|_| named || last) {
+                        cents = Some(format!("{:02}", hundredths));
+                        end = if named { at + 1 } else { at };
+                    }
+                }
+                let mut amount = whole.to_string();
+                if let Some(cents) = cents {
+                    amount.push(locale.decimal);
+                    amount.push_str(&format!("{:0<2}", cents));
+                }
+                let money = if locale.symbol_after {
+                    format!("{}{} {}", negative, amount, symbol)
+                } else {
+                    format!("{}{}{}", negative, symbol, amount)
+                };
+                Some((money, end))
+            }
+            // "20 percent", "12.5 per cent"
+            (Some(_), None) => {
+                let end = PERCENT
+                    .iter()
+                    .find(|words| {
+                        words.iter().enumerate().all(|(k, w)| bare(i + 1 + k) == *w && (k + 1 == words.len() || !punctuated(i + 1 + k)))
+                    })
+                    .map(|words| i + words.len());
+                let space = if locale.percent_space { " " } else { "" };
+                end.map(|end| (format!("{}{}{}%", negative, digits, space), end))
+            }
+            _ => None,
+        };
+
+        match formatted {
+            Some((formatted, end)) => {
+                let last = &text[spans[end].clone()];
+                let trail = &last[last.trim_end_matches(|c: char| !c.is_alphanumeric()).len()..];
+                out.push_str(&text[copied..spans[i].start]);
+                out.push_str(&first[..lead_len]);
+                out.push_str(&formatted);
+                out.push_str(trail);
+                copied = spans[end].end;
+                i = end + 1;
+            }
+            None => i += 1,
+        }
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// The byte range of each word in `text`
+fn word_spans(text: &str) -> Vec<std::ops::Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_whitespace(), start) {
+            (true, Some(s)) => {
+                spans.push(s..i);
+                start = None;
+            }
+            (false, None) => start = Some(i),
+            _ => {}
+        }
+    }
+    spans
+}
+
+/// What `numbers` does to dictation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dictation {
+    Spoken,
+    Digits,
+    Formatted(Locale),
+}
+
+static DICTATION: RwLock<Dictation> = RwLock::new(Dictation::Spoken);
+
+/// Apply `numbers` and `language` (startup and config reloads)
+pub fn configure(config: &Config) {
+    let mode = match config.numbers.as_str() {
+        "digits" => Dictation::Digits,
+        "formatted" => Dictation::Formatted(Locale::for_language(&config.language)),
+        _ => Dictation::Spoken,
+    };
+    if let Ok(mut current) = DICTATION.write() {
+        *current = mode;
+    }
+}
+
+/// Dictation with its spoken numbers as digits when `numbers = "digits"`,
+/// and its money and percentages formatted too with `numbers = "formatted"`
+pub fn dictation(text: &str) -> String {
+    match DICTATION.read().map_or(Dictation::Spoken, |mode| *mode) {
+        Dictation::Spoken => text.to_string(),
+        Dictation::Digits => to_digits(text, false),
+        Dictation::Formatted(locale) => format_amounts(&to_digits(text, false), &locale),
+    }
+}
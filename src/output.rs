@@ -0,0 +1,480 @@
+//! Output sinks for SS9K: where dictation goes
+//!
+//! This module handles:
+//! - The `OutputSink` trait and its sinks: typing at the cursor, the
+//!   clipboard, stdout, appending to `output_file`, POSTing to
+//!   `output_webhook`, and `exec:<command>` (each line to the stdin of one
+//!   long-running shell command, started again if it exits)
+//! - Picking one by name, for `output_mode` and for "command to <sink> <text>"
+//! - "command output <sink>": switching `output_mode` by voice until the next
+//!   config reload (clipboard-only dictation for apps where synthetic typing
+//!   is unreliable, say)
+//! - Pasting long dictation through the clipboard instead of typing it key by
+//!   key (`paste_min_chars`), then putting back what the clipboard held
+//! - Rewriting the selection through the same clipboard round trip ("command
+//!   snake selection")
+//! - Capitalizing sentence starts across utterances (`auto_capitalize`) and
+//!   putting a space between them (`smart_spacing`), from the end of what was
+//!   typed last
+//!
+//! Every sink gets the case mode applied; commands still run as usual
+//! whichever one dictation goes to. Home Assistant isn't a sink: it answers
+//! back, so main handles it.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Child, Stdio};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use enigo::{Direction, Key, Keyboard};
+use tracing::{debug, info};
+
+use crate::commands::{apply_case_mode, type_text};
+use crate::error::OutputError;
+use crate::keyboard::Keys;
+use crate::notifications::{self, Kind};
+use crate::{privacy, security, Config};
+
+/// Sinks "command to <sink> <text>" can name (`exec:` is config-only)
+pub const SINKS: &[&str] = &["type", "clipboard", "stdout", "file", "webhook"];
+
+/// Give up on a webhook after this long
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the pasted text stays on the clipboard before the old contents
+/// go back (the app reads it some time after the key press arrives)
+const PASTE_RESTORE_DELAY: Duration = Duration::from_millis(500);
+
+/// How long the app gets to put the selection on the clipboard after Ctrl+C
+const COPY_DELAY: Duration = Duration::from_millis(150);
+
+/// Dictation at least this many characters long is pasted (0 = always typed)
+static PASTE_MIN_CHARS: AtomicUsize = AtomicUsize::new(0);
+
+/// Capitalize the first word of each sentence in typed dictation
+static AUTO_CAPITALIZE: AtomicBool = AtomicBool::new(false);
+
+/// Put a space between typed dictation and what was typed before it
+static SMART_SPACING: AtomicBool = AtomicBool::new(false);
+
+/// The last few characters typed at the cursor, dictation and commands that
+/// type alike ("" at startup, and once the cursor may have moved)
+static TYPED_TAIL: Mutex<String> = Mutex::new(String::new());
+
+/// How much of what was typed `TYPED_TAIL` keeps
+const TAIL_CHARS: usize = 16;
+
+/// Somewhere dictated text can go
+pub trait OutputSink {
+    /// Deliver text that already has the case mode applied
+    fn emit(&mut self, output: &str) -> Result<(), OutputError>;
+
+    /// Deliver dictation (aliases applied, case mode not yet)
+    fn write(&mut self, text: &str) -> Result<(), OutputError> {
+        self.emit(&apply_case_mode(text))
+    }
+}
+
+/// Type at the cursor (remembered for "scratch that" and "read that")
+pub struct Typing<'a>(pub &'a mut Keys);
+
+impl OutputSink for Typing<'_> {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        if should_paste(output) {
+            return paste(self.0, output);
+        }
+        Ok(self.0.text(output)?)
+    }
+
+    fn write(&mut self, text: &str) -> Result<(), OutputError> {
+        type_text(self.0, text)
+    }
+}
+
+/// Put it on the clipboard; `notify` = show a notification each time
+pub struct Clipboard {
+    pub notify: bool,
+}
+
+/// Kept open: on X11 and Wayland the text is gone once its owner is dropped
+static CLIPBOARD: Mutex<Option<arboard::Clipboard>> = Mutex::new(None);
+
+/// The shared clipboard, opened on first use
+fn clipboard() -> Result<MutexGuard<'static, Option<arboard::Clipboard>>, OutputError> {
+    let mut clipboard = CLIPBOARD.lock().map_err(|_| OutputError::Poisoned)?;
+    if clipboard.is_none() {
+        *clipboard = Some(arboard::Clipboard::new()?);
+    }
+    Ok(clipboard)
+}
+
+/// What the clipboard holds, if it's text and not empty
+pub fn clipboard_text() -> Option<String> {
+    clipboard().ok()?.as_mut()?.get_text().ok().filter(|text| !text.is_empty())
+}
+
+impl OutputSink for Clipboard {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        if let Some(clipboard) = clipboard()?.as_mut() {
+            clipboard.set_text(output)?;
+        }
+        info!("📋 Copied: {}", privacy::redact(output));
+        if self.notify {
+            notifications::show(Kind::Result, &format!("📋 Copied: {}", privacy::redact(output)));
+        }
+        Ok(())
+    }
+}
+
+/// Remember the end of text just typed at the cursor, for `auto_capitalize`
+/// and `smart_spacing`
+pub fn typed(output: &str) {
+    let Ok(mut tail) = TYPED_TAIL.lock() else {
+        return;
+    };
+    tail.push_str(output);
+    let extra = tail.chars().count().saturating_sub(TAIL_CHARS);
+    if let Some((cut, _)) = tail.char_indices().nth(extra) {
+        tail.drain(..cut);
+    }
+}
+
+/// Forget what was typed: keys were pressed or a program ran, so the cursor
+/// may be somewhere else
+pub fn forget_typed() {
+    if let Ok(mut tail) = TYPED_TAIL.lock() {
+        tail.clear();
+    }
+}
+
+/// `output` with a space in front if `smart_spacing` is on and it would
+/// otherwise run into the text typed before it
+pub fn smart_space(output: &str) -> String {
+    if !SMART_SPACING.load(Ordering::SeqCst) {
+        return output.to_string();
+    }
+    let before = TYPED_TAIL.lock().map(|t| t.clone()).unwrap_or_default();
+    if needs_space(output, &before) {
+        format!(" {}", output)
+    } else {
+        output.to_string()
+    }
+}
+
+/// Whether `text` typed right after `before` needs a space in between: not
+/// when either side already has whitespace, after an opening bracket, quote,
+/// slash or dash, before closing punctuation, or with nothing typed before
+pub fn needs_space(text: &str, before: &str) -> bool {
+    let (Some(last), Some(first)) = (before.chars().last(), text.chars().next()) else {
+        return false;
+    };
+    !last.is_whitespace() && !first.is_whitespace() && !OPENERS.contains(&last) && !TRAILING.contains(&first)
+}
+
+/// What the next word goes right up against
+const OPENERS: &[char] = &['(', '[', '{', '<', '“', '‘', '«', '/', '\\', '-', '_', '@', '#', '$', '`'];
+
+/// What goes right up against the word before it
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '>', '”', '’', '»', '%', '…'];
+
+/// `output` with its sentence starts capitalized, if `auto_capitalize` is on
+/// The first word counts as one when nothing was typed yet or the last thing
+/// typed ended a sentence.
+pub fn auto_capitalize(output: &str) -> String {
+    if !AUTO_CAPITALIZE.load(Ordering::SeqCst) {
+        return output.to_string();
+    }
+    let before = TYPED_TAIL.lock().map(|t| t.clone()).unwrap_or_default();
+    capitalize_sentences(output, &before)
+}
+
+/// Capitalize the first letter of each sentence in `text`, which follows
+/// `before`: after . ! or ? and a space (closing quotes and brackets can sit
+/// in between), and at the start if `before` is empty or ends a sentence
+/// A period after a single letter ("e.g.", "J. Smith") doesn't end one.
+pub fn capitalize_sentences(text: &str, before: &str) -> String {
+    let mut start = ends_sentence(before);
+    let mut ended = false; // Just after . ! or ?, waiting for the space
+    let mut word = 0; // Letters and digits since the last other character
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c.is_whitespace() {
+            start |= ended;
+            ended = false;
+        } else if c.is_alphanumeric() {
+            ended = false;
+            word += 1;
+            if start && c.is_alphabetic() {
+                // Some capitals are a letter plus a combining mark ("ὐ"): leave those be
+                let upper: String = c.to_uppercase().collect();
+                if upper.chars().all(char::is_alphabetic) {
+                    result.push_str(&upper);
+                    start = false;
+                    continue;
+                }
+            }
+            start = false;
+        } else if matches!(c, '.' | '!' | '?') {
+            ended = c != '.' || word > 1;
+            start = false;
+        }
+        if !c.is_alphanumeric() {
+            word = 0;
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Whether typed text ending in `before` leaves the next word starting a sentence
+fn ends_sentence(before: &str) -> bool {
+    let before = before.trim_end().trim_end_matches(CLOSERS);
+    let Some(head) = before.strip_suffix(['.', '!', '?']) else {
+        return before.is_empty();
+    };
+    // Same rule as inside an utterance: "e.g." doesn't end a sentence
+    before.ends_with(['!', '?']) || head.chars().rev().take_while(|c| c.is_alphanumeric()).count() != 1
+}
+
+/// What can close a sentence after its . ! or ?
+const CLOSERS: &[char] = &['"', '\'', ')', ']', '}', '”', '’', '»'];
+
+/// Whether `output` is long enough to paste instead of typing (`paste_min_chars`)
+pub fn should_paste(output: &str) -> bool {
+    let min = PASTE_MIN_CHARS.load(Ordering::SeqCst);
+    min > 0 && output.chars().count() >= min
+}
+
+/// Put `output` on the clipboard, press Ctrl+V (Cmd+V on macOS), and put the
+/// old clipboard text back once the app has had time to read it
+pub fn paste(enigo: &mut Keys, output: &str) -> Result<(), OutputError> {
+    let previous = clipboard()?.as_mut().and_then(|clipboard| clipboard.get_text().ok());
+    paste_then_restore(enigo, output, previous)
+}
+
+/// Paste `output`, then put `previous` back on the clipboard
+fn paste_then_restore(enigo: &mut Keys, output: &str, previous: Option<String>) -> Result<(), OutputError> {
+    if let Some(clipboard) = clipboard()?.as_mut() {
+        clipboard.set_text(output)?;
+    }
+    shortcut(enigo, 'v')?;
+
+    if let Some(previous) = previous {
+        let pasted = output.to_string();
+        std::thread::spawn(move || {
+            std::thread::sleep(PASTE_RESTORE_DELAY);
+            let Ok(mut clipboard) = CLIPBOARD.lock() else {
+                return;
+            };
+            // Leave it alone if something else was copied in the meantime
+            if let Some(clipboard) = clipboard.as_mut()
+                && clipboard.get_text().is_ok_and(|text| text == pasted)
+                && let Err(e) = clipboard.set_text(previous)
+            {
+                debug!("📋 Couldn't restore the clipboard: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Press Ctrl (Cmd on macOS) with `key`
+fn shortcut(enigo: &mut Keys, key: char) -> Result<(), OutputError> {
+    #[cfg(target_os = "macos")]
+    let modifier = Key::Meta;
+    #[cfg(not(target_os = "macos"))]
+    let modifier = Key::Control;
+    enigo.key(modifier, Direction::Press)?;
+    let clicked = enigo.key(Key::Unicode(key), Direction::Click);
+    enigo.key(modifier, Direction::Release)?;
+    Ok(clicked?)
+}
+
+/// Replace the selection with `transform` of it: copied with Ctrl+C (Cmd+C on
+/// macOS), transformed and pasted back, after which the clipboard gets what
+/// it held before. The new text, or None if nothing was selected.
+pub fn transform_selection(enigo: &mut Keys, transform: impl FnOnce(&str) -> String) -> Result<Option<String>, OutputError> {
+    let previous = match clipboard()?.as_mut() {
+        Some(clipboard) => {
+            let previous = clipboard.get_text().ok();
+            // Emptied, so an empty selection can't be mistaken for what was there
+            clipboard.clear()?;
+            previous
+        }
+        None => None,
+    };
+    shortcut(enigo, 'c')?;
+    std::thread::sleep(COPY_DELAY);
+
+    let selected = clipboard()?.as_mut().and_then(|clipboard| clipboard.get_text().ok()).filter(|text| !text.is_empty());
+    let Some(selected) = selected else {
+        if let Some(previous) = previous
+            && let Some(clipboard) = clipboard()?.as_mut()
+        {
+            clipboard.set_text(previous)?;
+        }
+        return Ok(None);
+    };
+    let replaced = transform(&selected);
+    paste_then_restore(enigo, &replaced, previous)?;
+    Ok(Some(replaced))
+}
+
+/// Print one line per utterance
+pub struct Stdout;
+
+impl OutputSink for Stdout {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        let mut stdout = std::io::stdout().lock();
+        writeln!(stdout, "{}", output).and_then(|()| stdout.flush()).map_err(OutputError::Stdout)
+    }
+}
+
+/// Append one line per utterance to a file
+pub struct File(pub PathBuf);
+
+impl OutputSink for File {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        let write = || {
+            if let Some(dir) = self.0.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let mut file = OpenOptions::new().create(true).append(true).open(&self.0)?;
+            writeln!(file, "{}", output)
+        };
+        write().map_err(|source| OutputError::File { path: self.0.clone(), source })
+    }
+}
+
+/// POST `{"text": ...}` to a URL
+pub struct Webhook(pub String);
+
+impl OutputSink for Webhook {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        let response = reqwest::blocking::Client::builder()
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()?
+            .post(&self.0)
+            .header("Content-Type", "application/json")
+            .body(serde_json::json!({ "text": output }).to_string())
+            .send()?;
+        if !response.status().is_success() {
+            return Err(OutputError::WebhookStatus(response.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Write one line per utterance to a long-running shell command
+pub struct Exec(pub String);
+
+/// The running `exec:` command
+struct Pipe {
+    command: String,
+    child: Child,
+}
+
+static PIPE: Mutex<Option<Pipe>> = Mutex::new(None);
+
+impl OutputSink for Exec {
+    fn emit(&mut self, output: &str) -> Result<(), OutputError> {
+        let command = self.0.as_str();
+        let mut pipe = PIPE.lock().map_err(|_| OutputError::Poisoned)?;
+        // One retry: the command may have exited since the last line
+        for attempt in 0..2 {
+            let stale = match pipe.as_mut() {
+                Some(p) => p.command != command || !matches!(p.child.try_wait(), Ok(None)),
+                None => true,
+            };
+            if stale {
+                close(pipe.take());
+                *pipe = Some(spawn(command)?);
+            }
+            let Some(stdin) = pipe.as_mut().and_then(|p| p.child.stdin.as_mut()) else {
+                continue;
+            };
+            match writeln!(stdin, "{}", output).and_then(|()| stdin.flush()) {
+                Ok(()) => return Ok(()),
+                Err(e) if attempt == 1 => return Err(OutputError::Exec { command: command.to_string(), source: e }),
+                Err(e) => {
+                    debug!("📤 Output command stopped reading ({}), restarting it", e);
+                    close(pipe.take());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn spawn(command: &str) -> Result<Pipe, OutputError> {
+    let child = security::shell(command)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|source| OutputError::Exec { command: command.to_string(), source })?;
+    info!("📤 Sending dictation to: {}", command);
+    Ok(Pipe { command: command.to_string(), child })
+}
+
+/// Close the command's stdin and reap it in the background
+fn close(pipe: Option<Pipe>) {
+    if let Some(mut pipe) = pipe {
+        drop(pipe.child.stdin.take());
+        std::thread::spawn(move || pipe.child.wait());
+    }
+}
+
+/// `output_mode` as switched by voice (None = the config's)
+static OVERRIDE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Apply `paste_min_chars`, drop the voice switch, and stop the `exec:`
+/// command if the config no longer uses it (startup and reloads)
+pub fn configure(config: &Config) {
+    PASTE_MIN_CHARS.store(config.paste_min_chars, Ordering::SeqCst);
+    AUTO_CAPITALIZE.store(config.auto_capitalize, Ordering::SeqCst);
+    SMART_SPACING.store(config.smart_spacing, Ordering::SeqCst);
+    set_mode(None);
+    let Ok(mut pipe) = PIPE.lock() else {
+        return;
+    };
+    if pipe.as_ref().is_some_and(|p| config.output_mode.strip_prefix("exec:") != Some(p.command.as_str())) {
+        close(pipe.take());
+    }
+}
+
+/// Send dictation to another sink until the next config reload (None = back to `output_mode`)
+pub fn set_mode(name: Option<String>) {
+    if let Ok(mut mode) = OVERRIDE.lock() {
+        *mode = name;
+    }
+}
+
+/// Where dictation goes right now: the voice switch, else `output_mode`
+pub fn mode(config: &Config) -> String {
+    OVERRIDE.lock().ok().and_then(|m| m.clone()).unwrap_or_else(|| config.output_mode.clone())
+}
+
+/// Whether `output_mode` names a sink that doesn't need the keyboard
+pub fn is_sink_mode(mode: &str) -> bool {
+    mode != "type" && (SINKS.contains(&mode) || mode.starts_with("exec:"))
+}
+
+/// The sink called `name` ("clipboard", "exec:<command>"...), set up from
+/// `config`; "type" needs the caller's keyboard, so use `Typing` for it
+pub fn open(name: &str, config: &Config) -> Result<Box<dyn OutputSink>, OutputError> {
+    if let Some(command) = name.strip_prefix("exec:") {
+        return Ok(Box::new(Exec(command.to_string())));
+    }
+    match name {
+        "clipboard" => Ok(Box::new(Clipboard { notify: config.clipboard_notify })),
+        "stdout" => Ok(Box::new(Stdout)),
+        "file" if config.output_file.is_empty() => Err(OutputError::Unset { sink: "file", key: "output_file" }),
+        "file" => Ok(Box::new(File(PathBuf::from(shellexpand::tilde(&config.output_file).as_ref())))),
+        "webhook" if config.output_webhook.is_empty() => Err(OutputError::Unset { sink: "webhook", key: "output_webhook" }),
+        "webhook" => Ok(Box::new(Webhook(config.output_webhook.clone()))),
+        other => Err(OutputError::UnknownSink(other.to_string())),
+    }
+}
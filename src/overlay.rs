@@ -0,0 +1,116 @@
+//! Mouse grid overlay for SS9K
+//!
+//! This module handles:
+//! - Drawing the mouse grid's lines and cell numbers over the screen (X11,
+//!   and Wayland compositors that show XWayland windows on top)
+//! - Moving it as the grid zooms in, and taking it down when it closes
+//!
+//! The overlay is one borderless window shaped to just the lines and digits
+//! (the X shape extension), so everything between them stays visible, and
+//! with an empty input shape, so clicks go through to what's underneath.
+//! Without an X display it's skipped; the grid still works by the pointer.
+
+use crate::mouse::Grid;
+
+#[cfg(target_os = "linux")]
+use std::sync::mpsc::{self, Receiver, Sender};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+
+#[cfg(target_os = "linux")]
+use tracing::debug;
+#[cfg(target_os = "linux")]
+use x11rb::connection::{Connection, RequestConnection as _};
+#[cfg(target_os = "linux")]
+use x11rb::protocol::shape::{self, ConnectionExt as _, SK, SO};
+#[cfg(target_os = "linux")]
+use x11rb::protocol::xproto::{
+    ClipOrdering, ConfigureWindowAux, ConnectionExt as _, CreateWindowAux, Rectangle, StackMode, Window, WindowClass,
+};
+
+/// Color of the lines and numbers (0xRRGGBB on a true-color display)
+#[cfg(target_os = "linux")]
+const COLOR: u32 = 0xFF8800;
+
+/// The overlay thread, started the first time the grid opens
+#[cfg(target_os = "linux")]
+static OVERLAY: Mutex<Option<Sender<Option<Grid>>>> = Mutex::new(None);
+
+/// Draw the grid over `grid`'s area, or take it down (None)
+#[cfg(target_os = "linux")]
+pub fn show(grid: Option<Grid>) {
+    let Ok(mut overlay) = OVERLAY.lock() else {
+        return;
+    };
+    if overlay.is_none() && grid.is_none() {
+        return;
+    }
+    let tx = overlay.get_or_insert_with(|| {
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            if let Err(e) = run(&rx) {
+                debug!("🔲 No grid overlay: {}", e);
+                // Keep taking updates so senders don't fail
+                for _ in rx {}
+            }
+        });
+        tx
+    });
+    let _ = tx.send(grid);
+}
+
+/// No overlay off Linux
+#[cfg(not(target_os = "linux"))]
+pub fn show(_grid: Option<Grid>) {}
+
+/// Connect to the X server and follow the grid until SS9K exits
+#[cfg(target_os = "linux")]
+fn run(rx: &Receiver<Option<Grid>>) -> Result<(), Box<dyn std::error::Error>> {
+    let (conn, screen_num) = x11rb::connect(None)?;
+    if conn.extension_information(shape::X11_EXTENSION_NAME)?.is_none() {
+        return Err("the X server has no shape extension".into());
+    }
+    let screen = &conn.setup().roots[screen_num];
+    let window: Window = conn.generate_id()?;
+    let aux = CreateWindowAux::new().background_pixel(COLOR).override_redirect(1);
+    conn.create_window(
+        x11rb::COPY_DEPTH_FROM_PARENT,
+        window,
+        screen.root,
+        0,
+        0,
+        screen.width_in_pixels,
+        screen.height_in_pixels,
+        0,
+        WindowClass::INPUT_OUTPUT,
+        x11rb::COPY_FROM_PARENT,
+        &aux,
+    )?;
+    // Clicks land on whatever is under the overlay
+    conn.shape_rectangles(SO::SET, SK::INPUT, ClipOrdering::UNSORTED, window, 0, 0, &[])?;
+    conn.flush()?;
+
+    for grid in rx {
+        match grid {
+            Some(grid) => {
+                let rects: Vec<Rectangle> = grid.overlay().into_iter().map(rectangle).collect();
+                conn.shape_rectangles(SO::SET, SK::BOUNDING, ClipOrdering::UNSORTED, window, 0, 0, &rects)?;
+                conn.map_window(window)?;
+                conn.configure_window(window, &ConfigureWindowAux::new().stack_mode(StackMode::ABOVE))?;
+            }
+            None => {
+                conn.unmap_window(window)?;
+            }
+        }
+        conn.flush()?;
+    }
+    Ok(())
+}
+
+/// A grid rectangle in X's 16-bit coordinates
+#[cfg(target_os = "linux")]
+fn rectangle(rect: Grid) -> Rectangle {
+    let clamp = |v: i32| v.clamp(i32::from(i16::MIN), i32::from(i16::MAX)) as i16;
+    let size = |v: i32| v.clamp(0, i32::from(u16::MAX)) as u16;
+    Rectangle { x: clamp(rect.x), y: clamp(rect.y), width: size(rect.width), height: size(rect.height) }
+}
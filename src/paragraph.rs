@@ -0,0 +1,81 @@
+//! Paragraph mode for VAD dictation
+//!
+//! This module handles:
+//! - Collecting consecutive dictated utterances into one paragraph
+//! - Joining them with sensible spacing
+//! - Deciding when a pause is long enough to type the paragraph out
+//!
+//! VAD splits speech at every pause, so without this each phrase is typed
+//! on its own the moment it's transcribed. Holding them until the speaker
+//! stops (or says "command full stop") types the whole thought at once.
+
+use std::time::{Duration, Instant};
+
+/// Dictated text waiting to be typed
+#[derive(Debug, Default)]
+pub struct Paragraph {
+    text: String,
+    last_push: Option<Instant>,
+}
+
+impl Paragraph {
+    /// Add an utterance to the paragraph
+    pub fn push(&mut self, utterance: &str) {
+        let utterance = utterance.trim();
+        if utterance.is_empty() {
+            return;
+        }
+        self.text = join_utterance(&self.text, utterance);
+        self.last_push = Some(Instant::now());
+    }
+
+    /// Whether anything is waiting to be typed
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Time left before a pause of `gap` flushes the paragraph (None if empty)
+    pub fn time_left(&self, gap: Duration) -> Option<Duration> {
+        if self.is_empty() {
+            return None;
+        }
+        let elapsed = self.last_push.map_or(gap, |t| t.elapsed());
+        Some(gap.saturating_sub(elapsed))
+    }
+
+    /// End the current sentence with a period unless it already has closing punctuation
+    pub fn end_sentence(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with(['.', '!', '?', '…']) {
+            self.text.push('.');
+        }
+    }
+
+    /// Take the paragraph text, leaving it empty (None if there was nothing)
+    pub fn take(&mut self) -> Option<String> {
+        self.last_push = None;
+        if self.text.is_empty() {
+            None
+        } else {
+            Some(std::mem::take(&mut self.text))
+        }
+    }
+}
+
+/// Append `next` to `prev` with smart spacing:
+/// - no space before closing punctuation ("hello" + ", world" -> "hello, world")
+/// - no space after an opening bracket or existing whitespace
+/// - a trailing "..." (Whisper's mark for a cut-off phrase) is dropped so the sentence continues
+pub fn join_utterance(prev: &str, next: &str) -> String {
+    let prev = prev.strip_suffix("...").or_else(|| prev.strip_suffix('…')).unwrap_or(prev);
+    if prev.is_empty() {
+        return next.to_string();
+    }
+    let no_space = next.starts_with([',', '.', ';', ':', '!', '?', ')', ']', '}', '…'])
+        || prev.ends_with(['(', '[', '{'])
+        || prev.ends_with(char::is_whitespace);
+    if no_space {
+        format!("{}{}", prev, next)
+    } else {
+        format!("{} {}", prev, next)
+    }
+}
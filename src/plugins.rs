@@ -0,0 +1,298 @@
+//! WebAssembly plugins for SS9K (plugins feature)
+//!
+//! This module handles:
+//! - Loading the `.wasm` (or `.wat`) files `plugins` lists, again when the
+//!   list, a file or a plugin's `[plugin_settings.<name>]` changes
+//! - Calling their hooks: `on_transcription` (rewrite the text),
+//!   `on_command` (take an utterance over) and `on_subcommand` (leader
+//!   subcommands a plugin registers, like "command jira ...")
+//! - The `ss9k` imports plugins call back into: typing, keys, shell commands,
+//!   built-ins, logging
+//!
+//! ABI version 1. Strings are UTF-8, passed as (pointer, length) in the
+//! plugin's exported `memory`; SS9K gets room for the ones it sends with the
+//! plugin's `ss9k_alloc(len) -> ptr`. Every call runs on a fuel budget, so a
+//! plugin stuck in a loop fails its hook instead of hanging dictation, and
+//! its memory is capped at `MAX_MEMORY` (growing past it fails, -1).
+//!
+//! Exports (`ss9k_abi` and `memory` required, the rest optional):
+//! - `ss9k_abi() -> i32`: the ABI version it was built for (1)
+//! - `ss9k_alloc(len: i32) -> i32`
+//! - `ss9k_init(ptr, len)`: its settings as a JSON object; call
+//!   `subcommand` here to claim leader words
+//! - `on_transcription(ptr, len)`: call `set_text` to replace the text
+//! - `on_command(ptr, len) -> i32`: 1 = handled, SS9K does nothing else
+//! - `on_subcommand(ptr, len) -> i32`: the words after the leader; 1 = handled
+//!
+//! Imports (module `ss9k`, each taking a string): `log`, `subcommand`,
+//! `set_text`, `type_text`, `key` ("ctrl+shift+t"), `run`, `builtin`.
+//! Keys, typing and commands happen in order once the hook returns.
+
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use tracing::{info, warn};
+use wasmtime::{
+    Caller, Engine, Instance, Linker, Memory, Module, Store, StoreLimits, StoreLimitsBuilder, TypedFunc, WasmParams, WasmResults,
+};
+
+use crate::commands::{parse_action, perform_steps, Action, Phrases, Step};
+use crate::config::glob_files;
+use crate::error::PluginError;
+use crate::Config;
+
+/// ABI version this build speaks
+pub const ABI_VERSION: i32 = 1;
+
+/// Fuel for one hook call (about one unit per wasm instruction)
+const FUEL: u64 = 100_000_000;
+
+/// Most linear memory one plugin can have
+pub const MAX_MEMORY: usize = 64 << 20;
+
+/// What a plugin's calls into SS9K leave behind during one hook
+#[derive(Default)]
+struct Host {
+    steps: Vec<Step>,
+    text: Option<String>,     // From set_text
+    subcommands: Vec<String>, // From subcommand, during ss9k_init
+    name: String,
+    limits: StoreLimits,
+}
+
+struct Plugin {
+    name: String, // File name without extension
+    store: Store<Host>,
+    instance: Instance,
+    memory: Memory,
+    subcommands: Vec<String>,
+}
+
+/// Plugin files, their modification times and settings, as last loaded
+type Loaded = Vec<(PathBuf, Option<SystemTime>, String)>;
+
+static PLUGINS: Mutex<(Loaded, Vec<Plugin>)> = Mutex::new((Vec::new(), Vec::new()));
+
+/// The engine every plugin runs on, with fuel metering; the error if this
+/// platform can't have one
+static ENGINE: LazyLock<Result<Engine, String>> = LazyLock::new(|| {
+    let mut config = wasmtime::Config::new();
+    config.consume_fuel(true);
+    Engine::new(&config).map_err(|e| format!("{:#}", e))
+});
+
+fn engine() -> Result<&'static Engine, PluginError> {
+    ENGINE.as_ref().map_err(|message| PluginError::Engine(message.clone()))
+}
+
+/// Read a string out of the calling plugin's memory
+fn read(caller: &mut Caller<'_, Host>, ptr: i32, len: i32) -> wasmtime::Result<String> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(|e| e.into_memory())
+        .ok_or_else(|| wasmtime::Error::msg("plugin has no exported memory"))?;
+    let start = usize::try_from(ptr)?;
+    let bytes = memory
+        .data(&caller)
+        .get(start..start + usize::try_from(len)?)
+        .ok_or_else(|| wasmtime::Error::msg("string out of bounds"))?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// What an `ss9k` import does with the string it was passed
+type Import = fn(&mut Host, String);
+
+/// The `ss9k` imports
+fn linker(engine: &Engine) -> wasmtime::Result<Linker<Host>> {
+    let mut linker = Linker::new(engine);
+    let imports: [(&str, Import); 7] = [
+        ("log", |host, s| info!("🧩 {}: {}", host.name, s)),
+        ("subcommand", |host, s| host.subcommands.push(s.trim().to_lowercase())),
+        ("set_text", |host, s| host.text = Some(s)),
+        ("type_text", |host, s| host.steps.push(Step::Type(s))),
+        ("key", |host, s| host.steps.push(Step::Key(s))),
+        ("run", |host, s| host.steps.push(Step::Run(s))),
+        ("builtin", |host, s| host.steps.push(Step::Builtin(s))),
+    ];
+    for (name, import) in imports {
+        linker.func_wrap("ss9k", name, move |mut caller: Caller<'_, Host>, ptr: i32, len: i32| {
+            let s = read(&mut caller, ptr, len)?;
+            import(caller.data_mut(), s);
+            Ok(())
+        })?;
+    }
+    Ok(linker)
+}
+
+impl Plugin {
+    fn load(path: &Path, settings: &str) -> Result<Self, PluginError> {
+        let load_error = |e: wasmtime::Error| PluginError::Load { path: path.to_path_buf(), message: format!("{:#}", e) };
+        let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        let engine = engine()?;
+        let module = Module::from_file(engine, path).map_err(load_error)?;
+        let limits = StoreLimitsBuilder::new().memory_size(MAX_MEMORY).instances(1).build();
+        let mut store = Store::new(engine, Host { name: name.clone(), limits, ..Host::default() });
+        store.limiter(|host| &mut host.limits);
+        store.set_fuel(FUEL).map_err(load_error)?;
+        let instance = linker(engine).and_then(|l| l.instantiate(&mut store, &module)).map_err(load_error)?;
+        let memory = instance
+            .get_memory(&mut store, "memory")
+            .ok_or_else(|| load_error(wasmtime::Error::msg("no exported memory")))?;
+        let abi = instance
+            .get_typed_func::<(), i32>(&mut store, "ss9k_abi")
+            .and_then(|f| f.call(&mut store, ()))
+            .map_err(load_error)?;
+        if abi != ABI_VERSION {
+            return Err(PluginError::Load {
+                path: path.to_path_buf(),
+                message: format!("built for plugin ABI {}, this SS9K speaks {}", abi, ABI_VERSION),
+            });
+        }
+
+        let mut plugin = Self { name, store, instance, memory, subcommands: Vec::new() };
+        plugin.call_text::<()>("ss9k_init", settings)?;
+        plugin.subcommands = std::mem::take(&mut plugin.store.data_mut().subcommands);
+        Ok(plugin)
+    }
+
+    /// An export with this signature, if the plugin has one by that name
+    fn export<P: WasmParams, R: WasmResults>(&mut self, name: &'static str) -> Result<Option<TypedFunc<P, R>>, PluginError> {
+        let Some(func) = self.instance.get_func(&mut self.store, name) else {
+            return Ok(None);
+        };
+        func.typed(&self.store).map(Some).map_err(|e| self.error(name, e))
+    }
+
+    fn error(&self, hook: &'static str, e: wasmtime::Error) -> PluginError {
+        PluginError::Call { plugin: self.name.clone(), hook, message: format!("{:#}", e) }
+    }
+
+    /// Call `hook(ptr, len)` with a string, if the plugin exports it
+    fn call_text<R: WasmResults>(&mut self, hook: &'static str, text: &str) -> Result<Option<R>, PluginError> {
+        let Some(func) = self.export::<(i32, i32), R>(hook)? else {
+            return Ok(None);
+        };
+        let Some(alloc) = self.export::<i32, i32>("ss9k_alloc")? else {
+            return Err(self.error(hook, wasmtime::Error::msg("exports hooks but not ss9k_alloc")));
+        };
+        self.store.set_fuel(FUEL).map_err(|e| self.error(hook, e))?;
+        let len = i32::try_from(text.len()).map_err(|e| self.error(hook, e.into()))?;
+        let ptr = alloc.call(&mut self.store, len).map_err(|e| self.error("ss9k_alloc", e))?;
+        self.memory
+            .write(&mut self.store, usize::try_from(ptr).unwrap_or(usize::MAX), text.as_bytes())
+            .map_err(|e| self.error("ss9k_alloc", e.into()))?;
+        func.call(&mut self.store, (ptr, len)).map(Some).map_err(|e| self.error(hook, e))
+    }
+
+    /// Call a hook that returns 1 for handled, then do what it asked for
+    fn handle(&mut self, hook: &'static str, text: &str) -> Result<bool, PluginError> {
+        self.store.data_mut().steps.clear();
+        let handled = self.call_text::<i32>(hook, text)? == Some(1);
+        let steps = std::mem::take(&mut self.store.data_mut().steps);
+        if !steps.is_empty() {
+            perform_steps(&steps)?;
+        }
+        Ok(handled)
+    }
+}
+
+/// Load the plugins the config lists (startup and config reloads); unchanged
+/// plugins are kept as they are, state and all
+pub fn configure(config: &Config, config_path: Option<&Path>) {
+    let base = config_path.unwrap_or(Path::new("config.toml"));
+    let mut files = Vec::new();
+    for pattern in &config.plugins {
+        match glob_files(base, pattern) {
+            Ok(matched) if matched.is_empty() => warn!("⚠️ plugins: \"{}\" matched no files", pattern),
+            Ok(matched) => files.extend(matched),
+            Err(source) => warn!("⚠️ {}", PluginError::Pattern { pattern: pattern.clone(), source }),
+        }
+    }
+    let wanted: Loaded = files
+        .into_iter()
+        .map(|path| {
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            let name = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+            let settings = config.plugin_settings.get(&name).and_then(|t| serde_json::to_string(t).ok());
+            (path, modified, settings.unwrap_or_else(|| "{}".to_string()))
+        })
+        .collect();
+
+    let Ok(mut plugins) = PLUGINS.lock() else {
+        return;
+    };
+    if plugins.0 == wanted {
+        return;
+    }
+    let mut loaded = Vec::new();
+    for (path, _, settings) in &wanted {
+        match Plugin::load(path, settings) {
+            Ok(plugin) => {
+                if plugin.subcommands.is_empty() {
+                    info!("🧩 Plugin loaded: {}", plugin.name);
+                } else {
+                    info!("🧩 Plugin loaded: {} (command {})", plugin.name, plugin.subcommands.join(", command "));
+                }
+                loaded.push(plugin);
+            }
+            Err(e) => warn!("⚠️ {}", e),
+        }
+    }
+    *plugins = (wanted, loaded);
+}
+
+/// Names of the loaded plugins and the leader subcommands each one claimed
+pub fn loaded() -> Vec<(String, Vec<String>)> {
+    PLUGINS
+        .lock()
+        .map(|p| p.1.iter().map(|plugin| (plugin.name.clone(), plugin.subcommands.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// Let each plugin's `on_transcription` rewrite the text, in load order
+/// A failing plugin is skipped; the first failure is returned
+pub fn transform(text: &mut String) -> Result<(), PluginError> {
+    let Ok(mut plugins) = PLUGINS.lock() else {
+        return Ok(());
+    };
+    let mut first_error = None;
+    for plugin in &mut plugins.1 {
+        plugin.store.data_mut().text = None;
+        match plugin.call_text::<()>("on_transcription", text) {
+            Ok(_) => {
+                if let Some(replacement) = plugin.store.data_mut().text.take() {
+                    *text = replacement;
+                }
+            }
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Offer an utterance to the plugins: a claimed leader subcommand goes to its
+/// plugin's `on_subcommand`, anything else to each `on_command` until one
+/// handles it. True = a plugin handled it
+pub fn command(text: &str, config: &Config) -> Result<bool, PluginError> {
+    let Ok(mut plugins) = PLUGINS.lock() else {
+        return Ok(false);
+    };
+    if let Action::Builtin(cmd) = parse_action(text, &config.leader, &config.leaderless_commands, &Phrases::of(config), &config.aliases)
+        && let Some(word) = cmd.split_whitespace().next()
+        && let Some(plugin) = plugins.1.iter_mut().find(|p| p.subcommands.iter().any(|s| s == word))
+    {
+        if !plugin.handle("on_subcommand", &cmd)? {
+            warn!("⚠️ Plugin {} didn't understand 'command {}'", plugin.name, cmd);
+        }
+        return Ok(true);
+    }
+    for plugin in &mut plugins.1 {
+        if plugin.handle("on_command", text)? {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
@@ -0,0 +1,63 @@
+//! Privacy mode for SS9K
+//!
+//! With `privacy = true` (or "command privacy on") the words you say stay
+//! out of everything SS9K writes down: console and log file lines, history,
+//! the dictation log and desktop notifications show a length instead, and
+//! transcriptions aren't published over MQTT or D-Bus. Typing, commands and
+//! on-screen captions work as usual.
+//!
+//! The voice toggle lasts until the next config reload, which puts back
+//! whatever the config says.
+//!
+//! History and stats are created readable by you only on Unix, privacy mode
+//! or not (`append_private`).
+
+use std::borrow::Cow;
+use std::fs::OpenOptions;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::Config;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Apply `privacy` (startup and config reloads)
+pub fn configure(config: &Config) {
+    set(config.privacy);
+}
+
+/// Turn privacy mode on or off
+pub fn set(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// True while transcribed text must not be logged
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// The text itself, or just its length in privacy mode: "[42 chars]"
+pub fn redact(text: &str) -> Cow<'_, str> {
+    if enabled() {
+        Cow::Owned(format!("[{} chars]", text.chars().count()))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Append a line to a JSONL file under the data dir, creating it (and its
+/// directory) owner-only on Unix, like the control socket
+pub(crate) fn append_private(path: &Path, line: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(path)?;
+    writeln!(file, "{}", line)
+}
@@ -0,0 +1,170 @@
+//! Swearing mode for SS9K
+//!
+//! "command mode swearing" censors profanity in dictation. Which words count
+//! follows `language` ("auto" uses every list) plus your own `swear_words`;
+//! `swear_style` picks what a censored word becomes:
+//!
+//! - `"grawlix"`: comic-strip symbols from `grawlix`, "fuck" -> `@#$%`
+//! - `"asterisks"`: one `*` per letter, "fuck" -> `****`
+//! - `"first-letter"`: the first letter stays, "fuck" -> `f***`
+//! - `"remove"`: the word is left out
+//!
+//! Punctuation around a word stays put: "shit!" -> `@#$%!`.
+
+use std::sync::RwLock;
+
+use crate::Config;
+
+/// Profanity lists by language
+const SWEARS: &[(&str, &[&str])] = &[
+    ("en", &[
+        "fuck", "fucking", "fucked", "fucker", "fucks",
+        "shit", "shitting", "shitty", "shits",
+        "damn", "damned", "dammit",
+        "ass", "asses", "asshole", "assholes",
+        "bitch", "bitches", "bitchy",
+        "crap", "crappy",
+        "hell", "heck",
+        "bastard", "bastards",
+        "dick", "dicks",
+        "piss", "pissed", "pissing",
+        "cock", "cocks",
+        "cunt", "cunts",
+    ]),
+    ("de", &[
+        "scheiße", "scheisse", "scheiß", "scheiss", "verdammt", "verdammte",
+        "arsch", "arschloch", "ficken", "fick", "fotze", "wichser",
+        "kacke", "mist", "miststück", "hure", "schlampe",
+    ]),
+    ("fr", &[
+        "merde", "putain", "bordel", "connard", "connasse", "con", "conne",
+        "salope", "enculé", "chier", "foutre", "pute", "bite",
+    ]),
+    ("es", &[
+        "mierda", "joder", "coño", "puta", "puto", "cabrón", "cabron",
+        "gilipollas", "hostia", "carajo", "pendejo", "chingar", "verga",
+    ]),
+    ("it", &[
+        "cazzo", "merda", "stronzo", "stronza", "vaffanculo", "puttana",
+        "minchia", "coglione", "figa",
+    ]),
+    ("nl", &[
+        "kut", "godverdomme", "verdomme", "klootzak", "lul", "shit",
+        "kanker", "tering", "hoer",
+    ]),
+    ("pt", &[
+        "merda", "porra", "caralho", "foda", "puta", "cacete", "buceta",
+        "viado", "desgraçado",
+    ]),
+];
+
+/// The symbols `"grawlix"` cycles through by default
+pub const GRAWLIX: &str = "@#$%!&*";
+
+/// What a censored word becomes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Grawlix,
+    Asterisks,
+    FirstLetter,
+    Remove,
+}
+
+impl Style {
+    /// The `swear_style` names: "grawlix", "asterisks", "first-letter", "remove"
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grawlix" => Some(Style::Grawlix),
+            "asterisks" => Some(Style::Asterisks),
+            "first-letter" => Some(Style::FirstLetter),
+            "remove" => Some(Style::Remove),
+            _ => None,
+        }
+    }
+}
+
+/// The words to censor and how
+#[derive(Debug, Clone)]
+pub struct Censor {
+    words: Vec<String>,
+    style: Style,
+    grawlix: Vec<char>,
+}
+
+impl Censor {
+    /// The lists for `language` ("auto" = every language's) plus your own words
+    /// An empty `grawlix` means the default symbols.
+    pub fn new(language: &str, extra: &[String], style: Style, grawlix: &str) -> Self {
+        let mut words: Vec<String> = SWEARS
+            .iter()
+            .filter(|(lang, _)| language == "auto" || lang.eq_ignore_ascii_case(language))
+            .flat_map(|(_, words)| words.iter().map(|w| w.to_string()))
+            .collect();
+        words.extend(extra.iter().map(|w| w.trim().to_lowercase()).filter(|w| !w.is_empty()));
+        let grawlix = if grawlix.is_empty() { GRAWLIX } else { grawlix };
+        Censor { words, style, grawlix: grawlix.chars().collect() }
+    }
+
+    /// The text with its swear words censored (whitespace between words becomes one space)
+    pub fn apply(&self, text: &str) -> String {
+        let mut words: Vec<String> = Vec::new();
+        for word in text.split_whitespace() {
+            match self.word(word) {
+                Censored::Word(word) => words.push(word),
+                // "that's shit!" -> "that's!"
+                Censored::Removed(trailing) => {
+                    if let Some(last) = words.last_mut() {
+                        last.push_str(trailing);
+                    }
+                }
+            }
+        }
+        words.join(" ")
+    }
+
+    /// One word, censored if it's on the list
+    fn word<'a>(&self, word: &'a str) -> Censored<'a> {
+        let core = word.trim_matches(|c: char| !c.is_alphanumeric());
+        if core.is_empty() || !self.words.contains(&core.to_lowercase()) {
+            return Censored::Word(word.to_string());
+        }
+        let start = word.len() - word.trim_start_matches(|c: char| !c.is_alphanumeric()).len();
+        let (before, after) = (&word[..start], &word[start + core.len()..]);
+
+        let letters = core.chars().count();
+        let censored: String = match self.style {
+            // Similar length, but always recognizably a grawlix
+            Style::Grawlix => self.grawlix.iter().cycle().take(letters.clamp(3, 7)).collect(),
+            Style::Asterisks => "*".repeat(letters),
+            Style::FirstLetter => core.chars().take(1).chain("*".repeat(letters - 1).chars()).collect(),
+            Style::Remove => return Censored::Removed(after),
+        };
+        Censored::Word(format!("{}{}{}", before, censored, after))
+    }
+}
+
+/// A word after censoring: what to type, or the punctuation a removed one leaves
+enum Censored<'a> {
+    Word(String),
+    Removed(&'a str),
+}
+
+static CENSOR: RwLock<Option<Censor>> = RwLock::new(None);
+
+/// Apply `swear_style`, `swear_words`, `grawlix` and `language` (startup and config reloads)
+pub fn configure(config: &Config) {
+    let style = Style::from_name(&config.swear_style).unwrap_or(Style::Grawlix);
+    let censor = Censor::new(&config.language, &config.swear_words, style, &config.grawlix);
+    if let Ok(mut current) = CENSOR.write() {
+        *current = Some(censor);
+    }
+}
+
+/// Censor dictation with the configured lists and style; English grawlix
+/// until `configure` runs
+pub fn censor(text: &str) -> String {
+    if let Some(censor) = CENSOR.read().ok().as_deref().and_then(Option::as_ref) {
+        return censor.apply(text);
+    }
+    Censor::new("en", &[], Style::Grawlix, GRAWLIX).apply(text)
+}
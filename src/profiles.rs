@@ -0,0 +1,58 @@
+//! Named config profiles for SS9K
+//!
+//! This module handles:
+//! - Which `[profiles.<name>]` table is active (`--profile`, "command
+//!   profile <name>", `ss9k ctl profile <name>`)
+//! - Layering it over the config: its commands and aliases are added to the
+//!   top-level ones and win when a phrase is in both
+//!
+//! The active profile is merged in every time the config file is loaded, so
+//! it stays active across hot reloads. Its `mode` is applied when it's
+//! selected, not on every reload, so a mode you switched to by hand sticks.
+
+use std::sync::Mutex;
+
+use tracing::warn;
+
+use crate::config::Profile;
+use crate::Config;
+
+static ACTIVE: Mutex<Option<String>> = Mutex::new(None);
+
+/// Name of the active profile, if any
+pub fn active() -> Option<String> {
+    ACTIVE.lock().ok().and_then(|a| a.clone())
+}
+
+/// Make `name` the active profile (None = no profile); takes effect on the next load
+pub fn set(name: Option<String>) {
+    if let Ok(mut active) = ACTIVE.lock() {
+        *active = name;
+    }
+}
+
+/// Look a profile up by name, ignoring case
+pub fn find<'a>(config: &'a Config, name: &str) -> Option<(&'a String, &'a Profile)> {
+    config.profiles.iter().find(|(n, _)| n.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Names of the configured profiles, sorted
+pub fn names(config: &Config) -> Vec<&str> {
+    let mut names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+    names.sort();
+    names
+}
+
+/// Merge the active profile into a freshly loaded config
+pub fn apply(config: &mut Config) {
+    let Some(name) = active() else {
+        return;
+    };
+    let Some((_, profile)) = find(config, &name) else {
+        warn!("⚠️ No profile '{}' in the config (have: {}), using none", name, names(config).join(", "));
+        return;
+    };
+    let profile = profile.clone();
+    config.commands.extend(profile.commands);
+    config.aliases.extend(profile.aliases);
+}
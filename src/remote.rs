@@ -0,0 +1,108 @@
+//! Remote record trigger for SS9K (http feature)
+//!
+//! This module handles:
+//! - Listening on `remote_listen` (a LAN address, unlike the localhost-only
+//!   control API) so a phone, smart button or another machine can start and
+//!   stop dictation
+//! - `GET /status` and `POST /record/start|stop|toggle`, nothing else: no
+//!   typing, no mode or config changes
+//! - Requiring `remote_token` on every request, as `Authorization: Bearer`
+//!   or `?token=` for buttons that can only set a URL (`%XX` escapes in it
+//!   are decoded; `+` is taken as it is, not as a space)
+//!
+//! Plain HTTP, so the token is readable by anyone on the network path; keep
+//! it to a trusted LAN or VPN.
+
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+use arc_swap::ArcSwap;
+use tiny_http::{Method, Request, Response, Server};
+use tracing::{info, warn};
+
+use crate::control::{self, RecordRequest};
+use crate::error::HttpError;
+use crate::http::{json, HttpResponse};
+use crate::Config;
+
+/// Shortest `remote_token` we start with
+pub const MIN_TOKEN_LEN: usize = 16;
+
+/// Start the remote listener on `addr` in a background thread
+/// The token is read per request, so changing it needs no restart.
+pub fn start(addr: &str, config: Arc<ArcSwap<Config>>) -> Result<JoinHandle<()>, HttpError> {
+    if config.load().remote_token.trim().len() < MIN_TOKEN_LEN {
+        return Err(HttpError::WeakToken(MIN_TOKEN_LEN));
+    }
+    let server = Server::http(addr).map_err(|source| HttpError::Bind { addr: addr.to_string(), source })?;
+    info!("📱 Remote trigger listening on http://{}", addr);
+
+    Ok(std::thread::spawn(move || {
+        for request in server.incoming_requests() {
+            let response = route(&request, &config.load());
+            if let Err(e) = request.respond(response) {
+                warn!("⚠️ Remote response failed: {}", e);
+            }
+        }
+    }))
+}
+
+/// Check the token, then pick a response
+fn route(request: &Request, config: &Config) -> HttpResponse {
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    if !authorized(request, query, config.remote_token.trim()) {
+        let peer = request.remote_addr().map(|a| a.to_string()).unwrap_or_default();
+        warn!("⚠️ Remote request from {} rejected: bad or missing token", peer);
+        return Response::from_string("Unauthorized\n").with_status_code(401);
+    }
+    let record = match (request.method(), path) {
+        (Method::Get, "/status") => return json(&control::status(config)),
+        (Method::Post, "/record/start") => RecordRequest::Start,
+        (Method::Post, "/record/stop") => RecordRequest::Stop,
+        (Method::Post, "/record/toggle") => RecordRequest::Toggle,
+        _ => return Response::from_string("Not found\n").with_status_code(404),
+    };
+    if !control::request(record) {
+        return Response::from_string("Recording can't be controlled right now\n").with_status_code(503);
+    }
+    info!("📱 Remote {:?}", record);
+    json(&serde_json::json!({ "ok": true }))
+}
+
+/// Whether the request carries `token`, in the Authorization header or the query
+fn authorized(request: &Request, query: &str, token: &str) -> bool {
+    if token.len() < MIN_TOKEN_LEN {
+        return false;
+    }
+    let bearer = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Authorization"))
+        .and_then(|h| h.value.as_str().strip_prefix("Bearer ").map(str::to_string));
+    let queried = query.split('&').find_map(|pair| pair.strip_prefix("token=")).and_then(percent_decode);
+    [bearer, queried].into_iter().flatten().any(|given| same(given.trim(), token))
+}
+
+/// `%XX` escapes decoded; None if one is broken or the result isn't UTF-8
+fn percent_decode(value: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&byte, after)) = rest.split_first() {
+        if byte == b'%' {
+            // Two hex digits exactly: from_str_radix alone would take "%+1"
+            let hex = after.get(..2).filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))?;
+            bytes.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+            rest = &after[2..];
+        } else {
+            bytes.push(byte);
+            rest = after;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Compare without stopping at the first difference, so response timing
+/// doesn't give the token away a byte at a time
+fn same(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
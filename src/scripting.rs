@@ -0,0 +1,162 @@
+//! Script hooks for SS9K (scripting feature)
+//!
+//! This module handles:
+//! - Loading the Rhai script `script` points at, and again whenever the file
+//!   changes (checked as each utterance comes in)
+//! - `transform(text)`: rewrite every transcription before anything else
+//!   looks at it
+//! - `command(text)`: handle an utterance in the script, pressing keys,
+//!   typing, running programs and built-ins through `key`, `type_text`, `run`
+//!   and `builtin`; returning true means SS9K does nothing else with it
+//!
+//! Both hooks are optional. Scripts can't touch files or the network (apart
+//! from what `run` starts) and are stopped after `MAX_OPERATIONS`, so a
+//! runaway loop fails the hook instead of hanging dictation.
+
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use rhai::{Dynamic, Engine, Scope, AST};
+use tracing::{info, warn};
+
+use crate::commands::{get_case_mode, perform_steps, Step};
+use crate::error::ScriptError;
+use crate::{apps, Config};
+
+/// Most operations one hook call may take
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// The loaded script
+struct Script {
+    path: PathBuf,
+    modified: Option<SystemTime>, // When it was loaded, to notice edits
+    ast: Option<AST>,             // None = it didn't compile (warned once)
+}
+
+static SCRIPT: Mutex<Option<Script>> = Mutex::new(None);
+static STEPS: Mutex<Vec<Step>> = Mutex::new(Vec::new());
+
+static ENGINE: LazyLock<Engine> = LazyLock::new(|| {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|s| info!("📜 {}", s));
+    engine.on_debug(|s, _, _| info!("📜 {}", s));
+    let step = |step: Step| {
+        if let Ok(mut steps) = STEPS.lock() {
+            steps.push(step);
+        }
+    };
+    engine.register_fn("key", move |combo: &str| step(Step::Key(combo.to_string())));
+    engine.register_fn("type_text", move |text: &str| step(Step::Type(text.to_string())));
+    engine.register_fn("run", move |cmd: &str| step(Step::Run(cmd.to_string())));
+    engine.register_fn("builtin", move |cmd: &str| step(Step::Builtin(cmd.to_string())));
+    engine.register_fn("mode", || format!("{:?}", get_case_mode()).to_lowercase());
+    engine.register_fn("app", || apps::focused().unwrap_or_default());
+    engine
+});
+
+/// Load the script `script` names, or drop it (startup and config reloads)
+pub fn configure(config: &Config) {
+    let Ok(mut script) = SCRIPT.lock() else {
+        return;
+    };
+    if config.script.is_empty() {
+        *script = None;
+        return;
+    }
+    let path = PathBuf::from(shellexpand::tilde(&config.script).as_ref());
+    if script.as_ref().is_some_and(|s| s.path == path) {
+        return;
+    }
+    let mut loaded = Script { path, modified: None, ast: None };
+    loaded.refresh(true);
+    *script = Some(loaded);
+}
+
+impl Script {
+    /// Compile the file (`force`), or recompile if it changed since last time
+    fn refresh(&mut self, force: bool) {
+        let modified = std::fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if !force && modified == self.modified {
+            return;
+        }
+        let reloading = !force;
+        self.modified = modified;
+        match self.compile() {
+            Ok(ast) => {
+                info!("📜 Script {}: {:?}", if reloading { "reloaded" } else { "loaded" }, self.path);
+                self.ast = Some(ast);
+            }
+            Err(e) => {
+                warn!("⚠️ {}", e);
+                self.ast = None;
+            }
+        }
+    }
+
+    fn compile(&self) -> Result<AST, ScriptError> {
+        let source = std::fs::read_to_string(&self.path)
+            .map_err(|source| ScriptError::Read { path: self.path.clone(), source })?;
+        ENGINE
+            .compile(source)
+            .map_err(|e| ScriptError::Compile { path: self.path.clone(), message: e.to_string() })
+    }
+}
+
+/// Call `hook(text)` if the script defines it
+fn call(hook: &'static str, text: &str) -> Result<Option<Dynamic>, ScriptError> {
+    let Ok(mut script) = SCRIPT.lock() else {
+        return Ok(None);
+    };
+    let Some(script) = script.as_mut() else {
+        return Ok(None);
+    };
+    script.refresh(false);
+    let Some(ast) = &script.ast else {
+        return Ok(None);
+    };
+    if !ast.iter_functions().any(|f| f.name == hook && f.params.len() == 1) {
+        return Ok(None);
+    }
+    ENGINE
+        .call_fn::<Dynamic>(&mut Scope::new(), ast, hook, (text.to_string(),))
+        .map(Some)
+        .map_err(|e| ScriptError::Run { hook, message: e.to_string() })
+}
+
+/// Run `transform(text)`: the text it returns replaces the transcription
+/// (returning nothing leaves it as it was, "" drops it)
+pub fn transform(text: &str) -> Result<String, ScriptError> {
+    match call("transform", text)? {
+        Some(result) if !result.is_unit() => result.into_string().map_err(|type_name| ScriptError::Run {
+            hook: "transform",
+            message: format!("returned {} instead of a string", type_name),
+        }),
+        _ => Ok(text.to_string()),
+    }
+}
+
+/// Run `command(text)` and whatever it asked for; true = the script handled
+/// the utterance (returning false or nothing lets SS9K handle it as usual)
+pub fn command(text: &str) -> Result<bool, ScriptError> {
+    if let Ok(mut steps) = STEPS.lock() {
+        steps.clear();
+    }
+    let Some(result) = call("command", text)? else {
+        return Ok(false);
+    };
+    let handled = if result.is_unit() {
+        false
+    } else {
+        result.as_bool().map_err(|type_name| ScriptError::Run {
+            hook: "command",
+            message: format!("returned {} instead of true/false", type_name),
+        })?
+    };
+    let steps = STEPS.lock().map(|mut s| std::mem::take(&mut *s)).unwrap_or_default();
+    if !steps.is_empty() {
+        perform_steps(&steps)?;
+    }
+    Ok(handled)
+}
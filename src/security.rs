@@ -0,0 +1,166 @@
+//! Shell command policy for SS9K
+//!
+//! `[commands]` entries, `shell` and `type_shell` steps, shell `[[patterns]]`
+//! and `{shell:...}` placeholders all start programs, and a misheard phrase
+//! (or words captured into a {placeholder}) goes along with them. The
+//! `[security]` table narrows that down:
+//! - `allowed_programs`: only these programs may be started, and the command
+//!   can't use pipes, `;`, redirects or `$(...)` to start anything else
+//! - `shell = false`: no `sh -c` at all; the command is split into words like
+//!   sh would and the first one runs with the rest as arguments
+//! - `refuse_chars`: a command containing any of these doesn't run
+//!
+//! Commands are split with sh's quoting rules everywhere. cmd.exe doesn't
+//! honor those quotes, so on Windows `allowed_programs` refuses its
+//! operators (`& | < > ^ %`) even when they're quoted.
+//!
+//! Hooks, the exec output and custom TTS commands come straight from the
+//! config file, not from speech, so they're left alone: they go through
+//! `shell`, the same `sh -c` (`cmd /C` on Windows) without the checks.
+
+use std::process::Command;
+use std::sync::RwLock;
+
+use crate::config::Security;
+use crate::error::SecurityError;
+use crate::privacy;
+use crate::Config;
+
+static POLICY: RwLock<Option<Security>> = RwLock::new(None);
+
+/// Characters sh treats as more than part of a word
+const SHELL_OPERATORS: [char; 10] = ['|', '&', ';', '>', '<', '$', '`', '(', ')', '\n'];
+
+/// Characters cmd.exe acts on whatever sh-style quotes are around them
+#[cfg(target_os = "windows")]
+const CMD_OPERATORS: [char; 7] = ['|', '&', '>', '<', '^', '%', '\n'];
+
+/// Apply `[security]` (startup and config reloads)
+pub fn configure(config: &Config) {
+    if let Ok(mut policy) = POLICY.write() {
+        *policy = Some(config.security.clone());
+    }
+}
+
+/// The process to start for a shell command, or why `[security]` refuses it
+pub fn command(cmd: &str) -> Result<Command, SecurityError> {
+    let policy = POLICY.read().ok().and_then(|p| p.clone()).unwrap_or_default();
+    check(cmd, &policy)?;
+
+    if !policy.shell {
+        let words = split_words(cmd).ok_or_else(|| SecurityError::Quotes(privacy::redact(cmd).into_owned()))?.words;
+        let (program, args) = words.split_first().ok_or(SecurityError::Empty)?;
+        let mut command = Command::new(program);
+        command.args(args);
+        return Ok(command);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    if cmd.split_whitespace().count() == 1 && !cmd.contains(SHELL_OPERATORS) {
+        return Ok(Command::new(cmd.trim()));
+    }
+    Ok(shell(cmd))
+}
+
+/// `cmd` run by the platform shell: `sh -c` (`cmd /C` on Windows), with no
+/// policy applied, for commands that come from the config file
+pub fn shell(cmd: &str) -> Command {
+    #[cfg(target_os = "windows")]
+    let (program, flag) = ("cmd", "/C");
+    #[cfg(not(target_os = "windows"))]
+    let (program, flag) = ("sh", "-c");
+    let mut command = Command::new(program);
+    command.args([flag, cmd]);
+    command
+}
+
+/// Whether `policy` lets `cmd` run; the error names the command unless
+/// privacy mode is on, since placeholders put spoken words in it
+pub fn check(cmd: &str, policy: &Security) -> Result<(), SecurityError> {
+    if let Some(ch) = cmd.chars().find(|c| policy.refuse_chars.contains(*c)) {
+        return Err(SecurityError::Char { command: privacy::redact(cmd).into_owned(), ch });
+    }
+    if policy.allowed_programs.is_empty() {
+        return Ok(());
+    }
+
+    let split = split_words(cmd).ok_or_else(|| SecurityError::Quotes(privacy::redact(cmd).into_owned()))?;
+    #[cfg(target_os = "windows")]
+    let operators = split.operators || cmd.contains(CMD_OPERATORS);
+    #[cfg(not(target_os = "windows"))]
+    let operators = split.operators;
+    if policy.shell && operators {
+        return Err(SecurityError::Operators(privacy::redact(cmd).into_owned()));
+    }
+    let program = split.words.first().ok_or(SecurityError::Empty)?;
+    if !policy.allowed_programs.iter().any(|p| p == program) {
+        return Err(SecurityError::Program { command: privacy::redact(cmd).into_owned(), program: program.clone() });
+    }
+    Ok(())
+}
+
+/// A command split into words, and whether sh would do more with it than
+/// run one program (an unquoted pipe, `;`, redirect or substitution)
+pub struct Words {
+    pub words: Vec<String>,
+    pub operators: bool,
+}
+
+/// Split a command into words the way sh does: whitespace separates them,
+/// '...' is literal, "..." keeps spaces (\" \\ \$ \` escape inside it) and a
+/// backslash outside quotes escapes the next character
+/// None if a quote is left open.
+pub fn split_words(cmd: &str) -> Option<Words> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut operators = false;
+    let mut chars = cmd.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() && c != '\n' => {
+                words.extend(word.take());
+            }
+            '\'' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => current.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let current = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\' | '$' | '`') => current.push(c),
+                            c => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                        },
+                        c => {
+                            // A lone "$" (how fill_shell quotes one) stays literal
+                            operators |= c == '`' || (c == '$' && chars.peek() != Some(&'"'));
+                            current.push(c);
+                        }
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c => {
+                operators |= SHELL_OPERATORS.contains(&c);
+                word.get_or_insert_with(String::new).push(c);
+            }
+        }
+    }
+    words.extend(word);
+    Some(Words { words, operators })
+}
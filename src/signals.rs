@@ -0,0 +1,94 @@
+//! POSIX signal controls for SS9K (Unix only)
+//!
+//! This module handles:
+//! - SIGUSR1: toggle recording (VAD mode: toggle listening)
+//! - SIGUSR2: cycle to the next case mode
+//! - SIGHUP: reload the config file now
+//! - SIGINT/SIGTERM: shut down cleanly (release held keys, finish queued
+//!   transcriptions); a second one exits right away
+//!
+//! So a window manager keybinding can do `pkill -USR1 ss9k` instead of
+//! relying on rdev's global hooks (which Wayland doesn't allow).
+
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use arc_swap::ArcSwap;
+use signal_hook::consts::{SIGHUP, SIGINT, SIGTERM, SIGUSR1, SIGUSR2};
+use signal_hook::iterator::Signals;
+use tracing::{info, warn};
+
+use crate::commands::release_everything;
+use crate::control::{self, RecordRequest, State};
+use crate::error::SignalError;
+use crate::{daemon, metrics, Config, RECORDING, VAD_LISTENING};
+
+/// Longest we wait for queued transcriptions on shutdown
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(30);
+
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Install the handlers; signals are acted on in a background thread
+pub fn start(config: Arc<ArcSwap<Config>>, config_path: Option<PathBuf>) -> Result<JoinHandle<()>, SignalError> {
+    let mut signals = Signals::new([SIGUSR1, SIGUSR2, SIGHUP, SIGINT, SIGTERM])?;
+    Ok(std::thread::spawn(move || {
+        for signal in signals.forever() {
+            match signal {
+                // A guard would hide that the toggle happens on every SIGUSR1
+                #[allow(clippy::collapsible_match)]
+                SIGUSR1 => {
+                    if !control::request(RecordRequest::Toggle) {
+                        warn!("⚠️ SIGUSR1: recording can't be controlled right now");
+                    }
+                }
+                SIGUSR2 => {
+                    let mode = control::cycle_mode(&config.load());
+                    info!("🔀 Mode: {:?}", mode);
+                }
+                SIGHUP => {
+                    if let Err(e) = control::reload(&config, config_path.as_deref()) {
+                        warn!("⚠️ SIGHUP: {}", e);
+                    }
+                }
+                SIGINT | SIGTERM if SHUTTING_DOWN.swap(true, Ordering::SeqCst) => {
+                    info!("👋 Not waiting, exiting now");
+                    release_everything();
+                    std::process::exit(1);
+                }
+                // On its own thread so a second signal can cut it short
+                SIGINT | SIGTERM => {
+                    std::thread::spawn(shutdown);
+                }
+                _ => {}
+            }
+        }
+    }))
+}
+
+/// Stop taking audio, let go of held keys, finish what's queued, then exit
+fn shutdown() {
+    info!("👋 Shutting down...");
+    let _ = daemon::notify("STOPPING=1");
+    RECORDING.store(false, Ordering::SeqCst);
+    VAD_LISTENING.store(false, Ordering::SeqCst);
+    release_everything();
+
+    let start = Instant::now();
+    while (metrics::queue_depth() > 0 || control::state() == State::Processing) && start.elapsed() < FLUSH_TIMEOUT {
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    // Typing the last transcription may have pressed keys too
+    release_everything();
+
+    // Progress bars hide the cursor; don't leave the terminal without one
+    let mut stdout = std::io::stdout();
+    if stdout.is_terminal() {
+        let _ = write!(stdout, "\x1b[?25h");
+        let _ = stdout.flush();
+    }
+    std::process::exit(0);
+}
@@ -0,0 +1,172 @@
+//! Audible feedback cues for SS9K
+//!
+//! This module handles:
+//! - Generated tones for record start, record stop, command done, error and
+//!   a voice timer ending
+//! - Custom cue sounds from files (anything `decode_file` can read)
+//! - Playback on the default output device
+//!
+//! The terminal bell is only a fallback now: it's silent when the terminal is
+//! hidden, which is exactly when you need to know the hotkey registered.
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::audio::{decode_file, resample_audio};
+use crate::error::AudioError;
+use crate::Config;
+
+/// Moments that get a sound
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cue {
+    Start,   // Recording/listening started
+    Stop,    // Recording stopped, audio sent for processing
+    Command, // Utterance handled (command executed or text typed)
+    Error,   // Something failed
+    Timer,   // A voice timer ended
+}
+
+/// Decoded sound: mono samples and their sample rate
+type Sound = Arc<(Vec<f32>, u32)>;
+
+/// Decoded custom sound files, keyed by path
+static SOUND_FILES: LazyLock<Mutex<HashMap<String, Sound>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Play the sound for a cue in the background (no-op unless audio_feedback is on)
+pub fn play_cue(config: &Config, cue: Cue) {
+    if !config.audio_feedback {
+        return;
+    }
+    let path = match cue {
+        Cue::Start => &config.sound_start,
+        Cue::Stop => &config.sound_stop,
+        Cue::Command => &config.sound_command,
+        Cue::Error => &config.sound_error,
+        Cue::Timer => &config.sound_timer,
+    };
+    ring(cue, path);
+}
+
+/// Play a cue's sound (`path`, or its tone when empty) in the background
+/// whatever audio_feedback says (for things asked for, like timers)
+pub fn ring(cue: Cue, path: &str) {
+    let path = path.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = play(cue, &path) {
+            warn!("⚠️ Sound cue failed: {}", e);
+            terminal_bell();
+        }
+    });
+}
+
+/// Ring the terminal bell (fallback when there's no output device)
+fn terminal_bell() {
+    use std::io::Write;
+    print!("\x07");
+    let _ = std::io::stdout().flush();
+}
+
+/// Notes (frequency Hz, duration ms) for the generated tones; 0 Hz is a rest
+fn tone_notes(cue: Cue) -> &'static [(f32, u32)] {
+    match cue {
+        Cue::Start => &[(660.0, 60), (880.0, 90)],
+        Cue::Stop => &[(880.0, 60), (660.0, 90)],
+        Cue::Command => &[(1047.0, 50), (0.0, 40), (1047.0, 50)],
+        Cue::Error => &[(220.0, 120), (0.0, 40), (185.0, 200)],
+        Cue::Timer => &[(1319.0, 120), (0.0, 80), (1319.0, 120), (0.0, 80), (1568.0, 240)],
+    }
+}
+
+/// Synthesize a cue's tone at the given sample rate
+fn generate_tone(cue: Cue, rate: u32) -> Vec<f32> {
+    let fade = (rate / 200) as usize; // 5ms fade in/out to avoid clicks
+    let mut samples = Vec::new();
+    for &(freq, ms) in tone_notes(cue) {
+        let len = (rate as u64 * ms as u64 / 1000) as usize;
+        for i in 0..len {
+            let envelope = (i.min(len - i) as f32 / fade.max(1) as f32).min(1.0);
+            let phase = 2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32;
+            samples.push(0.3 * envelope * phase.sin());
+        }
+    }
+    samples
+}
+
+/// Load (and cache) a custom sound file, mono at its native rate
+fn load_sound(path: &str) -> Result<Sound, AudioError> {
+    if let Some(sound) = SOUND_FILES.lock().map_err(|_| AudioError::Poisoned)?.get(path) {
+        return Ok(sound.clone());
+    }
+    let expanded = shellexpand::tilde(path);
+    let sound = Arc::new(decode_file(Path::new(expanded.as_ref()))?);
+    SOUND_FILES.lock().map_err(|_| AudioError::Poisoned)?.insert(path.to_string(), sound.clone());
+    Ok(sound)
+}
+
+/// Play a cue on the default output device, blocking until it finishes
+fn play(cue: Cue, path: &str) -> Result<(), AudioError> {
+    let device = cpal::default_host().default_output_device().ok_or(AudioError::NoOutputDevice)?;
+    let config = device.default_output_config()?;
+    let rate = config.sample_rate().0;
+
+    let samples = if path.is_empty() {
+        generate_tone(cue, rate)
+    } else {
+        let sound = load_sound(path)?;
+        resample_audio(&sound.0, sound.1, rate)?
+    };
+    let duration = Duration::from_millis(samples.len() as u64 * 1000 / rate.max(1) as u64);
+
+    let stream_config: cpal::StreamConfig = config.clone().into();
+    let channels = stream_config.channels as usize;
+    let stream = match config.sample_format() {
+        SampleFormat::I8 => build_output::<i8>(&device, &stream_config, samples, channels),
+        SampleFormat::I16 => build_output::<i16>(&device, &stream_config, samples, channels),
+        SampleFormat::I32 => build_output::<i32>(&device, &stream_config, samples, channels),
+        SampleFormat::I64 => build_output::<i64>(&device, &stream_config, samples, channels),
+        SampleFormat::U8 => build_output::<u8>(&device, &stream_config, samples, channels),
+        SampleFormat::U16 => build_output::<u16>(&device, &stream_config, samples, channels),
+        SampleFormat::U32 => build_output::<u32>(&device, &stream_config, samples, channels),
+        SampleFormat::U64 => build_output::<u64>(&device, &stream_config, samples, channels),
+        SampleFormat::F32 => build_output::<f32>(&device, &stream_config, samples, channels),
+        SampleFormat::F64 => build_output::<f64>(&device, &stream_config, samples, channels),
+        format => Err(AudioError::UnsupportedFormat(format)),
+    }?;
+
+    stream.play()?;
+    // Let the device drain its buffer before the stream is dropped
+    std::thread::sleep(duration + Duration::from_millis(100));
+    Ok(())
+}
+
+/// Build an output stream that plays `samples` once (mono, copied to every channel)
+fn build_output<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    samples: Vec<f32>,
+    channels: usize,
+) -> Result<cpal::Stream, AudioError>
+where
+    T: SizedSample + FromSample<f32>,
+{
+    let mut position = 0;
+    let stream = device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+            for frame in data.chunks_mut(channels) {
+                let sample = T::from_sample(samples.get(position).copied().unwrap_or(0.0));
+                frame.fill(sample);
+                position += 1;
+            }
+        },
+        |err| warn!("Output stream error: {}", err),
+        None,
+    )?;
+    Ok(stream)
+}
@@ -0,0 +1,196 @@
+//! Usage statistics for SS9K
+//!
+//! This module handles:
+//! - One line per utterance in `stats.jsonl` (data dir): model, audio length,
+//!   time spent per stage, word count, command or not - never the text
+//! - Daily/weekly summaries and a per-model latency table for `ss9k stats`
+//!
+//! Meant to answer "is the bigger model worth the CPU?" with your own
+//! numbers. `save_stats = false` turns it off. On Unix the file is readable
+//! by you only, since it shows when you use SS9K.
+
+use chrono::{DateTime, Datelike, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tracing::warn;
+
+use crate::error::StatsError;
+use crate::privacy;
+use crate::Config;
+
+/// One processed utterance
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sample {
+    pub timestamp: String,   // Local time, RFC 3339
+    pub model: String,
+    pub audio_secs: f32,     // Length of what was said
+    pub resample_ms: u64,    // Converting to 16kHz
+    pub wake_word_ms: u64,   // Wake word check (0 without one)
+    pub transcribe_ms: u64,  // Whisper
+    pub output_ms: u64,      // Typing / running the command
+    pub words: usize,        // Words typed (0 for commands)
+    pub command: bool,
+}
+
+static FILE: Mutex<Option<PathBuf>> = Mutex::new(None); // None = not saving
+static WRITE_FAILED: AtomicBool = AtomicBool::new(false); // Warn once, not per utterance
+
+/// Default stats file: `<data dir>/ss9k/stats.jsonl`
+pub fn default_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(|| PathBuf::from(".")).join("ss9k").join("stats.jsonl")
+}
+
+/// Apply `save_stats` (startup and config reloads)
+pub fn configure(config: &Config) {
+    if let Ok(mut file) = FILE.lock() {
+        *file = config.save_stats.then(default_path);
+    }
+}
+
+/// Save a sample, if enabled
+pub fn record(sample: &Sample) {
+    let Some(path) = FILE.lock().ok().and_then(|f| f.clone()) else {
+        return;
+    };
+    match append(&path, sample) {
+        Ok(()) => WRITE_FAILED.store(false, Ordering::Relaxed),
+        Err(e) if !WRITE_FAILED.swap(true, Ordering::Relaxed) => warn!("⚠️ {}", e),
+        Err(_) => {}
+    }
+}
+
+fn append(path: &Path, sample: &Sample) -> Result<(), StatsError> {
+    let write_error = |source| StatsError::Write { path: path.to_path_buf(), source };
+    let line = serde_json::to_string(sample).map_err(|e| write_error(e.into()))?;
+    privacy::append_private(path, &line).map_err(write_error)
+}
+
+/// Read the stats file, oldest first (missing file = no stats; bad lines are skipped)
+pub fn load(path: &Path) -> Result<Vec<Sample>, StatsError> {
+    let file = match std::fs::File::open(path) {
+        Ok(f) => f,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(StatsError::Read { path: path.to_path_buf(), source }),
+    };
+    let mut samples = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.map_err(|source| StatsError::Read { path: path.to_path_buf(), source })?;
+        if let Ok(sample) = serde_json::from_str(&line) {
+            samples.push(sample);
+        }
+    }
+    Ok(samples)
+}
+
+/// How `ss9k stats` groups samples
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Period {
+    #[default]
+    Daily,
+    Weekly,
+}
+
+impl FromStr for Period {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "daily" | "day" | "days" => Ok(Self::Daily),
+            "weekly" | "week" | "weeks" => Ok(Self::Weekly),
+            other => Err(format!("Unknown period '{}' (expected daily or weekly)", other)),
+        }
+    }
+}
+
+/// Running totals for one row of a report
+#[derive(Default)]
+struct Totals {
+    utterances: usize,
+    commands: usize,
+    words: usize,
+    audio_secs: f64,
+    resample_ms: u64,
+    transcribe_ms: u64,
+    output_ms: u64,
+}
+
+impl Totals {
+    fn add(&mut self, s: &Sample) {
+        self.utterances += 1;
+        self.commands += usize::from(s.command);
+        self.words += s.words;
+        self.audio_secs += f64::from(s.audio_secs);
+        self.resample_ms += s.resample_ms + s.wake_word_ms;
+        self.transcribe_ms += s.transcribe_ms;
+        self.output_ms += s.output_ms;
+    }
+
+    fn avg_ms(&self, total: u64) -> u64 {
+        total / self.utterances.max(1) as u64
+    }
+
+    /// Transcribe time per second of audio (below 1.0 = faster than real time)
+    fn realtime_factor(&self) -> f64 {
+        if self.audio_secs > 0.0 { self.transcribe_ms as f64 / 1000.0 / self.audio_secs } else { 0.0 }
+    }
+}
+
+/// Label for the period a sample falls in: "2026-01-31" or "2026-W05"
+fn period_label(timestamp: &DateTime<FixedOffset>, period: Period) -> String {
+    match period {
+        Period::Daily => timestamp.format("%Y-%m-%d").to_string(),
+        Period::Weekly => {
+            let week = timestamp.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+    }
+}
+
+/// Summary tables: one row per day/week (newest `limit` of them), then per model
+pub fn report(samples: &[Sample], period: Period, limit: usize) -> String {
+    let mut periods: BTreeMap<String, Totals> = BTreeMap::new();
+    let mut models: BTreeMap<&str, Totals> = BTreeMap::new();
+    for sample in samples {
+        let Ok(timestamp) = DateTime::parse_from_rfc3339(&sample.timestamp) else {
+            continue;
+        };
+        periods.entry(period_label(&timestamp, period)).or_default().add(sample);
+        models.entry(&sample.model).or_default().add(sample);
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        "{:<10}  {:>6}  {:>8}  {:>7}  {:>9}  {:>11}  {:>10}  {:>9}",
+        if period == Period::Daily { "day" } else { "week" },
+        "words", "commands", "audio", "resample", "transcribe", "output", "realtime"
+    );
+    for (label, t) in periods.iter().skip(periods.len().saturating_sub(limit)) {
+        let _ = writeln!(
+            out,
+            "{:<10}  {:>6}  {:>8}  {:>6.0}s  {:>7}ms  {:>9}ms  {:>8}ms  {:>8.2}x",
+            label, t.words, t.commands, t.audio_secs,
+            t.avg_ms(t.resample_ms), t.avg_ms(t.transcribe_ms), t.avg_ms(t.output_ms), t.realtime_factor()
+        );
+    }
+
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{:<10}  {:>10}  {:>9}  {:>11}  {:>9}", "model", "utterances", "avg audio", "transcribe", "realtime");
+    for (model, t) in &models {
+        let _ = writeln!(
+            out,
+            "{:<10}  {:>10}  {:>8.1}s  {:>9}ms  {:>8.2}x",
+            model, t.utterances, t.audio_secs / t.utterances.max(1) as f64,
+            t.avg_ms(t.transcribe_ms), t.realtime_factor()
+        );
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "Stage times are averages per utterance; realtime = transcribe time / audio length (lower is faster).");
+    out
+}
@@ -0,0 +1,90 @@
+//! Transcript output formats for SS9K file mode
+//!
+//! This module handles:
+//! - Plain text (segments joined, what dictation would type)
+//! - SubRip (.srt) and WebVTT (.vtt) captions from whisper segment timestamps
+//! - JSON (full text plus timed segments, for scripts)
+
+use serde::Serialize;
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::audio::Segment;
+
+/// How `ss9k transcribe` renders its result
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Srt,
+    Vtt,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" | "txt" => Ok(Self::Text),
+            "srt" => Ok(Self::Srt),
+            "vtt" | "webvtt" => Ok(Self::Vtt),
+            "json" => Ok(Self::Json),
+            other => Err(format!("Unknown output format '{}' (expected text, srt, vtt or json)", other)),
+        }
+    }
+}
+
+/// Format milliseconds as HH:MM:SS<sep>mmm
+fn timestamp(ms: u64, sep: char) -> String {
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        (ms / 60_000) % 60,
+        (ms / 1000) % 60,
+        sep,
+        ms % 1000
+    )
+}
+
+/// Render segments in the given format
+pub fn render(segments: &[Segment], format: OutputFormat) -> String {
+    let cues = segments.iter().filter(|s| !s.text.trim().is_empty());
+    let mut out = String::new();
+
+    match format {
+        OutputFormat::Text => {
+            let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+            out.push_str(text.trim());
+        }
+        OutputFormat::Srt => {
+            for (i, seg) in cues.enumerate() {
+                let _ = writeln!(out, "{}", i + 1);
+                let _ = writeln!(out, "{} --> {}", timestamp(seg.start_ms, ','), timestamp(seg.end_ms, ','));
+                let _ = writeln!(out, "{}\n", seg.text.trim());
+            }
+        }
+        OutputFormat::Vtt => {
+            out.push_str("WEBVTT\n\n");
+            for seg in cues {
+                let _ = writeln!(out, "{} --> {}", timestamp(seg.start_ms, '.'), timestamp(seg.end_ms, '.'));
+                let _ = writeln!(out, "{}\n", seg.text.trim());
+            }
+        }
+        OutputFormat::Json => {
+            #[derive(Serialize)]
+            struct Transcript {
+                text: String,
+                segments: Vec<Segment>,
+            }
+            let text: String = segments.iter().map(|s| s.text.as_str()).collect();
+            let transcript = Transcript {
+                text: text.trim().to_string(),
+                segments: cues.map(|s| Segment { text: s.text.trim().to_string(), ..s.clone() }).collect(),
+            };
+            out = serde_json::to_string_pretty(&transcript).unwrap_or_default();
+        }
+    }
+
+    out
+}
@@ -0,0 +1,122 @@
+//! Switch access for SS9K
+//!
+//! This module handles:
+//! - Turning presses of a single-button switch into short / long / double gestures
+//! - Scanning through a list of actions (announced with TTS) for one-switch use
+//!
+//! Accessibility switches (USB/Bluetooth, usually exposed as a HID keyboard
+//! or mouse button) give one input. Gestures turn that into three, and
+//! scanning turns it into as many as you like: one gesture steps through
+//! the list, another picks the item that was just announced.
+
+use std::time::{Duration, Instant};
+
+/// What the switch did
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Gesture {
+    Short,  // Quick press, no second press followed
+    Long,   // Held for at least the long-press time
+    Double, // Two quick presses
+}
+
+/// Result of releasing the switch
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Release {
+    /// A gesture is complete
+    Gesture(Gesture),
+    /// Could be a short press or the start of a double; call `resolve(id)`
+    /// once the double-press window has passed
+    Pending(u64),
+    /// Release without a matching press (ignored)
+    None,
+}
+
+/// Gesture recognizer for one switch
+#[derive(Debug)]
+pub struct SwitchGestures {
+    long_press: Duration,
+    double_press: Duration,
+    pressed_at: Option<Instant>,
+    /// Short press waiting to see if a second one follows: (id, released at)
+    pending: Option<(u64, Instant)>,
+    next_id: u64,
+}
+
+impl SwitchGestures {
+    /// `long_press_ms`: hold time for a long press; `double_press_ms`: max gap between double presses
+    pub fn new(long_press_ms: u64, double_press_ms: u64) -> Self {
+        Self {
+            long_press: Duration::from_millis(long_press_ms),
+            double_press: Duration::from_millis(double_press_ms),
+            pressed_at: None,
+            pending: None,
+            next_id: 0,
+        }
+    }
+
+    /// Switch went down (key auto-repeat while held is ignored)
+    pub fn press(&mut self, now: Instant) {
+        if self.pressed_at.is_none() {
+            self.pressed_at = Some(now);
+        }
+    }
+
+    /// Switch came up
+    pub fn release(&mut self, now: Instant) -> Release {
+        let Some(pressed_at) = self.pressed_at.take() else {
+            return Release::None;
+        };
+        if now.duration_since(pressed_at) >= self.long_press {
+            self.pending = None;
+            return Release::Gesture(Gesture::Long);
+        }
+        if let Some((_, released_at)) = self.pending.take()
+            && pressed_at.duration_since(released_at) <= self.double_press
+        {
+            return Release::Gesture(Gesture::Double);
+        }
+        self.next_id += 1;
+        self.pending = Some((self.next_id, now));
+        Release::Pending(self.next_id)
+    }
+
+    /// The double-press window for `id` has passed: Short if nothing else happened since
+    pub fn resolve(&mut self, id: u64) -> Option<Gesture> {
+        match self.pending {
+            Some((pending_id, _)) if pending_id == id && self.pressed_at.is_none() => {
+                self.pending = None;
+                Some(Gesture::Short)
+            }
+            _ => None,
+        }
+    }
+
+    /// How long to wait before calling `resolve`
+    pub fn double_press_window(&self) -> Duration {
+        self.double_press
+    }
+}
+
+/// Position in the scan list
+#[derive(Debug, Default)]
+pub struct Scanner {
+    index: Option<usize>,
+}
+
+impl Scanner {
+    /// Move to the next item (wrapping) and return it
+    pub fn next<'a>(&mut self, items: &'a [String]) -> Option<&'a str> {
+        if items.is_empty() {
+            self.index = None;
+            return None;
+        }
+        let next = self.index.map_or(0, |i| (i + 1) % items.len());
+        self.index = Some(next);
+        Some(&items[next])
+    }
+
+    /// The item last announced, if any
+    pub fn current<'a>(&self, items: &'a [String]) -> Option<&'a str> {
+        self.index.and_then(|i| items.get(i)).map(String::as_str)
+    }
+}
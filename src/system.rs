@@ -0,0 +1,261 @@
+//! System control backends for SS9K
+//!
+//! This module handles:
+//! - Absolute volume control (wpctl/pactl/amixer, osascript, key taps on Windows)
+//! - Absolute brightness control (brightnessctl/light, PowerShell WMI, brightness CLI)
+//! - Do-not-disturb toggling (swaync, dunst, mako, GNOME)
+//! - Screen share detection (PipeWire video capture, or a user-provided check),
+//!   polled on its own thread
+//! - Finding the focused app (Hyprland, Sway, KDE Wayland, X11, macOS)
+//!
+//! Each setter tries the platform's tools in order and returns the name of the
+//! backend that worked, or None if nothing was available.
+
+use crate::keyboard::Keys;
+use crate::Config;
+use arc_swap::ArcSwap;
+#[cfg(target_os = "windows")]
+use enigo::{Key as EnigoKey, Keyboard};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How often the screen share watcher checks
+const SHARE_POLL: Duration = Duration::from_secs(2);
+
+/// What the screen share watcher last found
+static SCREEN_SHARING: AtomicBool = AtomicBool::new(false);
+
+/// Run a program quietly, returning true if it exited successfully
+fn run_quiet(program: &str, args: &[&str]) -> bool {
+    Command::new(program)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|s| s.success())
+        .unwrap_or(false)
+}
+
+/// Run a program, returning its trimmed output if it succeeded and printed something
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run_output(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).stdin(Stdio::null()).stderr(Stdio::null()).output().ok()?;
+    let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !text.is_empty()).then_some(text)
+}
+
+/// Set the default output volume to an absolute percentage (Linux)
+#[cfg(target_os = "linux")]
+pub fn set_volume(_enigo: &mut Keys, percent: u32) -> Option<&'static str> {
+    let fraction = format!("{:.2}", percent as f32 / 100.0);
+    let pct = format!("{}%", percent);
+    if run_quiet("wpctl", &["set-volume", "@DEFAULT_AUDIO_SINK@", &fraction]) {
+        Some("wpctl")
+    } else if run_quiet("pactl", &["set-sink-volume", "@DEFAULT_SINK@", &pct]) {
+        Some("pactl")
+    } else if run_quiet("amixer", &["-q", "sset", "Master", &pct]) {
+        Some("amixer")
+    } else {
+        None
+    }
+}
+
+/// Set the default output volume to an absolute percentage (macOS)
+#[cfg(target_os = "macos")]
+pub fn set_volume(_enigo: &mut Keys, percent: u32) -> Option<&'static str> {
+    let script = format!("set volume output volume {}", percent);
+    run_quiet("osascript", &["-e", &script]).then_some("osascript")
+}
+
+/// Set the default output volume to an absolute percentage (Windows)
+/// Windows volume keys move in 2% steps, so bottom out and step back up
+#[cfg(target_os = "windows")]
+pub fn set_volume(enigo: &mut Keys, percent: u32) -> Option<&'static str> {
+    for _ in 0..50 {
+        enigo.key(EnigoKey::VolumeDown, enigo::Direction::Click).ok()?;
+    }
+    for _ in 0..(percent / 2) {
+        enigo.key(EnigoKey::VolumeUp, enigo::Direction::Click).ok()?;
+    }
+    Some("volume keys")
+}
+
+/// Set the default output volume to an absolute percentage (other platforms)
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn set_volume(_enigo: &mut Keys, _percent: u32) -> Option<&'static str> {
+    None
+}
+
+/// Set the display brightness to an absolute percentage (Linux)
+#[cfg(target_os = "linux")]
+pub fn set_brightness(percent: u32) -> Option<&'static str> {
+    let pct = format!("{}%", percent);
+    let level = percent.to_string();
+    if run_quiet("brightnessctl", &["-q", "set", &pct]) {
+        Some("brightnessctl")
+    } else if run_quiet("light", &["-S", &level]) {
+        Some("light")
+    } else {
+        None
+    }
+}
+
+/// Set the display brightness to an absolute percentage (macOS, needs the `brightness` CLI)
+#[cfg(target_os = "macos")]
+pub fn set_brightness(percent: u32) -> Option<&'static str> {
+    let fraction = format!("{:.2}", percent as f32 / 100.0);
+    run_quiet("brightness", &[&fraction]).then_some("brightness")
+}
+
+/// Set the display brightness to an absolute percentage (Windows, laptop panels via WMI)
+#[cfg(target_os = "windows")]
+pub fn set_brightness(percent: u32) -> Option<&'static str> {
+    let script = format!(
+        "(Get-WmiObject -Namespace root/WMI -Class WmiMonitorBrightnessMethods).WmiSetBrightness(1,{})",
+        percent
+    );
+    run_quiet("powershell", &["-NoProfile", "-Command", &script]).then_some("WMI")
+}
+
+/// Set the display brightness to an absolute percentage (other platforms)
+#[cfg(not(any(target_os = "linux", target_os = "windows", target_os = "macos")))]
+pub fn set_brightness(_percent: u32) -> Option<&'static str> {
+    None
+}
+
+/// Turn desktop do-not-disturb on or off (Linux: swaync, dunst, mako, GNOME)
+#[cfg(target_os = "linux")]
+pub fn set_do_not_disturb(enabled: bool) -> Option<&'static str> {
+    let (swaync, dunst, mako) = if enabled {
+        ("-dn", "true", "-a")
+    } else {
+        ("-df", "false", "-r")
+    };
+    let banners = if enabled { "false" } else { "true" };
+    if run_quiet("swaync-client", &[swaync]) {
+        Some("swaync")
+    } else if run_quiet("dunstctl", &["set-paused", dunst]) {
+        Some("dunst")
+    } else if run_quiet("makoctl", &["mode", mako, "do-not-disturb"]) {
+        Some("mako")
+    } else if run_quiet("gsettings", &["set", "org.gnome.desktop.notifications", "show-banners", banners]) {
+        Some("gsettings")
+    } else {
+        None
+    }
+}
+
+/// Turn desktop do-not-disturb on or off (other platforms have no scriptable toggle)
+#[cfg(not(target_os = "linux"))]
+pub fn set_do_not_disturb(_enabled: bool) -> Option<&'static str> {
+    None
+}
+
+/// Check whether a screen share is active
+/// Runs `check_cmd` through the shell if set (exit 0 = sharing), otherwise uses
+/// the built-in check: any PipeWire video stream being captured (Linux only)
+pub fn screen_share_active(check_cmd: &str) -> bool {
+    if !check_cmd.trim().is_empty() {
+        #[cfg(target_os = "windows")]
+        return run_quiet("cmd", &["/C", check_cmd]);
+        #[cfg(not(target_os = "windows"))]
+        return run_quiet("sh", &["-c", check_cmd]);
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Command::new("pw-dump")
+            .stderr(Stdio::null())
+            .output()
+            .map(|out| String::from_utf8_lossy(&out.stdout).contains("\"Stream/Input/Video\""))
+            .unwrap_or(false)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Check for a screen share every couple of seconds while
+/// `pause_on_screen_share` is on, so the VAD loop only reads the answer
+pub fn watch_screen_share(config: Arc<ArcSwap<Config>>) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        loop {
+            let cfg = config.load();
+            let sharing = cfg.pause_on_screen_share && screen_share_active(&cfg.screen_share_check);
+            SCREEN_SHARING.store(sharing, Ordering::SeqCst);
+            std::thread::sleep(SHARE_POLL);
+        }
+    })
+}
+
+/// Whether the watcher last saw a screen share (false while it's off)
+pub fn screen_sharing() -> bool {
+    SCREEN_SHARING.load(Ordering::SeqCst)
+}
+
+/// Window class (app id on Wayland) of the focused window (Linux)
+/// Asks the compositor where it can tell (Hyprland, Sway, KDE via kdotool),
+/// then X11; GNOME on Wayland only reports XWayland windows
+#[cfg(target_os = "linux")]
+pub fn focused_app() -> Option<String> {
+    if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+        let window: serde_json::Value = serde_json::from_str(&run_output("hyprctl", &["activewindow", "-j"])?).ok()?;
+        return window["class"].as_str().filter(|c| !c.is_empty()).map(str::to_string);
+    }
+    if std::env::var_os("SWAYSOCK").is_some() {
+        let tree: serde_json::Value = serde_json::from_str(&run_output("swaymsg", &["-t", "get_tree"])?).ok()?;
+        return focused_sway_app(&tree);
+    }
+    if std::env::var_os("WAYLAND_DISPLAY").is_some()
+        && let Some(class) = run_output("kdotool", &["getactivewindow", "getwindowclassname"])
+    {
+        return Some(class);
+    }
+    if std::env::var_os("DISPLAY").is_some() {
+        if let Some(class) = run_output("xdotool", &["getactivewindow", "getwindowclassname"]) {
+            return Some(class);
+        }
+        // _NET_ACTIVE_WINDOW(WINDOW): window id # 0x3a00007
+        let active = run_output("xprop", &["-root", "_NET_ACTIVE_WINDOW"])?;
+        let id = active.rsplit(' ').next()?;
+        // WM_CLASS(STRING) = "kitty", "kitty" - the second one is the class
+        let class = run_output("xprop", &["-id", id, "WM_CLASS"])?;
+        return class.rsplit('"').nth(1).filter(|c| !c.is_empty()).map(str::to_string);
+    }
+    None
+}
+
+/// The focused window in a `swaymsg -t get_tree` tree: its app_id, or X11 class under XWayland
+#[cfg(target_os = "linux")]
+fn focused_sway_app(node: &serde_json::Value) -> Option<String> {
+    if node["focused"].as_bool() == Some(true) {
+        return node["app_id"]
+            .as_str()
+            .or_else(|| node["window_properties"]["class"].as_str())
+            .map(str::to_string);
+    }
+    ["nodes", "floating_nodes"]
+        .iter()
+        .filter_map(|key| node[key].as_array())
+        .flatten()
+        .find_map(focused_sway_app)
+}
+
+/// Name of the frontmost application (macOS)
+#[cfg(target_os = "macos")]
+pub fn focused_app() -> Option<String> {
+    run_output(
+        "osascript",
+        &["-e", "tell application \"System Events\" to get name of first application process whose frontmost is true"],
+    )
+}
+
+/// Focused app (other platforms can't tell without extra dependencies)
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn focused_app() -> Option<String> {
+    None
+}
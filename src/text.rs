@@ -0,0 +1,321 @@
+//! UTF-8 safe text helpers for SS9K
+//!
+//! This module handles:
+//! - Case-insensitive find/replace that maps matches back to the original text
+//! - `$VAR` expansion and `{name:...}` placeholder expansion in a single pass
+//! - Splitting off the last word, and finding where the last word or sentence
+//!   starts (for "scratch word" and "scratch sentence")
+//! - Splitting identifiers into words ("getUserName" -> "get User Name")
+//! - Edit distance, Jaro-Winkler similarity and Metaphone codes, for fuzzy
+//!   and phonetic phrase matching
+//!
+//! Whisper output is arbitrary Unicode, and lowercasing or slicing can move
+//! byte offsets ("İ" is 2 bytes, its lowercase "i̇" is 3). Everything here
+//! only ever slices at offsets that came from the string being sliced.
+
+use std::ops::Range;
+
+/// Byte ranges in `haystack` that match `needle` case-insensitively (non-overlapping, left to right)
+pub fn find_case_insensitive(haystack: &str, needle: &str) -> Vec<Range<usize>> {
+    let needle_lower = needle.to_lowercase();
+    if needle_lower.is_empty() {
+        return Vec::new();
+    }
+
+    // Lowercase the haystack, remembering (lowercase offset, original offset) at each char boundary
+    let mut lower = String::with_capacity(haystack.len());
+    let mut bounds = Vec::with_capacity(haystack.len() + 1);
+    for (i, c) in haystack.char_indices() {
+        bounds.push((lower.len(), i));
+        lower.extend(c.to_lowercase());
+    }
+    bounds.push((lower.len(), haystack.len()));
+
+    let original_at = |pos: usize| {
+        bounds
+            .binary_search_by_key(&pos, |&(l, _)| l)
+            .ok()
+            .map(|i| bounds[i].1)
+    };
+
+    let mut matches = Vec::new();
+    let mut search_start = 0;
+    while let Some(pos) = lower[search_start..].find(&needle_lower) {
+        let start = search_start + pos;
+        let end = start + needle_lower.len();
+        match (original_at(start), original_at(end)) {
+            (Some(orig_start), Some(orig_end)) => {
+                matches.push(orig_start..orig_end);
+                search_start = end;
+            }
+            // Match splits a character's lowercase expansion - not a real match
+            _ => search_start = start + lower[start..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    matches
+}
+
+/// Replace every case-insensitive match of `from` with `to`, keeping the rest of `text` as-is
+pub fn replace_case_insensitive(text: &str, from: &str, to: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0;
+    for range in find_case_insensitive(text, from) {
+        result.push_str(&text[copied..range.start]);
+        result.push_str(to);
+        copied = range.end;
+    }
+    result.push_str(&text[copied..]);
+    result
+}
+
+/// Expand `$NAME` references (ASCII letters, digits, `_`) using `lookup`
+/// Single pass: substituted values are never re-expanded, and a `$` that isn't
+/// followed by a name is kept literally without stopping later expansions
+pub fn expand_vars(text: &str, lookup: impl Fn(&str) -> Option<String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(dollar) = rest.find('$') {
+        result.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let name_len = after
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .unwrap_or(after.len());
+        if name_len == 0 {
+            result.push('$');
+        } else {
+            result.push_str(&lookup(&after[..name_len]).unwrap_or_default());
+        }
+        rest = &after[name_len..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Expand `{<prefix>...}` placeholders (e.g. prefix "shell:") with `expand(inner)`
+/// Single pass: expanded output is never scanned again; an unclosed placeholder is kept literally
+pub fn expand_placeholder(text: &str, prefix: &str, mut expand: impl FnMut(&str) -> String) -> String {
+    let open = format!("{{{}", prefix);
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(&open) {
+        let inner_start = start + open.len();
+        let Some(len) = rest[inner_start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(&expand(&rest[inner_start..inner_start + len]));
+        rest = &rest[inner_start + len + 1..];
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Split off the last whitespace-separated word: "press enter  3" -> ("press enter", "3")
+/// Returns None if there's only one word (or none)
+pub fn split_last_word(s: &str) -> Option<(&str, &str)> {
+    let trimmed = s.trim_end();
+    let (head, last) = trimmed.rsplit_once(char::is_whitespace)?;
+    let head = head.trim_end();
+    if head.trim_start().is_empty() {
+        return None;
+    }
+    Some((head, last))
+}
+
+/// Punctuation that ends a sentence
+const SENTENCE_ENDS: &[char] = &['.', '!', '?', '…'];
+
+/// Quotes and brackets that can close a sentence after its full stop
+const CLOSERS: &[char] = &['"', '\'', '’', '”', '»', ')', ']'];
+
+/// Where the last word starts, counting the whitespace before it:
+/// "hello big world " -> 9, so `&text[..9]` is "hello big"
+pub fn last_word_start(text: &str) -> usize {
+    text.trim_end().trim_end_matches(|c: char| !c.is_whitespace()).trim_end().len()
+}
+
+/// Where the last sentence starts, counting the whitespace before it:
+/// "Hi there. How are you?" -> 9, so `&text[..9]` is "Hi there."
+/// 0 when there's only one sentence. A full stop only ends a sentence when
+/// whitespace follows it, so "3.5" and "example.com" stay whole.
+pub fn last_sentence_start(text: &str) -> usize {
+    let body = text.trim_end().trim_end_matches(|c: char| SENTENCE_ENDS.contains(&c) || CLOSERS.contains(&c));
+    body.char_indices()
+        .rev()
+        .filter(|(_, c)| SENTENCE_ENDS.contains(c))
+        .find_map(|(i, c)| {
+            let rest = body[i + c.len_utf8()..].trim_start_matches(CLOSERS);
+            rest.starts_with(char::is_whitespace).then(|| body.len() - rest.len())
+        })
+        .unwrap_or(0)
+}
+
+/// An identifier's words, space-separated, so a case mode can join them up
+/// again: "getUserName" -> "get User Name", "HTTPServer" -> "HTTP Server",
+/// "user_id" and "user-id" -> "user id". Plain words pass through.
+/// A one-letter hump stays a word ("getX" -> "get X"), but a run of them
+/// reads as an acronym, as in "parseURL": "aBC" -> "a BC".
+pub fn identifier_words(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len() + 8);
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, '_' | '-' | '.' | '/') {
+            out.push(' ');
+            continue;
+        }
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        // A hump: "userName", "v2Api", or the end of an acronym in "HTTPServer"
+        if c.is_uppercase()
+            && let Some(prev) = prev
+            && (prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next.is_some_and(char::is_lowercase)))
+        {
+            out.push(' ');
+        }
+        out.push(c);
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Levenshtein distance: single-character inserts, deletes and substitutions
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+/// `edit_distance` as a similarity: 1.0 for equal strings, 0.0 for nothing in common
+pub fn levenshtein_similarity(a: &str, b: &str) -> f64 {
+    let longest = a.chars().count().max(b.chars().count());
+    if longest == 0 {
+        return 1.0;
+    }
+    1.0 - edit_distance(a, b) as f64 / longest as f64
+}
+
+/// Jaro-Winkler similarity, 0.0 to 1.0
+/// Kinder than Levenshtein to endings Whisper gets wrong ("emax" for
+/// "emacs"), since a shared start counts for more.
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    // Characters match if they're equal and no further apart than this
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+    let mut b_matched = vec![false; b.len()];
+    let mut a_matches = Vec::new();
+    for (i, ca) in a.iter().enumerate() {
+        let lo = i.saturating_sub(window);
+        let hi = (i + window + 1).min(b.len());
+        if let Some(j) = (lo..hi).find(|&j| !b_matched[j] && b[j] == *ca) {
+            b_matched[j] = true;
+            a_matches.push(*ca);
+        }
+    }
+    if a_matches.is_empty() {
+        return 0.0;
+    }
+    let b_matches = b.iter().zip(&b_matched).filter(|(_, m)| **m).map(|(c, _)| *c);
+    let transpositions = a_matches.iter().zip(b_matches).filter(|(x, y)| **x != *y).count() / 2;
+
+    let m = a_matches.len() as f64;
+    let jaro = (m / a.len() as f64 + m / b.len() as f64 + (m - transpositions as f64) / m) / 3.0;
+    let prefix = a.iter().zip(&b).take(4).take_while(|(x, y)| x == y).count();
+    jaro + prefix as f64 * 0.1 * (1.0 - jaro)
+}
+
+/// Metaphone code for one word: how it sounds, roughly, so homophones
+/// Whisper picks between get the same code ("knew" and "new" are both "N",
+/// "cellar" and "seller" both "SLR")
+/// A simplified take on Lawrence Philips' Metaphone. Letters other than
+/// a-z (digits, accents) are kept as they are.
+pub fn metaphone(word: &str) -> String {
+    let lower = word.to_lowercase();
+    let mut chars: Vec<char> = lower.chars().collect();
+    // Silent first letters
+    if let [a, b, ..] = chars[..]
+        && matches!((a, b), ('k', 'n') | ('g', 'n') | ('p', 'n') | ('a', 'e') | ('w', 'r'))
+    {
+        chars.remove(0);
+    }
+    if chars.first() == Some(&'x') {
+        chars[0] = 's';
+    }
+    if chars.starts_with(&['w', 'h']) {
+        chars.remove(1);
+    }
+
+    let vowel = |c: Option<&char>| c.is_some_and(|c| "aeiou".contains(*c));
+    let mut code = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).and_then(|p| chars.get(p));
+        let next = chars.get(i + 1);
+        let after = chars.get(i + 2);
+        // Doubled letters sound once, except "cc" ("accent")
+        if prev == Some(&c) && c != 'c' {
+            continue;
+        }
+        let sound: &str = match c {
+            'a' | 'e' | 'i' | 'o' | 'u' => if i == 0 { "A" } else { "" },
+            'b' => if prev == Some(&'m') && next.is_none() { "" } else { "B" },
+            'c' => match (next, after) {
+                (Some('i'), Some('a')) | (Some('h'), _) if prev == Some(&'s') => "K",
+                (Some('i'), Some('a')) | (Some('h'), _) => "X",
+                (Some('i' | 'e' | 'y'), _) if prev == Some(&'s') => "",
+                (Some('i' | 'e' | 'y'), _) => "S",
+                _ => "K",
+            },
+            'd' => if next == Some(&'g') && matches!(after, Some('e' | 'i' | 'y')) { "J" } else { "T" },
+            'g' => match next {
+                Some('h') if !vowel(after) => "",
+                Some('e' | 'i' | 'y') if prev == Some(&'d') => "",
+                Some('n') if after.is_none() || (after == Some(&'e') && chars.get(i + 3) == Some(&'d')) => "",
+                Some('i' | 'e' | 'y') => "J",
+                _ => "K",
+            },
+            'h' => if matches!(prev, Some('c' | 's' | 'p' | 't' | 'g')) || !vowel(next) { "" } else { "H" },
+            'k' => if prev == Some(&'c') { "" } else { "K" },
+            'p' => if next == Some(&'h') { "F" } else { "P" },
+            'q' => "K",
+            's' => match (next, after) {
+                (Some('h'), _) | (Some('i'), Some('o' | 'a')) => "X",
+                _ => "S",
+            },
+            't' => match (next, after) {
+                (Some('i'), Some('o' | 'a')) => "X",
+                (Some('h'), _) => "0",
+                (Some('c'), Some('h')) => "",
+                _ => "T",
+            },
+            'v' => "F",
+            'w' | 'y' => if vowel(next) { if c == 'w' { "W" } else { "Y" } } else { "" },
+            'x' => "KS",
+            'z' => "S",
+            'f' | 'j' | 'l' | 'm' | 'n' | 'r' => {
+                code.push(c.to_ascii_uppercase());
+                continue;
+            }
+            other => {
+                code.push(other);
+                continue;
+            }
+        };
+        code.push_str(sound);
+    }
+    code
+}
@@ -0,0 +1,117 @@
+//! Voice timers for SS9K
+//!
+//! "command set timer five minutes named tea" rings five minutes later: a
+//! desktop notification, the timer chime (`sound_timer`) and, with
+//! `tts_backend`, "tea timer done" out loud. These are asked for, so they
+//! show up whatever `notifications` and `audio_feedback` say.
+//! "command list timers" says what's running and how long each has left;
+//! "command cancel timer tea" stops one ("cancel timer" alone stops the
+//! newest).
+//!
+//! Timers live in memory and are gone when ss9k exits.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+use crate::commands::parse_wait;
+use crate::notifications::{self, Kind};
+use crate::sounds::{self, Cue};
+use crate::tts;
+use crate::Config;
+
+/// A running timer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timer {
+    pub id: u64,
+    pub name: String, // "tea", or the spoken duration when unnamed
+    pub due: Instant,
+}
+
+static TIMERS: Mutex<Vec<Timer>> = Mutex::new(Vec::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+static SOUND: Mutex<String> = Mutex::new(String::new());
+
+/// Apply `sound_timer` (startup and config reloads)
+pub fn configure(config: &Config) {
+    if let Ok(mut sound) = SOUND.lock() {
+        *sound = config.sound_timer.clone();
+    }
+}
+
+/// Parse what follows "set timer": "five minutes", "90 seconds named pasta",
+/// "ten minutes for tea" -> (duration, name)
+/// An unnamed timer is named after its duration.
+pub fn parse_timer(s: &str) -> Option<(Duration, String)> {
+    let s = s.trim();
+    let s = s.strip_prefix("for ").unwrap_or(s); // "set a timer for five minutes"
+    let (duration, name) = [" named ", " called ", " for "]
+        .iter()
+        .find_map(|sep| s.split_once(sep))
+        .map_or((s, s), |(duration, name)| (duration.trim(), name.trim()));
+    let ms = parse_wait(duration)?;
+    (ms > 0 && !name.is_empty()).then(|| (Duration::from_millis(ms), name.to_string()))
+}
+
+/// Start a timer; when it runs out it rings unless it was cancelled
+pub fn start(name: &str, duration: Duration) -> Timer {
+    let timer = Timer {
+        id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+        name: name.to_string(),
+        due: Instant::now() + duration,
+    };
+    if let Ok(mut timers) = TIMERS.lock() {
+        timers.push(timer.clone());
+    }
+
+    let id = timer.id;
+    std::thread::spawn(move || {
+        std::thread::sleep(duration);
+        if let Some(timer) = take(|t| t.id == id) {
+            ring(&timer);
+        }
+    });
+    timer
+}
+
+/// Remove and return the first timer `matches` picks, newest first
+fn take(matches: impl Fn(&Timer) -> bool) -> Option<Timer> {
+    let mut timers = TIMERS.lock().ok()?;
+    let index = timers.iter().rposition(matches)?;
+    Some(timers.remove(index))
+}
+
+/// Stop a timer by name (any case), or the newest one when `name` is None
+pub fn cancel(name: Option<&str>) -> Option<Timer> {
+    match name {
+        Some(name) => take(|t| t.name.eq_ignore_ascii_case(name.trim())),
+        None => take(|_| true),
+    }
+}
+
+/// Running timers, soonest first
+pub fn list() -> Vec<Timer> {
+    let mut timers = TIMERS.lock().map(|t| t.clone()).unwrap_or_default();
+    timers.sort_by_key(|t| t.due);
+    timers
+}
+
+/// "4m 05s", "1h 02m 00s", "12s": what's left of a timer, for logs and notifications
+pub fn format_remaining(timer: &Timer) -> String {
+    let secs = timer.due.saturating_duration_since(Instant::now()).as_secs_f64().ceil() as u64;
+    match (secs / 3600, secs / 60 % 60, secs % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
+
+/// Let everyone know a timer ran out
+fn ring(timer: &Timer) {
+    info!("⏰ Timer done: {}", timer.name);
+    notifications::show(Kind::Result, &format!("⏰ Timer done: {}", timer.name));
+    let sound = SOUND.lock().map(|s| s.clone()).unwrap_or_default();
+    sounds::ring(Cue::Timer, &sound);
+    tts::say(&format!("{} timer done", timer.name));
+}
@@ -0,0 +1,100 @@
+//! Text-to-speech feedback for SS9K
+//!
+//! This module handles:
+//! - Speaking confirmations ("mode snake", "do not disturb on")
+//! - Reading back text ("command read that")
+//! - Backends: espeak-ng, piper (piped to aplay), macOS `say`, or any
+//!   shell command that reads the text on stdin
+//!
+//! Speech runs on a background thread; utterances queue instead of overlapping.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, Mutex};
+
+use tracing::warn;
+
+use crate::security;
+
+/// Current backend and voice, updated from config by the processor thread
+static SETTINGS: LazyLock<Mutex<(String, String)>> =
+    LazyLock::new(|| Mutex::new((String::new(), String::new())));
+
+/// Held while speaking so utterances don't talk over each other
+static SPEAKING: Mutex<()> = Mutex::new(());
+
+/// Set the TTS backend ("" = off) and voice (espeak voice, piper model path, or say voice)
+pub fn configure(backend: &str, voice: &str) {
+    if let Ok(mut settings) = SETTINGS.lock() {
+        *settings = (backend.trim().to_string(), voice.trim().to_string());
+    }
+}
+
+/// Whether a TTS backend is configured
+pub fn enabled() -> bool {
+    SETTINGS.lock().map(|s| !s.0.is_empty()).unwrap_or(false)
+}
+
+/// Speak text in the background (no-op when TTS is off)
+pub fn say(text: &str) {
+    let Ok((backend, voice)) = SETTINGS.lock().map(|s| s.clone()) else {
+        return;
+    };
+    if backend.is_empty() || text.trim().is_empty() {
+        return;
+    }
+    let text = text.to_string();
+
+    std::thread::spawn(move || {
+        let _guard = SPEAKING.lock();
+        if let Err(e) = speak_blocking(&backend, &voice, &text) {
+            warn!("⚠️ TTS ({}) failed: {}", backend, e);
+        }
+    });
+}
+
+/// Run the backend and wait for it to finish
+fn speak_blocking(backend: &str, voice: &str, text: &str) -> std::io::Result<()> {
+    let mut cmd = match backend {
+        "espeak-ng" | "espeak" => {
+            let mut cmd = Command::new(backend);
+            if !voice.is_empty() {
+                cmd.args(["-v", voice]);
+            }
+            cmd.arg("--stdin");
+            cmd
+        }
+        "piper" => {
+            // Voice is the .onnx model; piper emits 22.05kHz s16le mono
+            let mut cmd = Command::new("sh");
+            cmd.args([
+                "-c",
+                "piper --model \"$1\" --output-raw | aplay -q -r 22050 -f S16_LE -t raw -",
+                "sh",
+            ]);
+            cmd.arg(shellexpand::tilde(voice).as_ref());
+            cmd
+        }
+        "say" => {
+            let mut cmd = Command::new("say");
+            if !voice.is_empty() {
+                cmd.args(["-v", voice]);
+            }
+            cmd
+        }
+        // Anything else is a shell command that reads the text on stdin
+        custom => security::shell(custom),
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Every backend reads the text on stdin, so dictated text never hits a shell
+        stdin.write_all(text.as_bytes())?;
+    }
+    child.wait()?;
+    Ok(())
+}
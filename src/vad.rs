@@ -5,7 +5,11 @@
 //! - Listening: VAD active, waiting for speech
 //! - Speaking: Speech detected, accumulating audio
 //! - Silence: Speech ended, waiting for silence timeout
+//!
+//! With adaptive silence on, the timeout follows the pauses the speaker
+//! actually makes (see `AdaptiveSilence`) instead of a fixed `silence_ms`.
 
+use std::collections::VecDeque;
 use std::time::{Duration, Instant};
 use voice_activity_detector::VoiceActivityDetector;
 
@@ -40,6 +44,63 @@ pub enum VadEvent {
     ReadyToProcess(Vec<f32>),
 }
 
+/// Number of recent pauses the adaptive timeout looks at
+const PAUSE_HISTORY: usize = 8;
+
+/// Adaptive silence timeout learned from the speaker's own pauses
+///
+/// Every mid-utterance pause (silence that ended in more speech) is recorded.
+/// The timeout is the longest recent pause plus half again, clamped to
+/// `min_ms..=max_ms`. Quick command bursts with short gaps pull it down toward
+/// `min_ms`; slow, thoughtful speech pushes it up so sentences aren't split.
+/// When an utterance is cut off and speech picks up again right away, the
+/// whole gap counts as a pause, so the next timeout waits longer.
+#[derive(Debug, Clone)]
+pub struct AdaptiveSilence {
+    initial_ms: u64,
+    min_ms: u64,
+    max_ms: u64,
+    pauses: VecDeque<u64>,
+}
+
+impl AdaptiveSilence {
+    /// Start at `initial_ms` (usually `vad_silence_ms`) and adapt within `min_ms..=max_ms`
+    pub fn new(initial_ms: u64, min_ms: u64, max_ms: u64) -> Self {
+        let max_ms = max_ms.max(min_ms);
+        Self {
+            initial_ms: initial_ms.clamp(min_ms, max_ms),
+            min_ms,
+            max_ms,
+            pauses: VecDeque::with_capacity(PAUSE_HISTORY),
+        }
+    }
+
+    /// Record a pause the speaker made before carrying on talking
+    pub fn record_pause(&mut self, pause_ms: u64) {
+        if self.pauses.len() == PAUSE_HISTORY {
+            self.pauses.pop_front();
+        }
+        self.pauses.push_back(pause_ms);
+    }
+
+    /// Record speech that resumed `gap_ms` after an utterance was cut off
+    /// following `waited_ms` of silence. Only a gap no longer than the wait
+    /// counts - anything longer is the next command, not the same sentence
+    pub fn record_cutoff(&mut self, waited_ms: u64, gap_ms: u64) {
+        if gap_ms <= waited_ms {
+            self.record_pause(waited_ms + gap_ms);
+        }
+    }
+
+    /// Current silence timeout in milliseconds
+    pub fn timeout_ms(&self) -> u64 {
+        match self.pauses.iter().max() {
+            Some(&longest) => (longest + longest / 2).clamp(self.min_ms, self.max_ms),
+            None => self.initial_ms,
+        }
+    }
+}
+
 /// Voice Activity Detector wrapper with state machine
 pub struct Vad {
     detector: VoiceActivityDetector,
@@ -67,6 +128,10 @@ pub struct Vad {
     wake_word_check_samples: usize,
     /// Whether wake word check has been emitted for current utterance
     wake_word_check_emitted: bool,
+    /// Adaptive silence timeout (None = fixed silence_ms)
+    adaptive: Option<AdaptiveSilence>,
+    /// When the last utterance was cut off, and how long it waited in silence
+    last_cutoff: Option<(Instant, u64)>,
 }
 
 impl Vad {
@@ -102,6 +167,8 @@ impl Vad {
             wake_word_enabled: false,
             wake_word_check_samples: (VAD_SAMPLE_RATE as f32 * 1.2) as usize, // 1.2 seconds
             wake_word_check_emitted: false,
+            adaptive: None,
+            last_cutoff: None,
         })
     }
 
@@ -115,6 +182,16 @@ impl Vad {
         self.wake_word_enabled = enabled;
     }
 
+    /// Enable adaptive silence timeout (None = always wait silence_ms)
+    pub fn set_adaptive_silence(&mut self, adaptive: Option<AdaptiveSilence>) {
+        self.adaptive = adaptive;
+    }
+
+    /// Silence to wait (before speech padding) for the current utterance
+    pub fn silence_timeout_ms(&self) -> u64 {
+        self.adaptive.as_ref().map_or(self.silence_ms, AdaptiveSilence::timeout_ms)
+    }
+
     /// Start listening (transition from Idle)
     pub fn start_listening(&mut self) -> Option<VadEvent> {
         if self.state == VadState::Idle {
@@ -141,6 +218,7 @@ impl Vad {
             self.silence_start = None;
             self.speech_start = None;
             self.wake_word_check_emitted = false;
+            self.last_cutoff = None;
             Some(VadEvent::StateChanged(VadState::Idle))
         } else {
             None
@@ -188,6 +266,10 @@ impl Vad {
                     }
 
                     if is_speech {
+                        // Speech right after a cutoff means that silence was really a pause
+                        if let (Some(adaptive), Some((cut_at, waited))) = (self.adaptive.as_mut(), self.last_cutoff.take()) {
+                            adaptive.record_cutoff(waited, cut_at.elapsed().as_millis() as u64);
+                        }
                         self.state = VadState::Speaking;
                         self.speech_start = Some(Instant::now());
                         self.audio_buffer.clear();
@@ -225,13 +307,17 @@ impl Vad {
 
                     if is_speech {
                         // Speech resumed, back to speaking
+                        if let (Some(adaptive), Some(silence_start)) = (self.adaptive.as_mut(), self.silence_start) {
+                            adaptive.record_pause(silence_start.elapsed().as_millis() as u64);
+                        }
                         self.state = VadState::Speaking;
                         self.silence_start = None;
                         events.push(VadEvent::StateChanged(VadState::Speaking));
                     } else if let Some(silence_start) = self.silence_start {
-                        // Check if silence has lasted long enough (silence_ms + speech_pad_ms)
+                        // Check if silence has lasted long enough (silence timeout + speech_pad_ms)
                         // The extra padding catches trailing words
-                        let total_wait = self.silence_ms + self.speech_pad_ms;
+                        let timeout = self.silence_timeout_ms();
+                        let total_wait = timeout + self.speech_pad_ms;
                         if silence_start.elapsed() >= Duration::from_millis(total_wait) {
                             // Check if speech was long enough
                             let speech_duration = self.speech_start
@@ -242,6 +328,7 @@ impl Vad {
                                 // Valid utterance! Ready to process
                                 let audio = std::mem::take(&mut self.audio_buffer);
                                 events.push(VadEvent::ReadyToProcess(audio));
+                                self.last_cutoff = Some((Instant::now(), total_wait));
                             }
 
                             // Back to listening for next utterance
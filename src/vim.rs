@@ -0,0 +1,232 @@
+//! Vim mode for SS9K
+//!
+//! "command mode vim" turns dictation into normal-mode keystrokes instead of
+//! text: "delete two words" sends `d2w`, "go to line forty" sends `40G`,
+//! "write quit" sends `:wq<CR>`. Phrases are an operator (delete, change,
+//! yank, indent, outdent), an optional count and a motion ("word", "end of
+//! line", "inside quotes"), or one of the fixed commands below. A phrase
+//! that isn't understood sends nothing, since typing it into normal mode
+//! would run whatever its letters mean.
+//!
+//! "insert <text>" and "append <text>" type text and go back to normal mode,
+//! "search <text>" searches forward and "ex <command>" runs an ex command.
+
+use crate::commands::{parse_count, strip_punct, Keystroke};
+
+/// Phrases with a fixed key sequence, in Vim notation
+const COMMANDS: &[(&[&str], &str)] = &[
+    // Files and windows
+    (&["write", "save"], ":w<CR>"),
+    (&["quit"], ":q<CR>"),
+    (&["write quit", "write and quit", "save quit", "save and quit"], ":wq<CR>"),
+    (&["force quit", "quit without saving"], ":q!<CR>"),
+    (&["write all", "save all"], ":wa<CR>"),
+    (&["next buffer"], ":bn<CR>"),
+    (&["previous buffer"], ":bp<CR>"),
+    (&["split"], ":split<CR>"),
+    (&["vertical split"], ":vsplit<CR>"),
+    (&["other window", "next window"], "<C-w>w"),
+
+    // Modes
+    (&["escape", "normal", "normal mode"], "<Esc>"),
+    (&["insert"], "i"),
+    (&["insert at start", "insert start"], "I"),
+    (&["append"], "a"),
+    (&["append at end", "append end"], "A"),
+    (&["open below", "open line", "new line below"], "o"),
+    (&["open above", "new line above"], "O"),
+    (&["visual"], "v"),
+    (&["visual line"], "V"),
+    (&["visual block"], "<C-v>"),
+
+    // Editing
+    (&["undo"], "u"),
+    (&["redo"], "<C-r>"),
+    (&["paste", "put"], "p"),
+    (&["paste above", "paste before"], "P"),
+    (&["join", "join lines"], "J"),
+    (&["repeat", "dot"], "."),
+    (&["swap case", "toggle case"], "~"),
+
+    // Moving around
+    (&["top", "go to top", "top of file"], "gg"),
+    (&["bottom", "go to bottom", "end of file"], "G"),
+    (&["next match"], "n"),
+    (&["previous match"], "N"),
+    (&["center", "center line"], "zz"),
+    (&["page down"], "<C-f>"),
+    (&["page up"], "<C-b>"),
+    (&["half page down"], "<C-d>"),
+    (&["half page up"], "<C-u>"),
+];
+
+/// Operators, which take a count and a motion ("delete" + "two words")
+const OPERATORS: &[(&[&str], &str)] = &[
+    (&["delete", "cut"], "d"),
+    (&["change"], "c"),
+    (&["yank", "copy"], "y"),
+    (&["indent"], ">"),
+    (&["outdent", "dedent", "unindent"], "<"),
+];
+
+/// Motions, on their own ("three words") or after an operator
+/// "line" is special: after an operator it doubles it (`dd`, `3yy`).
+const MOTIONS: &[(&[&str], &str)] = &[
+    (&["word", "words"], "w"),
+    (&["big word", "big words"], "W"),
+    (&["back", "back word", "back words", "word back", "words back"], "b"),
+    (&["end of word", "end word"], "e"),
+    (&["character", "characters", "char", "chars", "right"], "l"),
+    (&["left"], "h"),
+    (&["down"], "j"),
+    (&["up"], "k"),
+    (&["end of line", "to end of line", "rest of line"], "$"),
+    (&["start of line", "to start of line", "beginning of line"], "0"),
+    (&["first character", "to first character"], "^"),
+    (&["paragraph", "paragraphs", "next paragraph"], "}"),
+    (&["previous paragraph"], "{"),
+    (&["sentence", "sentences"], ")"),
+    (&["to top", "to top of file"], "gg"),
+    (&["to bottom", "to end of file"], "G"),
+    (&["inside word", "inner word"], "iw"),
+    (&["around word"], "aw"),
+    (&["inside quotes", "inner quotes"], "i\""),
+    (&["inside single quotes", "inner single quotes"], "i'"),
+    (&["inside parens", "inner parens"], "i("),
+    (&["inside brackets", "inner brackets"], "i["),
+    (&["inside braces", "inner braces"], "i{"),
+    (&["inside tag", "inner tag"], "it"),
+    (&["around quotes"], "a\""),
+    (&["around parens"], "a("),
+];
+
+const LINE: &[&str] = &["line", "lines"];
+
+/// Named keys Vim notation can hold, and the `[keybinds]` name each sends
+const NAMED_KEYS: &[(&str, &str)] = &[
+    ("Esc", "escape"),
+    ("CR", "return"),
+    ("BS", "backspace"),
+    ("Tab", "tab"),
+    ("Space", "space"),
+];
+
+/// A phrase's key sequence in Vim notation ("delete two words" -> `d2w`,
+/// "write quit" -> `:wq<CR>`), or None if it isn't a Vim phrase
+pub fn translate(text: &str) -> Option<String> {
+    let words: Vec<String> = text.split_whitespace().map(strip_punct).filter(|w| !w.is_empty()).collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    let (first, _) = words.split_first()?;
+
+    // Text that goes into the buffer keeps its case and punctuation
+    let rest = text.trim().split_once(char::is_whitespace).map(|(_, rest)| rest.trim()).filter(|r| !r.is_empty());
+    if let Some(rest) = rest {
+        match *first {
+            "insert" if lookup(COMMANDS, &words.join(" ")).is_none() => return Some(format!("i{}<Esc>", rest)),
+            "append" if lookup(COMMANDS, &words.join(" ")).is_none() => return Some(format!("a{}<Esc>", rest)),
+            // Whisper's closing period would end up in the pattern or command (E518)
+            "search" | "find" | "ex" => {
+                let rest = rest.trim_end_matches(['.', '!', '?', ',']).trim_end();
+                if !rest.is_empty() {
+                    return Some(format!("{}{}<CR>", if *first == "ex" { ":" } else { "/" }, rest));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let phrase = words.join(" ");
+    if let Some(keys) = lookup(COMMANDS, &phrase) {
+        return Some(keys.to_string());
+    }
+
+    // "go to line forty", "line forty"
+    let line = ["go to line", "goto line", "jump to line", "line"]
+        .iter()
+        .find_map(|prefix| phrase.strip_prefix(prefix).and_then(|rest| rest.strip_prefix(' ')));
+    if let Some(n) = line.and_then(parse_count) {
+        return Some(format!("{}G", n));
+    }
+
+    match OPERATORS.iter().find(|(names, _)| names.contains(first)) {
+        Some((_, operator)) => {
+            let (n, motion) = counted(&words[1..])?;
+            let n = n.map(|n| n.to_string()).unwrap_or_default();
+            if LINE.contains(&motion.as_str()) {
+                // "delete two lines" -> 2dd
+                return Some(format!("{}{}{}", n, operator, operator));
+            }
+            Some(format!("{}{}{}", operator, n, lookup(MOTIONS, &motion)?))
+        }
+        None => {
+            let (n, motion) = counted(&words)?;
+            let n = n.map(|n| n.to_string()).unwrap_or_default();
+            Some(format!("{}{}", n, lookup(MOTIONS, &motion)?))
+        }
+    }
+}
+
+/// The keystrokes Vim notation stands for: `<Esc>`, `<CR>`, `<C-r>` and the
+/// other named keys become chords, everything else is typed
+pub fn keystrokes(notation: &str) -> Vec<Keystroke> {
+    let mut keys = Vec::new();
+    let mut typed = String::new();
+    let mut rest = notation;
+
+    while let Some(c) = rest.chars().next() {
+        let named = rest
+            .strip_prefix('<')
+            .and_then(|r| r.split_once('>'))
+            .and_then(|(name, after)| Some((chord(name)?, after)));
+        match named {
+            Some((chord, after)) => {
+                if !typed.is_empty() {
+                    keys.push(Keystroke::Text(std::mem::take(&mut typed)));
+                }
+                keys.push(Keystroke::Chord(chord));
+                rest = after;
+            }
+            None => {
+                typed.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !typed.is_empty() {
+        keys.push(Keystroke::Text(typed));
+    }
+    keys
+}
+
+/// The `[keybinds]` chord for the inside of `<...>`: "Esc" -> "escape", "C-r" -> "ctrl+r"
+fn chord(name: &str) -> Option<String> {
+    if let Some(key) = name.strip_prefix("C-").filter(|k| k.chars().count() == 1) {
+        return Some(format!("ctrl+{}", key.to_lowercase()));
+    }
+    NAMED_KEYS.iter().find(|(vim, _)| *vim == name).map(|(_, key)| key.to_string())
+}
+
+/// A count and the motion around it: "two words", "words two" or just "words"
+fn counted(words: &[&str]) -> Option<(Option<usize>, String)> {
+    let whole = words.join(" ");
+    if is_motion(&whole) {
+        return Some((None, whole));
+    }
+    (1..words.len()).find_map(|split| {
+        let (head, tail) = (words[..split].join(" "), words[split..].join(" "));
+        match (parse_count(&head), parse_count(&tail)) {
+            (Some(n), _) if is_motion(&tail) => Some((Some(n), tail)),
+            (_, Some(n)) if is_motion(&head) => Some((Some(n), head)),
+            _ => None,
+        }
+    })
+}
+
+fn is_motion(phrase: &str) -> bool {
+    LINE.contains(&phrase) || lookup(MOTIONS, phrase).is_some()
+}
+
+
+fn lookup(table: &[(&[&str], &'static str)], phrase: &str) -> Option<&'static str> {
+    table.iter().find(|(names, _)| names.contains(&phrase)).map(|(_, keys)| *keys)
+}
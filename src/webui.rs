@@ -0,0 +1,145 @@
+//! Embedded web UI for SS9K (webui feature)
+//!
+//! This module handles:
+//! - `GET /` - a single page showing live transcriptions, history and mode
+//! - `GET /api/state` - what the page polls (mode, recording, history)
+//! - `GET /api/commands` / `PUT /api/commands` - read and edit [commands] and [aliases]
+//!
+//! Edits are written into the config file with the rest of it (comments
+//! included) left alone; the config watcher hot-reloads them like any save.
+//!
+//! Every `/api/*` request, reads included, passes the same localhost checks
+//! as the control API (see `http::check_local`): history is what you said,
+//! and another page mustn't read it. Custom commands run shell commands, so
+//! writes also need a JSON body; browsers won't send a cross-site JSON PUT
+//! without a CORS preflight we never answer either.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tiny_http::{Method, Request, Response};
+use tracing::{info, warn};
+
+use crate::control::{self, Status};
+use crate::history::{self, Entry};
+use crate::http::{check_local, header, json, read_body, Context, HttpResponse};
+use crate::Config;
+
+/// The page itself
+const INDEX_HTML: &str = include_str!("webui.html");
+
+/// What `/api/state` returns
+#[derive(Serialize)]
+struct State {
+    #[serde(flatten)]
+    status: Status,
+    history: Vec<Entry>,
+}
+
+/// Body of `/api/commands`
+#[derive(Serialize, Deserialize)]
+struct Commands {
+    commands: BTreeMap<String, String>,
+    aliases: BTreeMap<String, String>,
+}
+
+/// Handle web UI routes (None = not ours)
+pub fn route(request: &mut Request, ctx: &Context) -> Option<HttpResponse> {
+    // The API hands out history and commands, not just edits: all of it is local-only
+    if request.url().starts_with("/api/")
+        && let Err((status, message)) = check_local(request, ctx.port)
+    {
+        warn!("⚠️ Web UI request rejected: {}", message);
+        return Some(Response::from_string(message).with_status_code(status));
+    }
+    let response = match (request.method(), request.url()) {
+        (Method::Get, "/") | (Method::Get, "/index.html") => {
+            Response::from_string(INDEX_HTML).with_header(header("Content-Type", "text/html; charset=utf-8"))
+        }
+        (Method::Get, "/api/state") => json(&State {
+            status: control::status(&ctx.config.load()),
+            history: history::recent(),
+        }),
+        (Method::Get, "/api/commands") => json(&file_commands(ctx)),
+        (Method::Put, "/api/commands") => match save_commands(request, ctx) {
+            Ok(()) => json(&serde_json::json!({ "ok": true })),
+            Err((status, message)) => {
+                warn!("⚠️ Web UI edit rejected: {}", message);
+                Response::from_string(message).with_status_code(status)
+            }
+        },
+        _ => return None,
+    };
+    Some(response)
+}
+
+/// [commands] and [aliases] as the config file has them: the live config
+/// also holds the active profile's, which mustn't be saved back to the file
+fn file_commands(ctx: &Context) -> Commands {
+    let live = ctx.config.load();
+    let from_file = ctx
+        .config_path
+        .as_deref()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| toml::from_str::<Config>(&contents).ok());
+    let config = from_file.as_ref().unwrap_or(&live);
+    Commands {
+        commands: sorted(&config.commands),
+        aliases: sorted(&config.aliases),
+    }
+}
+
+fn sorted(map: &HashMap<String, String>) -> BTreeMap<String, String> {
+    map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
+}
+
+/// Validate a PUT /api/commands and write it to the config file
+fn save_commands(request: &mut Request, ctx: &Context) -> Result<(), (u16, String)> {
+    let is_json = request
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Content-Type") && h.value.as_str().starts_with("application/json"));
+    if !is_json {
+        return Err((415, "Expected Content-Type: application/json".to_string()));
+    }
+    let Some(path) = &ctx.config_path else {
+        return Err((409, "No config file to edit (running on built-in defaults)".to_string()));
+    };
+
+    let body = read_body(request)?;
+    let edit: Commands = serde_json::from_str(&body).map_err(|e| (400, format!("Invalid JSON: {}", e)))?;
+
+    write_commands(path, &edit).map_err(|e| (500, e))?;
+    info!(
+        "🌐 Web UI saved {} commands, {} aliases",
+        edit.commands.len(),
+        edit.aliases.len()
+    );
+    Ok(())
+}
+
+/// Replace [commands] and [aliases] in the config file, keeping everything else
+fn write_commands(path: &Path, edit: &Commands) -> Result<(), String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    let mut doc: toml_edit::DocumentMut = contents.parse().map_err(|e| format!("Config parse error: {}", e))?;
+
+    for (name, entries) in [("commands", &edit.commands), ("aliases", &edit.aliases)] {
+        if !doc.contains_key(name) {
+            doc.insert(name, toml_edit::Item::Table(toml_edit::Table::new()));
+        }
+        // Edit the existing table so comments above its header survive
+        let table = doc[name]
+            .as_table_mut()
+            .ok_or_else(|| format!("[{}] in the config isn't a regular table", name))?;
+        table.clear();
+        for (phrase, value) in entries {
+            let phrase = phrase.trim();
+            if !phrase.is_empty() {
+                table.insert(phrase, toml_edit::value(value.as_str()));
+            }
+        }
+    }
+
+    std::fs::write(path, doc.to_string()).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
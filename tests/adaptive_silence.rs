@@ -0,0 +1,107 @@
+//! Adaptive VAD silence timeout over synthetic speech/pause patterns
+//!
+//! Each pattern is the list of silences between bursts of speech. `simulate`
+//! plays them through `AdaptiveSilence` the way the VAD state machine does:
+//! a silence shorter than the wait is a pause, anything longer ends the
+//! utterance and the rest of the silence is the gap before the next one.
+
+#![cfg(feature = "vad")]
+
+use ss9k::vad::AdaptiveSilence;
+
+const PAD_MS: u64 = 300;
+
+/// Feed silences through the tracker; returns the indexes that cut the utterance
+fn simulate(adaptive: &mut AdaptiveSilence, silences: &[u64]) -> Vec<usize> {
+    let mut cuts = Vec::new();
+    for (i, &silence) in silences.iter().enumerate() {
+        let waited = adaptive.timeout_ms() + PAD_MS;
+        if silence < waited {
+            adaptive.record_pause(silence);
+        } else {
+            cuts.push(i);
+            adaptive.record_cutoff(waited, silence - waited);
+        }
+    }
+    cuts
+}
+
+#[test]
+fn starts_at_initial_timeout() {
+    let adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    assert_eq!(adaptive.timeout_ms(), 1000);
+}
+
+#[test]
+fn initial_timeout_is_clamped() {
+    assert_eq!(AdaptiveSilence::new(100, 400, 2500).timeout_ms(), 400);
+    assert_eq!(AdaptiveSilence::new(9000, 400, 2500).timeout_ms(), 2500);
+    // max below min collapses to min
+    assert_eq!(AdaptiveSilence::new(1000, 800, 300).timeout_ms(), 800);
+}
+
+#[test]
+fn quick_commands_shrink_timeout() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    // "command enter", "command undo" - barely any gap between words
+    let cuts = simulate(&mut adaptive, &[120, 150, 90, 140, 110]);
+    assert!(cuts.is_empty());
+    assert_eq!(adaptive.timeout_ms(), 400);
+}
+
+#[test]
+fn slow_speaker_is_not_cut_off_twice() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    // Thoughtful dictation: 1.5s pauses mid-sentence
+    let cuts = simulate(&mut adaptive, &[1500, 1500, 1500, 1500]);
+    // The first pause exceeds the starting 1.3s wait, but after that it has learned
+    assert_eq!(cuts, vec![0]);
+    assert_eq!(adaptive.timeout_ms(), 2250);
+}
+
+#[test]
+fn growing_pauses_stretch_timeout() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    let cuts = simulate(&mut adaptive, &[400, 700, 900, 1100]);
+    assert!(cuts.is_empty());
+    assert_eq!(adaptive.timeout_ms(), 1650);
+}
+
+#[test]
+fn timeout_never_exceeds_max() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    simulate(&mut adaptive, &[1200, 2000, 2600, 2700]);
+    assert_eq!(adaptive.timeout_ms(), 2500);
+}
+
+#[test]
+fn long_gap_after_cutoff_is_a_new_utterance() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    simulate(&mut adaptive, &[200, 250]);
+    let before = adaptive.timeout_ms();
+    // Finished a command, waited 5 seconds, said the next one
+    let cuts = simulate(&mut adaptive, &[5000]);
+    assert_eq!(cuts, vec![0]);
+    assert_eq!(adaptive.timeout_ms(), before);
+}
+
+#[test]
+fn old_pauses_age_out() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    // One long pause, then a run of quick commands
+    simulate(&mut adaptive, &[1200]);
+    assert_eq!(adaptive.timeout_ms(), 1800);
+    simulate(&mut adaptive, &[150; 8]);
+    assert_eq!(adaptive.timeout_ms(), 400);
+}
+
+#[test]
+fn switching_back_to_slow_speech_recovers() {
+    let mut adaptive = AdaptiveSilence::new(1000, 400, 2500);
+    simulate(&mut adaptive, &[100; 8]);
+    assert_eq!(adaptive.timeout_ms(), 400);
+    // Wait is now 700ms; a 900ms pause cuts once, then the timeout grows
+    let cuts = simulate(&mut adaptive, &[900, 900, 900]);
+    assert_eq!(cuts, vec![0]);
+    assert_eq!(adaptive.timeout_ms(), 1350);
+}
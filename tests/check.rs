@@ -0,0 +1,46 @@
+//! Config validation on a fixture with one of each kind of mistake
+//!
+//! tests/fixtures/config/problems.toml is a config as someone might get it
+//! wrong: misspelled keys and phrases that collide once normalized, with
+//! the same phrase under two table headers so line lookups have to respect
+//! which table they're in.
+
+use std::path::PathBuf;
+
+use ss9k::check::{check_file, Problem};
+
+fn problems() -> Vec<Problem> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/config/problems.toml");
+    check_file(&path).unwrap()
+}
+
+fn problem(table: Option<&str>, key: &str) -> Problem {
+    problems()
+        .into_iter()
+        .find(|p| p.table.as_deref() == table && p.key == key)
+        .unwrap_or_else(|| panic!("no problem with {:?} {:?} in {:?}", table, key, problems()))
+}
+
+#[test]
+fn unknown_keys_suggest_the_closest_known_one() {
+    let typo = problem(None, "hotkey_mod");
+    assert_eq!(typo.message, "unknown key (did you mean 'hotkey_mode'?)");
+    assert_eq!(typo.line, Some(3));
+
+    let unknown = problem(None, "whisper_temperature");
+    assert!(!unknown.message.contains("did you mean"), "{}", unknown.message);
+    assert_eq!(unknown.line, Some(4));
+}
+
+#[test]
+fn phrases_that_normalize_alike_are_duplicates() {
+    let duplicate = problem(Some("commands"), "Open  Browser");
+    assert!(duplicate.message.contains("\"open browser\" once normalized"), "{}", duplicate.message);
+}
+
+#[test]
+fn lines_are_looked_up_inside_the_problem_table() {
+    // The same phrase is set in [keybinds] first; each problem gets its own table's line
+    assert_eq!(problem(Some("commands"), "Open  Browser").line, Some(11));
+    assert_eq!(problem(Some("keybinds"), "Open  Browser").line, Some(7));
+}
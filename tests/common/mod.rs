@@ -0,0 +1,63 @@
+//! Helpers shared by the integration tests
+//!
+//! Each test file uses only some of them.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use ss9k::commands::{Fuzzy, Phrases};
+use ss9k::config::CommandSettings;
+
+/// No phrases and no fuzzy or phonetic matching, so only built-ins parse;
+/// fill in the tables a test needs with `..empty_phrases()`
+pub fn empty_phrases() -> Phrases<'static> {
+    static STRINGS: LazyLock<HashMap<String, String>> = LazyLock::new(HashMap::new);
+    static MACROS: LazyLock<HashMap<String, Vec<String>>> = LazyLock::new(HashMap::new);
+    static PRIORITIES: LazyLock<HashMap<String, i64>> = LazyLock::new(HashMap::new);
+    static SETTINGS: LazyLock<HashMap<String, CommandSettings>> = LazyLock::new(HashMap::new);
+    Phrases {
+        commands: &STRINGS,
+        keybinds: &STRINGS,
+        macros: &MACROS,
+        patterns: &[],
+        replacements: &[],
+        fuzzy: Fuzzy::Off,
+        phonetic: false,
+        priorities: &PRIORITIES,
+        settings: &SETTINGS,
+        command_aliases: &STRINGS,
+    }
+}
+
+/// English words for a number, the way people say it: 2026 -> "two thousand twenty six"
+pub fn spell(n: u64) -> String {
+    const SMALL: [&str; 20] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten", "eleven", "twelve",
+        "thirteen", "fourteen", "fifteen", "sixteen", "seventeen", "eighteen", "nineteen",
+    ];
+    const TENS: [&str; 10] = ["", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety"];
+    let below_thousand = |n: u64| {
+        let mut words = Vec::new();
+        if n >= 100 {
+            words.extend([SMALL[(n / 100) as usize], "hundred"]);
+        }
+        match n % 100 {
+            0 => {}
+            r @ 1..20 => words.push(SMALL[r as usize]),
+            r => words.extend([TENS[(r / 10) as usize], SMALL[(r % 10) as usize]].into_iter().filter(|w| *w != "zero")),
+        }
+        words.join(" ")
+    };
+    if n == 0 {
+        return "zero".to_string();
+    }
+    let mut parts = Vec::new();
+    for (scale, name) in [(1_000_000_000, "billion"), (1_000_000, "million"), (1_000, "thousand"), (1, "")] {
+        let group = n / scale % 1000;
+        if group > 0 {
+            parts.push(format!("{} {}", below_thousand(group), name).trim().to_string());
+        }
+    }
+    parts.join(" ")
+}
@@ -0,0 +1,100 @@
+//! Config migrations, one test per historical config version
+//!
+//! Fixtures in tests/fixtures/config/ are configs as each version wrote
+//! them. Migrating must land on the current shape with a diff describing
+//! every change, and loading must keep the user's settings.
+
+use std::path::PathBuf;
+
+use ss9k::migrate::{migrate, CONFIG_VERSION};
+use ss9k::Config;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/config").join(name)
+}
+
+fn table(name: &str) -> toml::Table {
+    toml::from_str(&std::fs::read_to_string(fixture(name)).unwrap()).unwrap()
+}
+
+#[test]
+fn default_config_is_current_version() {
+    assert_eq!(Config::default().config_version, CONFIG_VERSION);
+}
+
+#[test]
+fn v1_commands_lose_the_leader() {
+    let mut config = table("v1.toml");
+    let report = migrate(&mut config);
+
+    assert_eq!(report.from_version, 1);
+    assert!(report.warnings.is_empty(), "{:?}", report.warnings);
+    let mut changes = report.changes.clone();
+    changes.sort();
+    assert_eq!(
+        changes,
+        vec![
+            "+ [commands] \"open browser\" = \"firefox\"",
+            "+ [commands] \"screenshot\" = \"flameshot gui\"",
+            "+ config_version = 2",
+            "- [commands] \"Command screenshot\" = \"flameshot gui\"",
+            "- [commands] \"command open browser\" = \"firefox\"",
+        ]
+    );
+    assert_eq!(config["config_version"].as_integer(), Some(CONFIG_VERSION as i64));
+}
+
+#[test]
+fn v1_loads_with_settings_intact() {
+    let config = Config::load_from(&fixture("v1.toml")).unwrap();
+    assert_eq!(config.config_version, CONFIG_VERSION);
+    assert_eq!(config.model, "base");
+    assert_eq!(config.hotkey_mode, "toggle");
+    assert!(config.audio_feedback);
+    assert_eq!(config.commands.get("open browser").map(String::as_str), Some("firefox"));
+    assert_eq!(config.commands.get("screenshot").map(String::as_str), Some("flameshot gui"));
+    assert_eq!(config.commands.get("open terminal").map(String::as_str), Some("kitty"));
+    assert_eq!(config.commands.len(), 3);
+    assert_eq!(config.aliases.get("come and").map(String::as_str), Some("command"));
+}
+
+#[test]
+fn v1_custom_leader_keeps_existing_phrases() {
+    let config = Config::load_from(&fixture("v1_custom_leader.toml")).unwrap();
+    assert_eq!(config.commands.get("lock screen").map(String::as_str), Some("loginctl lock-session"));
+    // "open browser" was already there, so the leader version is dropped rather than overwriting it
+    assert_eq!(config.commands.get("open browser").map(String::as_str), Some("chromium"));
+    assert!(!config.commands.contains_key("voice open browser"));
+    // A phrase that's only the leader has nothing to strip
+    assert!(config.commands.contains_key("voice"));
+
+    let report = migrate(&mut table("v1_custom_leader.toml"));
+    assert!(report.changes.iter().any(|c| c.contains("\"open browser\" already set")));
+}
+
+#[test]
+fn current_version_is_untouched() {
+    let mut config = table("v2.toml");
+    let before = config.clone();
+    let report = migrate(&mut config);
+    assert_eq!(report.from_version, 2);
+    assert!(report.is_empty(), "{:?}", report);
+    assert_eq!(config, before);
+}
+
+#[test]
+fn unknown_keys_are_reported() {
+    let mut config: toml::Table = toml::from_str("config_version = 2\nhotkee = \"F9\"\nmodel = \"tiny\"").unwrap();
+    let report = migrate(&mut config);
+    assert!(report.changes.is_empty());
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].contains("'hotkee'"));
+}
+
+#[test]
+fn newer_version_warns() {
+    let mut config: toml::Table = toml::from_str("config_version = 99").unwrap();
+    let report = migrate(&mut config);
+    assert!(report.changes.is_empty());
+    assert!(report.warnings[0].contains("newer"));
+}
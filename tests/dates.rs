@@ -0,0 +1,32 @@
+//! Property tests for spoken dates and times
+//!
+//! Relative dates ("in three days", "next friday at 5 pm") are checked
+//! against arbitrary "now"s so month ends and leap years come up.
+
+mod common;
+
+use chrono::{Datelike, NaiveDate, TimeDelta, Weekday};
+use common::spell;
+use proptest::prelude::*;
+use ss9k::dates;
+
+proptest! {
+    #[test]
+    fn spoken_dates_count_from_now(day in 0u32..3650, minute in 0u32..1440, n in 1i64..400, weekday in 0u32..7, hour in 1u32..13) {
+        let now = NaiveDate::from_ymd_opt(2020, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap()
+            + TimeDelta::days(day as i64) + TimeDelta::minutes(minute as i64);
+        let spelled = spell(n as u64);
+        prop_assert_eq!(dates::parse(&format!("in {} days", spelled), now), Some(now + TimeDelta::days(n)));
+        prop_assert_eq!(dates::parse(&format!("{} weeks ago", n), now), Some(now - TimeDelta::weeks(n)));
+        prop_assert_eq!(dates::parse(&format!("{} minutes from now", spelled), now), Some(now + TimeDelta::minutes(n)));
+
+        let name = ["monday", "tuesday", "wednesday", "thursday", "friday", "saturday", "sunday"][weekday as usize];
+        let target = Weekday::try_from(weekday as u8).unwrap();
+        for (which, range) in [("", 0..=6), ("next ", 1..=7), ("last ", -7..=-1)] {
+            let when = dates::parse(&format!("{}{} at {} pm", which, name, hour), now).unwrap();
+            prop_assert_eq!(when.weekday(), target);
+            prop_assert!(range.contains(&(when.date() - now.date()).num_days()));
+            prop_assert_eq!(when.time(), chrono::NaiveTime::from_hms_opt(hour % 12 + 12, 0, 0).unwrap());
+        }
+    }
+}
@@ -0,0 +1,5 @@
+;; Test plugin for tests/plugins.rs: built for an ABI this SS9K doesn't speak
+(module
+  (memory (export "memory") 1)
+  (func (export "ss9k_abi") (result i32)
+    (i32.const 2)))
@@ -0,0 +1,5 @@
+;; Test plugin for tests/plugins.rs: starts with more memory than SS9K allows
+(module
+  (memory (export "memory") 2048)
+  (func (export "ss9k_abi") (result i32)
+    (i32.const 1)))
@@ -0,0 +1,45 @@
+;; Test plugin for tests/plugins.rs: claims "command shout", and its
+;; on_transcription capitalizes the text, except for
+;; - "loop...": never returns, so the fuel runs out
+;; - "grow...": asks for more memory than SS9K allows and reports the answer
+(module
+  (import "ss9k" "set_text" (func $set_text (param i32 i32)))
+  (import "ss9k" "subcommand" (func $subcommand (param i32 i32)))
+  (memory (export "memory") 1)
+  (data (i32.const 0) "shout")
+  (data (i32.const 16) "refused")
+  (data (i32.const 32) "grew")
+  (global $next (mut i32) (i32.const 1024))
+
+  (func (export "ss9k_abi") (result i32)
+    (i32.const 1))
+
+  (func (export "ss9k_alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr))
+
+  (func (export "ss9k_init") (param $ptr i32) (param $len i32)
+    (call $subcommand (i32.const 0) (i32.const 5)))
+
+  (func (export "on_transcription") (param $ptr i32) (param $len i32)
+    (local $i i32)
+    (local $c i32)
+    (if (i32.eq (i32.load8_u (local.get $ptr)) (i32.const 0x6C)) ;; l
+      (then (loop $forever (br $forever))))
+    (if (i32.eq (i32.load8_u (local.get $ptr)) (i32.const 0x67)) ;; g
+      (then
+        (if (i32.eq (memory.grow (i32.const 2048)) (i32.const -1))
+          (then (call $set_text (i32.const 16) (i32.const 7)))
+          (else (call $set_text (i32.const 32) (i32.const 4))))
+        (return)))
+    (block $done
+      (loop $each
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (local.set $c (i32.load8_u (i32.add (local.get $ptr) (local.get $i))))
+        (if (i32.and (i32.ge_u (local.get $c) (i32.const 0x61)) (i32.le_u (local.get $c) (i32.const 0x7A)))
+          (then (i32.store8 (i32.add (local.get $ptr) (local.get $i)) (i32.sub (local.get $c) (i32.const 32)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $each)))
+    (call $set_text (local.get $ptr) (local.get $len))))
@@ -0,0 +1,212 @@
+//! Golden-output tests for the audio and command pipelines
+//!
+//! Each test renders its results as text and compares them with a checked-in
+//! golden file, so refactors of commands.rs/audio.rs can't silently change
+//! behavior. After an intentional change, regenerate and review the diff:
+//!
+//!     SS9K_BLESS=1 cargo test --test golden
+//!
+//! Transcription needs a model and is skipped unless one is given:
+//!
+//!     SS9K_TEST_MODEL=~/.local/share/ss9k/models/ggml-tiny.en.bin cargo test --test golden
+//!
+//! It transcribes the audio fixtures that have a `.txt` transcript next to
+//! them, and fails if none do. To add one, record a few seconds of speech as
+//! tests/fixtures/audio/<name>.wav and bless it with the model above set.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ss9k::audio::{decode_file, resample_audio, transcribe, WHISPER_SAMPLE_RATE};
+use ss9k::commands::{parse_action, Fuzzy, Keystroke, Phrases};
+use ss9k::model::load_model;
+use ss9k::{emacs, vim};
+use ss9k::config::{CommandSettings, Pattern, Replacement};
+use ss9k::Config;
+
+fn fixtures_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// WAV fixtures in a stable order
+fn audio_fixtures() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(fixtures_dir().join("audio"))
+        .expect("tests/fixtures/audio missing")
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "wav"))
+        .collect();
+    files.sort();
+    files
+}
+
+/// Compare against a golden file, or rewrite it when SS9K_BLESS is set
+fn check_golden(path: &Path, actual: &str) {
+    if std::env::var_os("SS9K_BLESS").is_some() {
+        fs::write(path, actual).expect("failed to write golden file");
+        return;
+    }
+    let expected = fs::read_to_string(path)
+        .unwrap_or_else(|_| panic!("missing golden file {:?} (run with SS9K_BLESS=1 to create it)", path));
+    assert!(
+        expected == actual,
+        "output differs from {:?}\n--- expected\n{}\n--- actual\n{}\n(run with SS9K_BLESS=1 if the change is intended)",
+        path, expected, actual
+    );
+}
+
+#[test]
+fn command_parsing_matches_golden() {
+    let leaderless = ["escape".to_string(), "Scratch That".to_string()];
+    let custom_commands = HashMap::from([
+        ("open browser".to_string(), "firefox".to_string()),
+        ("note urgent {text}".to_string(), "notify-send {text}".to_string()),
+        ("search {q}".to_string(), "web {q}".to_string()),
+        ("search code for {q}".to_string(), "code-search {q}".to_string()),
+        ("reboot".to_string(), "systemctl reboot".to_string()),
+        ("build project".to_string(), "cargo build".to_string()),
+    ]);
+    let settings = HashMap::from([
+        ("reboot".to_string(), CommandSettings { confirm: true, ..Default::default() }),
+        (
+            "build project".to_string(),
+            CommandSettings {
+                cwd: "~/code/ss9k".to_string(),
+                env: HashMap::from([("RUST_LOG".to_string(), "debug".to_string())]),
+                ..Default::default()
+            },
+        ),
+    ]);
+    let keybinds = HashMap::from([
+        ("open terminal".to_string(), "ctrl+alt+t".to_string()),
+        ("go to tab {n}".to_string(), "alt+{n}".to_string()),
+    ]);
+    let macros = HashMap::from([
+        ("file bug".to_string(), vec!["shell \"xdg-open https://example.com/new\"".to_string(), "wait 1500".to_string()]),
+        ("note {text}".to_string(), vec!["type \"TODO: {text}\"".to_string()]),
+    ]);
+    let patterns = [
+        Pattern {
+            regex: r"go to line (\d+)".to_string(),
+            steps: vec!["key \"ctrl+g\"".to_string(), "type \"{1}\"".to_string(), "key \"enter\"".to_string()],
+            ..Default::default()
+        },
+        Pattern {
+            regex: r"open (?P<site>github|reddit)".to_string(),
+            shell: "xdg-open https://{site}.com".to_string(),
+            ..Default::default()
+        },
+    ];
+    let replacements = [
+        Replacement { regex: r"\bfor example\b".to_string(), with: "e.g.".to_string() },
+        Replacement { regex: r"(\d+) percent".to_string(), with: "{1}%".to_string() },
+        Replacement { regex: r"\bgit hub\b".to_string(), with: "GitHub".to_string() },
+    ];
+    let priorities = HashMap::from([("search {q}".to_string(), 10)]);
+    let command_aliases = HashMap::from([
+        ("zap".to_string(), "backspace".to_string()),
+        ("nuke line".to_string(), "shift end, backspace".to_string()),
+        ("grin".to_string(), "emoji smile".to_string()),
+    ]);
+    let phrases = Phrases {
+        commands: &custom_commands,
+        keybinds: &keybinds,
+        macros: &macros,
+        patterns: &patterns,
+        replacements: &replacements,
+        fuzzy: Fuzzy::Off,
+        phonetic: true,
+        priorities: &priorities,
+        settings: &settings,
+        command_aliases: &command_aliases,
+    };
+    let aliases = HashMap::from([("e max".to_string(), "emacs".to_string())]);
+
+    let input = fs::read_to_string(fixtures_dir().join("commands.input")).unwrap();
+    let mut actual = String::new();
+    for line in input.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let action = parse_action(line, "command", &leaderless, &phrases, &aliases);
+        actual.push_str(&format!("{:?} => {:?}\n", line, action));
+    }
+
+    check_golden(&fixtures_dir().join("commands.golden"), &actual);
+}
+
+/// Render an editor mode's keys for each phrase in `<mode>.input`
+fn check_editor_golden(mode: &str, translate: fn(&str) -> Option<String>, keystrokes: fn(&str) -> Vec<Keystroke>) {
+    let input = fs::read_to_string(fixtures_dir().join(format!("{}.input", mode))).unwrap();
+    let mut actual = String::new();
+    for line in input.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let notation = translate(line);
+        let keys = notation.as_deref().map(keystrokes);
+        actual.push_str(&format!("{:?} => {:?} {:?}\n", line, notation, keys));
+    }
+
+    check_golden(&fixtures_dir().join(format!("{}.golden", mode)), &actual);
+}
+
+#[test]
+fn vim_phrases_match_golden() {
+    check_editor_golden("vim", vim::translate, vim::keystrokes);
+}
+
+#[test]
+fn emacs_phrases_match_golden() {
+    check_editor_golden("emacs", emacs::translate, emacs::keystrokes);
+}
+
+#[test]
+fn audio_resampling_matches_golden() {
+    let mut actual = String::new();
+    for path in audio_fixtures() {
+        let (samples, rate) = decode_file(&path).unwrap();
+        let resampled = resample_audio(&samples, rate, WHISPER_SAMPLE_RATE).unwrap();
+
+        let rms = (resampled.iter().map(|s| s * s).sum::<f32>() / resampled.len().max(1) as f32).sqrt();
+        let peak = resampled.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        actual.push_str(&format!(
+            "{}: {} samples @ {}Hz -> {} samples @ {}Hz, rms {:.3}, peak {:.3}\n",
+            path.file_name().unwrap().to_string_lossy(),
+            samples.len(),
+            rate,
+            resampled.len(),
+            WHISPER_SAMPLE_RATE,
+            rms,
+            peak
+        ));
+    }
+
+    check_golden(&fixtures_dir().join("audio/resample.golden"), &actual);
+}
+
+/// Transcribes every fixture that has a sibling .txt transcript (greedy decoding)
+#[test]
+fn transcription_matches_golden() {
+    let Some(model_path) = std::env::var_os("SS9K_TEST_MODEL") else {
+        eprintln!("skipping: set SS9K_TEST_MODEL to a ggml model to run transcription goldens");
+        return;
+    };
+    let ctx = load_model(Path::new(&model_path)).unwrap();
+    let config = Config::default();
+
+    let mut checked = 0;
+    for path in audio_fixtures() {
+        let golden = path.with_extension("txt");
+        if !golden.exists() && std::env::var_os("SS9K_BLESS").is_none() {
+            continue;
+        }
+        let (samples, rate) = decode_file(&path).unwrap();
+        let resampled = resample_audio(&samples, rate, WHISPER_SAMPLE_RATE).unwrap();
+        let text = transcribe(&ctx, &resampled, &config).unwrap();
+        check_golden(&golden, &format!("{}\n", text));
+        checked += 1;
+    }
+    // Passing with nothing transcribed would look like coverage
+    assert!(checked > 0, "no audio fixture has a .txt transcript; add a speech WAV and bless it");
+}
@@ -0,0 +1,116 @@
+//! Hotkey names, mouse buttons and held modifiers
+//!
+//! A combo fires only with exactly its own modifiers down, whichever side
+//! of the keyboard they're on and in whatever order they were pressed.
+
+use rdev::{Button, EventType, Key};
+use ss9k::hotkeys::{parse_hotkey, parse_switch, HeldModifiers, Hotkey, Input, Modifiers};
+
+/// The thumb buttons' numbers, as rdev reports them here
+#[cfg(target_os = "windows")]
+const BACK_FORWARD: [u8; 2] = [1, 2];
+#[cfg(not(target_os = "windows"))]
+const BACK_FORWARD: [u8; 2] = [8, 9];
+
+const CTRL: Modifiers = Modifiers { ctrl: true, alt: false, shift: false, meta: false };
+const CTRL_ALT: Modifiers = Modifiers { ctrl: true, alt: true, shift: false, meta: false };
+
+#[test]
+fn single_keys_need_no_modifiers() {
+    assert_eq!(parse_hotkey("F12"), Some(Hotkey::new(Input::Key(Key::F12))));
+    assert_eq!(parse_hotkey(" scroll_lock "), Some(Hotkey::new(Input::Key(Key::ScrollLock))));
+    assert_eq!(parse_hotkey("numpad5"), Some(Hotkey::new(Input::Key(Key::Kp5))));
+    // Typed all the time, so only with a modifier
+    assert_eq!(parse_hotkey("Space"), None);
+    assert_eq!(parse_hotkey("D"), None);
+}
+
+#[test]
+fn modifier_combos() {
+    let ctrl_alt_space = Hotkey { input: Input::Key(Key::Space), modifiers: CTRL_ALT };
+    assert_eq!(parse_hotkey("Ctrl+Alt+Space"), Some(ctrl_alt_space));
+    assert_eq!(parse_hotkey("alt + control + space"), Some(ctrl_alt_space));
+    assert_eq!(parse_hotkey("Option+Ctrl+SPACE"), Some(ctrl_alt_space));
+
+    let meta = Modifiers { meta: true, ..Modifiers::default() };
+    for name in ["Super+D", "Win+d", "Cmd+D", "Meta+D"] {
+        assert_eq!(parse_hotkey(name), Some(Hotkey { input: Input::Key(Key::KeyD), modifiers: meta }), "{}", name);
+    }
+    let shift = Modifiers { shift: true, ..Modifiers::default() };
+    assert_eq!(parse_hotkey("Shift+F12"), Some(Hotkey { input: Input::Key(Key::F12), modifiers: shift }));
+    assert_eq!(parse_hotkey("Ctrl+7"), Some(Hotkey { input: Input::Key(Key::Num7), modifiers: CTRL }));
+}
+
+#[test]
+fn unknown_names_dont_parse() {
+    for name in ["", "F13", "Hyper+A", "Ctrl+", "+F12", "Ctrl+Foo", "Ctrl+Alt", "Ctrl+Shift+Semicolon", "F12+Ctrl"] {
+        assert_eq!(parse_hotkey(name), None, "{:?}", name);
+    }
+}
+
+#[test]
+fn mouse_buttons() {
+    fn button(name: &str) -> Option<Input> {
+        parse_hotkey(name).map(|hotkey| hotkey.input)
+    }
+    assert_eq!(button("Mouse_Middle"), Some(Input::Button(Button::Middle)));
+    assert_eq!(button("middle_click"), Some(Input::Button(Button::Middle)));
+    assert_eq!(button("Mouse4"), Some(Input::Button(Button::Unknown(BACK_FORWARD[0]))));
+    assert_eq!(button("MOUSE_BACK"), Some(Input::Button(Button::Unknown(BACK_FORWARD[0]))));
+    assert_eq!(button("mouse5"), Some(Input::Button(Button::Unknown(BACK_FORWARD[1]))));
+    assert_eq!(button("Mouse_Forward"), Some(Input::Button(Button::Unknown(BACK_FORWARD[1]))));
+    for n in 6..=9 {
+        assert_eq!(button(&format!("Mouse{}", n)), Some(Input::Button(Button::Unknown(n))));
+    }
+    let ctrl_back = Hotkey { input: Input::Button(Button::Unknown(BACK_FORWARD[0])), modifiers: CTRL };
+    assert_eq!(parse_hotkey("Ctrl+Mouse4"), Some(ctrl_back));
+
+    // Left and right clicks (and the wheel, 4-7 on X11) are never hotkeys
+    for name in ["Mouse1", "Mouse2", "Mouse3", "Mouse10", "Mouse256", "Mouse", "Mouse_Left", "Mouse-4", "Mouse 4"] {
+        assert_eq!(button(name), None, "{:?}", name);
+    }
+}
+
+#[test]
+fn switch_keys_take_buttons_too() {
+    assert_eq!(parse_switch(" mouse_middle"), Some(Input::Button(Button::Middle)));
+    assert_eq!(parse_switch("Mouse7"), Some(Input::Button(Button::Unknown(7))));
+    assert_eq!(parse_switch("space"), Some(Input::Key(Key::Space)));
+    assert_eq!(parse_switch("F9"), Some(Input::Key(Key::F9)));
+    assert_eq!(parse_switch(""), None);
+    assert_eq!(parse_switch("Mouse3"), None);
+}
+
+#[test]
+fn combos_fire_with_exactly_their_modifiers() {
+    let hotkey = parse_hotkey("Ctrl+Alt+Space").unwrap();
+    assert!(hotkey.pressed(Input::Key(Key::Space), CTRL_ALT));
+    assert!(!hotkey.pressed(Input::Key(Key::Space), CTRL));
+    assert!(!hotkey.pressed(Input::Key(Key::Space), Modifiers { shift: true, ..CTRL_ALT }));
+    assert!(!hotkey.pressed(Input::Key(Key::Return), CTRL_ALT));
+    assert!(!parse_hotkey("F12").unwrap().pressed(Input::Key(Key::F12), CTRL));
+}
+
+#[test]
+fn held_modifiers_follow_presses_and_releases() {
+    let mut held = HeldModifiers::default();
+    let mut event = |event| {
+        held.update(&event);
+        held.current()
+    };
+    assert_eq!(event(EventType::KeyPress(Key::ControlLeft)), CTRL);
+    assert_eq!(event(EventType::KeyPress(Key::AltGr)), CTRL_ALT);
+    // Other keys and mouse events change nothing
+    assert_eq!(event(EventType::KeyPress(Key::Space)), CTRL_ALT);
+    assert_eq!(event(EventType::ButtonPress(Button::Left)), CTRL_ALT);
+    assert_eq!(event(EventType::KeyRelease(Key::AltGr)), CTRL);
+
+    // Both Ctrls down: letting go of one leaves Ctrl held
+    assert_eq!(event(EventType::KeyPress(Key::ControlRight)), CTRL);
+    assert_eq!(event(EventType::KeyRelease(Key::ControlLeft)), CTRL);
+    assert_eq!(event(EventType::KeyRelease(Key::ControlRight)), Modifiers::default());
+
+    // A release with no press (it went down before SS9K started) is harmless
+    assert_eq!(event(EventType::KeyRelease(Key::MetaLeft)), Modifiers::default());
+    assert_eq!(event(EventType::KeyPress(Key::ShiftRight)), Modifiers { shift: true, ..Modifiers::default() });
+}
@@ -0,0 +1,60 @@
+//! Property tests for the lookup tables
+//!
+//! Punctuation, emoji and spelling alphabet words have built-in answers
+//! that the config can override, and Unicode characters can be named or
+//! read out as code points; these check both ways round.
+
+use std::collections::HashMap;
+
+use proptest::prelude::*;
+use ss9k::lookups::{emoji_for, punctuation_symbol, spelled_char, unicode_char, EMOJI, GERMAN, NATO, PUNCTUATION};
+
+proptest! {
+    #[test]
+    fn punctuation_table_overrides(index in 0..PUNCTUATION.len(), symbol in "\\PC{1,3}") {
+        let (names, builtin) = PUNCTUATION[index];
+        for name in names {
+            prop_assert_eq!(punctuation_symbol(&name.to_uppercase(), &HashMap::new()), Some(builtin.to_string()));
+            let custom = HashMap::from([(format!(" {} ", name), symbol.clone())]);
+            prop_assert_eq!(punctuation_symbol(name, &custom), Some(symbol.clone()));
+        }
+    }
+
+    #[test]
+    fn emoji_table_overrides(index in 0..EMOJI.len(), emoji in "\\PC{1,3}") {
+        let (names, builtin) = EMOJI[index];
+        for name in names {
+            prop_assert_eq!(emoji_for(name, &HashMap::new()), Some(builtin.to_string()));
+            let custom = HashMap::from([(name.to_uppercase(), emoji.clone())]);
+            prop_assert_eq!(emoji_for(name, &custom), Some(emoji.clone()));
+        }
+    }
+
+    #[test]
+    fn spelling_alphabets_override(index in 0..NATO.len() + GERMAN.len(), ch in "\\PC") {
+        let (alphabet, (words, letter)) = match index.checked_sub(NATO.len()) {
+            None => (NATO, NATO[index]),
+            Some(i) => (GERMAN, GERMAN[i]),
+        };
+        for word in words {
+            prop_assert_eq!(spelled_char(&word.to_uppercase(), alphabet, &HashMap::new()), Some(letter));
+            prop_assert_ne!(spelled_char(word, &[], &HashMap::new()), Some(letter));
+            let custom = HashMap::from([(word.to_string(), ch.clone())]);
+            prop_assert_eq!(spelled_char(word, alphabet, &custom), ch.chars().next());
+        }
+    }
+
+    #[test]
+    fn unicode_names_and_code_points(ch in any::<char>().prop_filter("typeable", |c| !c.is_control())) {
+        const DIGITS: [&str; 16] = [
+            "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "a", "b", "c", "delta", "echo", "f",
+        ];
+        let hex = format!("{:04X}", ch as u32);
+        prop_assert_eq!(unicode_char(&format!("U+{}", hex)), Some(ch));
+        let spoken: Vec<&str> = hex.chars().map(|d| DIGITS[d.to_digit(16).unwrap() as usize]).collect();
+        prop_assert_eq!(unicode_char(&spoken.join(" ")), Some(ch));
+        if let Some(name) = unicode_names2::name(ch) {
+            prop_assert_eq!(unicode_char(&name.to_string().to_lowercase()), Some(ch));
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! Turning MIDI messages into presses and releases
+//!
+//! Faders and expression pedals send every value they pass through, so a
+//! controller only presses when it crosses 64 and releases when it drops
+//! back under.
+#![cfg(feature = "midi")]
+
+use ss9k::hotkeys::MidiMessage;
+use ss9k::midi::Decoder;
+
+const CC: u8 = 0xB0;
+
+#[test]
+fn a_controller_sweep_presses_once() {
+    let mut decoder = Decoder::default();
+    let presses: Vec<_> = (0..=127).filter_map(|value| decoder.decode(&[CC, 11, value])).collect();
+    assert_eq!(presses, vec![(MidiMessage::Cc(11), true)]);
+
+    let releases: Vec<_> = (0..=127).rev().filter_map(|value| decoder.decode(&[CC, 11, value])).collect();
+    assert_eq!(releases, vec![(MidiMessage::Cc(11), false)]);
+}
+
+#[test]
+fn controllers_are_tracked_per_channel_and_number() {
+    let mut decoder = Decoder::default();
+    assert_eq!(decoder.decode(&[CC, 64, 127]), Some((MidiMessage::Cc(64), true)));
+    assert_eq!(decoder.decode(&[CC | 1, 64, 127]), Some((MidiMessage::Cc(64), true)));
+    assert_eq!(decoder.decode(&[CC, 65, 127]), Some((MidiMessage::Cc(65), true)));
+    assert_eq!(decoder.decode(&[CC, 64, 100]), None);
+    // Never pressed, so nothing to release
+    assert_eq!(decoder.decode(&[CC, 66, 0]), None);
+}
+
+#[test]
+fn notes_press_and_release() {
+    let mut decoder = Decoder::default();
+    assert_eq!(decoder.decode(&[0x90, 60, 100]), Some((MidiMessage::Note(60), true)));
+    assert_eq!(decoder.decode(&[0x90, 60, 0]), Some((MidiMessage::Note(60), false)));
+    assert_eq!(decoder.decode(&[0x80, 60, 64]), Some((MidiMessage::Note(60), false)));
+    assert_eq!(decoder.decode(&[0xE0, 0, 64]), None);
+    assert_eq!(decoder.decode(&[0x90, 60]), None);
+}
@@ -0,0 +1,57 @@
+//! Property tests for voice mouse control
+//!
+//! Spoken distances move and scroll by that much, and the grid's cells
+//! cover the area they split without gaps or overlaps, with the overlay
+//! drawn inside them.
+
+mod common;
+
+use common::spell;
+use proptest::prelude::*;
+use ss9k::mouse::{self, Grid, GridStep, Pointer};
+
+proptest! {
+    #[test]
+    fn mouse_moves_the_spoken_distance(n in 1u64..5000, direction in 0usize..4, spelled in any::<bool>(), scroll in any::<bool>()) {
+        let (name, sign) = [("up", -1), ("down", 1), ("left", -1), ("right", 1)][direction];
+        let amount = if spelled { spell(n) } else { n.to_string() };
+        let n = n as i32 * sign;
+        if scroll {
+            let length = match mouse::parse(&format!("scroll {} {}", name, amount)) {
+                Some(Pointer::Scroll { length, .. }) => length,
+                other => panic!("not a scroll: {:?}", other),
+            };
+            prop_assert_eq!(length, n);
+        } else {
+            let expected = if direction < 2 { Pointer::Move { dx: 0, dy: n } } else { Pointer::Move { dx: n, dy: 0 } };
+            prop_assert_eq!(mouse::parse(&format!("mouse {} {} pixels", name, amount)), Some(expected));
+        }
+        prop_assert_eq!(mouse::parse(&format!("mouse {} {}", name, "sideways")), None);
+    }
+
+    #[test]
+    fn grid_cells_tile_the_area(x in -5000i32..5000, y in -5000i32..5000, width in 1i32..8000, height in 1i32..8000, n in 1u8..10) {
+        let area = Grid { x, y, width, height };
+        let cell = area.cell(n).unwrap();
+        prop_assert!(cell.x >= area.x && cell.x + cell.width <= area.x + area.width);
+        prop_assert!(cell.y >= area.y && cell.y + cell.height <= area.y + area.height);
+        // The nine cells cover the area exactly
+        let cells: Vec<Grid> = (1..=9).map(|n| area.cell(n).unwrap()).collect();
+        let covered: i64 = cells.iter().map(|c| i64::from(c.width) * i64::from(c.height)).sum();
+        prop_assert_eq!(covered, i64::from(width) * i64::from(height));
+        prop_assert_eq!(area.cell(0), None);
+
+        let spoken = format!("{} {}", spell(u64::from(n)), n);
+        prop_assert_eq!(mouse::grid_step(&spoken), Some(GridStep::Zoom(vec![n, n])));
+    }
+
+    #[test]
+    fn grid_overlay_stays_inside_the_area(x in -5000i32..5000, y in -5000i32..5000, width in 1i32..8000, height in 1i32..8000) {
+        let area = Grid { x, y, width, height };
+        for rect in area.overlay() {
+            prop_assert!(rect.width > 0 && rect.height > 0);
+            prop_assert!(rect.x >= area.x && rect.x + rect.width <= area.x + area.width);
+            prop_assert!(rect.y >= area.y && rect.y + rect.height <= area.y + area.height);
+        }
+    }
+}
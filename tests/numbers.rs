@@ -0,0 +1,75 @@
+//! Property tests for spoken numbers
+//!
+//! Numbers are spelled out the way people say them and have to read back
+//! as the same value, whether as digits, ordinals, fractions or amounts.
+
+mod common;
+
+use common::spell;
+use proptest::prelude::*;
+use ss9k::commands::apply_math_mode;
+use ss9k::numbers::{format_amounts, parse_decimal, parse_integer, to_digits, Locale};
+
+proptest! {
+    #[test]
+    fn spoken_numbers_read_back(n in 0u64..1_000_000_000_000, negative in any::<bool>(), fraction in "[0-9]{0,3}") {
+        let words = spell(n);
+        prop_assert_eq!(parse_integer(&words), Some(n as i64));
+        prop_assert_eq!(parse_integer(&format!("negative {}", words)), Some(-(n as i64)));
+        prop_assert_eq!(apply_math_mode(&format!("{} plus one", words)), format!("{} + 1", n));
+        prop_assert_eq!(to_digits(&format!("I saw {}, I think.", words), true), format!("I saw {}, I think.", n));
+
+        let digits: Vec<&str> = fraction.chars().map(|d| ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine"][d as usize - '0' as usize]).collect();
+        let sign = if negative { "negative " } else { "" };
+        let spoken = format!("{}{} point {}", sign, words, digits.join(" "));
+        if !fraction.is_empty() {
+            let expected: f64 = format!("{}{}.{}", if negative { "-" } else { "" }, n, fraction).parse().unwrap();
+            prop_assert_eq!(parse_decimal(&spoken), Some(expected));
+        }
+    }
+
+    #[test]
+    fn ordinals_and_fractions_read_back(n in 1u64..1_000_000, d in prop::sample::select(vec![("halves", 2u64), ("thirds", 3), ("quarters", 4), ("eighths", 8), ("sixteenths", 16)]), top in 2u64..20) {
+        // "twenty three" -> "twenty third": the last word of the spelling becomes an ordinal
+        const ORDINALS: [(&str, &str); 12] = [
+            ("one", "first"), ("two", "second"), ("three", "third"), ("five", "fifth"), ("eight", "eighth"),
+            ("nine", "ninth"), ("twelve", "twelfth"), ("hundred", "hundredth"), ("thousand", "thousandth"),
+            ("million", "millionth"), ("billion", "billionth"), ("y", "ieth"),
+        ];
+        // "thirty second" is left as a length of time
+        prop_assume!(!(n % 100 > 20 && n % 10 == 2));
+        let words = spell(n);
+        let ordinal = ORDINALS
+            .iter()
+            .find_map(|(cardinal, ordinal)| words.strip_suffix(cardinal).map(|w| format!("{}{}", w, ordinal)))
+            .unwrap_or_else(|| format!("{}th", words));
+        let suffix = match (n % 10, n % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        let expected = format!("the {}{} time", n, suffix);
+        prop_assert_eq!(to_digits(&format!("the {} time", ordinal), true), expected);
+        prop_assert_eq!(parse_integer(&ordinal), None);
+
+        let (plural, d) = d;
+        let spoken = format!("{} {}", spell(top), plural);
+        prop_assert_eq!(apply_math_mode(&format!("{} plus one", spoken)), format!("{}/{} + 1", top, d));
+        prop_assert_eq!(parse_decimal(&format!("{} and {}", words, spoken)), Some(n as f64 + top as f64 / d as f64));
+    }
+
+    #[test]
+    fn money_and_percentages_are_formatted(whole in 0u64..100_000, cents in 0u64..100, language in prop_oneof![Just("en"), Just("de"), Just("nl")]) {
+        let locale = Locale::for_language(language);
+        let spoken = format!("It was {} dollars and {} cents, or {} percent.", spell(whole), spell(cents), spell(cents));
+        let amount = format!("{}{}{:02}", whole, locale.decimal, cents);
+        let money = if locale.symbol_after { format!("{} $", amount) } else { format!("${}", amount) };
+        let percent = if locale.percent_space { format!("{} %", cents) } else { format!("{}%", cents) };
+        let expected = format!("It was {}, or {}.", money, percent);
+        prop_assert_eq!(format_amounts(&to_digits(&spoken, false), &locale), expected);
+        let plain = format!("It was {} people, {} per day, {}. Dollars.", whole, cents, cents);
+        prop_assert_eq!(format_amounts(&plain, &locale), plain.clone());
+    }
+}
@@ -0,0 +1,46 @@
+//! Which phrase wins when several match an utterance
+//!
+//! `[priorities]` first, then the phrase with more literal words, then
+//! `[commands]` over `[keybinds]` over `[macros]`; exact phrases always
+//! come before ones with {placeholders}.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::empty_phrases;
+use ss9k::commands::{parse_action, Action, Phrases};
+
+fn table(entries: &[(&str, &str)]) -> HashMap<String, String> {
+    entries.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn parse(text: &str, phrases: &Phrases) -> Action {
+    parse_action(text, "command", &[], phrases, &HashMap::new())
+}
+
+#[test]
+fn more_literal_words_win() {
+    let commands = table(&[("search {q}", "web {q}"), ("search code for {q}", "grep -r {q}")]);
+    let phrases = Phrases { commands: &commands, ..empty_phrases() };
+    assert_eq!(parse("search code for lifetimes", &phrases), Action::Custom("grep -r 'lifetimes'".to_string()));
+    assert_eq!(parse("search lifetimes", &phrases), Action::Custom("web 'lifetimes'".to_string()));
+}
+
+#[test]
+fn priorities_override_literal_words() {
+    let commands = table(&[("search {q}", "web {q}"), ("search code for {q}", "grep -r {q}")]);
+    let priorities = HashMap::from([("search {q}".to_string(), 10)]);
+    let phrases = Phrases { commands: &commands, priorities: &priorities, ..empty_phrases() };
+    assert_eq!(parse("search code for lifetimes", &phrases), Action::Custom("web 'code for lifetimes'".to_string()));
+}
+
+#[test]
+fn exact_phrases_beat_placeholders_and_commands_beat_keybinds() {
+    let commands = table(&[("open {app}", "launch {app}"), ("save all", "sync")]);
+    let keybinds = table(&[("open files", "super+e"), ("save all", "ctrl+shift+s")]);
+    let phrases = Phrases { commands: &commands, keybinds: &keybinds, ..empty_phrases() };
+    assert_eq!(parse("open files", &phrases), Action::Keybind("super+e".to_string()));
+    assert_eq!(parse("open terminal", &phrases), Action::Custom("launch 'terminal'".to_string()));
+    assert_eq!(parse("save all", &phrases), Action::Custom("sync".to_string()));
+}
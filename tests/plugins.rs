@@ -0,0 +1,67 @@
+//! The plugin host ABI, against the `.wat` plugins in tests/fixtures/plugins
+//!
+//! The loaded plugins are global, so each test holds `LOADED` while it
+//! loads its own set and calls into them.
+#![cfg(feature = "plugins")]
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use ss9k::error::PluginError;
+use ss9k::plugins;
+use ss9k::Config;
+
+static LOADED: Mutex<()> = Mutex::new(());
+
+/// Load just `files` (names in tests/fixtures/plugins)
+fn load(files: &[&str]) -> MutexGuard<'static, ()> {
+    let guard = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let config_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/plugins/config.toml");
+    let config = Config { plugins: files.iter().map(|f| f.to_string()).collect(), ..Config::default() };
+    plugins::configure(&config, Some(&config_path));
+    guard
+}
+
+fn transform(text: &str) -> (String, Result<(), PluginError>) {
+    let mut text = text.to_string();
+    let result = plugins::transform(&mut text);
+    (text, result)
+}
+
+#[test]
+fn plugins_load_and_claim_subcommands() {
+    let _loaded = load(&["shout.wat"]);
+    assert_eq!(plugins::loaded(), vec![("shout".to_string(), vec!["shout".to_string()])]);
+}
+
+#[test]
+fn transcriptions_go_through_the_plugin() {
+    let _loaded = load(&["shout.wat"]);
+    let (text, result) = transform("hello, wörld");
+    result.unwrap();
+    assert_eq!(text, "HELLO, WöRLD");
+}
+
+#[test]
+fn runaway_plugins_run_out_of_fuel() {
+    let _loaded = load(&["shout.wat"]);
+    let (text, result) = transform("loop forever");
+    assert!(matches!(result, Err(PluginError::Call { hook: "on_transcription", .. })), "{:?}", result);
+    assert_eq!(text, "loop forever");
+    // The plugin still works after
+    assert_eq!(transform("ok").0, "OK");
+}
+
+#[test]
+fn memory_growth_is_capped() {
+    let _loaded = load(&["shout.wat"]);
+    let (text, result) = transform("grow");
+    result.unwrap();
+    assert_eq!(text, "refused");
+}
+
+#[test]
+fn oversized_and_newer_plugins_dont_load() {
+    let _loaded = load(&["hog.wat", "future.wat", "shout.wat"]);
+    assert_eq!(plugins::loaded().len(), 1);
+}
@@ -0,0 +1,65 @@
+//! The remote trigger's token check, over real HTTP
+//!
+//! Only `GET /status` is used: it answers 200 once the token is accepted
+//! and needs nothing else running.
+#![cfg(feature = "http")]
+
+use std::net::TcpListener;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+use reqwest::blocking::Client;
+use ss9k::remote::{self, MIN_TOKEN_LEN};
+use ss9k::Config;
+
+const TOKEN: &str = "3f9c0e7a5b2d4c18+a6e1/f0b9d7c2a584";
+
+/// A trigger with `token` on a free port; its base URL
+fn serve(token: &str) -> String {
+    let addr = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+    let config = Config { remote_token: token.to_string(), ..Config::default() };
+    remote::start(&addr.to_string(), Arc::new(ArcSwap::from_pointee(config))).unwrap();
+    format!("http://{}", addr)
+}
+
+fn status(url: &str, bearer: Option<&str>) -> u16 {
+    let mut request = Client::new().get(url);
+    if let Some(token) = bearer {
+        request = request.bearer_auth(token);
+    }
+    request.send().unwrap().status().as_u16()
+}
+
+#[test]
+fn short_tokens_are_refused_at_startup() {
+    for token in ["", "   ", &"x".repeat(MIN_TOKEN_LEN - 1)] {
+        let config = Config { remote_token: token.to_string(), ..Config::default() };
+        assert!(remote::start("127.0.0.1:0", Arc::new(ArcSwap::from_pointee(config))).is_err(), "{:?}", token);
+    }
+}
+
+#[test]
+fn bearer_and_query_tokens_both_work() {
+    let base = serve(TOKEN);
+    assert_eq!(status(&format!("{}/status", base), Some(TOKEN)), 200);
+    let escaped = TOKEN.replace('+', "%2B").replace('/', "%2f");
+    assert_eq!(status(&format!("{}/status?token={}", base, escaped), None), 200);
+    assert_eq!(status(&format!("{}/status?verbose=1&token={}", base, TOKEN), None), 200);
+}
+
+#[test]
+fn missing_or_wrong_tokens_are_unauthorized() {
+    let base = serve(TOKEN);
+    let url = format!("{}/status", base);
+    // Same length, last character off
+    let wrong = format!("{}5", &TOKEN[..TOKEN.len() - 1]);
+    assert_eq!(wrong.len(), TOKEN.len());
+
+    assert_eq!(status(&url, None), 401);
+    assert_eq!(status(&url, Some("")), 401);
+    assert_eq!(status(&url, Some(&wrong)), 401);
+    assert_eq!(status(&format!("{}?token={}", url, wrong), None), 401);
+    assert_eq!(status(&format!("{}?token=", url), None), 401);
+    assert_eq!(status(&format!("{}?token={}", url, &TOKEN[..MIN_TOKEN_LEN]), None), 401);
+    assert_eq!(status(&format!("{}?token=%zz{}", url, TOKEN), None), 401);
+}
@@ -0,0 +1,74 @@
+//! The Rhai `transform` and `command` hooks, against the scripts in
+//! tests/fixtures/scripts
+//!
+//! The loaded script is global, so each test holds `LOADED` while it loads
+//! its script and calls into it.
+#![cfg(feature = "scripting")]
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use ss9k::error::ScriptError;
+use ss9k::scripting;
+use ss9k::Config;
+
+static LOADED: Mutex<()> = Mutex::new(());
+
+/// Load `file` (a name in tests/fixtures/scripts) as the script
+fn load(file: &str) -> MutexGuard<'static, ()> {
+    let guard = LOADED.lock().unwrap_or_else(|e| e.into_inner());
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/scripts").join(file);
+    scripting::configure(&Config { script: path.to_string_lossy().into_owned(), ..Config::default() });
+    guard
+}
+
+fn failed_hook(result: Result<impl std::fmt::Debug, ScriptError>) -> (&'static str, String) {
+    match result {
+        Err(ScriptError::Run { hook, message }) => (hook, message),
+        other => panic!("expected a failed hook, got {:?}", other),
+    }
+}
+
+#[test]
+fn transform_rewrites_the_text() {
+    let _loaded = load("hooks.rhai");
+    assert_eq!(scripting::transform("teh quick fox").unwrap(), "the quick fox");
+    assert_eq!(scripting::transform("keep").unwrap(), "keep");
+    let (hook, message) = failed_hook(scripting::transform("number"));
+    assert_eq!(hook, "transform");
+    assert!(message.contains("instead of a string"), "{}", message);
+}
+
+#[test]
+fn command_handles_utterances() {
+    let _loaded = load("hooks.rhai");
+    assert!(scripting::command("lights off").unwrap());
+    assert!(!scripting::command("hello there").unwrap());
+}
+
+#[test]
+fn failing_hooks_return_the_error() {
+    let _loaded = load("hooks.rhai");
+    let (hook, message) = failed_hook(scripting::transform("fail"));
+    assert_eq!(hook, "transform");
+    assert!(message.contains("can't transform that"), "{}", message);
+    let (hook, _) = failed_hook(scripting::command("fail"));
+    assert_eq!(hook, "command");
+    // The script still works after
+    assert_eq!(scripting::transform("teh end").unwrap(), "the end");
+}
+
+#[test]
+fn runaway_hooks_hit_the_operation_limit() {
+    let _loaded = load("hooks.rhai");
+    let (hook, message) = failed_hook(scripting::transform("spin"));
+    assert_eq!(hook, "transform");
+    assert!(message.to_lowercase().contains("operations"), "{}", message);
+}
+
+#[test]
+fn scripts_that_dont_compile_leave_text_alone() {
+    let _loaded = load("broken.rhai");
+    assert_eq!(scripting::transform("teh end").unwrap(), "teh end");
+    assert!(!scripting::command("lights off").unwrap());
+}
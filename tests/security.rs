@@ -0,0 +1,90 @@
+//! The `[security]` policy for commands built from speech
+//!
+//! `check` is tested with explicit policies; `command` reads the configured
+//! one, so only `shell_false_runs_the_first_word` configures it.
+
+use ss9k::config::Security;
+use ss9k::error::SecurityError;
+use ss9k::security::{self, check};
+use ss9k::Config;
+
+fn allowing(programs: &[&str]) -> Security {
+    Security { allowed_programs: programs.iter().map(|p| p.to_string()).collect(), ..Security::default() }
+}
+
+#[test]
+fn anything_runs_without_a_policy() {
+    for cmd in ["rm -rf ~/tmp", "ls | wc -l", "echo $(date)"] {
+        assert!(check(cmd, &Security::default()).is_ok(), "{}", cmd);
+    }
+}
+
+#[test]
+fn only_allowed_programs_start() {
+    let policy = allowing(&["firefox", "xdg-open"]);
+    assert!(check("firefox --new-tab 'rust docs'", &policy).is_ok());
+    assert!(check("  xdg-open ~/notes.txt", &policy).is_ok());
+    for (cmd, program) in [("rm -rf /", "rm"), ("/usr/bin/firefox", "/usr/bin/firefox"), ("'fire'fox2", "firefox2")] {
+        match check(cmd, &policy) {
+            Err(SecurityError::Program { program: refused, .. }) => assert_eq!(refused, program),
+            other => panic!("{}: {:?}", cmd, other),
+        }
+    }
+    assert!(matches!(check("   ", &policy), Err(SecurityError::Empty)));
+    assert!(matches!(check("firefox 'rust docs", &policy), Err(SecurityError::Quotes(_))));
+}
+
+#[test]
+fn allowed_programs_refuse_shell_operators() {
+    let policy = allowing(&["firefox"]);
+    for cmd in [
+        "firefox; rm -rf ~",
+        "firefox | sh",
+        "firefox && curl evil.example",
+        "firefox > ~/.bashrc",
+        "firefox $(rm -rf ~)",
+        "firefox \"`rm -rf ~`\"",
+        "firefox \"$HOME\"",
+        "firefox\nrm -rf ~",
+    ] {
+        assert!(matches!(check(cmd, &policy), Err(SecurityError::Operators(_))), "{:?}", cmd);
+    }
+    // Quoted or escaped, they're only text for the program (sh's rules)
+    #[cfg(not(target_os = "windows"))]
+    for cmd in ["firefox 'a; b | c'", "firefox a\\;b", "firefox \"it's $\""] {
+        assert!(check(cmd, &policy).is_ok(), "{:?}", cmd);
+    }
+    // cmd.exe doesn't know those quotes
+    #[cfg(target_os = "windows")]
+    assert!(matches!(check("firefox 'a & b'", &policy), Err(SecurityError::Operators(_))));
+    // No shell, nothing to run them
+    let policy = Security { shell: false, ..policy };
+    assert!(check("firefox a;b", &policy).is_ok());
+}
+
+#[test]
+fn refused_chars_stop_any_command() {
+    let policy = Security { refuse_chars: "`;".to_string(), ..Security::default() };
+    assert!(check("echo hello", &policy).is_ok());
+    assert!(matches!(check("echo `id`", &policy), Err(SecurityError::Char { ch: '`', .. })));
+    assert!(matches!(check("echo 'a;b'", &policy), Err(SecurityError::Char { ch: ';', .. })));
+    let policy = Security { refuse_chars: "~".to_string(), ..allowing(&["ls"]) };
+    assert!(matches!(check("ls ~", &policy), Err(SecurityError::Char { ch: '~', .. })));
+}
+
+#[test]
+fn shell_false_runs_the_first_word() {
+    let security = Security { shell: false, ..Security::default() };
+    security::configure(&Config { security, ..Config::default() });
+
+    let command = security::command(r#"notify-send 'two words' "say \"hi\"" a\ b"#).unwrap();
+    assert_eq!(command.get_program(), "notify-send");
+    let args: Vec<_> = command.get_args().collect();
+    assert_eq!(args, ["two words", r#"say "hi""#, "a b"]);
+
+    // Nothing for a shell to act on: the ; is part of an argument
+    let command = security::command("echo a; rm -rf ~").unwrap();
+    assert_eq!(command.get_program(), "echo");
+    assert_eq!(command.get_args().collect::<Vec<_>>(), ["a;", "rm", "-rf", "~"]);
+    assert!(matches!(security::command("echo 'open"), Err(SecurityError::Quotes(_))));
+}
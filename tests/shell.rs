@@ -0,0 +1,28 @@
+//! Commands whose output is typed or filled into `{shell:...}`
+//!
+//! They run on the processor thread, so a command that never finishes
+//! must not hold it up.
+#![cfg(unix)]
+
+use std::sync::atomic::Ordering;
+use std::time::{Duration, Instant};
+
+use ss9k::commands::{capture_shell, ShellContext};
+
+#[test]
+fn output_comes_back_without_its_newlines() {
+    let context = ShellContext::default();
+    assert_eq!(capture_shell("printf 'one\\ntwo\\n\\n'", &context).as_deref(), Some("one\ntwo"));
+    assert_eq!(capture_shell("echo oops >&2; exit 3", &context).as_deref(), Some(""));
+}
+
+#[test]
+fn the_abort_hotkey_stops_a_stuck_command() {
+    std::thread::spawn(|| {
+        std::thread::sleep(Duration::from_millis(300));
+        ss9k::ABORTS.fetch_add(1, Ordering::SeqCst);
+    });
+    let started = Instant::now();
+    assert_eq!(capture_shell("sleep 30", &ShellContext::default()), None);
+    assert!(started.elapsed() < Duration::from_secs(5), "{:?}", started.elapsed());
+}
@@ -0,0 +1,342 @@
+//! Property tests for the text pipeline
+//!
+//! The command and dictation code is hand-rolled string slicing, so these
+//! throw arbitrary Unicode at it and check the invariants that should hold
+//! no matter what Whisper hands us. The `text` helpers get the same
+//! treatment since every parser slices through them.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::empty_phrases;
+use proptest::prelude::*;
+use ss9k::commands::{
+    apply_case_mode, apply_code_mode, apply_math_mode, expand_env_vars, normalize_aliases, normalize_for_matching,
+    fill_shell, match_pattern, match_phrase, parse_action, parse_keybind, parse_level, parse_step, parse_number_word, parse_times_suffix, parse_wait, set_case_mode, CaseMode, Fuzzy, Phrases,
+};
+use ss9k::config::Replacement;
+use ss9k::fillers::Fillers;
+use ss9k::output::{capitalize_sentences, needs_space};
+use ss9k::profanity::{Censor, Style};
+use ss9k::security::split_words;
+use ss9k::timers::parse_timer;
+use ss9k::text::{
+    edit_distance, expand_placeholder, expand_vars, find_case_insensitive, identifier_words, jaro_winkler, last_sentence_start,
+    last_word_start, levenshtein_similarity, metaphone, replace_case_insensitive, split_last_word,
+};
+
+/// Words the parsers care about, mixed with arbitrary Unicode so slicing hits multi-byte boundaries
+fn utterance() -> impl Strategy<Value = String> {
+    let word = prop_oneof![
+        Just("command".to_string()),
+        Just("times".to_string()),
+        Just("three".to_string()),
+        Just("twenty".to_string()),
+        Just("percent".to_string()),
+        Just("plus".to_string()),
+        Just("open paren".to_string()),
+        Just("İstanbul".to_string()),
+        Just("ẞtraße".to_string()),
+        Just("ǅ".to_string()),
+        "\\PC{0,8}",
+    ];
+    prop::collection::vec(word, 0..8).prop_map(|words| words.join(" "))
+}
+
+proptest! {
+    #[test]
+    fn aliases_empty_is_identity(text in "\\PC*") {
+        prop_assert_eq!(normalize_aliases(&text, &HashMap::new()), text);
+    }
+
+    #[test]
+    fn aliases_never_panic(text in utterance(), from in "\\PC{0,6}", to in "\\PC{0,6}") {
+        let aliases = HashMap::from([(from, to)]);
+        normalize_aliases(&text, &aliases);
+    }
+
+    #[test]
+    fn aliases_replace_case_insensitively(prefix in "[a-z ]{0,10}", suffix in "[a-z ]{0,10}") {
+        let aliases = HashMap::from([("e max".to_string(), "emacs".to_string())]);
+        let text = format!("{}E Max{}", prefix, suffix);
+        let expected = normalize_aliases(&format!("{}emacs{}", prefix, suffix), &aliases);
+        prop_assert_eq!(normalize_aliases(&text, &aliases), expected);
+    }
+
+    #[test]
+    fn case_mode_off_is_idempotent(text in "\\PC*") {
+        set_case_mode(CaseMode::Off);
+        let once = apply_case_mode(&text);
+        prop_assert_eq!(&once, &text);
+        prop_assert_eq!(apply_case_mode(&once), once);
+    }
+
+    #[test]
+    fn env_vars_round_trip_without_dollar(text in "[^$]*") {
+        prop_assert_eq!(expand_env_vars(&text), text);
+    }
+
+    #[test]
+    fn times_suffix_never_panics(cmd in utterance()) {
+        let (base, count) = parse_times_suffix(&cmd);
+        prop_assert!(cmd.contains(base));
+        prop_assert!(count <= 100);
+    }
+
+    #[test]
+    fn parsers_never_panic(text in utterance()) {
+        normalize_for_matching(&text);
+        parse_number_word(&text);
+        if let Some(level) = parse_level(&text) {
+            prop_assert!(level <= 100);
+        }
+        apply_math_mode(&text);
+        apply_code_mode(&text);
+        let none = HashMap::new();
+        let phrases = Phrases {
+            replacements: &[Replacement { regex: r"(\w+) percent".to_string(), with: "{1}%".to_string() }],
+            fuzzy: Fuzzy::JaroWinkler(0.8),
+            phonetic: true,
+            ..empty_phrases()
+        };
+        parse_action(&text, "command", &[], &phrases, &none);
+        parse_keybind(&text);
+        parse_step(&text);
+        parse_wait(&text);
+        parse_timer(&text);
+        match_phrase("search {query} now", &text);
+        match_pattern(r"command (?P<what>\w+) (\d+)?", &text);
+        for word in text.split_whitespace() {
+            metaphone(word);
+        }
+    }
+
+    #[test]
+    fn case_insensitive_matches_are_real_matches(text in utterance(), needle in prop_oneof![Just("i".to_string()), Just("ss".to_string()), "\\PC{1,3}"]) {
+        for range in find_case_insensitive(&text, &needle) {
+            prop_assert!(text.is_char_boundary(range.start) && text.is_char_boundary(range.end));
+            prop_assert_eq!(text[range].to_lowercase(), needle.to_lowercase());
+        }
+    }
+
+    #[test]
+    fn replace_without_match_is_identity(text in "[^qQ]*") {
+        prop_assert_eq!(replace_case_insensitive(&text, "q", "anything"), text);
+    }
+
+    #[test]
+    fn vars_expand_once(text in "\\PC*") {
+        // A value that looks like another variable must not be expanded again
+        let expanded = expand_vars(&text, |_| Some("$AGAIN".to_string()));
+        let names = expand_vars(&text, |_| Some("\u{1}".to_string())).matches('\u{1}').count();
+        prop_assert_eq!(expanded.matches("$AGAIN").count(), names);
+    }
+
+    #[test]
+    fn placeholders_expand_once(text in "(\\PC|\\{shell:[a-z ]*\\})*") {
+        let mut calls = 0;
+        let expanded = expand_placeholder(&text, "shell:", |_| {
+            calls += 1;
+            "{shell:again}".to_string()
+        });
+        let placeholders = text.matches("{shell:").count();
+        let leftovers = expanded.matches("{shell:again}").count();
+        prop_assert!(calls <= placeholders);
+        prop_assert!(leftovers >= calls);
+    }
+
+    #[test]
+    fn last_word_split_is_lossless(text in utterance()) {
+        if let Some((head, last)) = split_last_word(&text) {
+            prop_assert!(!last.is_empty() && !last.contains(char::is_whitespace));
+            prop_assert!(text.trim_end().starts_with(head));
+            prop_assert!(text.trim_end().ends_with(last));
+        }
+    }
+
+    #[test]
+    fn scratching_stops_at_a_boundary(text in utterance(), sentences in prop::collection::vec("[A-Za-z ,]{1,12}[.!?]", 1..4)) {
+        let word = last_word_start(&text);
+        prop_assert!(text.is_char_boundary(word));
+        prop_assert!(text[word..].split_whitespace().count() <= 1);
+        prop_assert_eq!(text[..word].trim_end(), &text[..word]);
+
+        let sentence = last_sentence_start(&text);
+        prop_assert!(text.is_char_boundary(sentence) && sentence <= text.trim_end().len());
+
+        // "One. Two! Three?" loses "Three?" and then "Two!"
+        let joined = sentences.join(" ");
+        let start = last_sentence_start(&joined);
+        let kept = sentences[..sentences.len() - 1].join(" ");
+        prop_assert_eq!(&joined[..start], kept.as_str());
+    }
+
+    #[test]
+    fn identifiers_split_into_their_words(text in utterance(), words in prop::collection::vec("[a-z]{1,6}", 1..5), join in prop_oneof![Just("_"), Just("-"), Just("camel")]) {
+        // Nothing but the separators goes missing
+        let split = identifier_words(&text);
+        let kept = |s: &str| s.chars().filter(|c| !c.is_whitespace() && !matches!(c, '_' | '-' | '.' | '/')).collect::<String>();
+        prop_assert_eq!(kept(&split), kept(&text));
+
+        let identifier = match join {
+            "camel" => words.iter().enumerate().map(|(i, w)| if i == 0 { w.clone() } else { w[..1].to_uppercase() + &w[1..] }).collect(),
+            sep => words.join(sep),
+        };
+        // Camel case can't tell one-letter humps in a row from an acronym
+        let expected = match join {
+            "camel" => words.iter().enumerate().fold(String::new(), |out, (i, w)| {
+                let run = i > 1 && w.len() == 1 && words[i - 1].len() == 1;
+                if out.is_empty() || run { out + w.as_str() } else { out + " " + w.as_str() }
+            }),
+            _ => words.join(" "),
+        };
+        prop_assert_eq!(identifier_words(&identifier).to_lowercase(), expected);
+    }
+
+    #[test]
+    fn similarities_are_fractions(a in "\\PC{0,12}", b in "\\PC{0,12}") {
+        for score in [levenshtein_similarity(&a, &b), jaro_winkler(&a, &b)] {
+            prop_assert!((0.0..=1.0).contains(&score), "{} for {:?} / {:?}", score, a, b);
+        }
+        prop_assert_eq!(edit_distance(&a, &b), edit_distance(&b, &a));
+        prop_assert_eq!(levenshtein_similarity(&a, &a), 1.0);
+        prop_assert_eq!(jaro_winkler(&a, &a), 1.0);
+    }
+
+    #[test]
+    fn captures_are_the_spoken_words(query in "[a-z]{1,6}( [a-zA-Z'$]{1,6}){0,3}") {
+        let captures = match_phrase("search for {query}", &format!("Search for {}.", query));
+        prop_assert_eq!(captures, Some(vec![("query".to_string(), query)]));
+    }
+
+    #[test]
+    fn waits_scale_by_unit(n in 0u64..100_000) {
+        prop_assert_eq!(parse_wait(&format!("{} milliseconds", n)), Some(n));
+        prop_assert_eq!(parse_wait(&format!("{} seconds", n)), Some(n * 1000));
+        prop_assert_eq!(parse_step(&format!("wait {}", n)), parse_step(&format!("wait {} ms", n)));
+    }
+
+    #[test]
+    fn capitalizing_only_touches_letters(text in utterance(), before in prop_oneof![Just(""), Just("Done."), Just("and"), Just("e.g.")]) {
+        let once = capitalize_sentences(&text, before);
+        prop_assert_eq!(capitalize_sentences(&once, before), once.clone());
+        let others = |s: &str| s.chars().filter(|c| !c.is_alphabetic()).collect::<String>();
+        prop_assert_eq!(others(&once), others(&text));
+    }
+
+    #[test]
+    fn spacing_never_doubles_up(text in utterance(), before in utterance()) {
+        if needs_space(&text, &before) {
+            prop_assert!(!before.ends_with(char::is_whitespace) && !text.starts_with(char::is_whitespace));
+            prop_assert!(!before.ends_with('(') && !text.starts_with(['.', ',', ')']));
+        }
+        let new_line = before + "\n";
+        prop_assert!(!needs_space(&text, ""));
+        prop_assert!(!needs_space(&text, &new_line));
+    }
+
+    #[test]
+    fn fillers_come_out_cleanly(text in utterance(), words in prop::collection::vec("[a-z]{5,8}", 2..6), at in any::<prop::sample::Index>()) {
+        let fillers = Fillers::for_language("auto", &["command".to_string()]);
+        let once = fillers.remove(&text);
+        prop_assert_eq!(fillers.remove(&once), once);
+
+        // Five-letter words are never English fillers
+        let english = Fillers::for_language("en", &[]);
+        let sentence = words.join(" ");
+        let i = at.index(words.len() - 1) + 1;
+        let (head, tail) = (words[..i].join(" "), words[i..].join(" "));
+        prop_assert_eq!(english.remove(&sentence), sentence.clone());
+        prop_assert_eq!(english.remove(&format!("{} um {}", head, tail)), sentence.clone());
+        prop_assert_eq!(english.remove(&format!("{}, uh, {}", head, tail)), format!("{}, {}", head, tail));
+        prop_assert_eq!(english.remove(&format!("{}, like, {}.", head, tail)), format!("{}, {}.", head, tail));
+        prop_assert_eq!(english.remove(&format!("{} like {}", head, tail)), format!("{} like {}", head, tail));
+        prop_assert_eq!(english.remove(&format!("{}, you know.", sentence)), format!("{}.", sentence));
+        let capitalized = sentence[..1].to_uppercase() + &sentence[1..];
+        prop_assert_eq!(english.remove(&format!("Um, {}", sentence)), capitalized);
+
+        // Someone's hesitation is someone else's word, so "auto" needs it set off
+        let auto = Fillers::for_language("auto", &[]);
+        for word in ["em", "bah", "hum", "hm", "um"] {
+            prop_assert_eq!(auto.remove(&format!("{} {} {}", head, word, tail)), format!("{} {} {}", head, word, tail));
+            prop_assert_eq!(auto.remove(&format!("{}, {}, {}", head, word, tail)), format!("{}, {}", head, tail));
+        }
+    }
+
+    #[test]
+    fn censoring_only_touches_swears(
+        text in utterance(),
+        words in prop::collection::vec("[a-z]{5,8}", 1..6),
+        style in prop_oneof![Just(Style::Grawlix), Just(Style::Asterisks), Just(Style::FirstLetter), Just(Style::Remove)],
+    ) {
+        let censor = Censor::new("auto", &["frak".to_string()], style, "");
+        censor.apply(&text);
+
+        let sentence = words.join(" ");
+        let swore = format!("{} Frak! {}", words[0], words[1..].join(" "));
+        let censored = censor.apply(&swore);
+        prop_assert_eq!(censor.apply(&sentence), sentence.clone());
+        prop_assert!(!censored.to_lowercase().contains("frak"));
+        prop_assert!(censored.starts_with(&words[0]) && censored.contains('!'));
+        prop_assert_eq!(censored.split_whitespace().count(), words.len() + usize::from(style != Style::Remove));
+    }
+
+    #[test]
+    fn timers_keep_their_name(n in 1u64..1000, name in "[a-z]{1,8}( [a-z]{1,8})?") {
+        let expected = Some((std::time::Duration::from_secs(n * 60), name.clone()));
+        prop_assert_eq!(parse_timer(&format!("{} minutes named {}", n, name)), expected.clone());
+        prop_assert_eq!(parse_timer(&format!("for {} minutes for {}", n, name)), expected);
+        prop_assert_eq!(parse_timer(&format!("{} seconds", n)).map(|(_, name)| name), Some(format!("{} seconds", n)));
+    }
+}
+
+// Spawns a shell per case, so fewer of them
+#[cfg(unix)]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn shell_captures_stay_plain_text(
+        value in "(\\{w\\})?\\PC{1,12}",
+        other in "\\PC{1,12}",
+        template in prop::sample::select(vec![
+            "printf %s {v} {w}",
+            "printf %s '{v}' '{w}'",
+            "printf %s \"{v}\" \"{w}\"",
+            "printf %s \"<{v}>\" '<{w}>'",
+        ]),
+    ) {
+        let captures = vec![("v".to_string(), value.clone()), ("w".to_string(), other.clone())];
+        let cmd = expand_env_vars(&fill_shell(template, &captures));
+        let out = std::process::Command::new("sh").args(["-c", &cmd]).output().unwrap();
+        let expected = if template.contains('<') { format!("<{}><{}>", value, other) } else { format!("{}{}", value, other) };
+        prop_assert_eq!(String::from_utf8_lossy(&out.stdout), expected);
+    }
+}
+
+// The [security] word splitter must read fill_shell's quoting the way sh does
+#[cfg(unix)]
+proptest! {
+    #[test]
+    fn shell_captures_split_to_one_word(
+        value in "\\PC{1,12}",
+        template in prop::sample::select(vec!["printf %s {v}", "printf %s '{v}'", "printf %s \"{v}\""]),
+    ) {
+        let cmd = expand_env_vars(&fill_shell(template, &vec![("v".to_string(), value.clone())]));
+        let split = split_words(&cmd).unwrap();
+        prop_assert!(!split.operators, "{} looks like it runs more", cmd);
+        prop_assert_eq!(split.words, vec!["printf".to_string(), "%s".to_string(), value]);
+    }
+}
+
+#[test]
+fn one_letter_humps() {
+    assert_eq!(identifier_words("getX"), "get X");
+    assert_eq!(identifier_words("setXAxis"), "set X Axis");
+    assert_eq!(identifier_words("aBCd"), "a B Cd");
+    // In a row they're an acronym, like the URL in "parseURL"
+    assert_eq!(identifier_words("aBC"), "a BC");
+    assert_eq!(identifier_words("aAA"), "a AA");
+    assert_eq!(identifier_words("parseURL"), "parse URL");
+}